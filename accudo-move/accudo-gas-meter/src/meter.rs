@@ -5,6 +5,7 @@ use crate::{
     traits::{AccudoGasMeter, GasAlgebra},
     CacheValueSizes,
 };
+use accudo_crypto::pq::SchemeId;
 use accudo_gas_algebra::{AbstractValueSize, Fee, FeePerGasUnit, NumTypeNodes};
 use accudo_gas_schedule::{
     gas_feature_versions::*,
@@ -617,6 +618,22 @@ where
             .charge_execution(KEYLESS_BASE_COST)
             .map_err(|e| e.finish(Location::Undefined))
     }
+
+    fn charge_post_quantum(&mut self, scheme: SchemeId) -> VMResult<()> {
+        if self.feature_version() < RELEASE_V1_40 {
+            return Ok(());
+        }
+
+        let res = match scheme {
+            SchemeId::Dilithium2 => self.algebra.charge_execution(POST_QUANTUM_DILITHIUM2_COST),
+            SchemeId::Dilithium3 => self.algebra.charge_execution(POST_QUANTUM_DILITHIUM3_COST),
+            SchemeId::Dilithium5 => self.algebra.charge_execution(POST_QUANTUM_DILITHIUM5_COST),
+            SchemeId::Falcon512 => self.algebra.charge_execution(POST_QUANTUM_FALCON512_COST),
+            // Classical schemes are already covered by the intrinsic cost.
+            _ => return Ok(()),
+        };
+        res.map_err(|e| e.finish(Location::Undefined))
+    }
 }
 
 impl<A> CacheValueSizes for StandardGasMeter<A>
@@ -1,6 +1,7 @@
 // Copyright © Accudo Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use accudo_crypto::pq::SchemeId;
 use accudo_gas_algebra::{
     AbstractValueSize, Fee, FeePerGasUnit, Gas, GasExpression, GasScalingFactor, Octa,
 };
@@ -128,6 +129,10 @@ pub trait AccudoGasMeter: MoveGasMeter {
     /// expensive computation required.
     fn charge_keyless(&mut self) -> VMResult<()>;
 
+    /// Charges an additional cost for every post-quantum signature the transaction carries,
+    /// priced per scheme since verification cost differs widely between parameter sets.
+    fn charge_post_quantum(&mut self, scheme: SchemeId) -> VMResult<()>;
+
     /// Charges IO gas for the transaction itself.
     fn charge_io_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()>;
 
@@ -5,6 +5,7 @@ use crate::log::{
     CallFrame, Dependency, EventStorage, EventTransient, ExecutionAndIOCosts, ExecutionGasEvent,
     FrameName, StorageFees, TransactionGasLog, WriteOpType, WriteStorage, WriteTransient,
 };
+use accudo_crypto::pq::SchemeId;
 use accudo_gas_algebra::{Fee, FeePerGasUnit, InternalGas, NumArgs, NumBytes, NumTypeNodes};
 use accudo_gas_meter::{AccudoGasMeter, GasAlgebra};
 use accudo_gas_schedule::gas_feature_versions::RELEASE_V1_30;
@@ -708,6 +709,12 @@ where
 
         res
     }
+
+    fn charge_post_quantum(&mut self, scheme: SchemeId) -> VMResult<()> {
+        let (_cost, res) = self.delegate_charge(|base| base.charge_post_quantum(scheme));
+
+        res
+    }
 }
 
 impl<G> GasProfiler<G>
@@ -8,6 +8,7 @@ use crate::{
     gas_schedule::VMGasParameters,
     ver::gas_feature_versions::{
         RELEASE_V1_10, RELEASE_V1_11, RELEASE_V1_12, RELEASE_V1_13, RELEASE_V1_15, RELEASE_V1_26,
+        RELEASE_V1_40,
     },
 };
 use accudo_gas_algebra::{
@@ -275,6 +276,26 @@ crate::gas_schedule::macros::define_gas_parameters!(
             max_aa_gas: Gas,
             { RELEASE_V1_26.. => "max_aa_gas" },
             60,
+        ],
+        [
+            post_quantum_dilithium2_cost: InternalGas,
+            { RELEASE_V1_40.. => "post_quantum.dilithium2" },
+            1_200_000,
+        ],
+        [
+            post_quantum_dilithium3_cost: InternalGas,
+            { RELEASE_V1_40.. => "post_quantum.dilithium3" },
+            1_800_000,
+        ],
+        [
+            post_quantum_dilithium5_cost: InternalGas,
+            { RELEASE_V1_40.. => "post_quantum.dilithium5" },
+            2_800_000,
+        ],
+        [
+            post_quantum_falcon512_cost: InternalGas,
+            { RELEASE_V1_40.. => "post_quantum.falcon512" },
+            1_000_000,
         ]
     ]
 );
@@ -1,6 +1,7 @@
 // Copyright © Accudo Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use accudo_crypto::pq::SchemeId;
 use accudo_gas_algebra::{
     AbstractValueSize, Fee, FeePerGasUnit, InternalGas, NumArgs, NumBytes, NumTypeNodes,
 };
@@ -595,5 +596,7 @@ where
         fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()>;
 
         fn charge_keyless(&mut self) -> VMResult<()>;
+
+        fn charge_post_quantum(&mut self, scheme: SchemeId) -> VMResult<()>;
     }
 }
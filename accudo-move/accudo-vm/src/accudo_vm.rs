@@ -992,6 +992,9 @@ impl AccudoVM {
         if txn_data.is_keyless() {
             gas_meter.charge_keyless()?;
         }
+        for scheme in txn_data.post_quantum_schemes() {
+            gas_meter.charge_post_quantum(*scheme)?;
+        }
 
         match executable {
             TransactionExecutableRef::Script(script) => {
@@ -1144,6 +1147,9 @@ impl AccudoVM {
         if txn_data.is_keyless() {
             gas_meter.charge_keyless()?;
         }
+        for scheme in txn_data.post_quantum_schemes() {
+            gas_meter.charge_post_quantum(*scheme)?;
+        }
 
         // Step 1: Obtain the payload. If any errors happen here, the entire transaction should fail
         let invariant_violation_error = || {
@@ -2,11 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{move_vm_ext::AccudoMoveResolver, transaction_metadata::TransactionMetadata};
+use accudo_crypto::pq::SchemeId;
 use accudo_gas_algebra::{Gas, GasExpression, InternalGas};
 use accudo_gas_meter::{StandardGasAlgebra, StandardGasMeter};
 use accudo_gas_schedule::{
-    gas_feature_versions::RELEASE_V1_13, gas_params::txn::KEYLESS_BASE_COST, AccudoGasParameters,
-    VMGasParameters,
+    gas_feature_versions::{RELEASE_V1_13, RELEASE_V1_40},
+    gas_params::txn::{
+        KEYLESS_BASE_COST, POST_QUANTUM_DILITHIUM2_COST, POST_QUANTUM_DILITHIUM3_COST,
+        POST_QUANTUM_DILITHIUM5_COST, POST_QUANTUM_FALCON512_COST,
+    },
+    AccudoGasParameters, VMGasParameters,
 };
 use accudo_logger::{enabled, Level};
 use accudo_memory_usage_tracker::MemoryTrackedGasMeter;
@@ -128,7 +133,15 @@ pub(crate) fn check_gas(
     let intrinsic_gas = txn_gas_params
         .calculate_intrinsic_gas(raw_bytes_len)
         .evaluate(gas_feature_version, &gas_params.vm);
-    let total_rounded: Gas = (intrinsic_gas + keyless).to_unit_round_up_with_params(txn_gas_params);
+    let mut post_quantum = InternalGas::zero();
+    if gas_feature_version >= RELEASE_V1_40 {
+        for scheme in txn_metadata.post_quantum_schemes() {
+            post_quantum = post_quantum
+                + post_quantum_verification_cost(*scheme, gas_feature_version, &gas_params.vm);
+        }
+    }
+    let total_rounded: Gas =
+        (intrinsic_gas + keyless + post_quantum).to_unit_round_up_with_params(txn_gas_params);
     if txn_metadata.max_gas_amount() < total_rounded {
         speculative_warn!(
             log_context,
@@ -221,3 +234,27 @@ pub(crate) fn check_gas(
 
     Ok(())
 }
+
+/// Returns the verification cost charged for a post-quantum signature of the given scheme,
+/// mirroring `AccudoGasMeter::charge_post_quantum`.
+fn post_quantum_verification_cost(
+    scheme: SchemeId,
+    gas_feature_version: u64,
+    vm_gas_params: &VMGasParameters,
+) -> InternalGas {
+    match scheme {
+        SchemeId::Dilithium2 => {
+            POST_QUANTUM_DILITHIUM2_COST.evaluate(gas_feature_version, vm_gas_params)
+        },
+        SchemeId::Dilithium3 => {
+            POST_QUANTUM_DILITHIUM3_COST.evaluate(gas_feature_version, vm_gas_params)
+        },
+        SchemeId::Dilithium5 => {
+            POST_QUANTUM_DILITHIUM5_COST.evaluate(gas_feature_version, vm_gas_params)
+        },
+        SchemeId::Falcon512 => {
+            POST_QUANTUM_FALCON512_COST.evaluate(gas_feature_version, vm_gas_params)
+        },
+        _ => InternalGas::zero(),
+    }
+}
@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use accudo_crypto::HashValue;
+use accudo_crypto::{pq::SchemeId, HashValue};
 use accudo_gas_algebra::{FeePerGasUnit, Gas, NumBytes};
 use accudo_types::{
     account_address::AccountAddress,
@@ -33,6 +33,7 @@ pub struct TransactionMetadata {
     pub script_hash: Vec<u8>,
     pub script_size: NumBytes,
     pub is_keyless: bool,
+    pub post_quantum_schemes: Vec<SchemeId>,
     pub entry_function_payload: Option<EntryFunction>,
     pub multisig_payload: Option<Multisig>,
     // Index of the transaction in the block.
@@ -79,6 +80,16 @@ impl TransactionMetadata {
             is_keyless: accudo_types::keyless::get_authenticators(txn)
                 .map(|res| !res.is_empty())
                 .unwrap_or(false),
+            post_quantum_schemes: txn
+                .authenticator_ref()
+                .to_single_key_authenticators()
+                .map(|authenticators| {
+                    authenticators
+                        .iter()
+                        .flat_map(|authenticator| authenticator.post_quantum_schemes())
+                        .collect()
+                })
+                .unwrap_or_default(),
             entry_function_payload: if txn.payload().is_multisig() {
                 None
             } else if let Ok(TransactionExecutableRef::EntryFunction(e)) =
@@ -184,6 +195,10 @@ impl TransactionMetadata {
         self.is_keyless
     }
 
+    pub fn post_quantum_schemes(&self) -> &[SchemeId] {
+        &self.post_quantum_schemes
+    }
+
     pub fn entry_function_payload(&self) -> Option<EntryFunction> {
         self.entry_function_payload.clone()
     }
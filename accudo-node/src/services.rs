@@ -83,7 +83,7 @@ pub fn bootstrap_api_and_indexer(
         node_config,
         db_rw.clone(),
         internal_indexer_db,
-        update_receiver,
+        update_receiver.clone(),
     ) {
         Some((runtime, db_indexer)) => (Some(runtime), Some(db_indexer)),
         None => (None, None),
@@ -104,6 +104,7 @@ pub fn bootstrap_api_and_indexer(
             db_rw.reader.clone(),
             mempool_client_sender.clone(),
             indexer_reader.clone(),
+            update_receiver,
             api_port_tx,
         )?)
     } else {
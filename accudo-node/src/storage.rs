@@ -53,7 +53,9 @@ pub(crate) fn bootstrap_db(
     Option<WatchReceiver<(Instant, Version)>>,
 )> {
     let internal_indexer_db = InternalIndexerDBService::get_indexer_db(node_config);
-    let (update_sender, update_receiver) = if internal_indexer_db.is_some() {
+    let websocket_api_enabled = node_config.api.enabled && node_config.api.websocket_enabled;
+    let (update_sender, update_receiver) = if internal_indexer_db.is_some() || websocket_api_enabled
+    {
         let (sender, receiver) = channel::<(Instant, Version)>((Instant::now(), 0 as Version));
         (Some(sender), Some(receiver))
     } else {
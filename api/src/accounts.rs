@@ -6,16 +6,17 @@ use crate::{
     accept_type::AcceptType,
     context::{api_spawn_blocking, Context},
     failpoint::fail_point_poem,
-    page::determine_limit,
+    page::{determine_limit, DEFAULT_PAGE_SIZE},
     response::{
-        account_not_found, resource_not_found, struct_field_not_found, BadRequestError,
-        BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404, InternalError,
+        account_not_found, api_forbidden, resource_not_found, struct_field_not_found,
+        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
+        InternalError,
     },
     ApiTags,
 };
 use accudo_api_types::{
-    AccountData, AccudoErrorCode, Address, AsConverter, AssetType, LedgerInfo, MoveModuleBytecode,
-    MoveModuleId, MoveResource, MoveStructTag, StateKeyWrapper, U64,
+    AccountData, AccudoErrorCode, Address, AsConverter, AssetType, BalanceChange, LedgerInfo,
+    MoveModuleBytecode, MoveModuleId, MoveResource, MoveStructTag, StateKeyWrapper, U64,
 };
 use accudo_sdk::types::{get_paired_fa_metadata_address, get_paired_fa_primary_store_address};
 use accudo_types::{
@@ -165,6 +166,60 @@ impl AccountsApi {
         .await
     }
 
+    /// Get account balance history
+    ///
+    /// Retrieves the changes of the account balance for coins / fungible asset (only for primary
+    /// fungible asset store) by the transactions in a range of versions, from the write sets of the
+    /// transactions. If the end of the range is not specified in the request, the latest ledger
+    /// version is used.
+    ///
+    /// The first write of the balance in the range is always returned, followed by the writes
+    /// which changed it. To continue after the last change returned, request again with a
+    /// `start_version` following it.
+    ///
+    /// The Accudo nodes prune account state history, via a configurable time window.
+    /// If the start of the range has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/accounts/:address/balance/:asset_type/history",
+        method = "get",
+        operation_id = "get_account_balance_history",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_balance_history(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+        asset_type: Path<AssetType>,
+        /// First ledger version of the range
+        start_version: Query<U64>,
+        /// Last ledger version of the range
+        ///
+        /// If not provided, it will be the latest version
+        end_version: Query<Option<U64>>,
+        /// Max number of changes to retrieve
+        ///
+        /// If not provided, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<BalanceChange>> {
+        fail_point_poem("endpoint_get_account_balance_history")?;
+        self.context
+            .check_api_output_enabled("Get account balance history", &accept_type)?;
+        if accept_type == AcceptType::Bcs {
+            return Err(api_forbidden(
+                "Get account balance history with BCS output",
+                "Please use JSON output instead.",
+            ));
+        }
+
+        let context = self.context.clone();
+        api_spawn_blocking(move || {
+            let account = Account::new(context, address.0, end_version.0, None, limit.0)?;
+            account.balance_history(asset_type.0, start_version.0 .0)
+        })
+        .await
+    }
+
     /// Get account modules
     ///
     /// Retrieves all account modules' bytecode for a given account at a specific ledger version.
@@ -233,6 +288,14 @@ pub struct Account {
     pub latest_ledger_info: LedgerInfo,
 }
 
+/// Keys of the state values which hold the balance of an account for an asset type
+struct BalanceStateKeys {
+    /// The coin store of the account, if the asset type is a coin
+    coin_store: Option<StateKey>,
+    /// The primary fungible store of the account, or of its paired fungible asset for a coin
+    primary_store: StateKey,
+}
+
 impl Account {
     pub fn new(
         context: Arc<Context>,
@@ -322,7 +385,84 @@ impl Account {
         asset_type: AssetType,
         accept_type: &AcceptType,
     ) -> BasicResultWith404<u64> {
-        let (fa_metadata_address, mut balance) = match asset_type {
+        let balance_keys = self.balance_state_keys(asset_type)?;
+        let balance = self.balance_at_version(&balance_keys, self.ledger_version)?;
+        match accept_type {
+            AcceptType::Json => BasicResponse::try_from_json((
+                balance,
+                &self.latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+            AcceptType::Bcs => BasicResponse::try_from_encoded((
+                bcs::to_bytes(&balance).unwrap(),
+                &self.latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+        }
+    }
+
+    /// Retrieves the changes of the balance by the transactions from `start_version` to the
+    /// lookup ledger version
+    pub fn balance_history(
+        &self,
+        asset_type: AssetType,
+        start_version: u64,
+    ) -> BasicResultWith404<Vec<BalanceChange>> {
+        let (_, end_version) = self
+            .context
+            .verify_state_history_range(start_version, Some(self.ledger_version))?;
+        let limit = determine_limit(
+            self.limit,
+            DEFAULT_PAGE_SIZE,
+            self.context.max_transactions_page_size(),
+            &self.latest_ledger_info,
+        )?;
+        let balance_keys = self.balance_state_keys(asset_type)?;
+        let state_keys: Vec<_> = balance_keys
+            .coin_store
+            .iter()
+            .chain([&balance_keys.primary_store])
+            .cloned()
+            .collect();
+        let writes = self
+            .context
+            .get_state_key_writes(&state_keys, start_version, end_version)
+            .context("Failed to read write sets from DB")
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AccudoErrorCode::InternalError,
+                    &self.latest_ledger_info,
+                )
+            })?;
+
+        let mut changes = vec![];
+        let mut previous_balance = None;
+        for (version, _) in writes {
+            let balance = self.balance_at_version(&balance_keys, version)?;
+            // Writes to the stores may leave the balance unchanged
+            if previous_balance == Some(balance) {
+                continue;
+            }
+            changes.push(BalanceChange {
+                version: version.into(),
+                balance: balance.into(),
+            });
+            if changes.len() == limit as usize {
+                break;
+            }
+            previous_balance = Some(balance);
+        }
+
+        BasicResponse::try_from_json((changes, &self.latest_ledger_info, BasicResponseStatus::Ok))
+    }
+
+    /// Returns the keys of the state values which hold the balance of the account
+    fn balance_state_keys(
+        &self,
+        asset_type: AssetType,
+    ) -> Result<BalanceStateKeys, BasicErrorWith404> {
+        let (fa_metadata_address, coin_store) = match asset_type {
             AssetType::Coin(move_struct_tag) => {
                 let coin_store_type_tag =
                     StructTag::from_str(&format!("0x1::coin::CoinStore<{}>", move_struct_tag))
@@ -333,47 +473,59 @@ impl Account {
                                 &self.latest_ledger_info,
                             )
                         })?;
-                // query coin balance
-                let state_value = self.context.get_state_value_poem(
-                    &StateKey::resource(&self.address.into(), &coin_store_type_tag).map_err(
-                        |err| {
-                            BasicErrorWith404::internal_with_code(
-                                err,
-                                AccudoErrorCode::InternalError,
-                                &self.latest_ledger_info,
-                            )
-                        },
-                    )?,
-                    self.ledger_version,
-                    &self.latest_ledger_info,
-                )?;
-                let coin_balance = match state_value {
-                    None => 0,
-                    Some(bytes) => bcs::from_bytes::<CoinStoreResourceUntyped>(&bytes)
-                        .map_err(|err| {
-                            BasicErrorWith404::internal_with_code(
-                                err,
-                                AccudoErrorCode::InternalError,
-                                &self.latest_ledger_info,
-                            )
-                        })?
-                        .coin(),
-                };
+                let coin_store = StateKey::resource(&self.address.into(), &coin_store_type_tag)
+                    .map_err(|err| {
+                        BasicErrorWith404::internal_with_code(
+                            err,
+                            AccudoErrorCode::InternalError,
+                            &self.latest_ledger_info,
+                        )
+                    })?;
                 (
                     get_paired_fa_metadata_address(&move_struct_tag),
-                    coin_balance,
+                    Some(coin_store),
                 )
             },
-            AssetType::FungibleAsset(fa_metadata_adddress) => (fa_metadata_adddress.into(), 0),
+            AssetType::FungibleAsset(fa_metadata_adddress) => (fa_metadata_adddress.into(), None),
         };
         let primary_fungible_store_address =
             get_paired_fa_primary_store_address(self.address.into(), fa_metadata_address);
-        if let Some(data_blob) = self.context.get_state_value_poem(
-            &StateKey::resource_group(
+        Ok(BalanceStateKeys {
+            coin_store,
+            primary_store: StateKey::resource_group(
                 &primary_fungible_store_address,
                 &ObjectGroupResource::struct_tag(),
             ),
-            self.ledger_version,
+        })
+    }
+
+    /// Reads the balance held by the coin store and the primary fungible store at a version
+    fn balance_at_version(
+        &self,
+        balance_keys: &BalanceStateKeys,
+        version: u64,
+    ) -> Result<u64, BasicErrorWith404> {
+        let mut balance = 0;
+        if let Some(coin_store) = &balance_keys.coin_store {
+            // query coin balance
+            let state_value =
+                self.context
+                    .get_state_value_poem(coin_store, version, &self.latest_ledger_info)?;
+            if let Some(bytes) = state_value {
+                balance += bcs::from_bytes::<CoinStoreResourceUntyped>(&bytes)
+                    .map_err(|err| {
+                        BasicErrorWith404::internal_with_code(
+                            err,
+                            AccudoErrorCode::InternalError,
+                            &self.latest_ledger_info,
+                        )
+                    })?
+                    .coin();
+            }
+        }
+        if let Some(data_blob) = self.context.get_state_value_poem(
+            &balance_keys.primary_store,
+            version,
             &self.latest_ledger_info,
         )? {
             if let Ok(object_group) = bcs::from_bytes::<ObjectGroupResource>(&data_blob) {
@@ -410,18 +562,7 @@ impl Account {
                 }
             }
         }
-        match accept_type {
-            AcceptType::Json => BasicResponse::try_from_json((
-                balance,
-                &self.latest_ledger_info,
-                BasicResponseStatus::Ok,
-            )),
-            AcceptType::Bcs => BasicResponse::try_from_encoded((
-                bcs::to_bytes(&balance).unwrap(),
-                &self.latest_ledger_info,
-                BasicResponseStatus::Ok,
-            )),
-        }
+        Ok(balance)
     }
 
     /// Retrieves the account resource for the associated account
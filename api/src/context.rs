@@ -8,8 +8,10 @@ use crate::{
     response::{
         bcs_api_disabled, block_not_found_by_height, block_not_found_by_version,
         block_pruned_by_height, json_api_disabled, version_not_found, version_pruned,
-        ForbiddenError, InternalError, NotFoundError, ServiceUnavailableError, StdApiError,
+        BadRequestError, ForbiddenError, InternalError, NotFoundError, ServiceUnavailableError,
+        StdApiError,
     },
+    websocket::CommitNotifier,
 };
 use accudo_api_types::{
     transaction::ReplayProtector, AccudoErrorCode, AsConverter, BcsBlock, GasEstimation,
@@ -19,7 +21,10 @@ use accudo_config::config::{GasEstimationConfig, NodeConfig, RoleType};
 use accudo_crypto::HashValue;
 use accudo_gas_schedule::{AccudoGasParameters, FromOnChainGasSchedule};
 use accudo_logger::{error, info, Schema};
-use accudo_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use accudo_mempool::{
+    MempoolClientRequest, MempoolClientSender, MempoolTransactionEvent, MempoolTransactionStatus,
+    SubmissionStatus,
+};
 use accudo_storage_interface::{
     state_store::state_view::db_state_view::{
         DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView,
@@ -48,6 +53,7 @@ use accudo_types::{
         use_case::{UseCaseAwareTransaction, UseCaseKey},
         IndexedTransactionSummary, SignedTransaction, Transaction, TransactionWithProof, Version,
     },
+    write_set::WriteOp,
 };
 use anyhow::{anyhow, bail, ensure, format_err, Context as AnyhowContext, Result};
 use futures::{channel::oneshot, SinkExt};
@@ -61,6 +67,7 @@ use serde::Serialize;
 use std::{
     cmp::Reverse,
     collections::{BTreeMap, HashMap},
+    net::IpAddr,
     ops::{Bound::Included, Deref},
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
@@ -68,6 +75,7 @@ use std::{
     },
     time::Instant,
 };
+use tokio::sync::broadcast;
 
 // Context holds application scope context
 #[derive(Clone)]
@@ -83,6 +91,9 @@ pub struct Context {
     simulate_txn_stats: Arc<FunctionStats>,
     pub indexer_reader: Option<Arc<dyn IndexerReader>>,
     pub wait_for_hash_active_connections: Arc<AtomicUsize>,
+    pub pending_transaction_stream_active_connections: Arc<AtomicUsize>,
+    commit_notifier: Option<CommitNotifier>,
+    pub websocket_active_connections: Arc<AtomicUsize>,
 }
 
 impl std::fmt::Debug for Context {
@@ -135,13 +146,30 @@ impl Context {
             simulate_txn_stats,
             indexer_reader,
             wait_for_hash_active_connections: Arc::new(AtomicUsize::new(0)),
+            pending_transaction_stream_active_connections: Arc::new(AtomicUsize::new(0)),
+            commit_notifier: None,
+            websocket_active_connections: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    /// Sets the notifier of the data committed to storage, which the websocket API is served
+    /// from.
+    pub fn set_commit_notifier(&mut self, commit_notifier: CommitNotifier) {
+        self.commit_notifier = Some(commit_notifier);
+    }
+
+    pub fn commit_notifier(&self) -> Option<&CommitNotifier> {
+        self.commit_notifier.as_ref()
+    }
+
     pub fn max_transactions_page_size(&self) -> u16 {
         self.node_config.api.max_transactions_page_size
     }
 
+    pub fn max_block_transactions_page_size(&self) -> u16 {
+        self.node_config.api.max_block_transactions_page_size
+    }
+
     pub fn max_events_page_size(&self) -> u16 {
         self.node_config.api.max_events_page_size
     }
@@ -195,6 +223,73 @@ impl Context {
         Ok(self.db.state_view_at_version(Some(version))?)
     }
 
+    /// Verifies the range of versions of a resource or balance history request, and returns the
+    /// latest ledger info along with the end of the range, which defaults to the latest version.
+    pub fn verify_state_history_range<E: StdApiError + BadRequestError>(
+        &self,
+        start_version: Version,
+        end_version: Option<Version>,
+    ) -> Result<(LedgerInfo, Version), E> {
+        let (latest_ledger_info, end_version) =
+            self.get_latest_ledger_info_and_verify_lookup_version(end_version)?;
+        if start_version < latest_ledger_info.oldest_ledger_version.0 {
+            return Err(version_pruned(start_version, &latest_ledger_info));
+        }
+        if start_version > end_version {
+            return Err(E::bad_request_with_code(
+                format!(
+                    "Given start_version ({}) is higher than end_version ({})",
+                    start_version, end_version
+                ),
+                AccudoErrorCode::InvalidInput,
+                &latest_ledger_info,
+            ));
+        }
+        let max_range = self.node_config.api.max_state_history_version_range;
+        if end_version - start_version >= max_range {
+            return Err(E::bad_request_with_code(
+                format!(
+                    "Requested too many versions: {} to {}, while limit is {} versions",
+                    start_version, end_version, max_range
+                ),
+                AccudoErrorCode::InvalidInput,
+                &latest_ledger_info,
+            ));
+        }
+        Ok((latest_ledger_info, end_version))
+    }
+
+    /// Returns the writes to the given state keys by the transactions from `start_version` to
+    /// `end_version` inclusive, grouped by version. Versions which wrote none of the keys are
+    /// skipped.
+    pub fn get_state_key_writes(
+        &self,
+        state_keys: &[StateKey],
+        start_version: Version,
+        end_version: Version,
+    ) -> Result<Vec<(Version, Vec<(StateKey, WriteOp)>)>> {
+        let mut writes = vec![];
+        for chunk_start in (start_version..=end_version).step_by(MAX_REQUEST_LIMIT as usize) {
+            let limit = std::cmp::min(MAX_REQUEST_LIMIT, end_version - chunk_start + 1);
+            let write_sets = self.db.get_write_set_iterator(chunk_start, limit)?;
+            for (version, write_set) in (chunk_start..).zip(write_sets) {
+                let write_set = write_set?;
+                let ops: Vec<_> = state_keys
+                    .iter()
+                    .filter_map(|key| {
+                        write_set
+                            .get_write_op(key)
+                            .map(|op| (key.clone(), op.clone()))
+                    })
+                    .collect();
+                if !ops.is_empty() {
+                    writes.push((version, ops));
+                }
+            }
+        }
+        Ok(writes)
+    }
+
     pub fn chain_id(&self) -> ChainId {
         self.chain_id
     }
@@ -215,11 +310,21 @@ impl Context {
         self.node_config.api.max_submit_transaction_batch_size
     }
 
-    pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
+    pub fn max_view_function_batch_size(&self) -> usize {
+        self.node_config.api.max_view_function_batch_size
+    }
+
+    pub async fn submit_transaction(
+        &self,
+        txn: SignedTransaction,
+        client_ip: Option<IpAddr>,
+    ) -> Result<SubmissionStatus> {
         let (req_sender, callback) = oneshot::channel();
         self.mp_sender
             .clone()
-            .send(MempoolClientRequest::SubmitTransaction(txn, req_sender))
+            .send(MempoolClientRequest::SubmitTransaction(
+                txn, client_ip, req_sender,
+            ))
             .await?;
 
         callback.await?
@@ -995,6 +1100,35 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    pub async fn get_pending_transaction_status(
+        &self,
+        hash: HashValue,
+    ) -> Result<Option<MempoolTransactionStatus>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetTransactionStatus(hash, req_sender))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    pub async fn subscribe_mempool_transaction_events(
+        &self,
+    ) -> Result<broadcast::Receiver<MempoolTransactionEvent>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::SubscribeTransactionEvents(req_sender))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
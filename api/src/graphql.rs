@@ -0,0 +1,535 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional GraphQL read API, served at `/v1/graphql` next to the REST API.
+//!
+//! Every field resolves through the same internals as the matching REST endpoint, so the
+//! results, errors and pruning behavior are the same, but a client can fetch e.g. an account
+//! with its resources, modules and events in a single request.
+//!
+//! Each query has a cost budget, in number of items read from storage. Fields returning a page
+//! cost as many items as the page may hold, bounded by the max page size of the matching REST
+//! endpoint, and every other field costs one item. See `ApiConfig::graphql_max_query_cost`.
+
+use crate::{
+    accept_type::AcceptType,
+    accounts::Account,
+    blocks::BlocksApi,
+    context::{api_spawn_blocking, Context},
+    events::EventsApi,
+    page::{Page, DEFAULT_PAGE_SIZE},
+    response::{
+        AccudoResponseContent, BasicError, BasicResponse, BasicResponseStatus, InternalError,
+    },
+    state::StateApi,
+    transactions::TransactionsApi,
+};
+use accudo_api_types::{
+    verify_field_identifier, verify_module_identifier, AccountData, AccudoErrorCode, Address,
+    Block, HashValue, IdentifierWrapper, IndexResponse, MoveModuleBytecode, MoveResource,
+    MoveStructTag, MoveType, MoveValue, TableItemRequest, Transaction, VerifyInput,
+    VerifyInputWithRecursion, VersionedEvent, U64,
+};
+use accudo_types::event::EventKey;
+use async_graphql::{
+    Context as GraphQlContext, EmptyMutation, EmptySubscription, ErrorExtensions, InputValueError,
+    InputValueResult, Json, Object, Request, Response, Result, Scalar, ScalarType, Schema, Value,
+};
+use poem::{handler, web::Data};
+use poem_openapi::types::ToJSON;
+use std::{
+    fmt::Display,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+/// Maximum nesting depth of a query, which is well above what the schema allows.
+const MAX_QUERY_DEPTH: usize = 8;
+
+pub type GraphQlSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// The GraphQL schema along with the cost budget given to each query.
+#[derive(Clone)]
+pub struct GraphQlApi {
+    schema: GraphQlSchema,
+    max_query_cost: u64,
+}
+
+impl GraphQlApi {
+    pub fn new(context: Arc<Context>) -> Self {
+        let max_query_cost = context.node_config.api.graphql_max_query_cost();
+        let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+            .data(context)
+            .limit_depth(MAX_QUERY_DEPTH)
+            .finish();
+        Self {
+            schema,
+            max_query_cost,
+        }
+    }
+
+    pub async fn execute(&self, request: Request) -> Response {
+        self.schema
+            .execute(request.data(QueryCost::new(self.max_query_cost)))
+            .await
+    }
+}
+
+#[handler]
+pub async fn graphql_handler(
+    api: Data<&GraphQlApi>,
+    request: poem::web::Json<Request>,
+) -> poem::web::Json<Response> {
+    poem::web::Json(api.execute(request.0).await)
+}
+
+/// Remaining cost budget of a query, in number of items read from storage.
+struct QueryCost {
+    max_cost: u64,
+    remaining: AtomicU64,
+}
+
+impl QueryCost {
+    fn new(max_cost: u64) -> Self {
+        Self {
+            max_cost,
+            remaining: AtomicU64::new(max_cost),
+        }
+    }
+
+    fn charge(&self, cost: u64) -> Result<()> {
+        self.remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |remaining| {
+                remaining.checked_sub(cost)
+            })
+            .map(|_| ())
+            .map_err(|_| {
+                graphql_error(
+                    format!(
+                        "Query exceeds the maximum cost of {} items read",
+                        self.max_cost
+                    ),
+                    AccudoErrorCode::InvalidInput,
+                )
+            })
+    }
+}
+
+/// Charges a single item against the budget of the query.
+fn charge_item(ctx: &GraphQlContext<'_>) -> Result<()> {
+    ctx.data_unchecked::<QueryCost>().charge(1)
+}
+
+/// Charges a full page against the budget of the query, and returns the page limit.
+fn charge_page(ctx: &GraphQlContext<'_>, limit: Option<u16>, max_page_size: u16) -> Result<u16> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(max_page_size);
+    ctx.data_unchecked::<QueryCost>().charge(limit as u64)?;
+    Ok(limit)
+}
+
+fn api_context(ctx: &GraphQlContext<'_>) -> Arc<Context> {
+    ctx.data_unchecked::<Arc<Context>>().clone()
+}
+
+fn graphql_error(message: impl Into<String>, error_code: AccudoErrorCode) -> async_graphql::Error {
+    async_graphql::Error::new(message).extend_with(|_, extensions| {
+        if let Ok(code) = async_graphql::to_value(error_code) {
+            extensions.set("code", code);
+        }
+    })
+}
+
+fn into_graphql_error<E: InternalError>(mut error: E) -> async_graphql::Error {
+    let error = error.inner_mut();
+    graphql_error(error.message.clone(), error.error_code)
+}
+
+fn parse<T>(name: &str, value: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    T::from_str(value).map_err(|err| {
+        graphql_error(
+            format!("'{}' invalid: {}", name, err),
+            AccudoErrorCode::InvalidInput,
+        )
+    })
+}
+
+fn verify(name: &str, result: anyhow::Result<()>) -> Result<()> {
+    result.map_err(|err| {
+        graphql_error(
+            format!("'{}' invalid: {:#}", name, err),
+            AccudoErrorCode::InvalidInput,
+        )
+    })
+}
+
+fn into_json<T: ToJSON + Send + Sync>(response: BasicResponse<T>) -> Result<Json<T>> {
+    match response {
+        BasicResponse::Ok(AccudoResponseContent::Json(json), ..) => Ok(Json(json.0)),
+        BasicResponse::Ok(AccudoResponseContent::Bcs(_), ..) => Err(graphql_error(
+            "Unexpected BCS response",
+            AccudoErrorCode::InternalError,
+        )),
+    }
+}
+
+/// Runs the given REST internals on the blocking pool and converts their JSON response.
+async fn resolve<T, E, F>(func: F) -> Result<Json<T>>
+where
+    T: ToJSON + Send + Sync + 'static,
+    E: InternalError + Send + 'static,
+    F: FnOnce() -> std::result::Result<BasicResponse<T>, E> + Send + 'static,
+{
+    into_json(api_spawn_blocking(func).await.map_err(into_graphql_error)?)
+}
+
+/// A u64 encoded as a string, as in the REST API. Numbers are also accepted as input.
+#[derive(Clone, Copy, Debug)]
+pub struct U64Scalar(u64);
+
+#[Scalar(name = "U64")]
+impl ScalarType for U64Scalar {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match &value {
+            Value::String(value) => Ok(Self(value.parse()?)),
+            Value::Number(number) => number
+                .as_u64()
+                .map(Self)
+                .ok_or_else(|| InputValueError::custom("Expected an unsigned 64-bit integer")),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Latest ledger information, including chain ID, ledger versions and epoch
+    async fn ledger_info(&self, ctx: &GraphQlContext<'_>) -> Result<Json<IndexResponse>> {
+        charge_item(ctx)?;
+        let context = api_context(ctx);
+        resolve(move || {
+            let ledger_info = context.get_latest_ledger_info::<BasicError>()?;
+            let index_response = IndexResponse::new(
+                ledger_info.clone(),
+                context.node_role(),
+                Some(accudo_build_info::get_git_hash()),
+            );
+            BasicResponse::try_from_json((index_response, &ledger_info, BasicResponseStatus::Ok))
+        })
+        .await
+    }
+
+    /// An account at a given ledger version, the latest one if not provided
+    async fn account(
+        &self,
+        address: String,
+        ledger_version: Option<U64Scalar>,
+    ) -> Result<AccountObject> {
+        Ok(AccountObject {
+            address: parse("address", &address)?,
+            ledger_version: ledger_version.map(|version| U64(version.0)),
+        })
+    }
+
+    /// Committed transactions, the latest ones if no start version is provided
+    async fn transactions(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        start: Option<U64Scalar>,
+        limit: Option<u16>,
+    ) -> Result<Json<Vec<Transaction>>> {
+        let context = api_context(ctx);
+        let max_page_size = context.max_transactions_page_size();
+        let limit = charge_page(ctx, limit, max_page_size)?;
+        let page = Page::new(start.map(|start| start.0), Some(limit), max_page_size);
+        let api = TransactionsApi { context };
+        resolve(move || api.list(&AcceptType::Json, page)).await
+    }
+
+    /// A transaction by hash, either committed or pending in mempool
+    async fn transaction_by_hash(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        hash: String,
+    ) -> Result<Json<Transaction>> {
+        charge_item(ctx)?;
+        let hash: HashValue = parse("hash", &hash)?;
+        let api = TransactionsApi {
+            context: api_context(ctx),
+        };
+        let response = api
+            .get_transaction_by_hash_inner(&AcceptType::Json, hash)
+            .await
+            .map_err(into_graphql_error)?;
+        into_json(response)
+    }
+
+    /// A committed transaction by version
+    async fn transaction_by_version(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        version: U64Scalar,
+    ) -> Result<Json<Transaction>> {
+        charge_item(ctx)?;
+        let api = TransactionsApi {
+            context: api_context(ctx),
+        };
+        resolve(move || api.get_transaction_by_version_inner(&AcceptType::Json, U64(version.0)))
+            .await
+    }
+
+    /// A block by height, optionally with its transactions
+    async fn block_by_height(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        height: U64Scalar,
+        with_transactions: Option<bool>,
+    ) -> Result<Json<Block>> {
+        let api = BlocksApi {
+            context: api_context(ctx),
+        };
+        let with_transactions = charge_block(ctx, &api.context, with_transactions)?;
+        resolve(move || api.get_by_height(AcceptType::Json, height.0, with_transactions)).await
+    }
+
+    /// The block holding a given version, optionally with its transactions
+    async fn block_by_version(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        version: U64Scalar,
+        with_transactions: Option<bool>,
+    ) -> Result<Json<Block>> {
+        let api = BlocksApi {
+            context: api_context(ctx),
+        };
+        let with_transactions = charge_block(ctx, &api.context, with_transactions)?;
+        resolve(move || api.get_by_version(AcceptType::Json, version.0, with_transactions)).await
+    }
+
+    /// An item of a table, with its key given as JSON
+    async fn table_item(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        handle: String,
+        key_type: String,
+        value_type: String,
+        key: Json<serde_json::Value>,
+        ledger_version: Option<U64Scalar>,
+    ) -> Result<Json<MoveValue>> {
+        charge_item(ctx)?;
+        let handle: Address = parse("handle", &handle)?;
+        let table_item_request = TableItemRequest {
+            key_type: parse::<MoveType>("key_type", &key_type)?,
+            value_type: parse::<MoveType>("value_type", &value_type)?,
+            key: key.0,
+        };
+        verify("table_item_request", table_item_request.verify())?;
+        let api = StateApi {
+            context: api_context(ctx),
+        };
+        resolve(move || {
+            api.table_item(
+                &AcceptType::Json,
+                handle,
+                table_item_request,
+                ledger_version.map(|version| U64(version.0)),
+            )
+        })
+        .await
+    }
+}
+
+/// Charges a block against the budget of the query, as a full page of block transactions if
+/// they are requested.
+fn charge_block(
+    ctx: &GraphQlContext<'_>,
+    context: &Context,
+    with_transactions: Option<bool>,
+) -> Result<bool> {
+    let with_transactions = with_transactions.unwrap_or_default();
+    if with_transactions {
+        let max_page_size = context.max_block_transactions_page_size();
+        charge_page(ctx, Some(max_page_size), max_page_size)?;
+    } else {
+        charge_item(ctx)?;
+    }
+    Ok(with_transactions)
+}
+
+/// An account at a given ledger version. Its fields are only read from storage when queried.
+pub struct AccountObject {
+    address: Address,
+    ledger_version: Option<U64>,
+}
+
+#[Object]
+impl AccountObject {
+    async fn address(&self) -> String {
+        self.address.to_string()
+    }
+
+    /// Sequence number and authentication key of the account
+    async fn data(&self, ctx: &GraphQlContext<'_>) -> Result<Json<AccountData>> {
+        charge_item(ctx)?;
+        let (context, address, ledger_version) =
+            (api_context(ctx), self.address, self.ledger_version);
+        resolve(move || {
+            Account::new(context, address, ledger_version, None, None)?.account(&AcceptType::Json)
+        })
+        .await
+    }
+
+    /// Resources of the account
+    async fn resources(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        limit: Option<u16>,
+    ) -> Result<Json<Vec<MoveResource>>> {
+        let context = api_context(ctx);
+        let limit = charge_page(ctx, limit, context.max_account_resources_page_size())?;
+        let (address, ledger_version) = (self.address, self.ledger_version);
+        resolve(move || {
+            Account::new(context, address, ledger_version, None, Some(limit))?
+                .resources(&AcceptType::Json)
+        })
+        .await
+    }
+
+    /// A resource of the account, e.g. `0x1::account::Account`
+    async fn resource(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        resource_type: String,
+    ) -> Result<Json<MoveResource>> {
+        charge_item(ctx)?;
+        let resource_type: MoveStructTag = parse("resource_type", &resource_type)?;
+        verify("resource_type", resource_type.verify(0))?;
+        let api = StateApi {
+            context: api_context(ctx),
+        };
+        let (address, ledger_version) = (self.address, self.ledger_version);
+        resolve(move || {
+            api.resource(
+                &AcceptType::Json,
+                address,
+                resource_type,
+                ledger_version.map(|version| version.0),
+            )
+        })
+        .await
+    }
+
+    /// Modules published under the account
+    async fn modules(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        limit: Option<u16>,
+    ) -> Result<Json<Vec<MoveModuleBytecode>>> {
+        let context = api_context(ctx);
+        let limit = charge_page(ctx, limit, context.max_account_modules_page_size())?;
+        let (address, ledger_version) = (self.address, self.ledger_version);
+        resolve(move || {
+            Account::new(context, address, ledger_version, None, Some(limit))?
+                .modules(&AcceptType::Json)
+        })
+        .await
+    }
+
+    /// A module published under the account, e.g. `coin`
+    async fn module(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        name: String,
+    ) -> Result<Json<MoveModuleBytecode>> {
+        charge_item(ctx)?;
+        verify("name", verify_module_identifier(&name))?;
+        let name: IdentifierWrapper = parse("name", &name)?;
+        let api = StateApi {
+            context: api_context(ctx),
+        };
+        let (address, ledger_version) = (self.address, self.ledger_version);
+        resolve(move || api.module(&AcceptType::Json, address, name, ledger_version)).await
+    }
+
+    /// Committed sequence number based transactions sent by the account
+    async fn transactions(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        start: Option<U64Scalar>,
+        limit: Option<u16>,
+    ) -> Result<Json<Vec<Transaction>>> {
+        let context = api_context(ctx);
+        let max_page_size = context.max_transactions_page_size();
+        let limit = charge_page(ctx, limit, max_page_size)?;
+        let page = Page::new(start.map(|start| start.0), Some(limit), max_page_size);
+        let api = TransactionsApi { context };
+        let address = self.address;
+        resolve(move || api.list_ordered_txns_by_account(&AcceptType::Json, page, address)).await
+    }
+
+    /// Events emitted to the event stream of the account with the given creation number
+    async fn events(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        creation_number: U64Scalar,
+        start: Option<U64Scalar>,
+        limit: Option<u16>,
+    ) -> Result<Json<Vec<VersionedEvent>>> {
+        let context = api_context(ctx);
+        let max_page_size = context.max_events_page_size();
+        let limit = charge_page(ctx, limit, max_page_size)?;
+        let page = Page::new(start.map(|start| start.0), Some(limit), max_page_size);
+        let api = EventsApi { context };
+        let address = self.address;
+        resolve(move || {
+            let account = Account::new(api.context.clone(), address, None, None, None)?;
+            api.list(
+                account.latest_ledger_info,
+                AcceptType::Json,
+                page,
+                EventKey::new(creation_number.0, address.into()),
+            )
+        })
+        .await
+    }
+
+    /// Events emitted to the event handle stored in a field of a resource of the account
+    async fn events_by_handle(
+        &self,
+        ctx: &GraphQlContext<'_>,
+        event_handle: String,
+        field_name: String,
+        start: Option<U64Scalar>,
+        limit: Option<u16>,
+    ) -> Result<Json<Vec<VersionedEvent>>> {
+        let event_handle: MoveStructTag = parse("event_handle", &event_handle)?;
+        verify("event_handle", event_handle.verify(0))?;
+        verify("field_name", verify_field_identifier(&field_name))?;
+        let field_name: IdentifierWrapper = parse("field_name", &field_name)?;
+
+        let context = api_context(ctx);
+        let max_page_size = context.max_events_page_size();
+        let limit = charge_page(ctx, limit, max_page_size)?;
+        let page = Page::new(start.map(|start| start.0), Some(limit), max_page_size);
+        let api = EventsApi { context };
+        let address = self.address;
+        resolve(move || {
+            let account = Account::new(api.context.clone(), address, None, None, None)?;
+            let key = account.find_event_key(event_handle, field_name.into())?;
+            api.list(account.latest_ledger_info, AcceptType::Json, page, key)
+        })
+        .await
+    }
+}
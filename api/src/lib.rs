@@ -14,6 +14,7 @@ pub mod context;
 mod error_converter;
 mod events;
 mod failpoint;
+mod graphql;
 mod index;
 mod log;
 pub mod metrics;
@@ -27,6 +28,7 @@ mod state;
 pub mod tests;
 mod transactions;
 mod view_function;
+mod websocket;
 
 /// API categories for the OpenAPI spec
 #[derive(Tags)]
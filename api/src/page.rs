@@ -6,7 +6,7 @@ use crate::response::BadRequestError;
 use accudo_api_types::{AccudoErrorCode, LedgerInfo};
 use serde::Deserialize;
 
-const DEFAULT_PAGE_SIZE: u16 = 25;
+pub const DEFAULT_PAGE_SIZE: u16 = 25;
 
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) struct Page {
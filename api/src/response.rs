@@ -544,6 +544,7 @@ generate_error_traits!(
     NotFound,
     Forbidden,
     PayloadTooLarge,
+    TooManyRequests,
     Internal,
     InsufficientStorage,
     ServiceUnavailable
@@ -10,6 +10,7 @@ use crate::{
     context::Context,
     error_converter::{convert_error, panic_handler},
     events::EventsApi,
+    graphql::{graphql_handler, GraphQlApi},
     index::IndexApi,
     log::middleware_log,
     set_failpoints,
@@ -17,12 +18,15 @@ use crate::{
     state::StateApi,
     transactions::TransactionsApi,
     view_function::ViewFunctionApi,
+    websocket::{subscribe_handler, CommitNotifier},
 };
 use accudo_config::config::{ApiConfig, NodeConfig};
 use accudo_logger::info;
 use accudo_mempool::MempoolClientSender;
 use accudo_storage_interface::DbReader;
-use accudo_types::{chain_id::ChainId, indexer::indexer_db_reader::IndexerReader};
+use accudo_types::{
+    chain_id::ChainId, indexer::indexer_db_reader::IndexerReader, transaction::Version,
+};
 use anyhow::{anyhow, Context as AnyhowContext};
 use futures::channel::oneshot;
 use poem::{
@@ -34,8 +38,11 @@ use poem::{
     EndpointExt, Route, Server,
 };
 use poem_openapi::{ContactObject, LicenseObject, OpenApiService};
-use std::{net::SocketAddr, sync::Arc};
-use tokio::runtime::{Handle, Runtime};
+use std::{net::SocketAddr, sync::Arc, time::Instant};
+use tokio::{
+    runtime::{Handle, Runtime},
+    sync::watch::Receiver as WatchReceiver,
+};
 
 const VERSION: &str = include_str!("../doc/.version");
 
@@ -46,12 +53,20 @@ pub fn bootstrap(
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     indexer_reader: Option<Arc<dyn IndexerReader>>,
+    version_update_receiver: Option<WatchReceiver<(Instant, Version)>>,
     port_tx: Option<oneshot::Sender<u16>>,
 ) -> anyhow::Result<Runtime> {
     let max_runtime_workers = get_max_runtime_workers(&config.api);
     let runtime = accudo_runtimes::spawn_named_runtime("api".into(), Some(max_runtime_workers));
 
-    let context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader);
+    let mut context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader);
+    if config.api.websocket_enabled {
+        let version_update_receiver = version_update_receiver
+            .ok_or_else(|| anyhow!("The websocket API requires storage commit notifications"))?;
+        let commit_notifier =
+            CommitNotifier::spawn(runtime.handle(), context.clone(), version_update_receiver);
+        context.set_commit_notifier(commit_notifier);
+    }
 
     attach_poem_to_runtime(runtime.handle(), context.clone(), config, false, port_tx)
         .context("Failed to attach poem to runtime")?;
@@ -236,21 +251,31 @@ pub fn attach_poem_to_runtime(
             .allow_methods(vec![Method::GET, Method::POST]);
 
         // Build routes for the API
+        let mut v1_route = Route::new()
+            .nest("/", api_service)
+            .at("/spec.json", poem::get(spec_json))
+            .at("/spec.yaml", poem::get(spec_yaml))
+            // TODO: We add this manually outside of the OpenAPI spec for now.
+            // https://github.com/poem-web/poem/issues/364
+            .at(
+                "/set_failpoint",
+                poem::get(set_failpoints::set_failpoint_poem).data(context.clone()),
+            );
+        if config.api.graphql_enabled {
+            v1_route = v1_route.at(
+                "/graphql",
+                poem::post(graphql_handler).data(GraphQlApi::new(context.clone())),
+            );
+        }
+        if context.commit_notifier().is_some() {
+            v1_route = v1_route.at(
+                "/subscribe",
+                poem::get(subscribe_handler).data(context.clone()),
+            );
+        }
         let route = Route::new()
             .at("/", poem::get(root_handler))
-            .nest(
-                "/v1",
-                Route::new()
-                    .nest("/", api_service)
-                    .at("/spec.json", poem::get(spec_json))
-                    .at("/spec.yaml", poem::get(spec_yaml))
-                    // TODO: We add this manually outside of the OpenAPI spec for now.
-                    // https://github.com/poem-web/poem/issues/364
-                    .at(
-                        "/set_failpoint",
-                        poem::get(set_failpoints::set_failpoint_poem).data(context.clone()),
-                    ),
-            )
+            .nest("/v1", v1_route)
             .with(cors)
             .with_if(config.api.compression_enabled, Compression::new())
             .with(PostSizeLimit::new(size_limit))
@@ -365,6 +390,7 @@ mod tests {
             context.mempool.ac_client.clone(),
             None,
             None,
+            None,
         );
         assert!(ret.is_ok());
 
@@ -5,6 +5,7 @@ use crate::{
     accept_type::AcceptType,
     context::api_spawn_blocking,
     failpoint::fail_point_poem,
+    page::{determine_limit, DEFAULT_PAGE_SIZE},
     response::{
         api_forbidden, build_not_found, module_not_found, resource_not_found, table_item_not_found,
         BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
@@ -15,7 +16,8 @@ use crate::{
 use accudo_api_types::{
     verify_module_identifier, AccudoErrorCode, Address, AsConverter, IdentifierWrapper,
     MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue, RawStateValueRequest,
-    RawTableItemRequest, TableItemRequest, VerifyInput, VerifyInputWithRecursion, U64,
+    RawTableItemRequest, ResourceChange, TableItemRequest, VerifyInput, VerifyInputWithRecursion,
+    U64,
 };
 use accudo_types::state_store::{state_key::StateKey, table::TableHandle, TStateView};
 use anyhow::Context as AnyhowContext;
@@ -83,6 +85,72 @@ impl StateApi {
         .await
     }
 
+    /// Get account resource history
+    ///
+    /// Retrieves the changes of an individual resource from a given account by the transactions in
+    /// a range of versions, from the write sets of the transactions. If the end of the range is not
+    /// specified in the request, the latest ledger version is used.
+    ///
+    /// The first write of the resource in the range is always returned, followed by the writes
+    /// which changed it. To continue after the last change returned, request again with a
+    /// `start_version` following it.
+    ///
+    /// The Accudo nodes prune transaction history, via a configurable time window.
+    /// If the start of the range has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/accounts/:address/resource/:resource_type/history",
+        method = "get",
+        operation_id = "get_account_resource_history",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_resource_history(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+        /// Name of struct to retrieve e.g. `0x1::account::Account`
+        resource_type: Path<MoveStructTag>,
+        /// First ledger version of the range
+        start_version: Query<U64>,
+        /// Last ledger version of the range
+        ///
+        /// If not provided, it will be the latest version
+        end_version: Query<Option<U64>>,
+        /// Max number of changes to retrieve
+        ///
+        /// If not provided, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<ResourceChange>> {
+        resource_type
+            .0
+            .verify(0)
+            .context("'resource_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AccudoErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_account_resource_history")?;
+        self.context
+            .check_api_output_enabled("Get account resource history", &accept_type)?;
+        if accept_type == AcceptType::Bcs {
+            return Err(api_forbidden(
+                "Get account resource history with BCS output",
+                "Please use JSON output instead.",
+            ));
+        }
+
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.resource_history(
+                address.0,
+                resource_type.0,
+                start_version.0 .0,
+                end_version.0.map(|inner| inner.0),
+                limit.0,
+            )
+        })
+        .await
+    }
+
     /// Get account module
     ///
     /// Retrieves an individual module from a given account and at a specific ledger version. If the
@@ -271,7 +339,7 @@ impl StateApi {
     ///
     /// JSON: Convert to MoveResource
     /// BCS: Leave it encoded as the resource
-    fn resource(
+    pub fn resource(
         &self,
         accept_type: &AcceptType,
         address: Address,
@@ -327,6 +395,83 @@ impl StateApi {
         }
     }
 
+    /// Read the changes of a resource by the transactions in a range of versions
+    pub fn resource_history(
+        &self,
+        address: Address,
+        resource_type: MoveStructTag,
+        start_version: u64,
+        end_version: Option<u64>,
+        limit: Option<u16>,
+    ) -> BasicResultWith404<Vec<ResourceChange>> {
+        let tag: StructTag = (&resource_type)
+            .try_into()
+            .context("Failed to parse given resource type")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AccudoErrorCode::InvalidInput)
+            })?;
+
+        let (ledger_info, end_version) = self
+            .context
+            .verify_state_history_range(start_version, end_version)?;
+        let limit = determine_limit(
+            limit,
+            DEFAULT_PAGE_SIZE,
+            self.context.max_transactions_page_size(),
+            &ledger_info,
+        )?;
+        let internal_error = |err| {
+            BasicErrorWith404::internal_with_code(err, AccudoErrorCode::InternalError, &ledger_info)
+        };
+
+        let state_view = self
+            .context
+            .state_view_at_version(end_version)
+            .map_err(internal_error)?;
+        let converter =
+            state_view.as_converter(self.context.db.clone(), self.context.indexer_reader.clone());
+        let (state_key, in_group) = converter
+            .resource_state_key(address, &tag)
+            .map_err(internal_error)?;
+        let writes = self
+            .context
+            .get_state_key_writes(&[state_key], start_version, end_version)
+            .context("Failed to read write sets from DB")
+            .map_err(internal_error)?;
+
+        let mut changes = vec![];
+        let mut previous_bytes = None;
+        for (version, ops) in writes {
+            let bytes = match ops[0].1.bytes() {
+                Some(bytes) => converter
+                    .resource_from_state_value(&tag, in_group, bytes.clone())
+                    .context("Failed to deserialize resource group retrieved from DB")
+                    .map_err(internal_error)?,
+                None => None,
+            };
+            // Writes to a resource group may leave this resource unchanged
+            if previous_bytes.as_ref() == Some(&bytes) {
+                continue;
+            }
+            let resource = bytes
+                .as_ref()
+                .map(|bytes| converter.try_into_resource(&tag, bytes))
+                .transpose()
+                .context("Failed to deserialize resource data retrieved from DB")
+                .map_err(internal_error)?;
+            changes.push(ResourceChange {
+                version: version.into(),
+                resource,
+            });
+            if changes.len() == limit as usize {
+                break;
+            }
+            previous_bytes = Some(bytes);
+        }
+
+        BasicResponse::try_from_json((changes, &ledger_info, BasicResponseStatus::Ok))
+    }
+
     /// Retrieve the module
     ///
     /// JSON: Parse ABI and bytecode
@@ -376,6 +376,56 @@ async fn test_get_account_balance(
     assert_eq!(concurrent_fa_balance, fa_balance);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_balance_history() {
+    let mut context = new_test_context(current_function_name!());
+    let start_version = context.get_latest_ledger_info().version() + 1;
+    let sender = &mut context.gen_account();
+    let receiver = context.gen_account();
+    let txn = context.mint_user_account(sender).await;
+    context.commit_block(&vec![txn]).await;
+    let txn1 = context.account_transfer(sender, &receiver, 100_000);
+    let txn2 = context.account_transfer(sender, &receiver, 200_000);
+    context.commit_block(&vec![txn1, txn2]).await;
+
+    let resp = context
+        .get(&account_balance_history(
+            &receiver.address().to_hex_literal(),
+            ACCUDO_COIN_TYPE_STR,
+            start_version,
+        ))
+        .await;
+    let balances: Vec<_> = resp
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| change["balance"].clone())
+        .collect();
+    assert_eq!(balances, vec!["100000", "300000"]);
+
+    // The balance of the paired fungible asset is the same
+    let fa_resp = context
+        .get(&account_balance_history(
+            &receiver.address().to_hex_literal(),
+            &AccountAddress::TEN.to_hex_literal(),
+            start_version,
+        ))
+        .await;
+    assert_eq!(fa_resp, resp);
+
+    // Each change matches the balance at its version
+    for change in resp.as_array().unwrap() {
+        let balance = context
+            .get(&format!(
+                "{}?ledger_version={}",
+                account_balance(&receiver.address().to_hex_literal(), ACCUDO_COIN_TYPE_STR),
+                change["version"].as_str().unwrap()
+            ))
+            .await;
+        assert_eq!(balance.to_string(), change["balance"].as_str().unwrap());
+    }
+}
+
 async fn test_get_account_modules_by_ledger_version_with_context(mut context: TestContext) {
     let initial_ledger_version = u64::from(context.get_latest_ledger_info().ledger_version);
     let payload =
@@ -730,6 +780,14 @@ fn account_balance(address: &str, coin_type: &str) -> String {
     format!("/accounts/{}/balance/{}", address, coin_type)
 }
 
+fn account_balance_history(address: &str, coin_type: &str, start_version: u64) -> String {
+    format!(
+        "{}/history?start_version={}",
+        account_balance(address, coin_type),
+        start_version
+    )
+}
+
 fn account_modules_with_ledger_version(address: &str, ledger_version: u64) -> String {
     format!(
         "{}?ledger_version={}",
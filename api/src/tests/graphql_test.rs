@@ -0,0 +1,103 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context_with_config;
+use accudo_api_test_context::{current_function_name, TestContext};
+use accudo_config::config::NodeConfig;
+use serde_json::{json, Value};
+
+fn new_graphql_test_context(test_name: String, max_query_cost: Option<u64>) -> TestContext {
+    let mut node_config = NodeConfig::default();
+    node_config.api.graphql_enabled = true;
+    node_config.api.graphql_max_query_cost = max_query_cost;
+    new_test_context_with_config(test_name, node_config, false, false)
+}
+
+async fn graphql(context: &TestContext, query: &str) -> Value {
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("POST")
+                .path("/v1/graphql")
+                .json(&json!({ "query": query })),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    serde_json::from_slice(resp.body()).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_graphql_resolves_like_rest() {
+    let context = new_graphql_test_context(current_function_name!(), None);
+    let account = context.get("/accounts/0x1").await;
+    let resource = context
+        .get("/accounts/0x1/resource/0x1::account::Account")
+        .await;
+    let module = context.get("/accounts/0x1/module/coin").await;
+    let transaction = context.get("/transactions/by_version/0").await;
+
+    let resp = graphql(
+        &context,
+        r#"{
+            account(address: "0x1") {
+                address
+                data
+                resource(resourceType: "0x1::account::Account")
+                module(name: "coin")
+            }
+            transactionByVersion(version: "0")
+        }"#,
+    )
+    .await;
+    assert!(resp.get("errors").is_none(), "{}", resp);
+    let data = &resp["data"];
+    assert_eq!(data["account"]["address"], "0x1");
+    assert_eq!(
+        data["account"]["data"]["sequence_number"],
+        account["sequence_number"]
+    );
+    assert_eq!(data["account"]["resource"]["data"], resource["data"]);
+    assert_eq!(data["account"]["module"]["abi"], module["abi"]);
+    assert_eq!(data["transactionByVersion"]["hash"], transaction["hash"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_graphql_errors() {
+    let context = new_graphql_test_context(current_function_name!(), None);
+    context.get("/").await;
+
+    let resp = graphql(
+        &context,
+        r#"{ account(address: "0x1") { resource(resourceType: "0x1::missing::Missing") } }"#,
+    )
+    .await;
+    assert_eq!(resp["data"], Value::Null);
+    assert_eq!(
+        resp["errors"][0]["extensions"]["code"],
+        "resource_not_found"
+    );
+
+    let resp = graphql(&context, r#"{ account(address: "invalid") { address } }"#).await;
+    assert_eq!(resp["errors"][0]["extensions"]["code"], "invalid_input");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_graphql_query_cost_limit() {
+    let context = new_graphql_test_context(current_function_name!(), Some(10));
+    context.get("/").await;
+
+    let resp = graphql(&context, "{ transactions(limit: 10) }").await;
+    assert!(resp.get("errors").is_none(), "{}", resp);
+
+    // The pages of a query add up against its cost budget
+    let resp = graphql(
+        &context,
+        "{ transactions(limit: 5) account(address: \"0x1\") { resources(limit: 6) } }",
+    )
+    .await;
+    assert_eq!(resp["errors"][0]["extensions"]["code"], "invalid_input");
+
+    // Pages default to the default page size
+    let resp = graphql(&context, "{ transactions }").await;
+    assert_eq!(resp["errors"][0]["extensions"]["code"], "invalid_input");
+}
@@ -9,6 +9,7 @@ mod converter_test;
 mod event_v2_translation_test;
 mod events_test;
 mod function_value_test;
+mod graphql_test;
 mod index_test;
 mod invalid_post_request_test;
 mod modules;
@@ -25,6 +26,7 @@ mod transaction_vector_test;
 mod transactions_test;
 mod view_function;
 mod webauthn_secp256r1_ecdsa;
+mod websocket_test;
 
 use accudo_api_test_context::{new_test_context_inner as super_new_test_context, TestContext};
 use accudo_config::config::{internal_indexer_db_config::InternalIndexerDBConfig, NodeConfig};
@@ -95,6 +95,80 @@ async fn test_get_account_resource_with_version_too_large() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resource_history() {
+    let mut context = new_test_context(current_function_name!());
+    let start_version = context.get_latest_ledger_info().version() + 1;
+    let account = &mut context.gen_account();
+    let other_account = context.gen_account();
+    let txn1 = context.create_user_account(account).await;
+    let txn2 = context.mint_user_account(account).await;
+    context.commit_block(&vec![txn1, txn2]).await;
+    let txn3 = context.account_transfer(account, &other_account, 100);
+    let txn4 = context.account_transfer(account, &other_account, 100);
+    context.commit_block(&vec![txn3, txn4]).await;
+
+    let path = get_account_resource_history(
+        &account.address().to_hex_literal(),
+        "0x1::account::Account",
+        start_version,
+    );
+    let resp = context.get(&path).await;
+    let sequence_numbers: Vec<_> = resp
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| change["resource"]["data"]["sequence_number"].clone())
+        .collect();
+    assert_eq!(sequence_numbers, vec!["0", "1", "2"]);
+
+    // The changes are paginated by version
+    let resp = context.get(&format!("{}&limit=2", path)).await;
+    assert_eq!(resp.as_array().unwrap().len(), 2);
+    let next_start_version = resp[1]["version"].as_str().unwrap().parse::<u64>().unwrap() + 1;
+    let resp = context
+        .get(&get_account_resource_history(
+            &account.address().to_hex_literal(),
+            "0x1::account::Account",
+            next_start_version,
+        ))
+        .await;
+    assert_eq!(resp.as_array().unwrap().len(), 1);
+    assert_eq!(resp[0]["resource"]["data"]["sequence_number"], "2");
+
+    // Nothing changed after the last transaction
+    let resp = context
+        .get(&get_account_resource_history(
+            &account.address().to_hex_literal(),
+            "0x1::account::Account",
+            context.get_latest_ledger_info().version(),
+        ))
+        .await;
+    assert_eq!(resp, json!([]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resource_history_invalid_range() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    context
+        .expect_status_code(400)
+        .get(&format!(
+            "{}&end_version={}",
+            get_account_resource_history("0xA550C18", "0x1::account::Account", ledger_version),
+            ledger_version - 1
+        ))
+        .await;
+    context
+        .expect_status_code(404)
+        .get(&format!(
+            "{}&end_version={}",
+            get_account_resource_history("0xA550C18", "0x1::account::Account", 0),
+            ledger_version + 1
+        ))
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_account_module() {
     let mut context = new_test_context(current_function_name!());
@@ -347,6 +421,13 @@ fn get_account_resource_with_version(address: &str, struct_tag: &str, version: u
     )
 }
 
+fn get_account_resource_history(address: &str, struct_tag: &str, start_version: u64) -> String {
+    format!(
+        "/accounts/{}/resource/{}/history?start_version={}",
+        address, struct_tag, start_version
+    )
+}
+
 fn get_account_module(address: &str, name: &str) -> String {
     format!("/accounts/{}/module/{}", address, name)
 }
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::new_test_context;
-use crate::tests::{new_test_context_with_config, new_test_context_with_orderless_flags};
+use crate::{
+    tests::{new_test_context_with_config, new_test_context_with_orderless_flags},
+    transactions::client_ip,
+};
 use accudo_api_test_context::{assert_json, current_function_name, pretty, TestContext};
 use accudo_config::config::{GasEstimationStaticOverride, NodeConfig, TransactionFilterConfig};
 use accudo_crypto::{
@@ -31,6 +34,11 @@ use move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, TypeTag},
 };
+use poem::{
+    http::{HeaderMap, HeaderValue},
+    web::RemoteAddr,
+    Addr,
+};
 use poem_openapi::types::ParseFromJSON;
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use rstest::rstest;
@@ -853,6 +861,34 @@ async fn test_get_pending_transaction_by_hash(
     context.check_golden_output(not_found);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_mempool_transaction_status() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    let body = bcs::to_bytes(&txn).unwrap();
+    let pending_txn = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", body)
+        .await;
+    let txn_hash = pending_txn["hash"].as_str().unwrap();
+
+    let status = context
+        .get(&format!("/transactions/mempool_status/{}", txn_hash))
+        .await;
+    assert_eq!(status["hash"], txn_hash);
+    assert_eq!(status["sender"], pending_txn["sender"]);
+    assert_eq!(status["gas_unit_price"], pending_txn["gas_unit_price"]);
+    assert_eq!(status["state"], "ready");
+    assert_eq!(status["rank"], "0");
+    assert!(status["broadcast_peers"].as_array().unwrap().is_empty());
+
+    context
+        .expect_status_code(404)
+        .get("/transactions/mempool_status/0xdadfeddcca7cb6396c735e9094c76c6e4e9cb3e3ef814730693aed59bd87b31d")
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 #[rstest(
     use_txn_payload_v2_format,
@@ -925,6 +961,39 @@ async fn test_wait_transaction_by_invalid_hash() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_mempool_transactions_disabled() {
+    // The stream is disabled by default
+    let mut context = new_test_context_with_config(
+        current_function_name!(),
+        NodeConfig::default(),
+        false,
+        false,
+    );
+
+    let resp = context
+        .expect_status_code(403)
+        .get("/transactions/stream")
+        .await;
+    assert_eq!(resp["error_code"], "api_disabled");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_mempool_transactions_too_many_connections() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.pending_transaction_stream_enabled = true;
+    node_config
+        .api
+        .pending_transaction_stream_max_active_connections = 0;
+    let mut context =
+        new_test_context_with_config(current_function_name!(), node_config, false, false);
+
+    context
+        .expect_status_code(503)
+        .get("/transactions/stream?sender=0x1&entry_function=0x1::coin::transfer")
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 #[rstest(
     use_txn_payload_v2_format,
@@ -2876,3 +2945,39 @@ async fn test_get_transactions_auxiliary_info_empty_range() {
 
     context.check_golden_output(resp);
 }
+
+#[test]
+fn test_client_ip() {
+    let remote_addr = RemoteAddr(Addr::SocketAddr("10.0.0.1:8080".parse().unwrap()));
+    let mut headers = HeaderMap::new();
+
+    // Without the trusted header, the peer is the client
+    assert_eq!(
+        client_ip(None, &headers, &remote_addr),
+        Some("10.0.0.1".parse().unwrap())
+    );
+    assert_eq!(
+        client_ip(Some("x-forwarded-for"), &headers, &remote_addr),
+        Some("10.0.0.1".parse().unwrap())
+    );
+
+    // Only the address appended by the proxy is trusted
+    headers.insert(
+        "x-forwarded-for",
+        HeaderValue::from_static("1.1.1.1, 2.2.2.2"),
+    );
+    assert_eq!(
+        client_ip(Some("x-forwarded-for"), &headers, &remote_addr),
+        Some("2.2.2.2".parse().unwrap())
+    );
+    assert_eq!(
+        client_ip(None, &headers, &remote_addr),
+        Some("10.0.0.1".parse().unwrap())
+    );
+
+    headers.insert("x-forwarded-for", HeaderValue::from_static("unknown"));
+    assert_eq!(
+        client_ip(Some("x-forwarded-for"), &headers, &remote_addr),
+        None
+    );
+}
@@ -650,3 +650,113 @@ async fn test_view_option_struct(
         .await;
     context.check_golden_output_no_prune(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch() {
+    let mut context = new_test_context(current_function_name!());
+    let creator = &mut context.gen_account();
+    let owner = &mut context.gen_account();
+    let txn1 = context.mint_user_account(creator).await;
+    let txn2 = context.account_transfer(creator, owner, 100_000);
+    context.commit_block(&vec![txn1]).await;
+    let funded_version = u64::from(context.get_latest_ledger_info().ledger_version);
+    context.commit_block(&vec![txn2]).await;
+    let latest_version = u64::from(context.get_latest_ledger_info().ledger_version);
+
+    let mut pinned_balance_request = build_coin_balance_request(&creator.address());
+    pinned_balance_request["ledger_version"] = json!(funded_version.to_string());
+    let resp = context
+        .post(
+            "/view/batch",
+            json!([
+                build_coin_balance_request(&owner.address()),
+                build_coin_decimals_request(),
+                pinned_balance_request,
+                build_coin_balance_request(&creator.address()),
+                {
+                    "function": "0x1::coin::missing",
+                    "arguments": [],
+                    "type_arguments": [],
+                },
+            ]),
+        )
+        .await;
+
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[0]["values"], json!(["100000"]));
+    assert_eq!(results[0]["ledger_version"], latest_version.to_string());
+    assert_eq!(results[1]["values"], json!([8]));
+    // The pinned request sees the balance from before the transfer
+    assert_eq!(results[2]["ledger_version"], funded_version.to_string());
+    let funded_balance: u64 = results[2]["values"][0].as_str().unwrap().parse().unwrap();
+    let balance: u64 = results[3]["values"][0].as_str().unwrap().parse().unwrap();
+    assert!(funded_balance > balance + 100_000);
+    // A failed function doesn't fail the rest of the batch
+    assert_eq!(results[4]["values"], Value::Null);
+    assert!(results[4]["error"]["message"].is_string());
+    assert_eq!(results[4]["gas_used"], "0");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_limits() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_view_function_batch_size = 1;
+    node_config.api.max_gas_view_function_batch = 0;
+    let context = new_test_context_with_config(current_function_name!(), node_config, false, false);
+
+    // The batch size is limited
+    context
+        .expect_status_code(400)
+        .post(
+            "/view/batch",
+            json!([build_coin_decimals_request(), build_coin_decimals_request()]),
+        )
+        .await;
+
+    // Functions beyond the gas limit of the batch fail
+    let resp = context
+        .post("/view/batch", json!([build_coin_decimals_request()]))
+        .await;
+    assert_eq!(resp[0]["values"], Value::Null);
+    assert!(resp[0]["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("used up its gas limit"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_gas_limit() {
+    // Measure the gas used by a single function
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .post("/view/batch", json!([build_coin_decimals_request()]))
+        .await;
+    let gas_used: u64 = resp[0]["gas_used"].as_str().unwrap().parse().unwrap();
+    assert!(gas_used > 0);
+
+    // The batch holds more functions than its gas limit can cover, although each function only
+    // uses a fraction of its own gas limit
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_view_function_batch_size = 20;
+    node_config.api.max_gas_view_function = 2 * gas_used;
+    node_config.api.max_gas_view_function_batch = 5 * gas_used;
+    let context = new_test_context_with_config(current_function_name!(), node_config, false, false);
+
+    let requests: Vec<_> = (0..20).map(|_| build_coin_decimals_request()).collect();
+    let resp = context.post("/view/batch", json!(requests)).await;
+
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 20);
+    let succeeded: Vec<_> = results
+        .iter()
+        .filter(|result| result["values"] == json!([8]))
+        .collect();
+    assert!(succeeded.len() >= 2);
+    assert!(succeeded.len() < results.len());
+    let total_gas_used: u64 = results
+        .iter()
+        .map(|result| result["gas_used"].as_str().unwrap().parse::<u64>().unwrap())
+        .sum();
+    assert!(total_gas_used <= 5 * gas_used);
+}
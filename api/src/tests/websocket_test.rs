@@ -0,0 +1,172 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{new_test_context, new_test_context_with_config};
+use crate::websocket::{
+    ClientMessage, CommitNotifier, ServerMessage, Subscription, SubscriptionFilter,
+};
+use accudo_api_test_context::current_function_name;
+use accudo_api_types::{AsConverter, MoveType, WriteSetChange};
+use accudo_config::config::NodeConfig;
+use accudo_types::account_config::{new_block_event_key, NewBlockEvent};
+use move_core_types::{language_storage::TypeTag, move_resource::MoveStructType};
+use serde_json::json;
+use std::{sync::Arc, time::Instant};
+use tokio::{runtime::Handle, sync::watch};
+
+#[test]
+fn test_client_message_format() {
+    let message: ClientMessage = serde_json::from_value(json!({
+        "type": "subscribe",
+        "id": "deposits",
+        "subscription": {
+            "type": "write_set_changes",
+            "address": "0x1",
+            "resource_type": "0x1::account::Account",
+        },
+    }))
+    .unwrap();
+    assert_eq!(message, ClientMessage::Subscribe {
+        id: "deposits".to_string(),
+        subscription: Subscription::WriteSetChanges {
+            address: "0x1".parse().unwrap(),
+            resource_type: Some("0x1::account::Account".parse().unwrap()),
+        },
+    });
+
+    let message: ClientMessage =
+        serde_json::from_value(json!({ "type": "unsubscribe", "id": "deposits" })).unwrap();
+    assert_eq!(message, ClientMessage::Unsubscribe {
+        id: "deposits".to_string()
+    });
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_commit_notifier_matches_subscriptions() {
+    let mut context = new_test_context(current_function_name!());
+    let (version_update_sender, version_update_receiver) = watch::channel((Instant::now(), 0));
+    let commit_notifier = CommitNotifier::spawn(
+        &Handle::current(),
+        context.context.clone(),
+        version_update_receiver,
+    );
+    let mut updates = commit_notifier.subscribe();
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    let ledger_info = context.get_latest_ledger_info();
+    version_update_sender
+        .send((Instant::now(), ledger_info.version()))
+        .unwrap();
+
+    let update = updates.recv().await.unwrap();
+    assert_eq!(update.ledger_info.version(), ledger_info.version());
+    assert_eq!(update.blocks.len(), 1);
+    assert_eq!(update.blocks[0].block_height, ledger_info.block_height);
+    assert_eq!(
+        update.blocks[0].last_version.0,
+        update.transactions.last().unwrap().version
+    );
+
+    let api_context = Arc::new(context.context.clone());
+    let resolve =
+        |subscription| SubscriptionFilter::resolve(api_context.clone(), subscription).unwrap();
+    let state_view = context.latest_state_view();
+    let converter = state_view.as_converter(
+        context.context.db.clone(),
+        context.context.indexer_reader.clone(),
+    );
+
+    let messages = resolve(Subscription::Blocks)
+        .matches("blocks", &update, &converter)
+        .unwrap();
+    assert!(matches!(&messages[..], [ServerMessage::Block { id, .. }] if id == "blocks"));
+
+    // The new block event is found by type, by event handle and by creation number
+    let new_block_event_type =
+        MoveType::from(&TypeTag::Struct(Box::new(NewBlockEvent::struct_tag())));
+    for subscription in [
+        Subscription::EventsByType {
+            event_type: new_block_event_type.clone(),
+        },
+        Subscription::EventsByHandle {
+            address: "0x1".parse().unwrap(),
+            event_handle: "0x1::block::BlockResource".parse().unwrap(),
+            field_name: "new_block_events".parse().unwrap(),
+        },
+        Subscription::EventsByCreationNumber {
+            address: "0x1".parse().unwrap(),
+            creation_number: new_block_event_key().get_creation_number().into(),
+        },
+    ] {
+        let messages = resolve(subscription)
+            .matches("new_blocks", &update, &converter)
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        match &messages[0] {
+            ServerMessage::Event { event, .. } => assert_eq!(event.typ, new_block_event_type),
+            message => panic!("Unexpected message: {:?}", message),
+        }
+    }
+
+    let messages = resolve(Subscription::WriteSetChanges {
+        address: account.address().into(),
+        resource_type: Some("0x1::account::Account".parse().unwrap()),
+    })
+    .matches("account", &update, &converter)
+    .unwrap();
+    assert_eq!(messages.len(), 1);
+    match &messages[0] {
+        ServerMessage::WriteSetChange {
+            change: WriteSetChange::WriteResource(write),
+            ..
+        } => assert_eq!(write.address, account.address().into()),
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    // Subscriptions to other accounts match nothing
+    let other_account = context.gen_account();
+    let messages = resolve(Subscription::WriteSetChanges {
+        address: other_account.address().into(),
+        resource_type: None,
+    })
+    .matches("other_account", &update, &converter)
+    .unwrap();
+    assert!(messages.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_commit_notifier_skips_versions_too_far_behind() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.websocket_max_catch_up_versions = 1;
+    let mut context =
+        new_test_context_with_config(current_function_name!(), node_config, false, false);
+    let (version_update_sender, version_update_receiver) = watch::channel((Instant::now(), 0));
+    let commit_notifier = CommitNotifier::spawn(
+        &Handle::current(),
+        context.context.clone(),
+        version_update_receiver,
+    );
+    let mut updates = commit_notifier.subscribe();
+
+    let first_version = context.get_latest_ledger_info().version() + 1;
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    let ledger_info = context.get_latest_ledger_info();
+    version_update_sender
+        .send((Instant::now(), ledger_info.version()))
+        .unwrap();
+
+    // Only the last version is read, along with the block containing it
+    let update = updates.recv().await.unwrap();
+    assert_eq!(
+        update.skipped_versions,
+        Some((first_version, ledger_info.version() - 1))
+    );
+    assert_eq!(update.transactions.len(), 1);
+    assert_eq!(update.transactions[0].version, ledger_info.version());
+    assert_eq!(update.blocks.len(), 1);
+    assert_eq!(update.blocks[0].block_height, ledger_info.block_height);
+}
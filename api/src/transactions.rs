@@ -15,7 +15,8 @@ use crate::{
         api_disabled, api_forbidden, transaction_not_found_by_hash,
         transaction_not_found_by_version, version_pruned, BadRequestError, BasicError,
         BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResult, BasicResultWith404,
-        ForbiddenError, InsufficientStorageError, InternalError,
+        ForbiddenError, InsufficientStorageError, InternalError, ServiceUnavailableError,
+        TooManyRequestsError,
     },
     view_function::convert_view_function_error,
     ApiTags,
@@ -23,10 +24,12 @@ use crate::{
 use accudo_api_types::{
     transaction::{PersistedAuxiliaryInfo, TransactionSummary},
     verify_function_identifier, verify_module_identifier, AccudoError, AccudoErrorCode, Address,
-    AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
-    Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
-    TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion, U64,
+    AsConverter, EncodeSubmissionRequest, EntryFunctionId, GasEstimation, GasEstimationBcs,
+    HashValue, HexEncodedBytes, LedgerInfo, MempoolTransactionEvent, MempoolTransactionEventType,
+    MempoolTransactionState, MempoolTransactionStatus, MoveType, PendingTransaction,
+    SubmitTransactionRequest, Transaction, TransactionData, TransactionOnChainData,
+    TransactionsBatchSingleSubmissionFailure, TransactionsBatchSubmissionResult, UserTransaction,
+    VerifyInput, VerifyInputWithRecursion, U64,
 };
 use accudo_crypto::{hash::CryptoHash, signing_message};
 use accudo_logger::error;
@@ -43,13 +46,24 @@ use accudo_types::{
 };
 use accudo_vm::{AccudoSimulationVM, AccudoVM};
 use anyhow::Context as AnyhowContext;
+use futures::stream::{self, BoxStream, StreamExt};
 use move_core_types::{ident_str, language_storage::ModuleId, vm_status::VMStatus};
+use poem::{http::HeaderMap, web::RemoteAddr};
 use poem_openapi::{
     param::{Path, Query},
-    payload::Json,
+    payload::{EventStream, Json},
     ApiRequest, OpenApi,
 };
-use std::{cmp::min, sync::Arc, time::Duration};
+use std::{
+    cmp::min,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::broadcast::error::RecvError;
 
 generate_success_response!(SubmitTransactionResponse, (202, Accepted));
 
@@ -59,6 +73,7 @@ generate_error_response!(
     (403, Forbidden),
     (404, NotFound),
     (413, PayloadTooLarge),
+    (429, TooManyRequests),
     (500, Internal),
     (503, ServiceUnavailable),
     (507, InsufficientStorage)
@@ -303,6 +318,115 @@ impl TransactionsApi {
         result
     }
 
+    /// Get mempool status of transaction by hash
+    ///
+    /// Look up a pending transaction by its hash in the mempool of this node, and return where
+    /// it is in its lifecycle: whether it is parked or ready for consensus, its rank by gas
+    /// price among ready transactions, the peers it has been broadcast to, and how often it was
+    /// included in a batch.
+    ///
+    /// A 404 is returned if the transaction is not in the mempool of this node, e.g. because
+    /// it has already been committed or was never submitted to this node.
+    #[oai(
+        path = "/transactions/mempool_status/:txn_hash",
+        method = "get",
+        operation_id = "get_mempool_transaction_status",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn get_mempool_transaction_status(
+        &self,
+        accept_type: AcceptType,
+        /// Hash of transaction to retrieve
+        txn_hash: Path<HashValue>,
+    ) -> BasicResultWith404<MempoolTransactionStatus> {
+        fail_point_poem("endpoint_get_mempool_transaction_status")?;
+        self.context
+            .check_api_output_enabled("Get mempool transaction status", &accept_type)?;
+        self.get_mempool_transaction_status_inner(&accept_type, txn_hash.0)
+            .await
+    }
+
+    /// Stream mempool transactions
+    ///
+    /// Stream the events of transactions entering and leaving the mempool of this node as
+    /// server-sent events: insertions, commits, rejections by consensus, and other removals,
+    /// e.g. because the transaction expired or was replaced. Events can be filtered by sender
+    /// and by the entry function called.
+    ///
+    /// The stream is best effort: clients falling too far behind miss events.
+    #[oai(
+        path = "/transactions/stream",
+        method = "get",
+        operation_id = "stream_mempool_transactions",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn stream_mempool_transactions(
+        &self,
+        /// Only stream transactions sent by this account
+        sender: Query<Option<Address>>,
+        /// Only stream transactions calling this entry function
+        entry_function: Query<Option<EntryFunctionId>>,
+    ) -> Result<EventStream<BoxStream<'static, MempoolTransactionEvent>>, BasicError> {
+        fail_point_poem("endpoint_stream_mempool_transactions")?;
+        if !self
+            .context
+            .node_config
+            .api
+            .pending_transaction_stream_enabled
+        {
+            return Err(api_disabled("Stream mempool transactions"));
+        }
+        let connection = ActiveConnection::open(
+            self.context
+                .pending_transaction_stream_active_connections
+                .clone(),
+            self.context
+                .node_config
+                .api
+                .pending_transaction_stream_max_active_connections,
+        )
+        .ok_or_else(|| {
+            BasicError::service_unavailable_with_code_no_info(
+                "Too many open mempool transaction streams",
+                AccudoErrorCode::InternalError,
+            )
+        })?;
+        let receiver = self
+            .context
+            .subscribe_mempool_transaction_events()
+            .await
+            .context("Failed to subscribe to mempool transaction events")
+            .map_err(|err| {
+                BasicError::internal_with_code_no_info(err, AccudoErrorCode::InternalError)
+            })?;
+
+        let sender = sender.0.map(AccountAddress::from);
+        let entry_function = entry_function.0;
+        let events = stream::unfold((receiver, connection), move |(mut receiver, connection)| {
+            let entry_function = entry_function.clone();
+            async move {
+                loop {
+                    let event = match receiver.recv().await {
+                        Ok(event) => event,
+                        // Keep going with the oldest event still buffered
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    };
+                    if sender.is_some_and(|sender| sender != event.sender) {
+                        continue;
+                    }
+                    let event = mempool_transaction_event(event);
+                    if entry_function.is_some() && event.entry_function != entry_function {
+                        continue;
+                    }
+                    return Some((event, (receiver, connection)));
+                }
+            }
+        })
+        .boxed();
+        Ok(EventStream::new(events).keep_alive(Duration::from_secs(15)))
+    }
+
     /// Get transaction by version
     ///
     /// Retrieves a transaction by a given version. If the version has been
@@ -499,6 +623,8 @@ impl TransactionsApi {
     async fn submit_transaction(
         &self,
         accept_type: AcceptType,
+        remote_addr: &RemoteAddr,
+        headers: &HeaderMap,
         data: SubmitTransactionPost,
     ) -> SubmitTransactionResult<PendingTransaction> {
         data.verify()
@@ -517,8 +643,17 @@ impl TransactionsApi {
             .check_api_output_enabled("Submit transaction", &accept_type)?;
         let ledger_info = self.context.get_latest_ledger_info()?;
         let signed_transaction = self.get_signed_transaction(&ledger_info, data)?;
-        self.create(&accept_type, &ledger_info, signed_transaction)
-            .await
+        self.create(
+            &accept_type,
+            &ledger_info,
+            signed_transaction,
+            client_ip(
+                self.context.node_config.api.client_ip_header.as_deref(),
+                headers,
+                remote_addr,
+            ),
+        )
+        .await
     }
 
     /// Submit batch transactions
@@ -552,6 +687,8 @@ impl TransactionsApi {
     async fn submit_transactions_batch(
         &self,
         accept_type: AcceptType,
+        remote_addr: &RemoteAddr,
+        headers: &HeaderMap,
         data: SubmitTransactionsBatchPost,
     ) -> SubmitTransactionsBatchResult<TransactionsBatchSubmissionResult> {
         data.verify()
@@ -581,8 +718,17 @@ impl TransactionsApi {
                 &ledger_info,
             ));
         }
-        self.create_batch(&accept_type, &ledger_info, signed_transactions_batch)
-            .await
+        self.create_batch(
+            &accept_type,
+            &ledger_info,
+            signed_transactions_batch,
+            client_ip(
+                self.context.node_config.api.client_ip_header.as_deref(),
+                headers,
+                remote_addr,
+            ),
+        )
+        .await
     }
 
     /// Simulate transaction
@@ -872,7 +1018,11 @@ impl TransactionsApi {
 
 impl TransactionsApi {
     /// List all transactions paging by ledger version
-    fn list(&self, accept_type: &AcceptType, page: Page) -> BasicResultWith404<Vec<Transaction>> {
+    pub fn list(
+        &self,
+        accept_type: &AcceptType,
+        page: Page,
+    ) -> BasicResultWith404<Vec<Transaction>> {
         let latest_ledger_info = self.context.get_latest_ledger_info()?;
         let ledger_version = latest_ledger_info.version();
 
@@ -960,7 +1110,7 @@ impl TransactionsApi {
         }
     }
 
-    async fn get_transaction_by_hash_inner(
+    pub async fn get_transaction_by_hash_inner(
         &self,
         accept_type: &AcceptType,
         hash: HashValue,
@@ -998,7 +1148,7 @@ impl TransactionsApi {
         .await
     }
 
-    fn get_transaction_by_version_inner(
+    pub fn get_transaction_by_version_inner(
         &self,
         accept_type: &AcceptType,
         version: U64,
@@ -1099,6 +1249,59 @@ impl TransactionsApi {
         ))
     }
 
+    async fn get_mempool_transaction_status_inner(
+        &self,
+        accept_type: &AcceptType,
+        hash: HashValue,
+    ) -> BasicResultWith404<MempoolTransactionStatus> {
+        let context = self.context.clone();
+        let latest_ledger_info =
+            api_spawn_blocking(move || context.get_latest_ledger_info()).await?;
+        let status = self
+            .context
+            .get_pending_transaction_status(hash.into())
+            .await
+            .context(format!(
+                "Failed to get mempool status of transaction {}",
+                hash
+            ))
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AccudoErrorCode::InternalError,
+                    &latest_ledger_info,
+                )
+            })?
+            .ok_or_else(|| transaction_not_found_by_hash(hash, &latest_ledger_info))?;
+
+        let status = MempoolTransactionStatus {
+            hash,
+            sender: status.sender.into(),
+            replay_protector: status.replay_protector.into(),
+            gas_unit_price: status.gas_unit_price.into(),
+            account_sequence_number: status.account_sequence_number.map(U64::from),
+            state: match status.state {
+                accudo_mempool::MempoolTransactionState::Parked => MempoolTransactionState::Parked,
+                accudo_mempool::MempoolTransactionState::Ready => MempoolTransactionState::Ready,
+            },
+            rank: status.rank.map(|rank| U64::from(rank as u64)),
+            insertion_timestamp_usecs: timestamp_usecs(status.insertion_time),
+            park_timestamp_usecs: status.park_time.map(timestamp_usecs),
+            broadcast_peers: status
+                .broadcast_peers
+                .iter()
+                .map(|peer| peer.to_string())
+                .collect(),
+            batch_inclusion_count: U64::from(status.consensus_pulled_count as u64),
+        };
+        BasicResponse::try_from_rust_value((
+            status,
+            &latest_ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
     /// Retrieves a transaction by hash. First the node tries to find the transaction
     /// in the DB. If the transaction is found there, it means the transaction is
     /// committed. If it is not found there, it looks in mempool. If it is found there,
@@ -1133,7 +1336,7 @@ impl TransactionsApi {
     }
 
     /// List sequence number based transactions for an account
-    fn list_ordered_txns_by_account(
+    pub fn list_ordered_txns_by_account(
         &self,
         accept_type: &AcceptType,
         page: Page,
@@ -1429,7 +1632,11 @@ impl TransactionsApi {
     }
 
     /// Submits a single transaction, and converts mempool codes to errors
-    async fn create_internal(&self, txn: SignedTransaction) -> Result<(), AccudoError> {
+    async fn create_internal(
+        &self,
+        txn: SignedTransaction,
+        client_ip: Option<IpAddr>,
+    ) -> Result<(), AccudoError> {
         if !txn.has_post_quantum_signature() {
             return Err(AccudoError::new_with_vm_status(
                 "Transaction missing post-quantum signature bundle",
@@ -1440,7 +1647,7 @@ impl TransactionsApi {
 
         let (mempool_status, vm_status_opt) = self
             .context
-            .submit_transaction(txn)
+            .submit_transaction(txn, client_ip)
             .await
             .context("Mempool failed to initially evaluate submitted transaction")
             .map_err(|err| {
@@ -1492,6 +1699,10 @@ impl TransactionsApi {
                 mempool_status.message,
                 AccudoErrorCode::RejectedByFilter,
             )),
+            MempoolStatusCode::RateLimited => Err(AccudoError::new_with_error_code(
+                mempool_status.message,
+                AccudoErrorCode::RateLimited,
+            )),
         }
     }
 
@@ -1501,8 +1712,9 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         ledger_info: &LedgerInfo,
         txn: SignedTransaction,
+        client_ip: Option<IpAddr>,
     ) -> SubmitTransactionResult<PendingTransaction> {
-        match self.create_internal(txn.clone()).await {
+        match self.create_internal(txn.clone(), client_ip).await {
             Ok(()) => match accept_type {
                 AcceptType::Json => {
                     let state_view = self
@@ -1557,6 +1769,9 @@ impl TransactionsApi {
                         ledger_info,
                     ),
                 ),
+                AccudoErrorCode::RateLimited => Err(
+                    SubmitTransactionError::too_many_requests_from_accudo_error(error, ledger_info),
+                ),
                 _ => Err(SubmitTransactionError::internal_from_accudo_error(
                     error,
                     ledger_info,
@@ -1571,11 +1786,12 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         ledger_info: &LedgerInfo,
         txns: Vec<SignedTransaction>,
+        client_ip: Option<IpAddr>,
     ) -> SubmitTransactionsBatchResult<TransactionsBatchSubmissionResult> {
         // Iterate through transactions keeping track of failures
         let mut txn_failures = Vec::new();
         for (idx, txn) in txns.iter().enumerate() {
-            if let Err(error) = self.create_internal(txn.clone()).await {
+            if let Err(error) = self.create_internal(txn.clone(), client_ip).await {
                 txn_failures.push(TransactionsBatchSingleSubmissionFailure {
                     error,
                     transaction_index: idx,
@@ -1912,3 +2128,83 @@ enum GetByVersionResponse {
     VersionTooOld,
     Found(TransactionData),
 }
+
+/// Counts an open connection until dropped, e.g. when the client of a stream disconnects.
+pub struct ActiveConnection(Arc<AtomicUsize>);
+
+impl ActiveConnection {
+    /// Returns `None` if `max_active_connections` are already open.
+    pub fn open(
+        active_connections: Arc<AtomicUsize>,
+        max_active_connections: usize,
+    ) -> Option<Self> {
+        if active_connections.fetch_add(1, Ordering::Relaxed) >= max_active_connections {
+            active_connections.fetch_sub(1, Ordering::Relaxed);
+            return None;
+        }
+        Some(Self(active_connections))
+    }
+}
+
+impl Drop for ActiveConnection {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns the IP address of the client that sent the request, used for the mempool admission
+/// quotas. That's the last address in the given header set by a trusted proxy if any, or the
+/// address of the peer if the header is missing.
+pub(crate) fn client_ip(
+    client_ip_header: Option<&str>,
+    headers: &HeaderMap,
+    remote_addr: &RemoteAddr,
+) -> Option<IpAddr> {
+    if let Some(header) = client_ip_header {
+        if let Some(value) = headers.get_all(header).iter().last() {
+            // Addresses the proxy didn't append can't be trusted
+            return value.to_str().ok()?.rsplit(',').next()?.trim().parse().ok();
+        }
+    }
+    remote_addr.as_socket_addr().map(|addr| addr.ip())
+}
+
+fn timestamp_usecs(time: SystemTime) -> U64 {
+    U64::from(
+        time.duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64,
+    )
+}
+
+fn mempool_transaction_event(
+    event: accudo_mempool::MempoolTransactionEvent,
+) -> MempoolTransactionEvent {
+    MempoolTransactionEvent {
+        typ: match event.kind {
+            accudo_mempool::MempoolTransactionEventKind::Inserted => {
+                MempoolTransactionEventType::Inserted
+            },
+            accudo_mempool::MempoolTransactionEventKind::Committed => {
+                MempoolTransactionEventType::Committed
+            },
+            accudo_mempool::MempoolTransactionEventKind::Rejected => {
+                MempoolTransactionEventType::Rejected
+            },
+            accudo_mempool::MempoolTransactionEventKind::Removed => {
+                MempoolTransactionEventType::Removed
+            },
+        },
+        hash: event.hash.into(),
+        sender: event.sender.into(),
+        replay_protector: event.replay_protector.into(),
+        gas_unit_price: event.gas_unit_price.into(),
+        entry_function: event
+            .entry_function
+            .map(|(module, function)| EntryFunctionId {
+                module: module.into(),
+                name: function.into(),
+            }),
+        timestamp_usecs: timestamp_usecs(event.timestamp),
+    }
+}
@@ -7,23 +7,35 @@ use crate::{
     context::{api_spawn_blocking, FunctionStats},
     failpoint::fail_point_poem,
     response::{
-        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        ForbiddenError, InternalError,
+        api_forbidden, version_not_found, version_pruned, BadRequestError, BasicErrorWith404,
+        BasicResponse, BasicResponseStatus, BasicResultWith404, ForbiddenError, InternalError,
     },
     ApiTags, Context,
 };
 use accudo_api_types::{
-    AccudoErrorCode, AsConverter, MoveValue, ViewFunction, ViewRequest,
-    MAX_RECURSIVE_TYPES_ALLOWED, U64,
+    AccudoErrorCode, AsConverter, BatchViewRequest, BatchViewResult, LedgerInfo, MoveValue,
+    ViewFunction, ViewRequest, MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use accudo_bcs_utils::serialize_uleb128;
-use accudo_types::{state_store::StateView, transaction::ViewFunctionError, vm_status::StatusCode};
+use accudo_storage_interface::state_store::state_view::db_state_view::DbStateView;
+use accudo_types::{
+    state_store::StateView,
+    transaction::{Version, ViewFunctionError, ViewFunctionOutput},
+    vm_status::StatusCode,
+};
 use accudo_vm::AccudoVM;
 use anyhow::Context as anyhowContext;
+use futures::{stream, StreamExt};
 use itertools::Itertools;
 use move_core_types::language_storage::TypeTag;
 use poem_openapi::{param::Query, payload::Json, ApiRequest, OpenApi};
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 /// API for executing Move view function.
 #[derive(Clone)]
@@ -90,6 +102,220 @@ impl ViewFunctionApi {
         api_spawn_blocking(move || view_request(context, accept_type, request, ledger_version))
             .await
     }
+
+    /// Execute a batch of view functions
+    ///
+    /// Execute many Move view functions in a single request, and return the result of each of
+    /// them in the order of the requests: either its return values, or its error.
+    ///
+    /// Each function is executed at the ledger version it requests, or at the ledger version of
+    /// the batch otherwise. Functions at the same ledger version see the same state, and the
+    /// functions of a batch are executed in parallel.
+    ///
+    /// The functions of a batch share a gas limit on top of the gas limit of each function. Each
+    /// function is charged the gas it used once it finishes, and once the gas limit of the batch
+    /// is used up, the functions that didn't start yet fail.
+    #[oai(
+        path = "/view/batch",
+        method = "post",
+        operation_id = "view_batch",
+        tag = "ApiTags::View"
+    )]
+    async fn view_function_batch(
+        &self,
+        accept_type: AcceptType,
+        /// View function requests with type and position arguments
+        requests: Json<Vec<BatchViewRequest>>,
+        /// Ledger version to get state of account for the requests which don't provide one
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<Vec<BatchViewResult>> {
+        fail_point_poem("endpoint_view_function_batch")?;
+        self.context
+            .check_api_output_enabled("Batch view function", &accept_type)?;
+        if accept_type == AcceptType::Bcs {
+            return Err(api_forbidden(
+                "Batch view function with BCS output",
+                "Please use JSON output instead.",
+            ));
+        }
+        if requests.0.len() > self.context.max_view_function_batch_size() {
+            return Err(BasicErrorWith404::bad_request_with_code_no_info(
+                format!(
+                    "Requested too many view functions: {}, while limit is {}",
+                    requests.0.len(),
+                    self.context.max_view_function_batch_size(),
+                ),
+                AccudoErrorCode::InvalidInput,
+            ));
+        }
+
+        let (ledger_info, batch_version) = self
+            .context
+            .get_latest_ledger_info_and_verify_lookup_version(ledger_version.0.map(|v| v.0))?;
+        let remaining_gas = Arc::new(BatchGas(AtomicU64::new(
+            self.context.node_config.api.max_gas_view_function_batch,
+        )));
+
+        let requests: Vec<_> = requests
+            .0
+            .into_iter()
+            .map(|request| {
+                let (request, version) = request.into_parts();
+                (request, version.map_or(batch_version, |v| v.0))
+            })
+            .collect();
+
+        // Functions at the same version share the state view of that version
+        let versions: BTreeSet<_> = requests.iter().map(|(_, version)| *version).collect();
+        let state_views = {
+            let context = self.context.clone();
+            let ledger_info = ledger_info.clone();
+            api_spawn_blocking(move || {
+                Ok::<_, BasicErrorWith404>(
+                    versions
+                        .into_iter()
+                        .map(|version| {
+                            let state_view = batch_state_view(&context, &ledger_info, version)
+                                .map_err(|mut err| err.inner_mut().clone());
+                            (version, state_view)
+                        })
+                        .collect::<BTreeMap<_, _>>(),
+                )
+            })
+            .await?
+        };
+
+        let mut tasks = vec![];
+        for (request, version) in requests {
+            let state_view = state_views
+                .get(&version)
+                .expect("A state view is looked up for every version")
+                .clone();
+            let context = self.context.clone();
+            let ledger_info = ledger_info.clone();
+            let remaining_gas = remaining_gas.clone();
+            tasks.push(async move {
+                let result = match state_view {
+                    Ok(state_view) => api_spawn_blocking(move || {
+                        view_batch_request(
+                            &context,
+                            &ledger_info,
+                            &state_view,
+                            request,
+                            &remaining_gas,
+                        )
+                    })
+                    .await
+                    .map_err(|mut err| err.inner_mut().clone()),
+                    Err(err) => Err(err),
+                };
+                match result {
+                    Ok((values, gas_used)) => BatchViewResult {
+                        ledger_version: version.into(),
+                        values: Some(values),
+                        error: None,
+                        gas_used: gas_used.into(),
+                    },
+                    Err(err) => BatchViewResult {
+                        ledger_version: version.into(),
+                        values: None,
+                        error: Some(err),
+                        gas_used: 0.into(),
+                    },
+                }
+            });
+        }
+        // Each running function reserves up to the gas limit of a function from the batch, so only
+        // as many as the batch can cover run at once, and later ones wait for the refunds
+        let max_gas_view_function = self.context.node_config.api.max_gas_view_function;
+        let concurrency = (self.context.node_config.api.max_gas_view_function_batch
+            / max_gas_view_function.max(1))
+        .max(1) as usize;
+        let results: Vec<_> = stream::iter(tasks).buffered(concurrency).collect().await;
+
+        BasicResponse::try_from_json((results, &ledger_info, BasicResponseStatus::Ok))
+    }
+}
+
+/// Gas left to the view functions of a batch.
+struct BatchGas(AtomicU64);
+
+impl BatchGas {
+    /// Reserves the gas limit of a function starting now, i.e. `max_gas_amount` capped at the gas
+    /// left, and returns it.
+    fn reserve(&self, max_gas_amount: u64) -> u64 {
+        let remaining = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                Some(remaining - remaining.min(max_gas_amount))
+            })
+            .expect("The update always succeeds");
+        remaining.min(max_gas_amount)
+    }
+
+    /// Gives back the part of the reserved gas a finished function didn't use.
+    fn refund(&self, reserved: u64, gas_used: u64) {
+        self.0
+            .fetch_add(reserved.saturating_sub(gas_used), Ordering::Relaxed);
+    }
+}
+
+/// Returns the state view at the given version, for a function of a batch.
+fn batch_state_view(
+    context: &Context,
+    ledger_info: &LedgerInfo,
+    version: Version,
+) -> Result<DbStateView, BasicErrorWith404> {
+    if version > ledger_info.version() {
+        return Err(version_not_found(version, ledger_info));
+    } else if version < ledger_info.oldest_ledger_version.0 {
+        return Err(version_pruned(version, ledger_info));
+    }
+    context.state_view_at_version(version).map_err(|err| {
+        BasicErrorWith404::internal_with_code(err, AccudoErrorCode::InternalError, ledger_info)
+    })
+}
+
+fn view_batch_request(
+    context: &Context,
+    ledger_info: &LedgerInfo,
+    state_view: &DbStateView,
+    request: ViewRequest,
+    remaining_gas: &BatchGas,
+) -> Result<(Vec<MoveValue>, u64), BasicErrorWith404> {
+    let view_function = state_view
+        .as_converter(context.db.clone(), context.indexer_reader.clone())
+        .convert_view_function(request)
+        .map_err(|err| {
+            BasicErrorWith404::bad_request_with_code(
+                err,
+                AccudoErrorCode::InvalidInput,
+                ledger_info,
+            )
+        })?;
+    check_view_filter(context, &view_function)?;
+
+    let max_gas_amount = remaining_gas.reserve(context.node_config.api.max_gas_view_function);
+    if max_gas_amount == 0 {
+        return Err(BasicErrorWith404::bad_request_with_code(
+            format!(
+                "The view functions of the batch used up its gas limit of {}",
+                context.node_config.api.max_gas_view_function_batch
+            ),
+            AccudoErrorCode::InvalidInput,
+            ledger_info,
+        ));
+    }
+    let output = execute_view_function(context, state_view, &view_function, max_gas_amount);
+    remaining_gas.refund(max_gas_amount, output.gas_used);
+
+    let values = output
+        .values
+        .map_err(|error| view_function_error(context, ledger_info, state_view, &error))?;
+    let values = return_values_to_json(context, ledger_info, state_view, &view_function, values)?;
+    Ok((values, output.gas_used))
 }
 
 fn view_request(
@@ -136,40 +362,18 @@ fn view_request(
         },
     };
 
-    // Reject the request if it's not allowed by the filter.
-    if !context.node_config.api.view_filter.allows(
-        view_function.module.address(),
-        view_function.module.name().as_str(),
-        view_function.function.as_str(),
-    ) {
-        return Err(BasicErrorWith404::forbidden_with_code_no_info(
-            format!(
-                "Function {}::{} is not allowed",
-                view_function.module, view_function.function
-            ),
-            AccudoErrorCode::InvalidInput,
-        ));
-    }
+    check_view_filter(&context, &view_function)?;
 
-    let output = AccudoVM::execute_view_function(
+    let output = execute_view_function(
+        &context,
         &state_view,
-        view_function.module.clone(),
-        view_function.function.clone(),
-        view_function.ty_args.clone(),
-        view_function.args.clone(),
+        &view_function,
         context.node_config.api.max_gas_view_function,
     );
 
-    let values = output.values.map_err(|status| {
-        let (err_string, vm_error_code) =
-            convert_view_function_error(&status, &state_view, &context);
-        BasicErrorWith404::bad_request_with_optional_vm_status_and_ledger_info(
-            anyhow::anyhow!(err_string),
-            AccudoErrorCode::InvalidInput,
-            vm_error_code,
-            Some(&ledger_info),
-        )
-    })?;
+    let values = output
+        .values
+        .map_err(|error| view_function_error(&context, &ledger_info, &state_view, &error))?;
     let result = match accept_type {
         AcceptType::Bcs => {
             // The return values are already BCS encoded, but we still need to encode the outside
@@ -193,45 +397,111 @@ fn view_request(
             BasicResponse::try_from_encoded((ret, &ledger_info, BasicResponseStatus::Ok))
         },
         AcceptType::Json => {
-            let return_types = state_view
-                .as_converter(context.db.clone(), context.indexer_reader.clone())
-                .function_return_types(&view_function)
-                .and_then(|tys| {
-                    tys.iter()
-                        .map(TypeTag::try_from)
-                        .collect::<anyhow::Result<Vec<_>>>()
-                })
-                .map_err(|err| {
-                    BasicErrorWith404::bad_request_with_code(
-                        err,
-                        AccudoErrorCode::InternalError,
-                        &ledger_info,
-                    )
-                })?;
-
-            let move_vals = values
-                .into_iter()
-                .zip(return_types.into_iter())
-                .map(|(v, ty)| {
-                    state_view
-                        .as_converter(context.db.clone(), context.indexer_reader.clone())
-                        .try_into_move_value(&ty, &v)
-                })
-                .collect::<anyhow::Result<Vec<_>>>()
-                .map_err(|err| {
-                    BasicErrorWith404::bad_request_with_code(
-                        err,
-                        AccudoErrorCode::InternalError,
-                        &ledger_info,
-                    )
-                })?;
+            let move_vals =
+                return_values_to_json(&context, &ledger_info, &state_view, &view_function, values)?;
 
             BasicResponse::try_from_json((move_vals, &ledger_info, BasicResponseStatus::Ok))
         },
     };
-    context.view_function_stats().increment(
-        FunctionStats::function_to_key(&view_function.module, &view_function.function),
-        output.gas_used,
-    );
     result.map(|r| r.with_gas_used(Some(output.gas_used)))
 }
+
+/// Rejects the view function if it's not allowed by the filter.
+fn check_view_filter(
+    context: &Context,
+    view_function: &ViewFunction,
+) -> Result<(), BasicErrorWith404> {
+    if !context.node_config.api.view_filter.allows(
+        view_function.module.address(),
+        view_function.module.name().as_str(),
+        view_function.function.as_str(),
+    ) {
+        return Err(BasicErrorWith404::forbidden_with_code_no_info(
+            format!(
+                "Function {}::{} is not allowed",
+                view_function.module, view_function.function
+            ),
+            AccudoErrorCode::InvalidInput,
+        ));
+    }
+    Ok(())
+}
+
+fn execute_view_function(
+    context: &Context,
+    state_view: &DbStateView,
+    view_function: &ViewFunction,
+    max_gas_amount: u64,
+) -> ViewFunctionOutput {
+    let output = AccudoVM::execute_view_function(
+        state_view,
+        view_function.module.clone(),
+        view_function.function.clone(),
+        view_function.ty_args.clone(),
+        view_function.args.clone(),
+        max_gas_amount,
+    );
+    if output.values.is_ok() {
+        context.view_function_stats().increment(
+            FunctionStats::function_to_key(&view_function.module, &view_function.function),
+            output.gas_used,
+        );
+    }
+    output
+}
+
+fn view_function_error(
+    context: &Context,
+    ledger_info: &LedgerInfo,
+    state_view: &DbStateView,
+    error: &ViewFunctionError,
+) -> BasicErrorWith404 {
+    let (err_string, vm_error_code) = convert_view_function_error(error, state_view, context);
+    BasicErrorWith404::bad_request_with_optional_vm_status_and_ledger_info(
+        anyhow::anyhow!(err_string),
+        AccudoErrorCode::InvalidInput,
+        vm_error_code,
+        Some(ledger_info),
+    )
+}
+
+fn return_values_to_json(
+    context: &Context,
+    ledger_info: &LedgerInfo,
+    state_view: &DbStateView,
+    view_function: &ViewFunction,
+    values: Vec<Vec<u8>>,
+) -> Result<Vec<MoveValue>, BasicErrorWith404> {
+    let return_types = state_view
+        .as_converter(context.db.clone(), context.indexer_reader.clone())
+        .function_return_types(view_function)
+        .and_then(|tys| {
+            tys.iter()
+                .map(TypeTag::try_from)
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .map_err(|err| {
+            BasicErrorWith404::bad_request_with_code(
+                err,
+                AccudoErrorCode::InternalError,
+                ledger_info,
+            )
+        })?;
+
+    values
+        .into_iter()
+        .zip(return_types.into_iter())
+        .map(|(v, ty)| {
+            state_view
+                .as_converter(context.db.clone(), context.indexer_reader.clone())
+                .try_into_move_value(&ty, &v)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|err| {
+            BasicErrorWith404::bad_request_with_code(
+                err,
+                AccudoErrorCode::InternalError,
+                ledger_info,
+            )
+        })
+}
@@ -0,0 +1,604 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional websocket API, served at `/v1/subscribe` next to the REST API.
+//!
+//! Clients subscribe to new blocks, to events by type or by event handle, and to the write set
+//! changes of an account, and are pushed the matching data as soon as it is committed, instead
+//! of polling the REST API. Data is rendered as in the REST API.
+//!
+//! A single background task follows the storage commit notifications and reads each batch of
+//! newly committed transactions and blocks once, then shares it with all the connections, which
+//! only keep what their subscriptions match.
+//!
+//! Client messages are JSON objects tagged by `type`, e.g.:
+//!
+//! ```json
+//! {"type": "subscribe", "id": "deposits", "subscription": {"type": "events_by_type", "event_type": "0x1::coin::DepositEvent"}}
+//! {"type": "unsubscribe", "id": "deposits"}
+//! ```
+//!
+//! Delivery is best effort: connections falling too far behind are sent an error and miss the
+//! updates in between. Likewise, when more than `websocket_max_catch_up_versions` versions were
+//! committed since the last read, or the versions were pruned in the meantime, the older ones
+//! are skipped and the connections are sent a `gap` message with the versions they miss.
+
+use crate::{
+    accounts::Account,
+    context::{api_spawn_blocking, Context},
+    response::{BasicError, BasicErrorWith404, InternalError},
+    transactions::ActiveConnection,
+};
+use accudo_api_types::{
+    verify_field_identifier, AccudoErrorCode, Address, AsConverter, Block, IdentifierWrapper,
+    LedgerInfo, MoveConverter, MoveStructTag, MoveType, TransactionOnChainData, VerifyInput,
+    VersionedEvent, WriteSetChange, U64,
+};
+use accudo_logger::{sample, sample::SampleRate, warn};
+use accudo_types::{
+    account_address::AccountAddress,
+    contract_event::{ContractEvent, EventWithVersion},
+    event::EventKey,
+    state_store::{state_key::inner::StateKeyInner, StateView},
+    transaction::Version,
+};
+use anyhow::Context as AnyhowContext;
+use futures::{SinkExt, StreamExt};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use poem::{
+    handler,
+    web::{
+        websocket::{Message, WebSocket, WebSocketStream},
+        Data,
+    },
+    IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    runtime::Handle,
+    sync::{
+        broadcast::{self, error::RecvError},
+        watch::Receiver as WatchReceiver,
+    },
+};
+
+/// Number of committed updates buffered for the connections falling behind.
+const COMMITTED_UPDATES_CHANNEL_SIZE: usize = 128;
+
+/// A message sent by the client.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Starts a subscription. The ID is chosen by the client and tags the matching messages.
+    Subscribe {
+        id: String,
+        subscription: Subscription,
+    },
+    /// Stops the subscription with the given ID.
+    Unsubscribe { id: String },
+}
+
+/// The data a client subscribes to.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Subscription {
+    /// New blocks, without their transactions.
+    Blocks,
+    /// Events of the given type, e.g. `0x1::coin::DepositEvent`.
+    EventsByType { event_type: MoveType },
+    /// Events of the event handle stored in the given field of a resource of an account, as in
+    /// `/accounts/:address/events/:event_handle/:field_name`.
+    EventsByHandle {
+        address: Address,
+        event_handle: MoveStructTag,
+        field_name: IdentifierWrapper,
+    },
+    /// Events of the event handle with the given creation number, as in
+    /// `/accounts/:address/events/:creation_number`.
+    EventsByCreationNumber {
+        address: Address,
+        creation_number: U64,
+    },
+    /// Write set changes to the resources and modules of an account, optionally only to the
+    /// resource of the given type.
+    WriteSetChanges {
+        address: Address,
+        resource_type: Option<MoveStructTag>,
+    },
+}
+
+/// A message sent by the server.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Subscribed {
+        id: String,
+    },
+    Unsubscribed {
+        id: String,
+    },
+    Block {
+        id: String,
+        block: Block,
+    },
+    Event {
+        id: String,
+        event: VersionedEvent,
+    },
+    WriteSetChange {
+        id: String,
+        version: U64,
+        change: WriteSetChange,
+    },
+    /// Committed versions skipped, whose data the subscriptions miss.
+    Gap {
+        first_version: U64,
+        last_version: U64,
+    },
+    /// An error, about the given subscription if any.
+    Error {
+        id: Option<String>,
+        message: String,
+    },
+}
+
+impl ServerMessage {
+    fn error(id: Option<String>, message: impl Into<String>) -> Self {
+        Self::Error {
+            id,
+            message: message.into(),
+        }
+    }
+}
+
+/// The transactions and blocks committed since the previous update.
+pub struct CommittedUpdate {
+    pub ledger_info: LedgerInfo,
+    /// The first and last versions skipped since the previous update, if any.
+    pub skipped_versions: Option<(Version, Version)>,
+    pub transactions: Vec<TransactionOnChainData>,
+    pub blocks: Vec<Block>,
+}
+
+/// Shares the data committed to storage with the websocket connections.
+#[derive(Clone)]
+pub struct CommitNotifier {
+    sender: broadcast::Sender<Arc<CommittedUpdate>>,
+}
+
+impl CommitNotifier {
+    /// Spawns the task reading the data committed to storage on every commit notification.
+    pub fn spawn(
+        runtime_handle: &Handle,
+        context: Context,
+        mut version_update_receiver: WatchReceiver<(Instant, Version)>,
+    ) -> Self {
+        let (sender, _) = broadcast::channel(COMMITTED_UPDATES_CHANNEL_SIZE);
+        let notifier = Self {
+            sender: sender.clone(),
+        };
+
+        // The next version and block height to notify, unknown until genesis is committed
+        let mut cursor = latest_ledger_info(&context)
+            .ok()
+            .map(|ledger_info| cursor_after(&ledger_info));
+        runtime_handle.spawn(async move {
+            while version_update_receiver.changed().await.is_ok() {
+                let context = context.clone();
+                let sender = sender.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    notify_committed_update(&context, &sender, cursor)
+                })
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+                match result {
+                    Ok(next_cursor) => cursor = Some(next_cursor),
+                    // Retried on the next commit
+                    Err(err) => {
+                        sample!(
+                            SampleRate::Duration(Duration::from_secs(60)),
+                            warn!("Failed to notify committed update: {:#}", err)
+                        );
+                    },
+                }
+            }
+        });
+
+        notifier
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<CommittedUpdate>> {
+        self.sender.subscribe()
+    }
+}
+
+/// Reads and broadcasts the data committed from the given cursor on, and returns the next cursor.
+/// Nothing is read while there are no connections.
+fn notify_committed_update(
+    context: &Context,
+    sender: &broadcast::Sender<Arc<CommittedUpdate>>,
+    cursor: Option<(Version, u64)>,
+) -> anyhow::Result<(Version, u64)> {
+    let ledger_info = latest_ledger_info(context)?;
+    let next_cursor = cursor_after(&ledger_info);
+    let (cursor_version, cursor_block_height) = match cursor {
+        Some(cursor) if sender.receiver_count() > 0 => cursor,
+        _ => return Ok(next_cursor),
+    };
+
+    // Skip the versions pruned or too far behind, along with the blocks before the first version
+    // read
+    let mut next_version = cursor_version.max(ledger_info.oldest_ledger_version.0).max(
+        (ledger_info.version() + 1)
+            .saturating_sub(context.node_config.api.websocket_max_catch_up_versions),
+    );
+    let mut next_block_height = cursor_block_height.max(ledger_info.oldest_block_height.0);
+    let skipped_versions =
+        (next_version > cursor_version).then(|| (cursor_version, next_version - 1));
+    if skipped_versions.is_some() && next_version <= ledger_info.version() {
+        let block = context
+            .get_block_by_version::<BasicErrorWith404>(next_version, &ledger_info, false)
+            .map_err(block_error)?;
+        next_block_height = next_block_height.max(block.block_height);
+    }
+
+    let mut transactions = vec![];
+    while next_version <= ledger_info.version() {
+        let limit = (ledger_info.version() - next_version + 1)
+            .min(context.max_transactions_page_size() as u64) as u16;
+        let page = context
+            .get_transactions(next_version, limit, ledger_info.version())
+            .context("Failed to read committed transactions")?;
+        next_version += page.len() as u64;
+        transactions.extend(page);
+    }
+
+    let mut blocks = vec![];
+    for block_height in next_block_height..=ledger_info.block_height.0 {
+        let block = context
+            .get_block_by_height::<BasicErrorWith404>(block_height, &ledger_info, false)
+            .map_err(block_error)?;
+        blocks.push(Block {
+            block_height: block.block_height.into(),
+            block_hash: block.block_hash.into(),
+            block_timestamp: block.block_timestamp.into(),
+            first_version: block.first_version.into(),
+            last_version: block.last_version.into(),
+            transactions: None,
+        });
+    }
+
+    // Connections may have all closed in the meantime
+    let _ = sender.send(Arc::new(CommittedUpdate {
+        ledger_info,
+        skipped_versions,
+        transactions,
+        blocks,
+    }));
+    Ok(next_cursor)
+}
+
+fn block_error(mut err: BasicErrorWith404) -> anyhow::Error {
+    anyhow::anyhow!(err.inner_mut().message.clone())
+}
+
+fn latest_ledger_info(context: &Context) -> anyhow::Result<LedgerInfo> {
+    context
+        .get_latest_storage_ledger_info::<BasicError>()
+        .map_err(|mut err| anyhow::anyhow!(err.inner_mut().message.clone()))
+}
+
+/// Returns the version and block height following the given ledger info.
+fn cursor_after(ledger_info: &LedgerInfo) -> (Version, u64) {
+    (ledger_info.version() + 1, ledger_info.block_height.0 + 1)
+}
+
+/// A subscription, resolved into what committed updates are matched against.
+#[derive(Clone, Debug)]
+pub enum SubscriptionFilter {
+    Blocks,
+    EventsByType(TypeTag),
+    EventsByKey(EventKey),
+    WriteSetChanges {
+        address: AccountAddress,
+        resource_type: Option<StructTag>,
+    },
+}
+
+impl SubscriptionFilter {
+    /// Validates the subscription and resolves its event handle, if any, at the latest version.
+    pub fn resolve(
+        context: Arc<Context>,
+        subscription: Subscription,
+    ) -> Result<Self, BasicErrorWith404> {
+        let filter = match subscription {
+            Subscription::Blocks => Self::Blocks,
+            Subscription::EventsByType { event_type } => Self::EventsByType(
+                (&event_type)
+                    .try_into()
+                    .context("'event_type' invalid")
+                    .map_err(invalid_input)?,
+            ),
+            Subscription::EventsByHandle {
+                address,
+                event_handle,
+                field_name,
+            } => {
+                event_handle
+                    .verify(0)
+                    .context("'event_handle' invalid")
+                    .map_err(invalid_input)?;
+                verify_field_identifier(field_name.as_str())
+                    .context("'field_name' invalid")
+                    .map_err(invalid_input)?;
+                let account = Account::new(context, address, None, None, None)?;
+                Self::EventsByKey(account.find_event_key(event_handle, field_name.into())?)
+            },
+            Subscription::EventsByCreationNumber {
+                address,
+                creation_number,
+            } => Self::EventsByKey(EventKey::new(creation_number.0, address.into())),
+            Subscription::WriteSetChanges {
+                address,
+                resource_type,
+            } => Self::WriteSetChanges {
+                address: address.into(),
+                resource_type: resource_type
+                    .map(|resource_type| (&resource_type).try_into())
+                    .transpose()
+                    .context("'resource_type' invalid")
+                    .map_err(invalid_input)?,
+            },
+        };
+        Ok(filter)
+    }
+
+    /// Returns the messages for the data of the committed update matching the filter.
+    pub fn matches<R: StateView>(
+        &self,
+        id: &str,
+        update: &CommittedUpdate,
+        converter: &MoveConverter<'_, R>,
+    ) -> anyhow::Result<Vec<ServerMessage>> {
+        let mut messages = vec![];
+        match self {
+            Self::Blocks => {
+                for block in &update.blocks {
+                    messages.push(ServerMessage::Block {
+                        id: id.to_string(),
+                        block: block.clone(),
+                    });
+                }
+            },
+            Self::EventsByType(_) | Self::EventsByKey(_) => {
+                let events: Vec<_> = update
+                    .transactions
+                    .iter()
+                    .flat_map(|txn| {
+                        txn.events
+                            .iter()
+                            .filter(|event| self.matches_event(event))
+                            .map(|event| EventWithVersion::new(txn.version, event.clone()))
+                    })
+                    .collect();
+                for event in converter.try_into_versioned_events(&events)? {
+                    messages.push(ServerMessage::Event {
+                        id: id.to_string(),
+                        event,
+                    });
+                }
+            },
+            Self::WriteSetChanges {
+                address,
+                resource_type,
+            } => {
+                for txn in &update.transactions {
+                    for (state_key, op) in txn.changes.write_op_iter() {
+                        match state_key.inner() {
+                            StateKeyInner::AccessPath(access_path)
+                                if access_path.address == *address => {},
+                            _ => continue,
+                        }
+                        for change in
+                            converter.try_into_write_set_changes(state_key.clone(), op.clone())?
+                        {
+                            if resource_type.is_none()
+                                || changed_resource_type(&change)?.as_ref()
+                                    == resource_type.as_ref()
+                            {
+                                messages.push(ServerMessage::WriteSetChange {
+                                    id: id.to_string(),
+                                    version: txn.version.into(),
+                                    change,
+                                });
+                            }
+                        }
+                    }
+                }
+            },
+        }
+        Ok(messages)
+    }
+
+    fn matches_event(&self, event: &ContractEvent) -> bool {
+        match self {
+            Self::EventsByType(event_type) => event.type_tag() == event_type,
+            Self::EventsByKey(key) => event.event_key() == Some(key),
+            _ => false,
+        }
+    }
+}
+
+/// Returns the type of the resource written or deleted by the change, if any.
+fn changed_resource_type(change: &WriteSetChange) -> anyhow::Result<Option<StructTag>> {
+    let resource_type = match change {
+        WriteSetChange::WriteResource(write) => &write.data.typ,
+        WriteSetChange::DeleteResource(delete) => &delete.resource,
+        _ => return Ok(None),
+    };
+    Ok(Some(resource_type.try_into()?))
+}
+
+fn invalid_input(err: anyhow::Error) -> BasicErrorWith404 {
+    BasicErrorWith404::bad_request_with_code_no_info(err, AccudoErrorCode::InvalidInput)
+}
+
+/// Upgrades the request to a websocket connection serving subscriptions.
+#[handler]
+pub async fn subscribe_handler(
+    websocket: WebSocket,
+    context: Data<&Arc<Context>>,
+) -> poem::Result<impl IntoResponse> {
+    let commit_notifier = context.commit_notifier().cloned().ok_or_else(|| {
+        BasicError::service_unavailable_with_code_no_info(
+            "Commit notifications are not available",
+            AccudoErrorCode::InternalError,
+        )
+    })?;
+    let connection = ActiveConnection::open(
+        context.websocket_active_connections.clone(),
+        context.node_config.api.websocket_max_active_connections,
+    )
+    .ok_or_else(|| {
+        BasicError::service_unavailable_with_code_no_info(
+            "Too many open websocket connections",
+            AccudoErrorCode::InternalError,
+        )
+    })?;
+    // Subscribe before the upgrade, so that no update is missed once connected
+    let updates = commit_notifier.subscribe();
+    let context = context.0.clone();
+    Ok(websocket.on_upgrade(move |socket| async move {
+        let _connection = connection;
+        serve_connection(context, updates, socket).await
+    }))
+}
+
+async fn serve_connection(
+    context: Arc<Context>,
+    mut updates: broadcast::Receiver<Arc<CommittedUpdate>>,
+    socket: WebSocketStream,
+) {
+    let (mut sink, mut stream) = socket.split();
+    let mut subscriptions = BTreeMap::new();
+    loop {
+        let messages = tokio::select! {
+            message = stream.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    vec![handle_client_message(&context, &mut subscriptions, &text).await]
+                },
+                Some(Ok(Message::Binary(_))) => {
+                    vec![ServerMessage::error(None, "Only text messages are supported")]
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => continue,
+            },
+            update = updates.recv() => match update {
+                Ok(update) => match_update(&context, &subscriptions, update).await,
+                Err(RecvError::Lagged(skipped)) => vec![ServerMessage::error(
+                    None,
+                    format!("Connection fell behind, {} committed updates were missed", skipped),
+                )],
+                Err(RecvError::Closed) => return,
+            },
+        };
+        for message in messages {
+            let text = match serde_json::to_string(&message) {
+                Ok(text) => text,
+                Err(err) => {
+                    warn!("Failed to serialize websocket message: {}", err);
+                    continue;
+                },
+            };
+            if sink.send(Message::Text(text)).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+async fn handle_client_message(
+    context: &Arc<Context>,
+    subscriptions: &mut BTreeMap<String, SubscriptionFilter>,
+    text: &str,
+) -> ServerMessage {
+    let message = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(err) => return ServerMessage::error(None, format!("Invalid message: {}", err)),
+    };
+    match message {
+        ClientMessage::Subscribe { id, subscription } => {
+            if subscriptions.contains_key(&id) {
+                return ServerMessage::error(Some(id), "Subscription ID already in use");
+            }
+            if subscriptions.len()
+                >= context
+                    .node_config
+                    .api
+                    .websocket_max_subscriptions_per_connection
+            {
+                return ServerMessage::error(Some(id), "Too many subscriptions on this connection");
+            }
+            let context = context.clone();
+            match api_spawn_blocking(move || SubscriptionFilter::resolve(context, subscription))
+                .await
+            {
+                Ok(filter) => {
+                    subscriptions.insert(id.clone(), filter);
+                    ServerMessage::Subscribed { id }
+                },
+                Err(mut err) => ServerMessage::error(Some(id), err.inner_mut().message.clone()),
+            }
+        },
+        ClientMessage::Unsubscribe { id } => match subscriptions.remove(&id) {
+            Some(_) => ServerMessage::Unsubscribed { id },
+            None => ServerMessage::error(Some(id), "Unknown subscription ID"),
+        },
+    }
+}
+
+async fn match_update(
+    context: &Arc<Context>,
+    subscriptions: &BTreeMap<String, SubscriptionFilter>,
+    update: Arc<CommittedUpdate>,
+) -> Vec<ServerMessage> {
+    if subscriptions.is_empty() {
+        return vec![];
+    }
+    let mut messages = vec![];
+    if let Some((first_version, last_version)) = update.skipped_versions {
+        messages.push(ServerMessage::Gap {
+            first_version: first_version.into(),
+            last_version: last_version.into(),
+        });
+    }
+    let context = context.clone();
+    let subscriptions = subscriptions.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        // Rendered with the modules of the update's version, which later upgrades may change
+        let state_view = context.state_view_at_version(update.ledger_info.version())?;
+        let converter = state_view.as_converter(context.db.clone(), context.indexer_reader.clone());
+        let mut messages = vec![];
+        for (id, filter) in &subscriptions {
+            messages.extend(filter.matches(id, &update, &converter)?);
+        }
+        anyhow::Ok(messages)
+    })
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|result| result);
+    match result {
+        Ok(matched) => messages.extend(matched),
+        Err(err) => messages.push(ServerMessage::error(
+            None,
+            format!("Failed to render committed update: {:#}", err),
+        )),
+    }
+    messages
+}
@@ -98,6 +98,8 @@ pub enum AccudoErrorCode {
     VmError = 403,
     /// The transaction was rejected due to a transaction filter.
     RejectedByFilter = 404,
+    /// The transaction was rejected by an admission quota of the mempool, and can be retried later.
+    RateLimited = 405,
 
     /// Health check failed.
     HealthCheckFailed = 500,
@@ -41,14 +41,15 @@ pub use move_types::{
     ResourceGroup, I128, I256, I64, MAX_RECURSIVE_TYPES_ALLOWED, U128, U256, U64,
 };
 use serde::{Deserialize, Deserializer};
-pub use state::RawStateValueRequest;
+pub use state::{BalanceChange, RawStateValueRequest, ResourceChange};
 use std::str::FromStr;
 pub use table::{RawTableItemRequest, TableItemRequest};
 pub use transaction::{
     AbstractSignature, AccountSignature, BlockMetadataTransaction, DeleteModule, DeleteResource,
     DeleteTableItem, DirectWriteSet, Ed25519Signature, EncodeSubmissionRequest,
     EntryFunctionPayload, Event, FeePayerSignature, GasEstimation, GasEstimationBcs,
-    GenesisPayload, GenesisTransaction, MultiAgentSignature, MultiEd25519Signature,
+    GenesisPayload, GenesisTransaction, MempoolTransactionEvent, MempoolTransactionEventType,
+    MempoolTransactionState, MempoolTransactionStatus, MultiAgentSignature, MultiEd25519Signature,
     MultiKeySignature, MultisigPayload, MultisigTransactionPayload, NoAccountSignature,
     PendingTransaction, PublicKey, ScriptPayload, ScriptWriteSet, Signature, SingleKeySignature,
     SubmitTransactionRequest, Transaction, TransactionData, TransactionId, TransactionInfo,
@@ -58,7 +59,7 @@ pub use transaction::{
     UserTransactionRequest, VersionedEvent, WriteModule, WriteResource, WriteSet, WriteSetChange,
     WriteSetPayload, WriteTableItem,
 };
-pub use view::{ViewFunction, ViewRequest};
+pub use view::{BatchViewRequest, BatchViewResult, ViewFunction, ViewRequest};
 pub use wrappers::{EventGuid, IdentifierWrapper, StateKeyWrapper};
 
 pub fn deserialize_from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -1,7 +1,7 @@
 // Copyright © Accudo Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::HexEncodedBytes;
+use crate::{HexEncodedBytes, MoveResource, U64};
 use poem_openapi::Object;
 use serde::{Deserialize, Serialize};
 
@@ -10,3 +10,21 @@ use serde::{Deserialize, Serialize};
 pub struct RawStateValueRequest {
     pub key: HexEncodedBytes,
 }
+
+/// A change of an account resource by a transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ResourceChange {
+    /// Version of the transaction which changed the resource
+    pub version: U64,
+    /// The resource after the transaction, or none if the transaction deleted it
+    pub resource: Option<MoveResource>,
+}
+
+/// A change of an account balance by a transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct BalanceChange {
+    /// Version of the transaction which changed the balance
+    pub version: U64,
+    /// The balance after the transaction
+    pub balance: U64,
+}
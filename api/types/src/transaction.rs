@@ -27,9 +27,10 @@ use accudo_types::{
     keyless,
     transaction::{
         authenticator::{
-            AbstractAuthenticator, AccountAuthenticator, AnyPublicKey, AnySignature, MultiKey,
-            MultiKeyAuthenticator, PostQuantumPublicKey, SingleKeyAuthenticator,
-            TransactionAuthenticator, MAX_NUM_OF_SIGS,
+            AbstractAuthenticator, AccountAuthenticator, AnyPublicKey, AnySignature,
+            HybridPublicKey, HybridSignature, MultiKey, MultiKeyAuthenticator,
+            PostQuantumPublicKey, SingleKeyAuthenticator, TransactionAuthenticator,
+            MAX_NUM_OF_SIGS,
         },
         webauthn::{PartialAuthenticatorAssertionResponse, MAX_WEBAUTHN_SIGNATURE_BYTES},
         Script, SignedTransaction, TransactionOutput, TransactionWithProof,
@@ -38,7 +39,7 @@ use accudo_types::{
 use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
 use bcs::to_bytes;
 use once_cell::sync::Lazy;
-use poem_openapi::{Object, Union};
+use poem_openapi::{Enum, Object, Union};
 use serde::{Deserialize, Serialize};
 use std::{
     boxed::Box,
@@ -203,6 +204,19 @@ pub enum ReplayProtector {
     SequenceNumber(U64),
 }
 
+impl From<accudo_types::transaction::ReplayProtector> for ReplayProtector {
+    fn from(replay_protector: accudo_types::transaction::ReplayProtector) -> Self {
+        match replay_protector {
+            accudo_types::transaction::ReplayProtector::Nonce(nonce) => {
+                ReplayProtector::Nonce(nonce.into())
+            },
+            accudo_types::transaction::ReplayProtector::SequenceNumber(seq_num) => {
+                ReplayProtector::SequenceNumber(seq_num.into())
+            },
+        }
+    }
+}
+
 /// Enum of the different types of transactions in Accudo
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Union)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -1521,6 +1535,8 @@ pub enum Signature {
     Secp256k1Ecdsa(Secp256k1Ecdsa),
     WebAuthn(WebAuthn),
     Keyless(Keyless),
+    PostQuantum(PostQuantum),
+    Hybrid(Hybrid),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
@@ -1588,6 +1604,67 @@ impl FederatedKeyless {
     }
 }
 
+/// A post-quantum public key or signature
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct PostQuantum {
+    /// Algorithm identifier, e.g. `dilithium3`.
+    pub scheme: String,
+    pub value: HexEncodedBytes,
+}
+
+impl PostQuantum {
+    pub fn new(scheme: SchemeId, value: HexEncodedBytes) -> Self {
+        Self {
+            scheme: scheme.to_string(),
+            value,
+        }
+    }
+
+    fn scheme_id(&self) -> Result<SchemeId> {
+        parse_post_quantum_scheme(&self.scheme)
+    }
+
+    fn ensure_same_scheme(&self, signature: &PostQuantum) -> Result<()> {
+        if self.scheme_id()? != signature.scheme_id()? {
+            bail!(
+                "Post-quantum public key scheme {} does not match signature scheme {}",
+                self.scheme,
+                signature.scheme
+            );
+        }
+        Ok(())
+    }
+}
+
+/// An Ed25519 public key or signature paired with a post-quantum one, both of which must verify
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct Hybrid {
+    pub ed25519: HexEncodedBytes,
+    pub post_quantum: PostQuantum,
+}
+
+impl Hybrid {
+    pub fn new(ed25519: HexEncodedBytes, post_quantum: PostQuantum) -> Self {
+        Self {
+            ed25519,
+            post_quantum,
+        }
+    }
+}
+
+fn parse_post_quantum_scheme(scheme: &str) -> Result<SchemeId> {
+    match scheme.to_ascii_lowercase().as_str() {
+        "dilithium3" | "dilithium-3" => Ok(SchemeId::Dilithium3),
+        "dilithium2" | "dilithium-2" => Ok(SchemeId::Dilithium2),
+        "dilithium5" | "dilithium-5" => Ok(SchemeId::Dilithium5),
+        "falcon512" | "falcon-512" => Ok(SchemeId::Falcon512),
+        _ => Err(anyhow!(
+            "Unsupported post-quantum signature scheme: {}",
+            scheme
+        )),
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
 pub struct PostQuantumSignature {
     /// Algorithm identifier, e.g. `dilithium3`.
@@ -1600,16 +1677,7 @@ pub struct PostQuantumSignature {
 
 impl PostQuantumSignature {
     fn parse_scheme(&self) -> Result<SchemeId> {
-        match self.scheme.to_ascii_lowercase().as_str() {
-            "dilithium3" | "dilithium-3" => Ok(SchemeId::Dilithium3),
-            "dilithium2" | "dilithium-2" => Ok(SchemeId::Dilithium2),
-            "dilithium5" | "dilithium-5" => Ok(SchemeId::Dilithium5),
-            "falcon512" | "falcon-512" => Ok(SchemeId::Falcon512),
-            _ => Err(anyhow!(
-                "Unsupported post-quantum signature scheme: {}",
-                self.scheme
-            )),
-        }
+        parse_post_quantum_scheme(&self.scheme)
     }
 
     fn to_components(&self) -> Result<(PostQuantumPublicKey, SignatureBundle)> {
@@ -1641,6 +1709,17 @@ impl TryFrom<&Signature> for AnySignature {
             },
             Signature::WebAuthn(s) => AnySignature::webauthn(s.value.inner().try_into()?),
             Signature::Keyless(s) => AnySignature::keyless(s.value.inner().try_into()?),
+            Signature::PostQuantum(s) => AnySignature::post_quantum(SignatureBundle::new(
+                s.scheme_id()?,
+                s.value.inner().to_vec(),
+            )),
+            Signature::Hybrid(s) => AnySignature::hybrid(HybridSignature::new(
+                SignatureBundle::new(SchemeId::Ed25519, s.ed25519.inner().to_vec()),
+                SignatureBundle::new(
+                    s.post_quantum.scheme_id()?,
+                    s.post_quantum.value.inner().to_vec(),
+                ),
+            )),
         })
     }
 }
@@ -1660,6 +1739,17 @@ impl From<&AnySignature> for Signature {
             AnySignature::Keyless { signature } => {
                 Signature::Keyless(Keyless::new(signature.to_bytes().into()))
             },
+            AnySignature::PostQuantum { signature } => Signature::PostQuantum(PostQuantum::new(
+                signature.scheme,
+                signature.bytes().to_vec().into(),
+            )),
+            AnySignature::Hybrid { signature } => Signature::Hybrid(Hybrid::new(
+                signature.classical().bytes().to_vec().into(),
+                PostQuantum::new(
+                    signature.post_quantum().scheme,
+                    signature.post_quantum().bytes().to_vec().into(),
+                ),
+            )),
         }
     }
 }
@@ -1673,6 +1763,8 @@ pub enum PublicKey {
     Secp256r1Ecdsa(Secp256r1Ecdsa),
     Keyless(Keyless),
     FederatedKeyless(FederatedKeyless),
+    PostQuantum(PostQuantum),
+    Hybrid(Hybrid),
 }
 
 impl TryFrom<&PublicKey> for AnyPublicKey {
@@ -1691,6 +1783,16 @@ impl TryFrom<&PublicKey> for AnyPublicKey {
             PublicKey::FederatedKeyless(p) => {
                 AnyPublicKey::federated_keyless(p.value.inner().try_into()?)
             },
+            PublicKey::PostQuantum(p) => {
+                AnyPublicKey::post_quantum(p.scheme_id()?, p.value.inner().to_vec())
+            },
+            PublicKey::Hybrid(p) => AnyPublicKey::hybrid(HybridPublicKey::new(
+                p.ed25519.inner().try_into()?,
+                PostQuantumPublicKey::new(
+                    p.post_quantum.scheme_id()?,
+                    p.post_quantum.value.inner().to_vec(),
+                ),
+            )),
         })
     }
 }
@@ -1713,6 +1815,16 @@ impl From<&AnyPublicKey> for PublicKey {
             AnyPublicKey::FederatedKeyless { public_key } => {
                 PublicKey::FederatedKeyless(FederatedKeyless::new(public_key.to_bytes().into()))
             },
+            AnyPublicKey::PostQuantum { scheme, public_key } => {
+                PublicKey::PostQuantum(PostQuantum::new(*scheme, public_key.clone().into()))
+            },
+            AnyPublicKey::Hybrid { public_key } => PublicKey::Hybrid(Hybrid::new(
+                public_key.classical().to_bytes().to_vec().into(),
+                PostQuantum::new(
+                    public_key.post_quantum().scheme(),
+                    public_key.post_quantum().as_bytes().to_vec().into(),
+                ),
+            )),
         }
     }
 }
@@ -1765,6 +1877,15 @@ impl VerifyInput for SingleKeySignature {
                 signature: s.value.clone(),
             }
             .verify(),
+            (PublicKey::PostQuantum(p), Signature::PostQuantum(s)) => p.ensure_same_scheme(s),
+            (PublicKey::Hybrid(p), Signature::Hybrid(s)) => {
+                Ed25519Signature {
+                    public_key: p.ed25519.clone(),
+                    signature: s.ed25519.clone(),
+                }
+                .verify()?;
+                p.post_quantum.ensure_same_scheme(&s.post_quantum)
+            },
             _ => bail!("Invalid public key, signature match."),
         }?;
 
@@ -1914,6 +2035,7 @@ impl TryFrom<&MultiKeySignature> for AccountAuthenticator {
                     )?;
                     AnyPublicKey::federated_keyless(key)
                 },
+                PublicKey::PostQuantum(_) | PublicKey::Hybrid(_) => public_key.try_into()?,
             };
             public_keys.push(key);
         }
@@ -1947,6 +2069,9 @@ impl TryFrom<&MultiKeySignature> for AccountAuthenticator {
                             )?;
                         AnySignature::keyless(signature)
                     },
+                    Signature::PostQuantum(_) | Signature::Hybrid(_) => {
+                        (&indexed_signature.signature).try_into()?
+                    },
                 };
             signatures.push((indexed_signature.index, signature));
         }
@@ -2424,6 +2549,71 @@ pub struct GasEstimation {
     pub prioritized_gas_estimate: Option<u64>,
 }
 
+/// Where a transaction currently sits in the mempool
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum MempoolTransactionState {
+    /// Not eligible for consensus yet, e.g. waiting for earlier transactions of the sender
+    Parked,
+    /// Eligible to be pulled into a batch by consensus
+    Ready,
+}
+
+/// Status of a pending transaction in the mempool of the node
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct MempoolTransactionStatus {
+    pub hash: HashValue,
+    pub sender: Address,
+    pub replay_protector: ReplayProtector,
+    pub gas_unit_price: U64,
+    /// Sequence number of the sender's account as last seen by the mempool
+    pub account_sequence_number: Option<U64>,
+    pub state: MempoolTransactionState,
+    /// Number of ready transactions ranked ahead of this one (at most 1000), only set if the
+    /// transaction is ready
+    pub rank: Option<U64>,
+    /// Time the transaction was inserted into the mempool of the node
+    pub insertion_timestamp_usecs: U64,
+    /// Time the transaction was last parked, if it ever was
+    pub park_timestamp_usecs: Option<U64>,
+    /// Peers the node has broadcast the transaction to
+    pub broadcast_peers: Vec<String>,
+    /// Number of times consensus pulled the transaction into a batch
+    pub batch_inclusion_count: U64,
+}
+
+/// What happened to a transaction in a mempool transaction event
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum MempoolTransactionEventType {
+    /// The transaction was inserted into the mempool
+    Inserted,
+    /// The transaction, or a later one of the same sender, was committed
+    Committed,
+    /// The transaction was rejected by consensus
+    Rejected,
+    /// The transaction was removed for any other reason, e.g. it expired or was replaced
+    Removed,
+}
+
+/// An event of a transaction entering or leaving the mempool of the node
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct MempoolTransactionEvent {
+    #[serde(rename = "type")]
+    #[oai(rename = "type")]
+    pub typ: MempoolTransactionEventType,
+    pub hash: HashValue,
+    pub sender: Address,
+    pub replay_protector: ReplayProtector,
+    pub gas_unit_price: U64,
+    /// Entry function called by the transaction, if any
+    pub entry_function: Option<EntryFunctionId>,
+    /// Time the event happened on the node
+    pub timestamp_usecs: U64,
+}
+
 /// API representation of persisted auxiliary transaction information
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
 pub struct PersistedAuxiliaryInfo {
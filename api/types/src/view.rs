@@ -1,7 +1,7 @@
 // Copyright © Accudo Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{EntryFunctionId, MoveType};
+use crate::{AccudoError, EntryFunctionId, MoveType, MoveValue, U64};
 use accudo_types::serde_helper::vec_bytes;
 use move_core_types::{
     identifier::Identifier,
@@ -20,6 +20,47 @@ pub struct ViewRequest {
     pub arguments: Vec<serde_json::Value>,
 }
 
+/// View request for the Batch Move View Function API
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct BatchViewRequest {
+    pub function: EntryFunctionId,
+    /// Type arguments of the function
+    pub type_arguments: Vec<MoveType>,
+    /// Arguments of the function
+    pub arguments: Vec<serde_json::Value>,
+    /// Ledger version to execute the function at
+    ///
+    /// If not provided, it will be the ledger version of the batch
+    pub ledger_version: Option<U64>,
+}
+
+impl BatchViewRequest {
+    /// Splits the request into a view request and its ledger version
+    pub fn into_parts(self) -> (ViewRequest, Option<U64>) {
+        let request = ViewRequest {
+            function: self.function,
+            type_arguments: self.type_arguments,
+            arguments: self.arguments,
+        };
+        (request, self.ledger_version)
+    }
+}
+
+/// Result of one view function of a batch
+///
+/// Holds either the return values or the error of the function
+#[derive(Clone, Debug, Serialize, Object)]
+pub struct BatchViewResult {
+    /// Ledger version the function was executed at
+    pub ledger_version: U64,
+    /// Return values of the function, if it succeeded
+    pub values: Option<Vec<MoveValue>>,
+    /// Error of the function, if it failed
+    pub error: Option<AccudoError>,
+    /// Gas used by the function
+    pub gas_used: U64,
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ViewFunction {
     pub module: ModuleId,
@@ -53,6 +53,13 @@ pub struct ApiConfig {
     pub transaction_simulation_enabled: bool,
     /// Maximum number of transactions that can be sent with the Batch submit API
     pub max_submit_transaction_batch_size: usize,
+    /// Optional: Header in which a trusted reverse proxy passes the IP address of the client, e.g.
+    /// `X-Forwarded-For`, used for the per IP mempool admission quota.
+    ///
+    /// The last address in the header is used, i.e. the one appended by the proxy. Only set this
+    /// if the API can't be reached without going through the proxy, as clients can set the header
+    /// themselves. If not set, the address of the peer is used, i.e. the one of the proxy if any.
+    pub client_ip_header: Option<String>,
     /// Maximum page size for transaction paginated APIs
     pub max_transactions_page_size: u16,
     /// Maximum page size for block transaction APIs
@@ -67,6 +74,15 @@ pub struct ApiConfig {
     ///
     /// This limits the execution length of a view function to the given gas used.
     pub max_gas_view_function: u64,
+    /// Maximum number of view functions that can be executed with the Batch view API
+    pub max_view_function_batch_size: usize,
+    /// Maximum gas unit limit for all the view functions of a batch together
+    ///
+    /// Each view function of a batch is also limited by `max_gas_view_function`.
+    pub max_gas_view_function_batch: u64,
+    /// Maximum number of versions scanned by a single request for the history of a resource or a
+    /// balance
+    pub max_state_history_version_range: u64,
     /// Optional: Maximum number of worker threads for the API.
     ///
     /// If not set, `runtime_worker_multiplier` will multiply times the number of CPU cores on the machine
@@ -89,6 +105,32 @@ pub struct ApiConfig {
     pub wait_by_hash_poll_interval_ms: u64,
     /// The number of active wait_by_hash requests that can be active at any given time.
     pub wait_by_hash_max_active_connections: usize,
+    /// Enables the pending transaction stream API
+    ///
+    /// The stream is unauthenticated and exposes all transactions pending in mempool, so it is
+    /// disabled by default.
+    #[serde(default = "default_disabled")]
+    pub pending_transaction_stream_enabled: bool,
+    /// The number of pending transaction streams that can be open at any given time.
+    pub pending_transaction_stream_max_active_connections: usize,
+    /// Enables the GraphQL read API
+    #[serde(default = "default_disabled")]
+    pub graphql_enabled: bool,
+    /// Optional: Maximum cost of a GraphQL query, in number of items read from storage.
+    ///
+    /// If not set, a query may read as many items as one full page of each paginated API.
+    pub graphql_max_query_cost: Option<u64>,
+    /// Enables the websocket API to subscribe to new blocks, events and write set changes
+    #[serde(default = "default_disabled")]
+    pub websocket_enabled: bool,
+    /// The number of websocket connections that can be open at any given time.
+    pub websocket_max_active_connections: usize,
+    /// The number of subscriptions that can be active on a single websocket connection.
+    pub websocket_max_subscriptions_per_connection: usize,
+    /// The number of committed versions read for the websocket connections at once. When more
+    /// versions were committed since the last read, the older ones are skipped, and the
+    /// connections are notified of the gap.
+    pub websocket_max_catch_up_versions: u64,
 }
 
 const DEFAULT_ADDRESS: &str = "127.0.0.1";
@@ -98,6 +140,8 @@ pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 10;
 pub const DEFAULT_MAX_PAGE_SIZE: u16 = 100;
 const DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE: u16 = 9999;
 const DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE: u16 = 9999;
+const DEFAULT_MAX_VIEW_FUNCTION_BATCH_SIZE: usize = 50;
+const DEFAULT_MAX_STATE_HISTORY_VERSION_RANGE: u64 = 10_000;
 const DEFAULT_MAX_VIEW_GAS: u64 = 2_000_000; // We keep this value the same as the max number of gas allowed for one single transaction defined in accudo-gas.
 
 fn default_enabled() -> bool {
@@ -126,12 +170,16 @@ impl Default for ApiConfig {
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
+            client_ip_header: None,
             max_block_transactions_page_size: *MAX_RECEIVING_BLOCK_TXNS as u16,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_events_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_account_resources_page_size: DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE,
             max_account_modules_page_size: DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE,
             max_gas_view_function: DEFAULT_MAX_VIEW_GAS,
+            max_view_function_batch_size: DEFAULT_MAX_VIEW_FUNCTION_BATCH_SIZE,
+            max_gas_view_function_batch: 10 * DEFAULT_MAX_VIEW_GAS,
+            max_state_history_version_range: DEFAULT_MAX_STATE_HISTORY_VERSION_RANGE,
             max_runtime_workers: None,
             runtime_worker_multiplier: 2,
             gas_estimation: GasEstimationConfig::default(),
@@ -141,6 +189,14 @@ impl Default for ApiConfig {
             wait_by_hash_timeout_ms: 1_000,
             wait_by_hash_poll_interval_ms: 20,
             wait_by_hash_max_active_connections: 100,
+            pending_transaction_stream_enabled: default_disabled(),
+            pending_transaction_stream_max_active_connections: 100,
+            graphql_enabled: default_disabled(),
+            graphql_max_query_cost: None,
+            websocket_enabled: default_disabled(),
+            websocket_max_active_connections: 100,
+            websocket_max_subscriptions_per_connection: 20,
+            websocket_max_catch_up_versions: 10_000,
         }
     }
 }
@@ -156,6 +212,21 @@ impl ApiConfig {
             None => DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT,
         }
     }
+
+    pub fn graphql_max_query_cost(&self) -> u64 {
+        self.graphql_max_query_cost.unwrap_or_else(|| {
+            [
+                self.max_transactions_page_size,
+                self.max_block_transactions_page_size,
+                self.max_events_page_size,
+                self.max_account_resources_page_size,
+                self.max_account_modules_page_size,
+            ]
+            .iter()
+            .map(|page_size| *page_size as u64)
+            .sum()
+        })
+    }
 }
 
 impl ConfigSanitizer for ApiConfig {
@@ -190,6 +261,28 @@ impl ConfigSanitizer for ApiConfig {
             ));
         }
 
+        // Verify that the GraphQL API can serve its JSON responses
+        if api_config.graphql_enabled && !api_config.json_output_enabled {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The GraphQL API requires json_output_enabled!".into(),
+            ));
+        }
+
+        // Verify that the websocket API can serve its JSON messages
+        if api_config.websocket_enabled && !api_config.json_output_enabled {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The websocket API requires json_output_enabled!".into(),
+            ));
+        }
+        if api_config.websocket_enabled && api_config.websocket_max_catch_up_versions == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "websocket_max_catch_up_versions must be greater than 0!".into(),
+            ));
+        }
+
         // Sanitize the gas estimation config
         GasEstimationConfig::sanitize(node_config, node_type, chain_id)?;
 
@@ -305,4 +398,45 @@ mod tests {
                 .unwrap_err();
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
+
+    #[test]
+    fn test_sanitize_graphql_without_json_output() {
+        // Create a node config with the GraphQL API enabled and JSON output disabled
+        let node_config = NodeConfig {
+            api: ApiConfig {
+                enabled: true,
+                json_output_enabled: false,
+                graphql_enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails because
+        // the GraphQL API only serves JSON.
+        let error =
+            ApiConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_websocket_without_catch_up_versions() {
+        // Create a node config with the websocket API enabled and no versions to catch up on
+        let node_config = NodeConfig {
+            api: ApiConfig {
+                enabled: true,
+                websocket_enabled: true,
+                websocket_max_catch_up_versions: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            ApiConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
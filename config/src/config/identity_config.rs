@@ -38,6 +38,10 @@ pub struct IdentityBlob {
     /// Optional Kyber private key for hybrid network handshakes
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network_post_quantum_private_key: Option<KyberPrivateKey>,
+    /// Optional Kyber private key that was rotated out. Hybrid handshakes that encapsulate
+    /// to it are still accepted, until peers pick up the new key from the chain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_previous_post_quantum_private_key: Option<KyberPrivateKey>,
 }
 
 impl IdentityBlob {
@@ -10,6 +10,7 @@ use accudo_global_constants::DEFAULT_BUCKETS;
 use accudo_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -37,6 +38,74 @@ impl Default for LoadBalancingThresholdConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MempoolJournalConfig {
+    /// Whether to journal accepted transactions to disk and replay them through validation on
+    /// startup, so that pending transactions survive node restarts.
+    pub enabled: bool,
+    /// Path of the journal file. Relative paths are resolved against the data directory.
+    pub path: PathBuf,
+    /// Maximum size of the journal in bytes. When exceeded, the journal is compacted down to the
+    /// transactions still pending in the mempool, dropping the oldest ones if needed.
+    pub max_bytes: u64,
+}
+
+impl Default for MempoolJournalConfig {
+    fn default() -> MempoolJournalConfig {
+        MempoolJournalConfig {
+            enabled: false,
+            path: PathBuf::from("mempool_journal"),
+            max_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// A token bucket quota on the number of transactions admitted into mempool.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdmissionQuotaConfig {
+    /// Maximum number of transactions admitted in a burst
+    pub bucket_size: usize,
+    /// Number of transactions admitted per second once the burst is used up
+    pub fill_rate: usize,
+}
+
+/// A quota shared by all transactions whose gas unit price falls into a tier.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GasPriceTierQuotaConfig {
+    /// Lowest gas unit price of the tier. The tier spans up to the next higher tier.
+    pub min_gas_unit_price: u64,
+    pub quota: AdmissionQuotaConfig,
+}
+
+/// Admission quotas applied to the transactions submitted by clients of the node, on top of the
+/// mempool capacity limits. A transaction is only admitted if none of the quotas is exhausted.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MempoolAdmissionQuotasConfig {
+    /// Quota per IP address the transaction was submitted from. Behind a reverse proxy, all the
+    /// clients share the quota of the proxy unless `api.client_ip_header` is set.
+    pub per_ip: Option<AdmissionQuotaConfig>,
+    /// Quota per transaction sender
+    pub per_sender: Option<AdmissionQuotaConfig>,
+    /// Quota per module of the entry function called by the transaction
+    pub per_module: Option<AdmissionQuotaConfig>,
+    /// Quotas per gas unit price tier. Transactions priced below the lowest tier are not limited.
+    pub per_gas_price_tier: Vec<GasPriceTierQuotaConfig>,
+}
+
+impl MempoolAdmissionQuotasConfig {
+    fn quotas(&self) -> impl Iterator<Item = &AdmissionQuotaConfig> {
+        self.per_ip
+            .iter()
+            .chain(self.per_sender.iter())
+            .chain(self.per_module.iter())
+            .chain(self.per_gas_price_tier.iter().map(|tier| &tier.quota))
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MempoolConfig {
@@ -104,6 +173,17 @@ pub struct MempoolConfig {
     pub enable_max_load_balancing_at_any_load: bool,
     /// Maximum number of orderless transactions allowed in the Mempool per user
     pub orderless_txn_capacity_per_user: usize,
+    /// Minimum gas unit price increase (in percent) for a pending transaction to be replaced by one
+    /// with a different payload, expiration time or max gas amount, e.g. to cancel it. If not set,
+    /// a pending transaction can only be replaced by the same one with a higher gas unit price.
+    pub replacement_min_gas_price_bump_pct: Option<u64>,
+    /// Number of transaction events buffered for each subscriber of the pending transaction feed.
+    /// Subscribers falling further behind miss the oldest events.
+    pub transaction_event_channel_size: usize,
+    /// On-disk journal of accepted transactions, replayed on startup.
+    pub journal: MempoolJournalConfig,
+    /// Admission quotas for transactions submitted by clients of this node.
+    pub admission_quotas: MempoolAdmissionQuotasConfig,
 }
 
 impl Default for MempoolConfig {
@@ -170,17 +250,37 @@ impl Default for MempoolConfig {
             ],
             enable_max_load_balancing_at_any_load: false,
             orderless_txn_capacity_per_user: 1000,
+            replacement_min_gas_price_bump_pct: Some(10),
+            transaction_event_channel_size: 4_096,
+            journal: MempoolJournalConfig::default(),
+            admission_quotas: MempoolAdmissionQuotasConfig::default(),
         }
     }
 }
 
 impl ConfigSanitizer for MempoolConfig {
     fn sanitize(
-        _node_config: &NodeConfig,
+        node_config: &NodeConfig,
         _node_type: NodeType,
         _chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
-        Ok(()) // TODO: add reasonable verifications
+        let sanitizer_name = Self::get_sanitizer_name();
+        let mempool_config = &node_config.mempool;
+
+        // Verify that the admission quotas can back a token bucket
+        for quota in mempool_config.admission_quotas.quotas() {
+            if quota.fill_rate == 0 || quota.bucket_size < quota.fill_rate {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "Admission quotas must have a positive fill rate no larger than the bucket size! Got: {:?}",
+                        quota
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -352,4 +452,30 @@ mod tests {
             local_max_broadcasts_per_peer
         );
     }
+
+    #[test]
+    fn test_sanitize_invalid_admission_quotas() {
+        // Create a node config with a quota that refills faster than its bucket size
+        let node_config = NodeConfig {
+            mempool: MempoolConfig {
+                admission_quotas: MempoolAdmissionQuotasConfig {
+                    per_gas_price_tier: vec![GasPriceTierQuotaConfig {
+                        min_gas_unit_price: 0,
+                        quota: AdmissionQuotaConfig {
+                            bucket_size: 10,
+                            fill_rate: 100,
+                        },
+                    }],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            MempoolConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
@@ -39,6 +39,8 @@ use std::{
 /// Current supported protocol negotiation handshake version. See
 /// [`accudo_network::protocols::wire::v1`](../../network/protocols/wire/handshake/v1/index.html).
 pub const HANDSHAKE_VERSION: u8 = 1;
+/// Handshake version for the classical Noise IK handshake, without the Kyber768 hybrid mode.
+pub const CLASSICAL_HANDSHAKE_VERSION: u8 = 0;
 pub const NETWORK_CHANNEL_SIZE: usize = 1024;
 pub const PING_INTERVAL_MS: u64 = 10_000;
 pub const PING_TIMEOUT_MS: u64 = 20_000;
@@ -128,6 +130,10 @@ pub struct NetworkConfig {
     pub max_parallel_deserialization_tasks: Option<usize>,
     /// Whether or not to enable latency aware peer dialing
     pub enable_latency_aware_dialing: bool,
+    /// Whether peers must complete the hybrid Noise + Kyber768 handshake. This only applies
+    /// to the validator network. Otherwise, the hybrid handshake is used with any peer that
+    /// advertises a Kyber key and the classical Noise IK handshake is accepted from the rest.
+    pub require_post_quantum_handshake: bool,
 }
 
 impl Default for NetworkConfig {
@@ -169,6 +175,7 @@ impl NetworkConfig {
             outbound_tx_buffer_size_bytes: None,
             max_parallel_deserialization_tasks: None,
             enable_latency_aware_dialing: true,
+            require_post_quantum_handshake: true,
         };
 
         // Configure the number of parallel deserialization tasks
@@ -189,6 +196,15 @@ impl NetworkConfig {
         }
     }
 
+    /// Returns the minimum handshake version this network accepts from its peers
+    pub fn handshake_version(&self) -> u8 {
+        if self.require_post_quantum_handshake && self.network_id.is_validator_network() {
+            HANDSHAKE_VERSION
+        } else {
+            CLASSICAL_HANDSHAKE_VERSION
+        }
+    }
+
     pub fn identity_key(&self) -> x25519::PrivateKey {
         let key = match &self.identity {
             Identity::FromConfig(config) => Some(config.private_key()),
@@ -235,6 +251,16 @@ impl NetworkConfig {
         }
     }
 
+    /// Returns the rotated out Kyber key that inbound hybrid handshakes may still use
+    pub fn identity_previous_post_quantum_key(&self) -> Option<KyberPrivateKey> {
+        match &self.identity {
+            Identity::FromFile(config) => IdentityBlob::from_file(&config.path)
+                .ok()
+                .and_then(|blob| blob.network_previous_post_quantum_private_key),
+            _ => None,
+        }
+    }
+
     pub fn discovery_methods(&self) -> Vec<&DiscoveryMethod> {
         // TODO: This is a backwards compatibility feature.  Deprecate discovery_method
         if self.discovery_method != DiscoveryMethod::None && !self.discovery_methods.is_empty() {
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::Error;
-use accudo_secure_storage::{InMemoryStorage, Namespaced, OnDiskStorage, Storage, VaultStorage};
+use accudo_secure_storage::{
+    InMemoryStorage, Namespaced, OnDiskStorage, RemoteSigner, RemoteSignerStorage, Storage,
+    VaultStorage,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     fs::File,
@@ -20,6 +23,7 @@ pub enum SecureBackend {
     InMemoryStorage,
     Vault(VaultConfig),
     OnDiskStorage(OnDiskStorageConfig),
+    RemoteSigner(RemoteSignerConfig),
 }
 
 impl SecureBackend {
@@ -29,6 +33,7 @@ impl SecureBackend {
             | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. }) => {
                 namespace.as_deref()
             },
+            SecureBackend::RemoteSigner(config) => config.storage.namespace(),
             SecureBackend::InMemoryStorage => None,
         }
     }
@@ -39,13 +44,25 @@ impl SecureBackend {
             | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. }) => {
                 *namespace = None;
             },
+            SecureBackend::RemoteSigner(config) => config.storage.clear_namespace(),
             SecureBackend::InMemoryStorage => {},
         }
     }
 
     /// Returns true iff the backend is in memory
     pub fn is_in_memory(&self) -> bool {
-        matches!(self, SecureBackend::InMemoryStorage)
+        match self {
+            SecureBackend::RemoteSigner(config) => config.storage.is_in_memory(),
+            backend => matches!(backend, SecureBackend::InMemoryStorage),
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        match self {
+            SecureBackend::OnDiskStorage(config) => config.set_data_dir(data_dir),
+            SecureBackend::RemoteSigner(config) => config.storage.set_data_dir(data_dir),
+            SecureBackend::InMemoryStorage | SecureBackend::Vault(_) => {},
+        }
     }
 }
 
@@ -97,6 +114,25 @@ pub struct OnDiskStorageConfig {
     data_dir: PathBuf,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteSignerConfig {
+    /// Path to the Unix domain socket of the external signer holding the consensus key. This is
+    /// expected to be a full path.
+    pub socket_path: PathBuf,
+    /// Timeout for each request to the signer, in milliseconds.
+    #[serde(default = "RemoteSignerConfig::default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Storage for all other data, e.g., safety data and the waypoint.
+    pub storage: Box<SecureBackend>,
+}
+
+impl RemoteSignerConfig {
+    fn default_timeout_ms() -> u64 {
+        5_000
+    }
+}
+
 /// Tokens can either be directly within this config or stored somewhere on disk.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -191,6 +227,10 @@ impl From<&SecureBackend> for Storage {
                     storage
                 }
             },
+            SecureBackend::RemoteSigner(config) => Storage::from(RemoteSignerStorage::new(
+                Box::new(config.storage.as_ref().into()),
+                RemoteSigner::new(config.socket_path.clone(), config.timeout_ms),
+            )),
         }
     }
 }
@@ -290,6 +330,28 @@ vault:
         serde_yaml::to_string(&from_disk).unwrap();
     }
 
+    #[test]
+    fn test_remote_signer_parsing() {
+        let text = r#"
+type: "remote_signer"
+socket_path: "/run/signer.sock"
+storage:
+    type: "in_memory_storage"
+        "#;
+
+        let backend: SecureBackend = serde_yaml::from_str(text).unwrap();
+        assert_eq!(
+            backend,
+            SecureBackend::RemoteSigner(RemoteSignerConfig {
+                socket_path: PathBuf::from("/run/signer.sock"),
+                timeout_ms: 5_000,
+                storage: Box::new(SecureBackend::InMemoryStorage),
+            })
+        );
+        assert!(backend.is_in_memory());
+        serde_yaml::to_string(&backend).unwrap();
+    }
+
     #[test]
     fn test_token_reading() {
         let temppath = accudo_temppath::TempPath::new();
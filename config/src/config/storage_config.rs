@@ -10,9 +10,10 @@ use crate::{
     utils,
 };
 use accudo_logger::warn;
-use accudo_types::chain_id::ChainId;
+use accudo_types::{account_address::AccountAddress, chain_id::ChainId};
 use anyhow::{bail, ensure, Result};
 use arr_macro::arr;
+use move_core_types::language_storage::StructTag;
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
 use std::{
@@ -32,6 +33,8 @@ pub const BUFFERED_STATE_TARGET_ITEMS_FOR_TEST: usize = 10;
 #[serde(default, deny_unknown_fields)]
 pub struct DbPathConfig {
     pub ledger_db_path: Option<PathBuf>,
+    /// Where the ledger cold DB lives, see `LedgerColdStorageConfig`.
+    pub ledger_cold_db_path: Option<PathBuf>,
     pub state_kv_db_path: Option<ShardedDbPathConfig>,
     pub state_merkle_db_path: Option<ShardedDbPathConfig>,
     pub hot_state_kv_db_path: Option<ShardedDbPathConfig>,
@@ -250,6 +253,8 @@ pub struct StorageConfig {
     pub ensure_rlimit_nofile: u64,
     /// panic if failed to ensure `ulimit -n`
     pub assert_rlimit_nofile: bool,
+    /// Keeps the history of selected state keys beyond the prune window.
+    pub state_value_history_config: StateValueHistoryConfig,
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -259,6 +264,11 @@ pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
         batch_size: 0,
         user_pruning_window_offset: 0,
     },
+    ledger_cold_storage_config: LedgerColdStorageConfig {
+        enable: false,
+        hot_window: 0,
+        batch_size: 0,
+    },
     state_merkle_pruner_config: StateMerklePrunerConfig {
         enable: false,
         prune_window: 0,
@@ -289,6 +299,37 @@ pub struct LedgerPrunerConfig {
     pub user_pruning_window_offset: u64,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LedgerColdStorageConfig {
+    /// Boolean to enable/disable moving old ledger history (transactions, write sets and events)
+    /// out of the ledger DB into the ledger cold DB, which can live on a different (cheaper) disk
+    /// via `db_path_overrides.ledger_cold_db_path`. Reads fall through to the cold DB
+    /// transparently. Not compatible with the ledger pruner.
+    pub enable: bool,
+    /// Number of the most recent versions kept in the ledger DB.
+    pub hot_window: u64,
+    /// Number of versions to move to the cold DB a time.
+    pub batch_size: usize,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StateValueHistoryConfig {
+    /// Boolean to enable/disable keeping a change log of the state keys matching any of the
+    /// filters below, which is not pruned by the state kv pruner, so that historical reads of
+    /// those keys keep working on a pruned node. The history of a filter is only available since
+    /// the version it's added at.
+    pub enable: bool,
+    /// Accounts whose resources and modules are kept.
+    pub accounts: Vec<AccountAddress>,
+    /// Resource (or resource group) types kept under any account, e.g.
+    /// "0x1::coin::CoinStore<0x1::accudo_coin::AccudoCoin>".
+    pub resource_types: Vec<String>,
+    /// Table handles whose items are kept.
+    pub table_handles: Vec<AccountAddress>,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StateMerklePrunerConfig {
@@ -329,6 +370,7 @@ impl From<EpochSnapshotPrunerConfig> for StateMerklePrunerConfig {
 #[serde(default, deny_unknown_fields)]
 pub struct PrunerConfig {
     pub ledger_pruner_config: LedgerPrunerConfig,
+    pub ledger_cold_storage_config: LedgerColdStorageConfig,
     pub state_merkle_pruner_config: StateMerklePrunerConfig,
     pub epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig,
 }
@@ -344,6 +386,16 @@ impl Default for LedgerPrunerConfig {
     }
 }
 
+impl Default for LedgerColdStorageConfig {
+    fn default() -> Self {
+        LedgerColdStorageConfig {
+            enable: false,
+            hot_window: 10_000_000,
+            batch_size: 5_000,
+        }
+    }
+}
+
 impl Default for StateMerklePrunerConfig {
     fn default() -> Self {
         StateMerklePrunerConfig {
@@ -399,6 +451,7 @@ impl Default for StorageConfig {
             max_num_nodes_per_lru_cache_shard: DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
             ensure_rlimit_nofile: 0,
             assert_rlimit_nofile: false,
+            state_value_history_config: StateValueHistoryConfig::default(),
         }
     }
 }
@@ -415,6 +468,7 @@ impl StorageConfig {
     pub fn get_dir_paths(&self) -> StorageDirPaths {
         let default_dir = self.dir();
         let mut ledger_db_path = None;
+        let mut ledger_cold_db_path = None;
         let mut state_kv_db_paths = ShardedDbPaths::default();
         let mut state_merkle_db_paths = ShardedDbPaths::default();
         let mut hot_state_kv_db_paths = ShardedDbPaths::default();
@@ -423,6 +477,9 @@ impl StorageConfig {
             db_path_overrides
                 .ledger_db_path
                 .clone_into(&mut ledger_db_path);
+            db_path_overrides
+                .ledger_cold_db_path
+                .clone_into(&mut ledger_cold_db_path);
 
             if let Some(state_kv_db_path) = db_path_overrides.state_kv_db_path.as_ref() {
                 state_kv_db_paths = ShardedDbPaths::new(state_kv_db_path);
@@ -440,6 +497,7 @@ impl StorageConfig {
         StorageDirPaths::new(
             default_dir,
             ledger_db_path,
+            ledger_cold_db_path,
             state_kv_db_paths,
             state_merkle_db_paths,
             hot_state_kv_db_paths,
@@ -460,6 +518,7 @@ impl StorageConfig {
 pub struct StorageDirPaths {
     default_path: PathBuf,
     ledger_db_path: Option<PathBuf>,
+    ledger_cold_db_path: Option<PathBuf>,
     state_kv_db_paths: ShardedDbPaths,
     state_merkle_db_paths: ShardedDbPaths,
     hot_state_kv_db_paths: ShardedDbPaths,
@@ -478,6 +537,12 @@ impl StorageDirPaths {
         }
     }
 
+    pub fn ledger_cold_db_root_path(&self) -> &PathBuf {
+        self.ledger_cold_db_path
+            .as_ref()
+            .unwrap_or(&self.default_path)
+    }
+
     pub fn state_kv_db_metadata_root_path(&self) -> &PathBuf {
         self.state_kv_db_paths
             .metadata_path()
@@ -512,6 +577,7 @@ impl StorageDirPaths {
         Self {
             default_path: path.as_ref().to_path_buf(),
             ledger_db_path: None,
+            ledger_cold_db_path: None,
             state_kv_db_paths: Default::default(),
             state_merkle_db_paths: Default::default(),
             hot_state_kv_db_paths: Default::default(),
@@ -521,6 +587,7 @@ impl StorageDirPaths {
     fn new(
         default_path: PathBuf,
         ledger_db_path: Option<PathBuf>,
+        ledger_cold_db_path: Option<PathBuf>,
         state_kv_db_paths: ShardedDbPaths,
         state_merkle_db_paths: ShardedDbPaths,
         hot_state_kv_db_paths: ShardedDbPaths,
@@ -528,6 +595,7 @@ impl StorageDirPaths {
         Self {
             default_path,
             ledger_db_path,
+            ledger_cold_db_path,
             state_kv_db_paths,
             state_merkle_db_paths,
             hot_state_kv_db_paths,
@@ -644,6 +712,46 @@ impl ConfigSanitizer for StorageConfig {
             ));
         }
 
+        let ledger_cold_storage_config = &config.storage_pruner_config.ledger_cold_storage_config;
+        if ledger_cold_storage_config.enable {
+            if config.storage_pruner_config.ledger_pruner_config.enable {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "ledger_cold_storage_config and ledger_pruner_config can't be enabled at the same time.".to_string(),
+                ));
+            }
+            if ledger_cold_storage_config.hot_window == 0
+                || ledger_cold_storage_config.batch_size == 0
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "hot_window and batch_size in ledger_cold_storage_config must be positive."
+                        .to_string(),
+                ));
+            }
+        }
+
+        let state_value_history_config = &config.state_value_history_config;
+        if state_value_history_config.enable {
+            if state_value_history_config.accounts.is_empty()
+                && state_value_history_config.resource_types.is_empty()
+                && state_value_history_config.table_handles.is_empty()
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "state_value_history_config is enabled without any key to keep.".to_string(),
+                ));
+            }
+            for resource_type in &state_value_history_config.resource_types {
+                if let Err(err) = StructTag::from_str(resource_type) {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        format!("Invalid resource type {resource_type:?} in state_value_history_config: {err}"),
+                    ));
+                }
+            }
+        }
+
         if let Some(db_path_overrides) = config.db_path_overrides.as_ref() {
             if !config.rocksdb_configs.enable_storage_sharding {
                 return Err(Error::ConfigSanitizerFailed(
@@ -662,6 +770,16 @@ impl ConfigSanitizer for StorageConfig {
                 }
             }
 
+            if let Some(ledger_cold_db_path) = db_path_overrides.ledger_cold_db_path.as_ref() {
+                if !ledger_cold_db_path.is_absolute() {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        "Path {ledger_cold_db_path:?} in db_path_overrides is not an absolute path."
+                            .to_string(),
+                    ));
+                }
+            }
+
             if let Some(state_kv_db_path) = db_path_overrides.state_kv_db_path.as_ref() {
                 if let Some(metadata_path) = state_kv_db_path.metadata_path.as_ref() {
                     if !metadata_path.is_absolute() {
@@ -702,8 +820,8 @@ impl ConfigSanitizer for StorageConfig {
 #[cfg(test)]
 mod test {
     use crate::config::{
-        config_optimizer::ConfigOptimizer, NodeConfig, NodeType, PrunerConfig, ShardPathConfig,
-        ShardedDbPathConfig, StorageConfig,
+        config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer, NodeConfig, NodeType,
+        PrunerConfig, ShardPathConfig, ShardedDbPathConfig, StorageConfig,
     };
     use accudo_types::chain_id::ChainId;
 
@@ -782,6 +900,54 @@ mod test {
         assert!(path_overrides.get_shard_paths().is_err());
     }
 
+    #[test]
+    fn test_sanitize_ledger_cold_storage_config() {
+        let mut node_config = NodeConfig::default();
+        node_config
+            .storage
+            .storage_pruner_config
+            .ledger_cold_storage_config
+            .enable = true;
+        // The ledger pruner is enabled by default.
+        assert!(StorageConfig::sanitize(&node_config, NodeType::Validator, None).is_err());
+
+        node_config
+            .storage
+            .storage_pruner_config
+            .ledger_pruner_config
+            .enable = false;
+        assert!(StorageConfig::sanitize(&node_config, NodeType::Validator, None).is_ok());
+
+        node_config
+            .storage
+            .storage_pruner_config
+            .ledger_cold_storage_config
+            .hot_window = 0;
+        assert!(StorageConfig::sanitize(&node_config, NodeType::Validator, None).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_state_value_history_config() {
+        let mut node_config = NodeConfig::default();
+        node_config.storage.state_value_history_config.enable = true;
+        // Nothing to keep.
+        assert!(StorageConfig::sanitize(&node_config, NodeType::Validator, None).is_err());
+
+        node_config
+            .storage
+            .state_value_history_config
+            .resource_types
+            .push("0x1::account::Account".to_string());
+        assert!(StorageConfig::sanitize(&node_config, NodeType::Validator, None).is_ok());
+
+        node_config
+            .storage
+            .state_value_history_config
+            .resource_types
+            .push("0x1::account".to_string());
+        assert!(StorageConfig::sanitize(&node_config, NodeType::Validator, None).is_err());
+    }
+
     #[test]
     fn test_optimize_ensure_rlimit_nofile() {
         let mut node_config = NodeConfig::default();
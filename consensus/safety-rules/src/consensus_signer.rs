@@ -0,0 +1,54 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Error;
+use accudo_consensus_types::common::Author;
+use accudo_crypto::{bls12381, hash::CryptoHash, signing_message};
+use accudo_secure_storage::RemoteSigner;
+use accudo_types::validator_signer::ValidatorSigner;
+use serde::Serialize;
+
+/// Signs consensus messages either with a consensus key held in memory or by delegating to an
+/// external signer that holds the key on our behalf.
+pub(crate) enum ConsensusSigner {
+    Local(ValidatorSigner),
+    Remote {
+        author: Author,
+        public_key: bls12381::PublicKey,
+        signer: RemoteSigner,
+    },
+}
+
+impl ConsensusSigner {
+    pub fn author(&self) -> Author {
+        match self {
+            ConsensusSigner::Local(signer) => signer.author(),
+            ConsensusSigner::Remote { author, .. } => *author,
+        }
+    }
+
+    pub fn public_key(&self) -> bls12381::PublicKey {
+        match self {
+            ConsensusSigner::Local(signer) => signer.public_key(),
+            ConsensusSigner::Remote { public_key, .. } => public_key.clone(),
+        }
+    }
+
+    pub fn sign<T: Serialize + CryptoHash>(
+        &self,
+        message: &T,
+    ) -> Result<bls12381::Signature, Error> {
+        match self {
+            ConsensusSigner::Local(signer) => signer
+                .sign(message)
+                .map_err(|err| Error::SerializationError(err.to_string())),
+            ConsensusSigner::Remote {
+                public_key, signer, ..
+            } => {
+                let signing_message = signing_message(message)
+                    .map_err(|err| Error::SerializationError(err.to_string()))?;
+                Ok(signer.sign(public_key, &signing_message)?)
+            },
+        }
+    }
+}
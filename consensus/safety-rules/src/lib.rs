@@ -4,6 +4,7 @@
 
 #![forbid(unsafe_code)]
 
+mod consensus_signer;
 mod consensus_state;
 mod counters;
 mod error;
@@ -11,7 +11,7 @@ use accudo_consensus_types::{common::Author, safety_data::SafetyData};
 use accudo_crypto::{bls12381, PrivateKey};
 use accudo_global_constants::{CONSENSUS_KEY, OWNER_ACCOUNT, SAFETY_DATA, WAYPOINT};
 use accudo_logger::prelude::*;
-use accudo_secure_storage::{KVStorage, Storage};
+use accudo_secure_storage::{KVStorage, RemoteSigner, Storage};
 use accudo_types::waypoint::Waypoint;
 
 /// SafetyRules needs an abstract storage interface to act as a common utility for storing
@@ -32,11 +32,48 @@ impl PersistentSafetyStorage {
     /// Use this to instantiate a PersistentStorage for a new data store, one that has no
     /// SafetyRules values set.
     pub fn initialize(
-        mut internal_store: Storage,
+        internal_store: Storage,
         author: Author,
         consensus_private_key: bls12381::PrivateKey,
         waypoint: Waypoint,
         enable_cached_safety_data: bool,
+    ) -> Self {
+        Self::initialize_internal(
+            internal_store,
+            author,
+            Some(consensus_private_key),
+            waypoint,
+            enable_cached_safety_data,
+        )
+    }
+
+    /// Use this to instantiate a PersistentStorage for a new data store whose consensus key is
+    /// held by a remote signer, and therefore never written to storage.
+    pub fn initialize_with_remote_signer(
+        internal_store: Storage,
+        author: Author,
+        waypoint: Waypoint,
+        enable_cached_safety_data: bool,
+    ) -> Self {
+        assert!(
+            internal_store.remote_signer().is_some(),
+            "Storage is not backed by a remote signer"
+        );
+        Self::initialize_internal(
+            internal_store,
+            author,
+            None,
+            waypoint,
+            enable_cached_safety_data,
+        )
+    }
+
+    fn initialize_internal(
+        mut internal_store: Storage,
+        author: Author,
+        consensus_private_key: Option<bls12381::PrivateKey>,
+        waypoint: Waypoint,
+        enable_cached_safety_data: bool,
     ) -> Self {
         // Initialize the keys and accounts
         Self::initialize_keys_and_accounts(&mut internal_store, author, consensus_private_key)
@@ -64,17 +101,19 @@ impl PersistentSafetyStorage {
     fn initialize_keys_and_accounts(
         internal_store: &mut Storage,
         author: Author,
-        consensus_private_key: bls12381::PrivateKey,
+        consensus_private_key: Option<bls12381::PrivateKey>,
     ) -> Result<(), Error> {
-        let result = internal_store.set(CONSENSUS_KEY, consensus_private_key);
-        // Attempting to re-initialize existing storage. This can happen in environments like
-        // forge. Rather than be rigid here, leave it up to the developer to detect
-        // inconsistencies or why they did not reset storage between rounds. Do not repeat the
-        // checks again below, because it is just too strange to have a partially configured
-        // storage.
-        if let Err(accudo_secure_storage::Error::KeyAlreadyExists(_)) = result {
-            warn!("Attempted to re-initialize existing storage");
-            return Ok(());
+        if let Some(consensus_private_key) = consensus_private_key {
+            let result = internal_store.set(CONSENSUS_KEY, consensus_private_key);
+            // Attempting to re-initialize existing storage. This can happen in environments like
+            // forge. Rather than be rigid here, leave it up to the developer to detect
+            // inconsistencies or why they did not reset storage between rounds. Do not repeat the
+            // checks again below, because it is just too strange to have a partially configured
+            // storage.
+            if let Err(accudo_secure_storage::Error::KeyAlreadyExists(_)) = result {
+                warn!("Attempted to re-initialize existing storage");
+                return Ok(());
+            }
         }
 
         internal_store.set(OWNER_ACCOUNT, author)?;
@@ -96,6 +135,11 @@ impl PersistentSafetyStorage {
         Ok(self.internal_store.get(OWNER_ACCOUNT).map(|v| v.value)?)
     }
 
+    /// Returns the remote signer holding the consensus key, if the storage is backed by one
+    pub fn remote_signer(&self) -> Option<&RemoteSigner> {
+        self.internal_store.remote_signer()
+    }
+
     pub fn default_consensus_sk(
         &self,
     ) -> Result<bls12381::PrivateKey, accudo_secure_storage::Error> {
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_signer::ConsensusSigner,
     consensus_state::ConsensusState,
     counters,
     error::Error,
@@ -41,7 +42,7 @@ pub(crate) fn next_round(round: Round) -> Result<Round, Error> {
 /// @TODO consider a cache of verified QCs to cut down on verification costs
 pub struct SafetyRules {
     pub(crate) persistent_storage: PersistentSafetyStorage,
-    pub(crate) validator_signer: Option<ValidatorSigner>,
+    pub(crate) validator_signer: Option<ConsensusSigner>,
     pub(crate) epoch_state: Option<EpochState>,
     // Skip verification of signatures and well-formed, this can be set if it's used in local mode
     // where consensus already verifies.
@@ -115,13 +116,10 @@ impl SafetyRules {
         &self,
         message: &T,
     ) -> Result<bls12381::Signature, Error> {
-        let signer = self.signer()?;
-        signer
-            .sign(message)
-            .map_err(|err| Error::SerializationError(err.to_string()))
+        self.signer()?.sign(message)
     }
 
-    pub(crate) fn signer(&self) -> Result<&ValidatorSigner, Error> {
+    pub(crate) fn signer(&self) -> Result<&ConsensusSigner, Error> {
         self.validator_signer
             .as_ref()
             .ok_or_else(|| Error::NotInitialized("validator_signer".into()))
@@ -322,12 +320,31 @@ impl SafetyRules {
                         "in set",
                     );
                     Ok(())
+                } else if let Some(signer) = self.persistent_storage.remote_signer() {
+                    // The consensus key is held by an external signer, so a signer that can't
+                    // be reached is reported as such rather than as a missing key.
+                    match signer.public_keys() {
+                        Ok(public_keys) if public_keys.contains(&expected_key) => {
+                            self.validator_signer = Some(ConsensusSigner::Remote {
+                                author,
+                                public_key: expected_key,
+                                signer: signer.clone(),
+                            });
+                            Ok(())
+                        },
+                        Ok(_) => Err(Error::ValidatorKeyNotFound(format!(
+                            "Remote signer does not hold the consensus key {}",
+                            expected_key
+                        ))),
+                        Err(error) => Err(error.into()),
+                    }
                 } else {
                     // Try to export the consensus key directly from storage.
                     match self.persistent_storage.consensus_sk_by_pk(expected_key) {
                         Ok(consensus_key) => {
-                            self.validator_signer =
-                                Some(ValidatorSigner::new(author, Arc::new(consensus_key)));
+                            self.validator_signer = Some(ConsensusSigner::Local(
+                                ValidatorSigner::new(author, Arc::new(consensus_key)),
+                            ));
                             Ok(())
                         },
                         Err(Error::SecureStorageMissingDataError(error)) => {
@@ -60,17 +60,28 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
 
             let backend = &config.backend;
             let internal_storage: Storage = backend.into();
-            PersistentSafetyStorage::initialize(
-                internal_storage,
-                identity_blob
-                    .account_address
-                    .expect("AccountAddress needed for safety rules"),
-                identity_blob
-                    .consensus_private_key
-                    .expect("Consensus key needed for safety rules"),
-                waypoint,
-                config.enable_cached_safety_data,
-            )
+            let author = identity_blob
+                .account_address
+                .expect("AccountAddress needed for safety rules");
+            match identity_blob.consensus_private_key {
+                Some(consensus_private_key) => PersistentSafetyStorage::initialize(
+                    internal_storage,
+                    author,
+                    consensus_private_key,
+                    waypoint,
+                    config.enable_cached_safety_data,
+                ),
+                // The consensus key is held by the remote signer rather than the identity blob
+                None if internal_storage.remote_signer().is_some() => {
+                    PersistentSafetyStorage::initialize_with_remote_signer(
+                        internal_storage,
+                        author,
+                        waypoint,
+                        config.enable_cached_safety_data,
+                    )
+                },
+                None => panic!("Consensus key needed for safety rules"),
+            }
         } else {
             panic!(
                 "Safety rules storage is not initialized, provide an initial safety rules config"
@@ -5,6 +5,8 @@
 extern crate claims;
 mod local;
 mod networking;
+#[cfg(unix)]
+mod remote_signer;
 mod safety_rules;
 mod serializer;
 mod suite;
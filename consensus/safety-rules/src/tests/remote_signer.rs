@@ -0,0 +1,78 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    test_utils, tests::suite, Error, PersistentSafetyStorage, SafetyRules, SafetyRulesManager,
+    TSafetyRules,
+};
+use accudo_crypto::bls12381;
+use accudo_global_constants::CONSENSUS_KEY;
+use accudo_secure_storage::{
+    InMemoryStorage, KVStorage, LocalSigner, RemoteSigner, RemoteSignerStorage, Storage,
+};
+use accudo_types::validator_signer::ValidatorSigner;
+use std::thread;
+
+/// Verifies that SafetyRules can sign through a remote signer without the consensus key ever
+/// being written to storage.
+#[test]
+fn test() {
+    suite::run_test_suite(&safety_rules());
+}
+
+fn safety_rules() -> suite::Callback {
+    Box::new(move || {
+        let signer = ValidatorSigner::from_int(0);
+
+        // The socket lives for the remainder of the test process
+        let socket_path = tempfile::tempdir().unwrap().into_path().join("signer.sock");
+        let listener = LocalSigner::bind(&socket_path).unwrap();
+        let local_signer = LocalSigner::new(vec![signer.private_key().clone()]);
+        thread::spawn(move || local_signer.serve(listener));
+
+        let storage = Storage::from(RemoteSignerStorage::new(
+            Box::new(Storage::from(InMemoryStorage::new())),
+            RemoteSigner::new(socket_path, 5_000),
+        ));
+        let waypoint = test_utils::validator_signers_to_waypoint(&[&signer]);
+        let mut storage = PersistentSafetyStorage::initialize_with_remote_signer(
+            storage,
+            signer.author(),
+            waypoint,
+            true,
+        );
+        assert!(storage
+            .internal_store()
+            .get::<bls12381::PrivateKey>(CONSENSUS_KEY)
+            .is_err());
+
+        let safety_rules_manager = SafetyRulesManager::new_local(storage);
+        let safety_rules = safety_rules_manager.client();
+        (safety_rules, signer)
+    })
+}
+
+/// Verifies that an unreachable signer is reported as a storage error instead of a missing key.
+#[test]
+fn test_unreachable_signer() {
+    let signer = ValidatorSigner::from_int(0);
+    let socket_path = tempfile::tempdir().unwrap().into_path().join("signer.sock");
+    let storage = Storage::from(RemoteSignerStorage::new(
+        Box::new(Storage::from(InMemoryStorage::new())),
+        RemoteSigner::new(socket_path, 5_000),
+    ));
+    let waypoint = test_utils::validator_signers_to_waypoint(&[&signer]);
+    let storage = PersistentSafetyStorage::initialize_with_remote_signer(
+        storage,
+        signer.author(),
+        waypoint,
+        true,
+    );
+
+    let mut safety_rules = SafetyRules::new(storage, false);
+    let (proof, _) = test_utils::make_genesis(&signer);
+    assert!(matches!(
+        safety_rules.initialize(&proof),
+        Err(Error::SecureStorageUnexpectedError(_))
+    ));
+}
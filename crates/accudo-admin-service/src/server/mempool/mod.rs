@@ -1,13 +1,22 @@
 // Copyright © Accudo Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use accudo_crypto::HashValue;
 use accudo_logger::info;
 use accudo_mempool::{MempoolClientRequest, MempoolClientSender};
 use accudo_system_utils::utils::{reply_with, reply_with_status};
 use accudo_types::account_address::AccountAddress;
-use futures_channel::oneshot::Canceled;
-use http::{Request, Response, StatusCode};
+use futures_channel::oneshot::{self, Canceled};
+use http::{
+    header::{HeaderValue, CONTENT_TYPE},
+    Request, Response, StatusCode,
+};
 use hyper::Body;
+use serde::Serialize;
+use std::{collections::HashMap, str::FromStr};
+
+/// Number of transactions returned by the priority queue endpoint if no limit is given.
+const DEFAULT_PRIORITY_QUEUE_LIMIT: usize = 100;
 
 pub async fn mempool_handle_parking_lot_address_request(
     _req: Request<Body>,
@@ -53,3 +62,146 @@ async fn get_parking_lot_addresses(
         },
     }
 }
+
+/// Lists the transactions of the account given by the `sender` query parameter.
+pub async fn mempool_handle_transactions_by_sender_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    let address: AccountAddress = match parse_query_param(&req, "sender") {
+        Ok(Some(address)) => address,
+        Ok(None) => return Ok(missing_query_param("sender")),
+        Err(response) => return Ok(response),
+    };
+
+    let result = send_request(mempool_client_sender, |sender| {
+        MempoolClientRequest::GetTransactionsBySender(address, sender)
+    })
+    .await;
+    reply_with_json("transactions by sender", result)
+}
+
+/// Lists the accounts in the parking lot, with their parked sequence numbers and the gaps of
+/// sequence numbers keeping them parked.
+pub async fn mempool_handle_parking_lot_request(
+    _req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    let result = send_request(mempool_client_sender, MempoolClientRequest::GetParkingLot).await;
+    reply_with_json("parking lot", result)
+}
+
+/// Lists the ready transactions in priority queue order, up to the `limit` query parameter.
+pub async fn mempool_handle_priority_queue_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    let limit = match parse_query_param(&req, "limit") {
+        Ok(limit) => limit.unwrap_or(DEFAULT_PRIORITY_QUEUE_LIMIT),
+        Err(response) => return Ok(response),
+    };
+
+    let result = send_request(mempool_client_sender, |sender| {
+        MempoolClientRequest::GetPriorityQueue(limit, sender)
+    })
+    .await;
+    reply_with_json("priority queue", result)
+}
+
+/// Evicts the transaction given by the `hash` query parameter, and returns it, or null if it
+/// wasn't in mempool.
+pub async fn mempool_handle_evict_transaction_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    let hash: HashValue = match parse_query_param(&req, "hash") {
+        Ok(Some(hash)) => hash,
+        Ok(None) => return Ok(missing_query_param("hash")),
+        Err(response) => return Ok(response),
+    };
+
+    info!("Evicting transaction {hash} from mempool.");
+    let result = send_request(mempool_client_sender, |sender| {
+        MempoolClientRequest::EvictTransaction(hash, sender)
+    })
+    .await;
+    reply_with_json("evicted transaction", result)
+}
+
+/// Evicts all transactions of the account given by the `sender` query parameter, and returns
+/// them.
+pub async fn mempool_handle_evict_sender_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    let address: AccountAddress = match parse_query_param(&req, "sender") {
+        Ok(Some(address)) => address,
+        Ok(None) => return Ok(missing_query_param("sender")),
+        Err(response) => return Ok(response),
+    };
+
+    info!("Evicting all transactions of {address} from mempool.");
+    let result = send_request(mempool_client_sender, |sender| {
+        MempoolClientRequest::EvictSender(address, sender)
+    })
+    .await;
+    reply_with_json("evicted transactions", result)
+}
+
+fn parse_query_param<T>(req: &Request<Body>, name: &str) -> Result<Option<T>, Response<Body>>
+where
+    T: FromStr,
+    T::Err: ToString,
+{
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+    match query_pairs.get(name) {
+        Some(val) => val
+            .parse()
+            .map(Some)
+            .map_err(|err: T::Err| reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        None => Ok(None),
+    }
+}
+
+fn missing_query_param(name: &str) -> Response<Body> {
+    reply_with_status(
+        StatusCode::BAD_REQUEST,
+        format!("Missing query parameter {name}."),
+    )
+}
+
+async fn send_request<T>(
+    mempool_client_sender: MempoolClientSender,
+    request: impl FnOnce(oneshot::Sender<T>) -> MempoolClientRequest,
+) -> Result<T, Canceled> {
+    let (sender, receiver) = oneshot::channel();
+
+    match mempool_client_sender.clone().try_send(request(sender)) {
+        Ok(_) => receiver.await,
+        Err(e) => {
+            info!("Failed to send request to mempool: {e:?}");
+            Err(Canceled)
+        },
+    }
+}
+
+fn reply_with_json<T: Serialize>(
+    name: &str,
+    result: Result<T, Canceled>,
+) -> hyper::Result<Response<Body>> {
+    let body = result
+        .map_err(|e| e.to_string())
+        .and_then(|value| serde_json::to_vec_pretty(&value).map_err(|e| e.to_string()));
+    match body {
+        Ok(body) => {
+            info!("Finished getting {name} from mempool.");
+            let headers = vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))];
+            Ok(reply_with(headers, body))
+        },
+        Err(e) => {
+            info!("Failed to get {name} from mempool: {e}");
+            Ok(reply_with_status(StatusCode::INTERNAL_SERVER_ERROR, e))
+        },
+    }
+}
@@ -22,6 +22,7 @@ use std::{
     collections::HashMap,
     convert::Infallible,
     net::{SocketAddr, ToSocketAddrs},
+    path::PathBuf,
     sync::Arc,
 };
 use tokio::runtime::Runtime;
@@ -30,10 +31,14 @@ mod consensus;
 #[cfg(unix)]
 mod malloc;
 mod mempool;
+mod storage;
 
 #[derive(Default)]
 pub struct Context {
     config: AdminServiceConfig,
+    // Where DB checkpoints are created before being streamed. Lives under the storage dir, so
+    // that checkpoints can hard link the live DB files.
+    checkpoint_root: PathBuf,
 
     accudo_db: RwLock<Option<Arc<DbReaderWriter>>>,
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
@@ -95,6 +100,7 @@ impl AdminService {
             runtime,
             context: Arc::new(Context {
                 config,
+                checkpoint_root: node_config.storage.dir().join("admin_checkpoints"),
                 ..Default::default()
             }),
         };
@@ -239,6 +245,82 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/mempool/transactions") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::mempool_handle_transactions_by_sender_request(
+                        req,
+                        mempool_client_sender,
+                    )
+                    .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/mempool/parking-lot") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::mempool_handle_parking_lot_request(req, mempool_client_sender).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/mempool/priority-queue") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::mempool_handle_priority_queue_request(req, mempool_client_sender).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::POST, "/debug/mempool/evict/transaction") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::mempool_handle_evict_transaction_request(req, mempool_client_sender)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::POST, "/debug/mempool/evict/sender") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::mempool_handle_evict_sender_request(req, mempool_client_sender).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/storage/checkpoint") => {
+                let accudo_db = context.accudo_db.read().clone();
+                if let Some(accudo_db) = accudo_db {
+                    storage::handle_checkpoint_request(
+                        req,
+                        accudo_db.writer.clone(),
+                        context.checkpoint_root.clone(),
+                    )
+                    .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Accudo db is not available.",
+                    ))
+                }
+            },
             _ => Ok(reply_with_status(StatusCode::NOT_FOUND, "Not found.")),
         }
     }
@@ -0,0 +1,144 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use accudo_logger::{info, warn};
+use accudo_storage_interface::DbWriter;
+use accudo_system_utils::utils::{reply_with, reply_with_status, spawn_blocking};
+use anyhow::Result;
+use http::header::{HeaderValue, CONTENT_DISPOSITION, CONTENT_TYPE};
+use hyper::{
+    body::{Bytes, Sender},
+    Body, Request, Response, StatusCode,
+};
+use std::{
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::runtime::Handle;
+
+/// Name of the top level directory in the tarball, matching the default storage dir name, so the
+/// tarball can be extracted directly into the data dir of a new node.
+const TARBALL_ROOT: &str = "db";
+
+/// Tarball bytes are batched up to this size before being sent to the client.
+const STREAM_BUFFER_SIZE: usize = 1 << 20;
+
+/// Creates a checkpoint of the ledger DB, state KV DB and state merkle DB of the running node,
+/// and streams it back as a tarball. The checkpoint is deleted once streaming finishes.
+pub async fn handle_checkpoint_request(
+    _req: Request<Body>,
+    accudo_db: Arc<dyn DbWriter>,
+    checkpoint_root: PathBuf,
+) -> hyper::Result<Response<Body>> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let cp_path = checkpoint_root.join(format!("checkpoint_{timestamp}"));
+
+    info!("Creating DB checkpoint at {cp_path:?}.");
+
+    let path = cp_path.clone();
+    if let Err(e) = spawn_blocking(move || create_checkpoint(accudo_db.as_ref(), &path)).await {
+        warn!("Failed to create DB checkpoint: {e:?}");
+        remove_checkpoint(&cp_path);
+        return Ok(reply_with_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            e.to_string(),
+        ));
+    }
+
+    info!("Finished creating DB checkpoint, streaming it as a tarball.");
+
+    let (sender, body) = Body::channel();
+    let handle = Handle::current();
+    tokio::task::spawn_blocking(move || {
+        match stream_tarball(&cp_path, BodyWriter::new(sender, handle)) {
+            Ok(()) => info!("Finished streaming DB checkpoint."),
+            Err(e) => warn!("Failed to stream DB checkpoint: {e:?}"),
+        }
+        remove_checkpoint(&cp_path);
+    });
+
+    let headers: Vec<(_, HeaderValue)> = vec![
+        (CONTENT_TYPE, HeaderValue::from_static("application/x-tar")),
+        (
+            CONTENT_DISPOSITION,
+            HeaderValue::from_static("attachment; filename=\"db_checkpoint.tar\""),
+        ),
+    ];
+    Ok(reply_with(headers, body))
+}
+
+fn create_checkpoint(accudo_db: &dyn DbWriter, cp_path: &Path) -> Result<()> {
+    std::fs::create_dir_all(cp_path)?;
+    accudo_db.create_live_checkpoint(cp_path)?;
+    Ok(())
+}
+
+fn stream_tarball(cp_path: &Path, writer: BodyWriter) -> Result<()> {
+    let mut builder = tar::Builder::new(BufWriter::with_capacity(STREAM_BUFFER_SIZE, writer));
+    builder.append_dir_all(TARBALL_ROOT, cp_path)?;
+    builder
+        .into_inner()?
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .finish();
+    Ok(())
+}
+
+fn remove_checkpoint(cp_path: &Path) {
+    if let Err(e) = std::fs::remove_dir_all(cp_path) {
+        warn!("Failed to remove DB checkpoint at {cp_path:?}: {e:?}");
+    }
+}
+
+/// Writes into the body of a streaming response, from a blocking thread.
+///
+/// The response is aborted unless `finish()` is called, so that the client doesn't take a
+/// truncated tarball for a complete one.
+struct BodyWriter {
+    sender: Option<Sender>,
+    handle: Handle,
+}
+
+impl BodyWriter {
+    fn new(sender: Sender, handle: Handle) -> Self {
+        Self {
+            sender: Some(sender),
+            handle,
+        }
+    }
+
+    fn finish(mut self) {
+        // Dropping the sender ends the body normally.
+        self.sender.take();
+    }
+}
+
+impl Write for BodyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let sender = self
+            .sender
+            .as_mut()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "Body already finished."))?;
+        self.handle
+            .block_on(sender.send_data(Bytes::copy_from_slice(buf)))
+            .map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for BodyWriter {
+    fn drop(&mut self) {
+        if let Some(sender) = self.sender.take() {
+            sender.abort();
+        }
+    }
+}
@@ -95,6 +95,17 @@ impl PrivateKey {
     pub fn to_bytes(&self) -> [u8; Self::LENGTH] {
         self.privkey.to_bytes()
     }
+
+    /// Signs a message that has already been serialized via `signing_message`, i.e., one that
+    /// already carries its `CryptoHasher` domain separator. This is intended for external signers,
+    /// which receive consensus messages in serialized form rather than as typed structs.
+    pub fn sign_signing_message(&self, signing_message: &[u8]) -> bls12381::Signature {
+        bls12381::Signature {
+            sig: self
+                .privkey
+                .sign(signing_message, DST_BLS_SIG_IN_G2_WITH_POP, &[]),
+        }
+    }
 }
 
 ///////////////////////
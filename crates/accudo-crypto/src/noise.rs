@@ -240,6 +240,7 @@ pub struct NoiseConfig {
     public_key: x25519::PublicKey,
     pq_private_key: Option<KyberPrivateKey>,
     pq_public_key: Option<KyberPublicKey>,
+    previous_pq_private_key: Option<KyberPrivateKey>,
 }
 
 /// Remote peer identity information required during the handshake.
@@ -295,9 +296,20 @@ impl NoiseConfig {
             public_key,
             pq_private_key,
             pq_public_key,
+            previous_pq_private_key: None,
         }
     }
 
+    /// Also accepts hybrid handshakes from initiators that still encapsulate to the
+    /// given (previous) Kyber key, e.g., while a rotated key propagates to peers.
+    pub fn with_previous_post_quantum_key(
+        mut self,
+        previous_pq_private_key: Option<KyberPrivateKey>,
+    ) -> Self {
+        self.previous_pq_private_key = previous_pq_private_key;
+        self
+    }
+
     /// Handy getter to access the configuration's public key
     pub fn public_key(&self) -> x25519::PublicKey {
         self.public_key
@@ -566,6 +578,8 @@ impl NoiseConfig {
         let dh_output = self.private_key.diffie_hellman(&rs);
         k = mix_key(&mut ck, &dh_output)?;
 
+        // <- pq (optional)
+        let mut previous_pq_keys = None;
         if handshake_version > 0 {
             let mut len_bytes = [0u8; 2];
             cursor
@@ -584,6 +598,14 @@ impl NoiseConfig {
                 .pq_private_key
                 .as_ref()
                 .ok_or(NoiseError::MissingPostQuantumKey)?;
+            if let Some(previous_pq_private) = &self.previous_pq_private_key {
+                let shared = previous_pq_private
+                    .decapsulate(&ciphertext)
+                    .map_err(|_| NoiseError::PostQuantumNegotiation)?;
+                let mut previous_ck = ck.clone();
+                let previous_k = mix_key(&mut previous_ck, &shared)?;
+                previous_pq_keys = Some((previous_ck, previous_k));
+            }
             let shared = pq_private
                 .decapsulate(&ciphertext)
                 .map_err(|_| NoiseError::PostQuantumNegotiation)?;
@@ -594,17 +616,32 @@ impl NoiseConfig {
         let offset = cursor.position() as usize;
         let received_encrypted_payload = &cursor.into_inner()[offset..];
 
-        let aead = aes_key(&k[..]);
-        let mut in_out = received_encrypted_payload.to_vec();
-        let nonce = aead::Nonce::assume_unique_for_key([0u8; AES_NONCE_SIZE]);
-        let received_payload = aead
-            .open_in_place(nonce, Aad::from(&h), &mut in_out)
-            .map_err(|_| NoiseError::Decrypt)?;
+        let open_payload = |k: &[u8]| {
+            let aead = aes_key(k);
+            let mut in_out = received_encrypted_payload.to_vec();
+            let nonce = aead::Nonce::assume_unique_for_key([0u8; AES_NONCE_SIZE]);
+            let len = aead
+                .open_in_place(nonce, Aad::from(&h), &mut in_out)
+                .map_err(|_| NoiseError::Decrypt)?
+                .len();
+            in_out.truncate(len);
+            Ok::<_, NoiseError>(in_out)
+        };
+        // Kyber decapsulation never fails on a mismatched key, so the payload decryption
+        // tells us which of our Kyber keys the initiator encapsulated to
+        let received_payload = match (open_payload(&k[..]), previous_pq_keys) {
+            (Err(NoiseError::Decrypt), Some((previous_ck, previous_k))) => {
+                let received_payload = open_payload(&previous_k[..])?;
+                ck = previous_ck;
+                received_payload
+            },
+            (received_payload, _) => received_payload?,
+        };
         mix_hash(&mut h, received_encrypted_payload);
 
         // return
         let handshake_state = ResponderHandshakeState { h, ck, rs, re };
-        Ok((rs, handshake_state, received_payload.to_vec()))
+        Ok((rs, handshake_state, received_payload))
     }
 
     /// A responder can respond to an initiator by calling this function with the state obtained,
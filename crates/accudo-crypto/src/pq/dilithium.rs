@@ -0,0 +1,98 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serializable wrappers around Dilithium3 key material.
+//!
+//! These let Dilithium keys be stored and loaded with the same AIP-80 encoding
+//! as the classical keys (e.g. in CLI profiles or key files).
+
+use crate::traits::{CryptoMaterialError, ValidCryptoMaterial, ValidCryptoMaterialStringExt};
+use accudo_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use pqcrypto_dilithium::dilithium3;
+use std::convert::TryFrom;
+
+/// Length in bytes of a Dilithium3 public key.
+pub const DILITHIUM3_PUBLIC_KEY_LENGTH: usize = dilithium3::public_key_bytes();
+/// Length in bytes of a Dilithium3 secret key.
+pub const DILITHIUM3_PRIVATE_KEY_LENGTH: usize = dilithium3::secret_key_bytes();
+/// Length in bytes of a detached Dilithium3 signature.
+pub const DILITHIUM3_SIGNATURE_LENGTH: usize = dilithium3::signature_bytes();
+
+/// Wrapper for Dilithium3 private keys.
+///
+/// The secret key does not embed the public key, so the two halves have to be
+/// stored side by side and recombined with `Dilithium3KeyPair::from_keys`.
+#[derive(DeserializeKey, SerializeKey, SilentDisplay, SilentDebug)]
+pub struct Dilithium3PrivateKey(Vec<u8>);
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(Dilithium3PrivateKey: Clone);
+
+/// Wrapper for Dilithium3 public keys.
+#[derive(Clone, PartialEq, Eq, Hash, SerializeKey, DeserializeKey)]
+pub struct Dilithium3PublicKey(Vec<u8>);
+
+impl Dilithium3PrivateKey {
+    /// Returns the underlying byte representation.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(any(test, feature = "cloneable-private-keys"))]
+impl Clone for Dilithium3PrivateKey {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl Dilithium3PublicKey {
+    /// Returns the underlying byte representation.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for Dilithium3PublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Dilithium3PublicKey({})", hex::encode(&self.0))
+    }
+}
+
+impl TryFrom<&[u8]> for Dilithium3PrivateKey {
+    type Error = CryptoMaterialError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.len() != DILITHIUM3_PRIVATE_KEY_LENGTH {
+            return Err(CryptoMaterialError::DeserializationError);
+        }
+        Ok(Self(value.to_vec()))
+    }
+}
+
+impl TryFrom<&[u8]> for Dilithium3PublicKey {
+    type Error = CryptoMaterialError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.len() != DILITHIUM3_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::DeserializationError);
+        }
+        Ok(Self(value.to_vec()))
+    }
+}
+
+impl ValidCryptoMaterial for Dilithium3PrivateKey {
+    const AIP_80_PREFIX: &'static str = "dilithium3-priv-";
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+}
+
+impl ValidCryptoMaterial for Dilithium3PublicKey {
+    const AIP_80_PREFIX: &'static str = "dilithium3-pub-";
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+}
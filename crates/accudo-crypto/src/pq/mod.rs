@@ -7,16 +7,22 @@
 //! level code can negotiate capabilities or send dual-signature payloads
 //! during the migration to post-quantum primitives.
 
+mod dilithium;
 mod kyber;
 
 use crate::ed25519::{
     Ed25519PublicKey, Ed25519Signature, ED25519_PUBLIC_KEY_LENGTH, ED25519_SIGNATURE_LENGTH,
 };
 use anyhow::{anyhow, bail, Context};
-use pqcrypto_dilithium::dilithium3;
+use pqcrypto_dilithium::{dilithium2, dilithium3, dilithium5};
+use pqcrypto_falcon::falcon512;
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, fmt, sync::Arc};
 
+pub use dilithium::{
+    Dilithium3PrivateKey, Dilithium3PublicKey, DILITHIUM3_PRIVATE_KEY_LENGTH,
+    DILITHIUM3_PUBLIC_KEY_LENGTH, DILITHIUM3_SIGNATURE_LENGTH,
+};
 pub use kyber::{
     KyberCiphertext, KyberKeyPair, KyberPrivateKey, KyberPublicKey, KYBER_CIPHERTEXT_LENGTH,
     KYBER_PRIVATE_KEY_LENGTH, KYBER_PUBLIC_KEY_LENGTH, KYBER_SHARED_SECRET_LENGTH,
@@ -67,6 +73,14 @@ impl SchemeId {
             SchemeId::ShakeLattice => "shake_lattice",
         }
     }
+
+    /// Returns true for the post-quantum signature schemes.
+    pub fn is_post_quantum_signature(self) -> bool {
+        matches!(
+            self,
+            SchemeId::Dilithium2 | SchemeId::Dilithium3 | SchemeId::Dilithium5 | SchemeId::Falcon512
+        )
+    }
 }
 
 impl fmt::Display for SchemeId {
@@ -238,45 +252,119 @@ impl SignatureAlgorithm for Ed25519Verifier {
     }
 }
 
-/// Adapter exposing Dilithium 3 verification through the unified trait.
-pub struct Dilithium3Verifier;
+/// Implements a Dilithium parameter set verifier through the unified trait.
+macro_rules! dilithium_verifier {
+    ($(#[$meta:meta])* $name:ident, $module:ident, $scheme:expr) => {
+        $(#[$meta])*
+        pub struct $name;
+
+        impl SignatureAlgorithm for $name {
+            fn scheme(&self) -> SchemeId {
+                $scheme
+            }
+
+            fn public_key_length(&self) -> Option<usize> {
+                Some($module::PUBLIC_KEY_BYTES)
+            }
+
+            fn signature_length(&self) -> Option<usize> {
+                Some($module::SIGNATURE_BYTES)
+            }
+
+            fn verify(
+                &self,
+                public_key: &[u8],
+                message: &[u8],
+                signature: &[u8],
+            ) -> anyhow::Result<()> {
+                if public_key.len() != $module::PUBLIC_KEY_BYTES {
+                    bail!(
+                        "unexpected {} public key size: expected {}, got {}",
+                        $scheme,
+                        $module::PUBLIC_KEY_BYTES,
+                        public_key.len()
+                    );
+                }
+                if signature.len() != $module::SIGNATURE_BYTES {
+                    bail!(
+                        "unexpected {} signature size: expected {}, got {}",
+                        $scheme,
+                        $module::SIGNATURE_BYTES,
+                        signature.len()
+                    );
+                }
+
+                let pk = $module::PublicKey::from_bytes(public_key)
+                    .map_err(|e| anyhow!("failed to parse {} public key: {e:?}", $scheme))?;
+                let sig = $module::DetachedSignature::from_bytes(signature)
+                    .map_err(|e| anyhow!("failed to parse {} signature: {e:?}", $scheme))?;
+
+                $module::verify_detached_signature(&sig, message, &pk)
+                    .map_err(|e| anyhow!("{} verification failed: {e:?}", $scheme))
+            }
+        }
+    };
+}
 
-impl SignatureAlgorithm for Dilithium3Verifier {
+dilithium_verifier!(
+    /// Adapter exposing Dilithium 2 verification through the unified trait.
+    Dilithium2Verifier,
+    dilithium2,
+    SchemeId::Dilithium2
+);
+
+dilithium_verifier!(
+    /// Adapter exposing Dilithium 3 verification through the unified trait.
+    Dilithium3Verifier,
+    dilithium3,
+    SchemeId::Dilithium3
+);
+
+dilithium_verifier!(
+    /// Adapter exposing Dilithium 5 verification through the unified trait.
+    Dilithium5Verifier,
+    dilithium5,
+    SchemeId::Dilithium5
+);
+
+/// Adapter exposing Falcon-512 verification through the unified trait.
+///
+/// Falcon signatures are compressed and therefore variable length, so only an
+/// upper bound is enforced before parsing.
+pub struct Falcon512Verifier;
+
+impl SignatureAlgorithm for Falcon512Verifier {
     fn scheme(&self) -> SchemeId {
-        SchemeId::Dilithium3
+        SchemeId::Falcon512
     }
 
     fn public_key_length(&self) -> Option<usize> {
-        Some(dilithium3::PUBLIC_KEY_BYTES)
-    }
-
-    fn signature_length(&self) -> Option<usize> {
-        Some(dilithium3::SIGNATURE_BYTES)
+        Some(falcon512::PUBLIC_KEY_BYTES)
     }
 
     fn verify(&self, public_key: &[u8], message: &[u8], signature: &[u8]) -> anyhow::Result<()> {
-        if public_key.len() != dilithium3::PUBLIC_KEY_BYTES {
+        if public_key.len() != falcon512::PUBLIC_KEY_BYTES {
             bail!(
-                "unexpected dilithium public key size: expected {}, got {}",
-                dilithium3::PUBLIC_KEY_BYTES,
+                "unexpected falcon512 public key size: expected {}, got {}",
+                falcon512::PUBLIC_KEY_BYTES,
                 public_key.len()
             );
         }
-        if signature.len() != dilithium3::SIGNATURE_BYTES {
+        if signature.is_empty() || signature.len() > falcon512::SIGNATURE_BYTES {
             bail!(
-                "unexpected dilithium signature size: expected {}, got {}",
-                dilithium3::SIGNATURE_BYTES,
+                "unexpected falcon512 signature size: expected at most {}, got {}",
+                falcon512::SIGNATURE_BYTES,
                 signature.len()
             );
         }
 
-        let pk = dilithium3::PublicKey::from_bytes(public_key)
-            .map_err(|e| anyhow!("failed to parse dilithium public key: {e:?}"))?;
-        let sig = dilithium3::DetachedSignature::from_bytes(signature)
-            .map_err(|e| anyhow!("failed to parse dilithium signature: {e:?}"))?;
+        let pk = falcon512::PublicKey::from_bytes(public_key)
+            .map_err(|e| anyhow!("failed to parse falcon512 public key: {e:?}"))?;
+        let sig = falcon512::DetachedSignature::from_bytes(signature)
+            .map_err(|e| anyhow!("failed to parse falcon512 signature: {e:?}"))?;
 
-        dilithium3::verify_detached_signature(&sig, message, &pk)
-            .map_err(|e| anyhow!("dilithium verification failed: {e:?}"))
+        falcon512::verify_detached_signature(&sig, message, &pk)
+            .map_err(|e| anyhow!("falcon512 verification failed: {e:?}"))
     }
 }
 
@@ -297,6 +385,38 @@ impl Dilithium3KeyPair {
         })
     }
 
+    /// Rebuilds a keypair from its stored private and public halves.
+    ///
+    /// Dilithium secret keys do not embed the public key, so the pairing is
+    /// checked by signing and verifying a probe message.
+    pub fn from_keys(
+        private_key: &Dilithium3PrivateKey,
+        public_key: &Dilithium3PublicKey,
+    ) -> anyhow::Result<Self> {
+        let keypair = Self {
+            public_key: public_key.as_bytes().to_vec(),
+            secret_key: Arc::from(private_key.as_bytes().to_vec()),
+        };
+        let probe = b"dilithium3-keypair-check";
+        let signature = keypair.sign(probe)?;
+        Dilithium3Verifier
+            .verify(keypair.public_key(), probe, signature.bytes())
+            .context("dilithium private key does not match the public key")?;
+        Ok(keypair)
+    }
+
+    /// Returns the private half as a serializable key.
+    pub fn to_private_key(&self) -> Dilithium3PrivateKey {
+        Dilithium3PrivateKey::try_from(self.secret_key.as_ref())
+            .expect("generated dilithium secret key has a valid length")
+    }
+
+    /// Returns the public half as a serializable key.
+    pub fn to_public_key(&self) -> Dilithium3PublicKey {
+        Dilithium3PublicKey::try_from(self.public_key.as_slice())
+            .expect("generated dilithium public key has a valid length")
+    }
+
     /// Returns the raw public key bytes.
     pub fn public_key(&self) -> &[u8] {
         &self.public_key
@@ -327,7 +447,10 @@ impl Dilithium3KeyPair {
 pub fn baseline_registry() -> AlgorithmRegistry {
     let mut registry = AlgorithmRegistry::default();
     registry.register_signature(Ed25519Verifier);
+    registry.register_signature(Dilithium2Verifier);
     registry.register_signature(Dilithium3Verifier);
+    registry.register_signature(Dilithium5Verifier);
+    registry.register_signature(Falcon512Verifier);
     registry
 }
 
@@ -338,8 +461,36 @@ mod tests {
         ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
         traits::{SigningKey, Uniform},
     };
+    use proptest::prelude::*;
     use rand::{rngs::StdRng, SeedableRng};
 
+    const PQ_SIGNATURE_SCHEMES: [SchemeId; 4] = [
+        SchemeId::Dilithium2,
+        SchemeId::Dilithium3,
+        SchemeId::Dilithium5,
+        SchemeId::Falcon512,
+    ];
+
+    /// Generates a fresh keypair for `scheme` and returns its public key along
+    /// with a detached signature over `message`.
+    fn sign_with(scheme: SchemeId, message: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        macro_rules! sign {
+            ($module:ident) => {{
+                let (public, secret) = $module::keypair();
+                let signature = $module::detached_sign(message, &secret);
+                (public.as_bytes().to_vec(), signature.as_bytes().to_vec())
+            }};
+        }
+
+        match scheme {
+            SchemeId::Dilithium2 => sign!(dilithium2),
+            SchemeId::Dilithium3 => sign!(dilithium3),
+            SchemeId::Dilithium5 => sign!(dilithium5),
+            SchemeId::Falcon512 => sign!(falcon512),
+            _ => unreachable!("not a post-quantum signature scheme: {scheme}"),
+        }
+    }
+
     #[test]
     fn ed25519_adapter_verifies_signatures() {
         let mut rng = StdRng::seed_from_u64(42);
@@ -375,4 +526,93 @@ mod tests {
             .verify(keypair.public_key(), message, signature.bytes())
             .expect("dilithium signature should verify");
     }
+
+    #[test]
+    fn dilithium_keys_roundtrip_through_encoding() {
+        use crate::traits::ValidCryptoMaterialStringExt;
+
+        let keypair = Dilithium3KeyPair::generate().expect("dilithium keypair generation");
+        let encoded_private = keypair.to_private_key().to_encoded_string().unwrap();
+        let encoded_public = keypair.to_public_key().to_encoded_string().unwrap();
+
+        let private_key = Dilithium3PrivateKey::from_encoded_string(&encoded_private).unwrap();
+        let public_key = Dilithium3PublicKey::from_encoded_string(&encoded_public).unwrap();
+        let restored = Dilithium3KeyPair::from_keys(&private_key, &public_key)
+            .expect("matching halves should recombine");
+        assert_eq!(restored.public_key(), keypair.public_key());
+
+        let other = Dilithium3KeyPair::generate().expect("dilithium keypair generation");
+        assert!(Dilithium3KeyPair::from_keys(&private_key, &other.to_public_key()).is_err());
+    }
+
+    #[test]
+    fn baseline_registry_covers_pq_signature_schemes() {
+        let registry = baseline_registry();
+        for scheme in PQ_SIGNATURE_SCHEMES {
+            let adapter = registry
+                .signature(scheme)
+                .unwrap_or_else(|| panic!("{scheme} adapter registered"));
+            assert_eq!(adapter.scheme(), scheme);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn pq_verifiers_accept_valid_signatures(
+            scheme in prop::sample::select(PQ_SIGNATURE_SCHEMES.to_vec()),
+            message in prop::collection::vec(any::<u8>(), 0..256),
+        ) {
+            let (public_key, signature) = sign_with(scheme, &message);
+            let adapter = baseline_registry().signature(scheme).unwrap();
+
+            prop_assert_eq!(adapter.public_key_length(), Some(public_key.len()));
+            if let Some(length) = adapter.signature_length() {
+                prop_assert_eq!(length, signature.len());
+            }
+            prop_assert!(adapter.verify(&public_key, &message, &signature).is_ok());
+        }
+
+        #[test]
+        fn pq_verifiers_reject_tampered_signatures(
+            scheme in prop::sample::select(PQ_SIGNATURE_SCHEMES.to_vec()),
+            message in prop::collection::vec(any::<u8>(), 0..256),
+            index in any::<prop::sample::Index>(),
+            mask in 1u8..,
+        ) {
+            let (public_key, mut signature) = sign_with(scheme, &message);
+            let adapter = baseline_registry().signature(scheme).unwrap();
+
+            let index = index.index(signature.len());
+            signature[index] ^= mask;
+            prop_assert!(adapter.verify(&public_key, &message, &signature).is_err());
+        }
+
+        #[test]
+        fn pq_verifiers_reject_other_messages(
+            scheme in prop::sample::select(PQ_SIGNATURE_SCHEMES.to_vec()),
+            message in prop::collection::vec(any::<u8>(), 0..256),
+            other in prop::collection::vec(any::<u8>(), 0..256),
+        ) {
+            prop_assume!(message != other);
+            let (public_key, signature) = sign_with(scheme, &message);
+            let adapter = baseline_registry().signature(scheme).unwrap();
+
+            prop_assert!(adapter.verify(&public_key, &other, &signature).is_err());
+        }
+
+        #[test]
+        fn pq_verifiers_reject_truncated_signatures(
+            scheme in prop::sample::select(PQ_SIGNATURE_SCHEMES.to_vec()),
+            message in prop::collection::vec(any::<u8>(), 0..256),
+            cut in any::<prop::sample::Index>(),
+        ) {
+            let (public_key, signature) = sign_with(scheme, &message);
+            let adapter = baseline_registry().signature(scheme).unwrap();
+
+            let cut = cut.index(signature.len());
+            prop_assert!(adapter.verify(&public_key, &message, &signature[..cut]).is_err());
+        }
+    }
 }
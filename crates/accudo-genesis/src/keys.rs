@@ -5,6 +5,7 @@ use accudo_config::{config::IdentityBlob, keys::ConfigKey};
 use accudo_crypto::{
     bls12381,
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    pq::{KyberKeyPair, KyberPrivateKey, KyberPublicKey},
     x25519, PrivateKey,
 };
 use accudo_keygen::KeyGen;
@@ -21,6 +22,8 @@ pub struct PrivateIdentity {
     pub consensus_private_key: bls12381::PrivateKey,
     pub full_node_network_private_key: x25519::PrivateKey,
     pub validator_network_private_key: x25519::PrivateKey,
+    pub full_node_network_post_quantum_private_key: Option<KyberPrivateKey>,
+    pub validator_network_post_quantum_private_key: Option<KyberPrivateKey>,
 }
 
 /// Type for serializing public keys file
@@ -32,6 +35,8 @@ pub struct PublicIdentity {
     pub consensus_proof_of_possession: Option<bls12381::ProofOfPossession>,
     pub full_node_network_public_key: Option<x25519::PublicKey>,
     pub validator_network_public_key: Option<x25519::PublicKey>,
+    pub full_node_network_post_quantum_public_key: Option<KyberPublicKey>,
+    pub validator_network_post_quantum_public_key: Option<KyberPublicKey>,
 }
 
 /// Generates objects used for a user in genesis
@@ -42,6 +47,8 @@ pub fn generate_key_objects(
     let consensus_key = ConfigKey::new(keygen.generate_bls12381_private_key());
     let validator_network_key = ConfigKey::new(keygen.generate_x25519_private_key()?);
     let full_node_network_key = ConfigKey::new(keygen.generate_x25519_private_key()?);
+    let validator_network_pq_key = KyberKeyPair::generate()?;
+    let full_node_network_pq_key = KyberKeyPair::generate()?;
 
     let account_address = AuthenticationKey::ed25519(&account_key.public_key()).account_address();
 
@@ -51,12 +58,16 @@ pub fn generate_key_objects(
         account_private_key: Some(account_key.private_key()),
         consensus_private_key: Some(consensus_key.private_key()),
         network_private_key: validator_network_key.private_key(),
+        network_post_quantum_private_key: Some(validator_network_pq_key.private.clone()),
+        network_previous_post_quantum_private_key: None,
     };
     let vfn_blob = IdentityBlob {
         account_address: Some(account_address),
         account_private_key: None,
         consensus_private_key: None,
         network_private_key: full_node_network_key.private_key(),
+        network_post_quantum_private_key: Some(full_node_network_pq_key.private.clone()),
+        network_previous_post_quantum_private_key: None,
     };
 
     let private_identity = PrivateIdentity {
@@ -65,6 +76,8 @@ pub fn generate_key_objects(
         consensus_private_key: consensus_key.private_key(),
         full_node_network_private_key: full_node_network_key.private_key(),
         validator_network_private_key: validator_network_key.private_key(),
+        full_node_network_post_quantum_private_key: Some(full_node_network_pq_key.private),
+        validator_network_post_quantum_private_key: Some(validator_network_pq_key.private),
     };
 
     let public_identity = PublicIdentity {
@@ -76,6 +89,8 @@ pub fn generate_key_objects(
         )),
         full_node_network_public_key: Some(full_node_network_key.public_key()),
         validator_network_public_key: Some(validator_network_key.public_key()),
+        full_node_network_post_quantum_public_key: Some(full_node_network_pq_key.public),
+        validator_network_post_quantum_public_key: Some(validator_network_pq_key.public),
     };
 
     Ok((validator_blob, vfn_blob, private_identity, public_identity))
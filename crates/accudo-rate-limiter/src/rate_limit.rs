@@ -172,6 +172,14 @@ impl<Key: Eq + Hash + Clone + Debug> TokenBucketRateLimiter<Key> {
         }
         remove
     }
+
+    /// Garbage collects all buckets that aren't in use and have refilled completely.  This only
+    /// loses no state if new buckets start full, i.e. `new_bucket_start_percentage` is 100.
+    pub fn garbage_collect_full_buckets(&self) {
+        self.buckets
+            .write()
+            .retain(|_, bucket| Arc::strong_count(bucket) > 1 || !bucket.lock().is_full());
+    }
 }
 
 /// A token bucket object that keeps track of everything related to a key
@@ -344,6 +352,12 @@ impl Bucket {
         tokens_allowed
     }
 
+    /// Whether the bucket is at its maximum size after a refill
+    pub fn is_full(&mut self) -> bool {
+        self.refill();
+        self.tokens >= self.size
+    }
+
     /// Tells us when the next refill is
     pub fn time_of_next_refill(&self) -> Instant {
         self.last_refresh_time + ONE_SEC
@@ -547,4 +561,24 @@ mod tests {
         assert!(!rate_limiter.try_garbage_collect_key(&key_to_keep));
         assert_num_keys(&rate_limiter, 1);
     }
+
+    #[test]
+    fn test_garbage_collect_full_buckets() {
+        let rate_limiter = TokenBucketRateLimiter::test(2, 1);
+
+        // Only the full bucket that isn't in use should disappear
+        let _bucket_in_use = rate_limiter.bucket("in use");
+        rate_limiter.bucket("full");
+        rate_limiter
+            .bucket("not full")
+            .lock()
+            .acquire_all_tokens(1)
+            .unwrap();
+        assert_num_keys(&rate_limiter, 3);
+
+        rate_limiter.garbage_collect_full_buckets();
+        assert_num_keys(&rate_limiter, 2);
+        assert!(rate_limiter.buckets.read().contains_key("in use"));
+        assert!(rate_limiter.buckets.read().contains_key("not full"));
+    }
 }
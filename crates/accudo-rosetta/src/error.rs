@@ -311,7 +311,9 @@ impl From<RestError> for ApiError {
                 AccudoErrorCode::HealthCheckFailed => {
                     ApiError::InternalError(Some(err.error.message))
                 },
-                AccudoErrorCode::MempoolIsFull => ApiError::MempoolIsFull(Some(err.error.message)),
+                AccudoErrorCode::MempoolIsFull | AccudoErrorCode::RateLimited => {
+                    ApiError::MempoolIsFull(Some(err.error.message))
+                },
                 AccudoErrorCode::WebFrameworkError => {
                     ApiError::InternalError(Some(err.error.message))
                 },
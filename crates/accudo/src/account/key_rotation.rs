@@ -1,11 +1,15 @@
 // Copyright © Accudo Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{
-    account_address_from_auth_key, account_address_from_public_key, AuthenticationKeyInputOptions,
-    CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, EncodingOptions,
-    ExtractEd25519PublicKey, HardwareWalletOptions, ParseEd25519PrivateKey, ProfileConfig,
-    ProfileOptions, PublicKeyInputOptions, RestOptions, TransactionOptions, TransactionSummary,
+use crate::{
+    common::types::{
+        account_address_from_auth_key, account_address_from_public_key,
+        AuthenticationKeyInputOptions, CliCommand, CliConfig, CliError, CliTypedResult,
+        ConfigSearchMode, EncodingOptions, ExtractEd25519PublicKey, HardwareWalletOptions,
+        ParseEd25519PrivateKey, ProfileConfig, ProfileOptions, PublicKeyInputOptions, RestOptions,
+        TransactionOptions, TransactionSummary,
+    },
+    op::key::SaveKey,
 };
 use accudo_cached_packages::accudo_stdlib;
 use accudo_crypto::{
@@ -15,6 +19,7 @@ use accudo_crypto::{
 };
 use accudo_ledger;
 use accudo_rest_client::{error::RestError, Client};
+use accudo_sdk::types::{HybridAccountKey, PostQuantumAccountKey};
 use accudo_types::{
     account_address::AccountAddress,
     account_config::{RotationProofChallenge, CORE_CODE_ADDRESS},
@@ -36,6 +41,11 @@ use std::{collections::BTreeMap, path::PathBuf};
 /// If you wish to rotate from a ledger wallet, it must have its own
 /// profile. If you wish to rotate to a ledger wallet, specify the new
 /// derivation path or index accordingly.
+///
+/// To rotate to a post-quantum account, provide a Dilithium3 key generated
+/// with `accudo key generate --key-type dilithium3`.  With `--hybrid`, the
+/// new authentication key is a 2-of-2 multi-key of the current Ed25519 key
+/// and the Dilithium3 key.
 #[derive(Debug, Parser)]
 pub struct RotateKey {
     #[clap(flatten)]
@@ -44,6 +54,10 @@ pub struct RotateKey {
     #[clap(flatten)]
     pub(crate) new_auth_key_options: NewAuthKeyOptions,
 
+    /// Keep the current Ed25519 key alongside the new Dilithium3 key in a hybrid multi-key
+    #[clap(long, requires = "new_post_quantum_key_file")]
+    pub(crate) hybrid: bool,
+
     #[clap(flatten)]
     pub(crate) new_profile_options: NewProfileOptions,
 }
@@ -71,6 +85,12 @@ pub(crate) struct NewAuthKeyOptions {
     /// Given index `n` maps to BIP44 derivation path `m/44'/637'/n'/0'/0`
     #[clap(long)]
     pub(crate) new_derivation_index: Option<String>,
+
+    /// File name that contains the new Dilithium3 private key encoded in the type from `--encoding`
+    ///
+    /// The public key is read from the `.pub` file next to it
+    #[clap(long, value_parser)]
+    pub(crate) new_post_quantum_key_file: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
@@ -137,6 +157,10 @@ impl CliCommand<RotateSummary> for RotateKey {
             }
         };
 
+        if let Some(key_file) = self.new_auth_key_options.new_post_quantum_key_file.clone() {
+            return self.rotate_to_post_quantum(key_file).await;
+        }
+
         // Get current signer options.
         let current_derivation_path = if self.txn_options.profile_options.profile.is_some() {
             self.txn_options.profile_options.derivation_path()?
@@ -266,6 +290,73 @@ impl CliCommand<RotateSummary> for RotateKey {
             .await
             .map(TransactionSummary::from)?;
 
+        self.finish_rotation(txn_summary, ProfileConfig {
+            public_key: Some(new_public_key),
+            account: Some(current_address),
+            private_key: new_private_key,
+            derivation_path: new_derivation_path,
+            ..Default::default()
+        })
+    }
+}
+
+impl RotateKey {
+    /// Rotates to a Dilithium3 or hybrid authentication key.
+    ///
+    /// Dilithium3 keys can't sign the `RotationProofChallenge` expected by
+    /// `rotate_authentication_key`, so this goes through `rotate_authentication_key_call`,
+    /// which does not update the `OriginatingAddress` table.
+    async fn rotate_to_post_quantum(self, key_file: PathBuf) -> CliTypedResult<RotateSummary> {
+        let keypair =
+            SaveKey::load_dilithium3_key(self.txn_options.encoding_options.encoding, &key_file)?;
+        let current_address = self.txn_options.sender_address()?;
+
+        let (new_private_key, new_public_key, new_auth_key) = if self.hybrid {
+            let (current_private_key, _) = self.txn_options.get_key_and_address()?;
+            let key = HybridAccountKey::from_keys(current_private_key.clone(), keypair.clone())
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            (
+                Some(current_private_key),
+                Some(key.public_key().clone()),
+                key.authentication_key(),
+            )
+        } else {
+            let key = PostQuantumAccountKey::from_keypair(keypair.clone());
+            (None, None, key.authentication_key())
+        };
+
+        // Check that auth key is actually changing.
+        if self.txn_options.auth_key(current_address).await? == new_auth_key {
+            return Err(CliError::CommandArgumentError(
+                "New authentication key cannot be the same as the current authentication key"
+                    .to_string(),
+            ));
+        }
+
+        let txn_summary = self
+            .txn_options
+            .submit_transaction(accudo_stdlib::account_rotate_authentication_key_call(
+                new_auth_key.to_vec(),
+            ))
+            .await
+            .map(TransactionSummary::from)?;
+
+        self.finish_rotation(txn_summary, ProfileConfig {
+            public_key: new_public_key,
+            account: Some(current_address),
+            private_key: new_private_key,
+            post_quantum_private_key: Some(keypair.to_private_key()),
+            post_quantum_public_key: Some(keypair.to_public_key()),
+            ..Default::default()
+        })
+    }
+
+    /// Checks the rotation transaction succeeded and saves the new keys to a profile
+    fn finish_rotation(
+        self,
+        txn_summary: TransactionSummary,
+        new_keys: ProfileConfig,
+    ) -> CliTypedResult<RotateSummary> {
         let txn_string = serde_json::to_string_pretty(&txn_summary)
             .map_err(|err| CliError::UnableToParse("transaction summary", err.to_string()))?;
         eprintln!("{}", txn_string);
@@ -303,10 +394,12 @@ impl CliCommand<RotateSummary> for RotateKey {
 
         // Create new config.
         let mut new_profile_config = ProfileConfig {
-            public_key: Some(new_public_key),
-            account: Some(current_address),
-            private_key: new_private_key,
-            derivation_path: new_derivation_path,
+            public_key: new_keys.public_key,
+            account: new_keys.account,
+            private_key: new_keys.private_key,
+            post_quantum_private_key: new_keys.post_quantum_private_key,
+            post_quantum_public_key: new_keys.post_quantum_public_key,
+            derivation_path: new_keys.derivation_path,
             ..self.txn_options.profile_options.profile()?
         };
 
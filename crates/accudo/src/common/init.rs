@@ -6,18 +6,21 @@ use crate::{
     account::key_rotation::lookup_address,
     common::{
         types::{
-            account_address_from_public_key, get_mint_site_url, CliCommand, CliConfig, CliError,
-            CliTypedResult, ConfigSearchMode, EncodingOptions, HardwareWalletOptions,
-            PrivateKeyInputOptions, ProfileConfig, ProfileOptions, PromptOptions, RngArgs,
-            DEFAULT_PROFILE,
+            account_address_from_public_key, get_mint_site_url, AccountScheme, CliCommand,
+            CliConfig, CliError, CliTypedResult, ConfigSearchMode, EncodingOptions,
+            HardwareWalletOptions, PrivateKeyInputOptions, ProfileConfig, ProfileOptions,
+            PromptOptions, RngArgs, DEFAULT_PROFILE,
         },
         utils::{
             explorer_account_link, fund_account, prompt_yes_with_override, read_line,
             strip_private_key_prefix,
         },
     },
+    op::key::SaveKey,
+};
+use accudo_crypto::{
+    ed25519::Ed25519PrivateKey, pq::Dilithium3KeyPair, PrivateKey, ValidCryptoMaterialStringExt,
 };
-use accudo_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, ValidCryptoMaterialStringExt};
 use accudo_ledger;
 use async_trait::async_trait;
 use clap::Parser;
@@ -26,6 +29,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     fmt::{Display, Formatter},
+    path::PathBuf,
     str::FromStr,
 };
 
@@ -64,6 +68,21 @@ pub struct InitTool {
     #[clap(flatten)]
     pub(crate) hardware_wallet_options: HardwareWalletOptions,
 
+    /// Signature scheme of the account
+    ///
+    /// `post-quantum` uses a single Dilithium3 key, `hybrid` requires both an Ed25519 and a
+    /// Dilithium3 signature on every transaction.
+    #[clap(long, default_value_t = AccountScheme::Ed25519)]
+    pub account_scheme: AccountScheme,
+
+    /// Dilithium3 private key file for `post-quantum` and `hybrid` accounts
+    ///
+    /// The public key is read from the `.pub` file next to it, as written by
+    /// `accudo key generate --key-type dilithium3`.  If not given, the key of the existing
+    /// profile is kept, or a new one is generated.
+    #[clap(long, value_parser)]
+    pub post_quantum_key_file: Option<PathBuf>,
+
     #[clap(flatten)]
     pub rng_args: RngArgs,
     #[clap(flatten)]
@@ -203,10 +222,37 @@ impl CliCommand<()> for InitTool {
         // Set the derivation_path to the one user chose
         profile_config.derivation_path.clone_from(&derivation_path);
 
+        // Post-quantum key
+        let post_quantum_keypair = if self.account_scheme == AccountScheme::Ed25519 {
+            None
+        } else if self.is_hardware_wallet() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Hardware wallets can't be used with the {} account scheme",
+                self.account_scheme
+            )));
+        } else if let Some(ref key_file) = self.post_quantum_key_file {
+            eprintln!("Using command line argument for post-quantum key");
+            Some(SaveKey::load_dilithium3_key(
+                self.encoding_options.encoding,
+                key_file,
+            )?)
+        } else if let Some(keypair) = profile_config.post_quantum_keypair()? {
+            eprintln!("No post-quantum key given, keeping existing key...");
+            Some(keypair)
+        } else {
+            eprintln!("No post-quantum key given, generating key...");
+            Some(Dilithium3KeyPair::generate().map_err(|err| {
+                CliError::UnexpectedError(format!("Failed to generate dilithium3 key {:?}", err))
+            })?)
+        };
+
         // Private key
         let private_key = if self.is_hardware_wallet() {
             // Private key stays in ledger
             None
+        } else if self.account_scheme == AccountScheme::PostQuantum {
+            // Post-quantum accounts only sign with the Dilithium3 key
+            None
         } else {
             let ed25519_private_key = if let Some(key) = self
                 .private_key_options
@@ -249,7 +295,7 @@ impl CliCommand<()> for InitTool {
                     .as_str(),
                 false,
             ) {
-                Ok(pub_key_str) => pub_key_str,
+                Ok(pub_key_str) => Some(pub_key_str),
                 Err(err) => {
                     return Err(CliError::UnexpectedError(format!(
                         "Unexpected Ledger Error: {:?}",
@@ -258,7 +304,7 @@ impl CliCommand<()> for InitTool {
                 },
             }
         } else {
-            private_key.clone().unwrap().public_key()
+            private_key.as_ref().map(|key| key.public_key())
         };
 
         let rest_url = Url::parse(
@@ -270,13 +316,30 @@ impl CliCommand<()> for InitTool {
         .map_err(|err| CliError::UnableToParse("rest_url", err.to_string()))?;
         let client = accudo_rest_client::Client::new(rest_url);
 
+        profile_config.private_key = private_key;
+        profile_config.public_key = public_key;
+        profile_config.post_quantum_private_key = post_quantum_keypair
+            .as_ref()
+            .map(|keypair| keypair.to_private_key());
+        profile_config.post_quantum_public_key = post_quantum_keypair
+            .as_ref()
+            .map(|keypair| keypair.to_public_key());
+
         // lookup the address from onchain instead of deriving it
         // if this is the rotated key, deriving it will outputs an incorrect address
-        let derived_address = account_address_from_public_key(&public_key);
+        let derived_address = match (
+            profile_config.post_quantum_key()?,
+            &profile_config.public_key,
+        ) {
+            (Some(key), _) => key.authentication_key().account_address(),
+            (None, Some(public_key)) => account_address_from_public_key(public_key),
+            (None, None) => {
+                return Err(CliError::UnexpectedError(
+                    "No public key available to derive the account address".to_string(),
+                ))
+            },
+        };
         let address = lookup_address(&client, derived_address, false).await?;
-
-        profile_config.private_key = private_key;
-        profile_config.public_key = Some(public_key);
         profile_config.account = Some(address);
 
         // Create account if it doesn't exist (and there's a faucet)
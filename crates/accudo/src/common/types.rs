@@ -24,6 +24,10 @@ use accudo_api_types::ViewFunction;
 use accudo_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
     encoding_type::{EncodingError, EncodingType},
+    pq::{
+        Dilithium3KeyPair, Dilithium3PrivateKey, Dilithium3PublicKey, SchemeId, SignatureBundle,
+        DILITHIUM3_SIGNATURE_LENGTH,
+    },
     x25519, PrivateKey, ValidCryptoMaterialStringExt,
 };
 use accudo_framework::chunked_publish::{
@@ -40,7 +44,10 @@ use accudo_rest_client::{
 };
 use accudo_sdk::{
     transaction_builder::TransactionFactory,
-    types::{HardwareWalletAccount, HardwareWalletType, LocalAccount, TransactionSigner},
+    types::{
+        HardwareWalletAccount, HardwareWalletType, HybridAccountKey, LocalAccount,
+        PostQuantumAccountKey, TransactionSigner,
+    },
 };
 use accudo_transaction_simulation::SimulationStateStore;
 use accudo_transaction_simulation_session::Session;
@@ -48,9 +55,13 @@ use accudo_types::{
     account_config::AccountResource,
     chain_id::ChainId,
     transaction::{
-        authenticator::AuthenticationKey, EntryFunction, MultisigTransactionPayload,
-        PersistedAuxiliaryInfo, ReplayProtector, Script, SignedTransaction, TransactionArgument,
-        TransactionPayload, TransactionStatus,
+        authenticator::{
+            AccountAuthenticator, AnySignature, AuthenticationKey, MultiKeyAuthenticator,
+            SingleKeyAuthenticator,
+        },
+        EntryFunction, MultisigTransactionPayload, PersistedAuxiliaryInfo, RawTransaction,
+        ReplayProtector, Script, SignedTransaction, TransactionArgument, TransactionPayload,
+        TransactionStatus,
     },
 };
 use accudo_vm_types::output::VMOutput;
@@ -287,6 +298,22 @@ pub struct ProfileConfig {
         deserialize_with = "deserialize_material_with_prefix"
     )]
     pub public_key: Option<Ed25519PublicKey>,
+    /// Dilithium3 private key for post-quantum or hybrid accounts
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        serialize_with = "serialize_material_with_prefix",
+        deserialize_with = "deserialize_material_with_prefix"
+    )]
+    pub post_quantum_private_key: Option<Dilithium3PrivateKey>,
+    /// Dilithium3 public key for post-quantum or hybrid accounts
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        serialize_with = "serialize_material_with_prefix",
+        deserialize_with = "deserialize_material_with_prefix"
+    )]
+    pub post_quantum_public_key: Option<Dilithium3PublicKey>,
     /// Account for commands
     #[serde(
         skip_serializing_if = "Option::is_none",
@@ -304,12 +331,58 @@ pub struct ProfileConfig {
     pub derivation_path: Option<String>,
 }
 
+impl ProfileConfig {
+    /// The account scheme implied by the keys stored in the profile
+    pub fn account_scheme(&self) -> AccountScheme {
+        match (&self.post_quantum_public_key, &self.private_key) {
+            (None, _) => AccountScheme::Ed25519,
+            (Some(_), None) => AccountScheme::PostQuantum,
+            (Some(_), Some(_)) => AccountScheme::Hybrid,
+        }
+    }
+
+    /// Loads the Dilithium3 keypair of the profile, if it has one
+    pub fn post_quantum_keypair(&self) -> CliTypedResult<Option<Dilithium3KeyPair>> {
+        match (&self.post_quantum_private_key, &self.post_quantum_public_key) {
+            (Some(private_key), Some(public_key)) => {
+                Dilithium3KeyPair::from_keys(private_key, public_key)
+                    .map(Some)
+                    .map_err(|err| {
+                        CliError::UnableToParse("post_quantum_private_key", err.to_string())
+                    })
+            },
+            (None, None) => Ok(None),
+            _ => Err(CliError::CommandArgumentError(
+                "Profile must contain both or neither of ['post_quantum_private_key', 'post_quantum_public_key']"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Loads the post-quantum signing key of the profile, if it has one
+    pub fn post_quantum_key(&self) -> CliTypedResult<Option<PostQuantumProfileKey>> {
+        let Some(keypair) = self.post_quantum_keypair()? else {
+            return Ok(None);
+        };
+        Ok(Some(match &self.private_key {
+            Some(ed25519_private_key) => PostQuantumProfileKey::Hybrid(
+                HybridAccountKey::from_keys(ed25519_private_key.clone(), keypair)
+                    .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
+            ),
+            None => {
+                PostQuantumProfileKey::PostQuantum(PostQuantumAccountKey::from_keypair(keypair))
+            },
+        }))
+    }
+}
+
 /// ProfileConfig but without the private parts
 #[derive(Debug, Serialize)]
 pub struct ProfileSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network: Option<Network>,
     pub has_private_key: bool,
+    pub account_scheme: AccountScheme,
     #[serde(
         skip_serializing_if = "Option::is_none",
         serialize_with = "serialize_material_with_prefix",
@@ -331,7 +404,9 @@ impl From<&ProfileConfig> for ProfileSummary {
     fn from(config: &ProfileConfig) -> Self {
         ProfileSummary {
             network: config.network,
-            has_private_key: config.private_key.is_some(),
+            has_private_key: config.private_key.is_some()
+                || config.post_quantum_private_key.is_some(),
+            account_scheme: config.account_scheme(),
             public_key: config.public_key.clone(),
             account: config.account,
             rest_url: config.rest_url.clone(),
@@ -469,6 +544,8 @@ pub enum KeyType {
     X25519,
     /// A BLS12381 key for consensus
     Bls12381,
+    /// Dilithium3 post-quantum key used for signing
+    Dilithium3,
 }
 
 impl Display for KeyType {
@@ -477,6 +554,7 @@ impl Display for KeyType {
             KeyType::Ed25519 => "ed25519",
             KeyType::X25519 => "x25519",
             KeyType::Bls12381 => "bls12381",
+            KeyType::Dilithium3 => "dilithium3",
         };
         write!(f, "{}", str)
     }
@@ -490,11 +568,91 @@ impl FromStr for KeyType {
             "ed25519" => Ok(KeyType::Ed25519),
             "x25519" => Ok(KeyType::X25519),
             "bls12381" => Ok(KeyType::Bls12381),
-            _ => Err("Invalid key type: Must be one of [ed25519, x25519]"),
+            "dilithium3" => Ok(KeyType::Dilithium3),
+            _ => Err("Invalid key type: Must be one of [ed25519, x25519, bls12381, dilithium3]"),
         }
     }
 }
 
+/// Signature schemes an account's authentication key can be derived from
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountScheme {
+    /// A single Ed25519 key
+    #[default]
+    Ed25519,
+    /// A single Dilithium3 key
+    PostQuantum,
+    /// A 2-of-2 multi-key of an Ed25519 and a Dilithium3 key
+    Hybrid,
+}
+
+impl Display for AccountScheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            AccountScheme::Ed25519 => "ed25519",
+            AccountScheme::PostQuantum => "post-quantum",
+            AccountScheme::Hybrid => "hybrid",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// Signing key of a profile using a post-quantum or hybrid account scheme
+pub enum PostQuantumProfileKey {
+    PostQuantum(PostQuantumAccountKey),
+    Hybrid(HybridAccountKey),
+}
+
+impl PostQuantumProfileKey {
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        match self {
+            PostQuantumProfileKey::PostQuantum(key) => key.authentication_key(),
+            PostQuantumProfileKey::Hybrid(key) => key.authentication_key(),
+        }
+    }
+
+    pub fn into_local_account(self, address: AccountAddress, sequence_number: u64) -> LocalAccount {
+        match self {
+            PostQuantumProfileKey::PostQuantum(key) => {
+                LocalAccount::new_post_quantum(address, key, sequence_number)
+            },
+            PostQuantumProfileKey::Hybrid(key) => {
+                LocalAccount::new_hybrid(address, key, sequence_number)
+            },
+        }
+    }
+
+    /// Attaches zeroed signatures of the right shape, so the transaction can be
+    /// simulated without being a valid signed transaction
+    pub fn simulation_transaction(&self, raw_txn: RawTransaction) -> SignedTransaction {
+        let zero_pq_signature =
+            SignatureBundle::new(SchemeId::Dilithium3, vec![0u8; DILITHIUM3_SIGNATURE_LENGTH]);
+        let authenticator = match self {
+            PostQuantumProfileKey::PostQuantum(key) => {
+                AccountAuthenticator::single_key(SingleKeyAuthenticator::new(
+                    key.public_key(),
+                    AnySignature::post_quantum(zero_pq_signature),
+                ))
+            },
+            PostQuantumProfileKey::Hybrid(key) => AccountAuthenticator::multi_key(
+                MultiKeyAuthenticator::new_with_post_quantum(key.multi_key().clone(), vec![
+                    (
+                        0,
+                        AnySignature::ed25519(
+                            Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+                        ),
+                        Some(zero_pq_signature.clone()),
+                    ),
+                    (1, AnySignature::post_quantum(zero_pq_signature), None),
+                ])
+                .expect("Hybrid signatures always match the multi-key layout"),
+            ),
+        };
+        SignedTransaction::new_single_sender(raw_txn, authenticator)
+    }
+}
+
 #[derive(Debug, Default, Parser)]
 pub struct ProfileOptions {
     /// Profile to use from the CLI config
@@ -1862,7 +2020,37 @@ impl TransactionOptions {
             )
     }
 
+    /// Retrieves the post-quantum signing key of the profile and the associated address
+    ///
+    /// Returns `None` if a private key is given on the command line, or if the profile
+    /// uses a plain Ed25519 account.
+    pub fn get_post_quantum_key_and_address(
+        &self,
+    ) -> CliTypedResult<Option<(PostQuantumProfileKey, AccountAddress)>> {
+        if self.private_key_options.has_key_or_file() {
+            return Ok(None);
+        }
+        let Some(profile) = CliConfig::load_profile(
+            self.profile_options.profile_name(),
+            ConfigSearchMode::CurrentDirAndParents,
+        )?
+        else {
+            return Ok(None);
+        };
+        let Some(key) = profile.post_quantum_key()? else {
+            return Ok(None);
+        };
+        let address = self
+            .sender_account
+            .or(profile.account)
+            .unwrap_or_else(|| key.authentication_key().account_address());
+        Ok(Some((key, address)))
+    }
+
     pub fn sender_address(&self) -> CliTypedResult<AccountAddress> {
+        if let Some((_, address)) = self.get_post_quantum_key_and_address()? {
+            return Ok(address);
+        }
         Ok(self.get_key_and_address()?.1)
     }
 
@@ -1915,7 +2103,14 @@ impl TransactionOptions {
         payload: TransactionPayload,
     ) -> CliTypedResult<Transaction> {
         let client = self.rest_client()?;
-        let (sender_public_key, sender_address) = self.get_public_key_and_address()?;
+        let post_quantum_signer = self.get_post_quantum_key_and_address()?;
+        let (sender_public_key, sender_address) = match &post_quantum_signer {
+            Some((_, address)) => (None, *address),
+            None => {
+                let (public_key, address) = self.get_public_key_and_address()?;
+                (Some(public_key), address)
+            },
+        };
 
         // Ask to confirm price if the gas unit price is estimated above the lowest value when
         // it is automatically estimated
@@ -1978,11 +2173,15 @@ impl TransactionOptions {
                 txn_builder.build()
             };
 
-            let signed_transaction = SignedTransaction::new(
-                unsigned_transaction,
-                sender_public_key.clone(),
-                Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
-            );
+            let signed_transaction = match (&post_quantum_signer, &sender_public_key) {
+                (Some((key, _)), _) => key.simulation_transaction(unsigned_transaction),
+                (None, Some(public_key)) => SignedTransaction::new(
+                    unsigned_transaction,
+                    public_key.clone(),
+                    Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+                ),
+                (None, None) => unreachable!("Ed25519 senders always have a public key"),
+            };
 
             let txns = client
                 .simulate_with_gas_estimation(&signed_transaction, true, false)
@@ -2023,8 +2222,17 @@ impl TransactionOptions {
             .with_transaction_expiration_time(self.gas_options.expiration_secs);
 
         // Sign it with the appropriate signer
-        let transaction = match self.get_transaction_account_type() {
-            Ok(AccountType::Local) => {
+        let transaction = match (post_quantum_signer, self.get_transaction_account_type()) {
+            (Some((key, _)), _) => {
+                let sender_account = &mut key.into_local_account(sender_address, sequence_number);
+                let mut txn_builder = transaction_factory.payload(payload);
+                if self.replay_protection_type == ReplayProtectionType::Nonce {
+                    let mut rng = rand::thread_rng();
+                    txn_builder = txn_builder.upgrade_payload_with_rng(&mut rng, true, true);
+                };
+                sender_account.sign_with_transaction_builder(txn_builder)
+            },
+            (None, Ok(AccountType::Local)) => {
                 let (private_key, _) = self.get_key_and_address()?;
                 let sender_account =
                     &mut LocalAccount::new(sender_address, private_key, sequence_number);
@@ -2035,10 +2243,14 @@ impl TransactionOptions {
                 };
                 sender_account.sign_with_transaction_builder(txn_builder)
             },
-            Ok(AccountType::HardwareWallet) => {
+            (None, Ok(AccountType::HardwareWallet)) => {
                 let sender_account = &mut HardwareWalletAccount::new(
                     sender_address,
-                    sender_public_key,
+                    sender_public_key.ok_or_else(|| {
+                        CliError::CommandArgumentError(
+                            "Hardware wallet profile is missing a public key".to_string(),
+                        )
+                    })?,
                     self.profile_options
                         .derivation_path()
                         .expect("derivative path is missing from profile")
@@ -2053,7 +2265,7 @@ impl TransactionOptions {
                 };
                 sender_account.sign_with_transaction_builder(txn_builder)?
             },
-            Err(err) => return Err(err),
+            (None, Err(err)) => return Err(err),
         };
 
         // Submit the transaction, printing out a useful transaction link
@@ -11,9 +11,9 @@ use crate::{
             CliCommand, CliError, CliResult, CliTypedResult, OptionalPoolAddressArgs,
             PoolAddressArgs, ProfileOptions, RestOptions, TransactionOptions, TransactionSummary,
         },
-        utils::read_from_file,
+        utils::{read_from_file, write_to_file, write_to_user_only_file},
     },
-    genesis::git::from_yaml,
+    genesis::git::{from_yaml, to_yaml},
     node::analyze::{
         analyze_validators::{AnalyzeValidators, ValidatorStats},
         fetch_metadata::FetchMetadata,
@@ -25,8 +25,12 @@ use accudo_backup_cli::{
     utils::GlobalRestoreOpt,
 };
 use accudo_cached_packages::accudo_stdlib;
+use accudo_config::config::IdentityBlob;
 use accudo_crypto::{
-    bls12381, bls12381::PublicKey, pq::KyberPublicKey, x25519, ValidCryptoMaterialStringExt,
+    bls12381,
+    bls12381::PublicKey,
+    pq::{KyberKeyPair, KyberPublicKey},
+    x25519, ValidCryptoMaterialStringExt,
 };
 use accudo_genesis::config::{HostAndPort, OperatorConfiguration};
 use accudo_logger::Level;
@@ -54,7 +58,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     convert::{TryFrom, TryInto},
-    path::PathBuf,
+    path::{Path, PathBuf},
     time::Duration,
 };
 
@@ -82,6 +86,7 @@ pub enum NodeTool {
     RunLocalnet(RunLocalnet),
     UpdateConsensusKey(UpdateConsensusKey),
     UpdateValidatorNetworkAddresses(UpdateValidatorNetworkAddresses),
+    RotateNetworkPostQuantumKey(RotateNetworkPostQuantumKey),
 }
 
 impl NodeTool {
@@ -109,6 +114,7 @@ impl NodeTool {
                 .map(|_| "".to_string()),
             UpdateConsensusKey(tool) => tool.execute_serialized().await,
             UpdateValidatorNetworkAddresses(tool) => tool.execute_serialized().await,
+            RotateNetworkPostQuantumKey(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -240,10 +246,12 @@ impl ValidatorNetworkAddressesArgs {
             };
 
         let validator_network_post_quantum_public_key =
-            if let Some(public_key) = self.validator_network_post_quantum_public_key {
-                Some(public_key)
+            if let Some(ref public_key) = self.validator_network_post_quantum_public_key {
+                Some(public_key.clone())
             } else if let Some(operator_config) = operator_config {
-                operator_config.validator_network_post_quantum_public_key
+                operator_config
+                    .validator_network_post_quantum_public_key
+                    .clone()
             } else {
                 None
             };
@@ -258,10 +266,12 @@ impl ValidatorNetworkAddressesArgs {
             };
 
         let full_node_network_post_quantum_public_key =
-            if let Some(public_key) = self.full_node_network_post_quantum_public_key {
-                Some(public_key)
+            if let Some(ref public_key) = self.full_node_network_post_quantum_public_key {
+                Some(public_key.clone())
             } else if let Some(operator_config) = operator_config {
-                operator_config.full_node_network_post_quantum_public_key
+                operator_config
+                    .full_node_network_post_quantum_public_key
+                    .clone()
             } else {
                 None
             };
@@ -1193,6 +1203,147 @@ impl CliCommand<TransactionSummary> for UpdateValidatorNetworkAddresses {
     }
 }
 
+/// Rotate the Kyber post-quantum network keys of a validator
+///
+/// Generates new Kyber768 keys and writes them to the given identity files.  If an operator
+/// configuration file is provided, its post-quantum public keys are updated as well, so the new
+/// keys can be published on-chain with `update-validator-network-addresses`.
+///
+/// Peers keep dialing with the on-chain keys until the new ones take effect at the next epoch,
+/// so the replaced keys are kept in the identity files and still accepted for inbound handshakes.
+/// Once the new keys are on-chain, run this again with `--drop-previous-key` and restart the node.
+#[derive(Parser)]
+pub struct RotateNetworkPostQuantumKey {
+    /// Validator identity file e.g. `validator-identity.yaml`
+    #[clap(long, value_parser)]
+    pub(crate) validator_identity_file: Option<PathBuf>,
+
+    /// Validator full node identity file e.g. `validator-full-node-identity.yaml`
+    #[clap(long, value_parser)]
+    pub(crate) full_node_identity_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) operator_config_file_args: OperatorConfigFileArgs,
+
+    /// Stop accepting the previous Kyber keys instead of rotating
+    ///
+    /// Only use this once the current keys are published on-chain and in effect.
+    #[clap(long)]
+    pub(crate) drop_previous_key: bool,
+}
+
+impl RotateNetworkPostQuantumKey {
+    fn read_identity_file(path: &Path) -> CliTypedResult<IdentityBlob> {
+        from_yaml(&String::from_utf8(read_from_file(path)?).map_err(CliError::from)?)
+    }
+
+    fn write_identity_file(path: &Path, identity: &IdentityBlob) -> CliTypedResult<()> {
+        write_to_user_only_file(
+            path,
+            &path.display().to_string(),
+            to_yaml(identity)?.as_bytes(),
+        )
+    }
+
+    /// Replaces the Kyber key in the identity file, returning the new public key.  The
+    /// replaced key is kept as the previous key, so peers can still use it until the new
+    /// key is published on-chain.
+    fn rotate_identity_file(path: &Path) -> CliTypedResult<KyberPublicKey> {
+        let mut identity = Self::read_identity_file(path)?;
+        let key_pair = KyberKeyPair::generate()?;
+        identity.network_previous_post_quantum_private_key = identity
+            .network_post_quantum_private_key
+            .replace(key_pair.private);
+        Self::write_identity_file(path, &identity)?;
+        Ok(key_pair.public)
+    }
+
+    /// Removes the previous Kyber key from the identity file
+    fn drop_previous_key(path: &Path) -> CliTypedResult<()> {
+        let mut identity = Self::read_identity_file(path)?;
+        identity.network_previous_post_quantum_private_key = None;
+        Self::write_identity_file(path, &identity)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotatedNetworkPostQuantumKeys {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator_network_post_quantum_public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_node_network_post_quantum_public_key: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<RotatedNetworkPostQuantumKeys> for RotateNetworkPostQuantumKey {
+    fn command_name(&self) -> &'static str {
+        "RotateNetworkPostQuantumKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<RotatedNetworkPostQuantumKeys> {
+        if self.validator_identity_file.is_none() && self.full_node_identity_file.is_none() {
+            return Err(CliError::CommandArgumentError(
+                "Must provide at least one of --validator-identity-file or --full-node-identity-file"
+                    .to_string(),
+            ));
+        }
+
+        if self.drop_previous_key {
+            for path in [&self.validator_identity_file, &self.full_node_identity_file]
+                .into_iter()
+                .flatten()
+            {
+                Self::drop_previous_key(path)?;
+            }
+            return Ok(RotatedNetworkPostQuantumKeys {
+                validator_network_post_quantum_public_key: None,
+                full_node_network_post_quantum_public_key: None,
+            });
+        }
+
+        let validator_network_post_quantum_public_key = self
+            .validator_identity_file
+            .as_deref()
+            .map(Self::rotate_identity_file)
+            .transpose()?;
+        let full_node_network_post_quantum_public_key = self
+            .full_node_identity_file
+            .as_deref()
+            .map(Self::rotate_identity_file)
+            .transpose()?;
+
+        if let Some(mut operator_config) = self.operator_config_file_args.load()? {
+            if let Some(ref public_key) = validator_network_post_quantum_public_key {
+                operator_config.validator_network_post_quantum_public_key =
+                    Some(public_key.clone());
+            }
+            if let Some(ref public_key) = full_node_network_post_quantum_public_key {
+                operator_config.full_node_network_post_quantum_public_key =
+                    Some(public_key.clone());
+            }
+            let operator_config_file = self
+                .operator_config_file_args
+                .operator_config_file
+                .as_ref()
+                .expect("Operator config file must be present if it was loaded");
+            write_to_file(
+                operator_config_file,
+                &operator_config_file.display().to_string(),
+                to_yaml(&operator_config)?.as_bytes(),
+            )?;
+        }
+
+        Ok(RotatedNetworkPostQuantumKeys {
+            validator_network_post_quantum_public_key: validator_network_post_quantum_public_key
+                .map(|key| key.to_encoded_string())
+                .transpose()?,
+            full_node_network_post_quantum_public_key: full_node_network_post_quantum_public_key
+                .map(|key| key.to_encoded_string())
+                .transpose()?,
+        })
+    }
+}
+
 /// Analyze the performance of one or more validators
 #[derive(Parser)]
 pub struct AnalyzeValidatorPerformance {
@@ -1536,8 +1687,15 @@ impl Time {
 
 #[cfg(test)]
 mod tests {
-    use crate::{CliResult, Tool};
+    use crate::{
+        genesis::git::{from_yaml, to_yaml},
+        CliResult, Tool,
+    };
+    use accudo_config::config::IdentityBlob;
+    use accudo_crypto::{pq::KyberKeyPair, PrivateKey, ValidCryptoMaterialStringExt};
+    use accudo_keygen::KeyGen;
     use clap::Parser;
+    use std::path::Path;
 
     // TODO: there have to be cleaner ways to test things. Maybe a CLI test framework?
 
@@ -1569,6 +1727,79 @@ mod tests {
         assert_contains(error_message, "Timed out while checking endpoint");
     }
 
+    #[tokio::test]
+    async fn test_rotate_network_post_quantum_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let identity_file = dir.path().join("validator-identity.yaml");
+        let original_key = KyberKeyPair::generate().unwrap();
+        let identity = IdentityBlob {
+            account_address: None,
+            account_private_key: None,
+            consensus_private_key: None,
+            network_private_key: KeyGen::from_os_rng().generate_x25519_private_key().unwrap(),
+            network_post_quantum_private_key: Some(original_key.private),
+            network_previous_post_quantum_private_key: None,
+        };
+        std::fs::write(&identity_file, to_yaml(&identity).unwrap()).unwrap();
+        let identity_file_arg = identity_file.to_str().unwrap();
+
+        // At least one identity file is required
+        let error_message =
+            run_tool_with_args(&["accudo", "node", "rotate-network-post-quantum-key"])
+                .await
+                .unwrap_err();
+        assert_contains(error_message, "Must provide at least one of");
+
+        // Rotating replaces the key, but keeps accepting the original one
+        let output = run_tool_with_args(&[
+            "accudo",
+            "node",
+            "rotate-network-post-quantum-key",
+            "--validator-identity-file",
+            identity_file_arg,
+        ])
+        .await
+        .unwrap();
+        let rotated = read_identity(&identity_file);
+        let rotated_key = rotated
+            .network_post_quantum_private_key
+            .unwrap()
+            .public_key();
+        assert_ne!(rotated_key, original_key.public);
+        assert_eq!(
+            rotated
+                .network_previous_post_quantum_private_key
+                .map(|key| key.public_key()),
+            Some(original_key.public)
+        );
+        assert_eq!(rotated.network_private_key, identity.network_private_key);
+        assert_contains(output, &rotated_key.to_encoded_string().unwrap());
+
+        // Dropping the previous key leaves the current key in place
+        run_tool_with_args(&[
+            "accudo",
+            "node",
+            "rotate-network-post-quantum-key",
+            "--validator-identity-file",
+            identity_file_arg,
+            "--drop-previous-key",
+        ])
+        .await
+        .unwrap();
+        let dropped = read_identity(&identity_file);
+        assert_eq!(
+            dropped
+                .network_post_quantum_private_key
+                .map(|key| key.public_key()),
+            Some(rotated_key)
+        );
+        assert!(dropped.network_previous_post_quantum_private_key.is_none());
+    }
+
+    fn read_identity(path: &Path) -> IdentityBlob {
+        from_yaml(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
     async fn run_tool_with_args(args: &[&str]) -> CliResult {
         let tool: Tool = Tool::try_parse_from(args).map_err(|msg| msg.to_string())?;
         tool.execute().await
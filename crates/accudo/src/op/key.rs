@@ -16,7 +16,10 @@ use crate::{
 };
 use accudo_config::config::{Peer, PeerRole, HANDSHAKE_VERSION};
 use accudo_crypto::{
-    bls12381, ed25519, ed25519::Ed25519PrivateKey, encoding_type::EncodingType, pq::KyberPublicKey,
+    bls12381, ed25519,
+    ed25519::Ed25519PrivateKey,
+    encoding_type::EncodingType,
+    pq::{Dilithium3KeyPair, Dilithium3PrivateKey, Dilithium3PublicKey, KyberPublicKey},
     x25519, PrivateKey, ValidCryptoMaterial,
 };
 use accudo_genesis::config::HostAndPort;
@@ -231,7 +234,7 @@ impl NetworkKeyInputOptions {
     }
 }
 
-/// Generates a `x25519`, `ed25519` or `bls12381` key.
+/// Generates a `x25519`, `ed25519`, `bls12381` or `dilithium3` key.
 ///
 /// This can be used for generating an identity.  Two files will be created
 /// `output_file` and `output_file.pub`.  `output_file` will contain the private
@@ -239,7 +242,7 @@ impl NetworkKeyInputOptions {
 /// key encoded with the `encoding`.
 #[derive(Debug, Parser)]
 pub struct GenerateKey {
-    /// Key type to generate. Must be one of [x25519, ed25519, bls12381]
+    /// Key type to generate. Must be one of [x25519, ed25519, bls12381, dilithium3]
     #[clap(long, default_value_t = KeyType::Ed25519)]
     pub(crate) key_type: KeyType,
     /// Vanity prefix that resultant account address should start with, e.g. 0xaceface or d00d. Each
@@ -326,6 +329,15 @@ impl CliCommand<HashMap<&'static str, PathBuf>> for GenerateKey {
                 let private_key = keygen.generate_bls12381_private_key();
                 self.save_params.save_bls_key(&private_key, "bls12381")
             },
+            KeyType::Dilithium3 => {
+                let keypair = Dilithium3KeyPair::generate().map_err(|err| {
+                    CliError::UnexpectedError(format!(
+                        "Failed to generate dilithium3 key {:?}",
+                        err
+                    ))
+                })?;
+                self.save_params.save_dilithium3_key(&keypair, "dilithium3")
+            },
         }
     }
 }
@@ -453,6 +465,13 @@ impl CliCommand<HashMap<&'static str, PathBuf>> for ExtractPublicKey {
                     )?,
                 ]
             },
+            KeyType::Dilithium3 => {
+                return Err(CliError::CommandArgumentError(
+                    "A dilithium3 public key cannot be derived from its private key, use the \
+                     public key file written by `key generate` instead"
+                        .to_string(),
+                ))
+            },
         };
         Ok(HashMap::from_iter(files))
     }
@@ -511,6 +530,50 @@ impl SaveKey {
         Ok(map)
     }
 
+    /// Saves a Dilithium3 keypair to files encoded in a string
+    ///
+    /// Unlike the classical keys, the public key can't be derived from the private key,
+    /// so both halves are written out here.
+    pub fn save_dilithium3_key(
+        self,
+        keypair: &Dilithium3KeyPair,
+        key_name: &'static str,
+    ) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
+        let encoded_private_key = self
+            .encoding_options
+            .encoding
+            .encode_key(key_name, &keypair.to_private_key())?;
+        let encoded_public_key = self
+            .encoding_options
+            .encoding
+            .encode_key(key_name, &keypair.to_public_key())?;
+
+        // Write private and public keys to files
+        let public_key_file = self.public_key_file()?;
+        self.file_options
+            .save_to_file_confidential(key_name, &encoded_private_key)?;
+        write_to_file(&public_key_file, key_name, &encoded_public_key)?;
+
+        let mut map = HashMap::new();
+        map.insert("PrivateKey Path", self.file_options.output_file);
+        map.insert("PublicKey Path", public_key_file);
+        Ok(map)
+    }
+
+    /// Loads a Dilithium3 keypair saved by `save_dilithium3_key`
+    pub fn load_dilithium3_key(
+        encoding: EncodingType,
+        key_file: &Path,
+    ) -> CliTypedResult<Dilithium3KeyPair> {
+        let private_key: Dilithium3PrivateKey = encoding.load_key("dilithium3", key_file)?;
+        let public_key: Dilithium3PublicKey = encoding.load_key(
+            "dilithium3",
+            &append_file_extension(key_file, PUBLIC_KEY_EXTENSION)?,
+        )?;
+        Dilithium3KeyPair::from_keys(&private_key, &public_key)
+            .map_err(|err| CliError::UnableToParse("dilithium3 key", err.to_string()))
+    }
+
     /// Saves material to an enocded file
     pub fn save_material<Key: ValidCryptoMaterial>(
         self,
@@ -14,13 +14,14 @@ use crate::{
     common::{
         init::{InitTool, Network},
         types::{
-            account_address_from_public_key, AccountAddressWrapper, ArgWithTypeVec,
+            account_address_from_public_key, AccountAddressWrapper, AccountScheme, ArgWithTypeVec,
             AuthenticationKeyInputOptions, ChunkedPublishOption, CliError, CliTypedResult,
             EncodingOptions, EntryFunctionArguments, FaucetOptions, GasOptions, KeyType,
             LargePackagesModuleOption, MoveManifestAccountWrapper, MovePackageOptions,
             OptionalPoolAddressArgs, OverrideSizeCheckOption, PoolAddressArgs,
-            PrivateKeyInputOptions, PromptOptions, PublicKeyInputOptions, RestOptions, RngArgs,
-            SaveFile, ScriptFunctionArguments, TransactionOptions, TransactionSummary, TypeArgVec,
+            PrivateKeyInputOptions, ProfileOptions, PromptOptions, PublicKeyInputOptions,
+            RestOptions, RngArgs, SaveFile, ScriptFunctionArguments, TransactionOptions,
+            TransactionSummary, TypeArgVec,
         },
         utils::write_to_file,
     },
@@ -257,7 +258,47 @@ impl CliTestFramework {
                 new_private_key_file: None,
                 new_derivation_path: None,
                 new_derivation_index: None,
+                new_post_quantum_key_file: None,
             },
+            hybrid: false,
+            new_profile_options: NewProfileOptions {
+                skip_saving_profile: true,
+                save_to_profile: None,
+            },
+        }
+        .execute()
+        .await?;
+
+        Ok(response)
+    }
+
+    /// Rotates the account at `index` to the Dilithium3 key in `key_file`, keeping its
+    /// Ed25519 key in a hybrid multi-key if `hybrid` is set.
+    pub async fn rotate_key_to_post_quantum(
+        &mut self,
+        index: usize,
+        key_file: PathBuf,
+        hybrid: bool,
+    ) -> CliTypedResult<RotateSummary> {
+        let response = RotateKey {
+            txn_options: TransactionOptions {
+                private_key_options: PrivateKeyInputOptions::from_private_key(
+                    self.private_key(index),
+                )
+                .unwrap(),
+                sender_account: Some(self.account_id(index)),
+                rest_options: self.rest_options(),
+                prompt_options: PromptOptions::yes(),
+                ..Default::default()
+            },
+            new_auth_key_options: NewAuthKeyOptions {
+                new_private_key: None,
+                new_private_key_file: None,
+                new_derivation_path: None,
+                new_derivation_index: None,
+                new_post_quantum_key_file: Some(key_file),
+            },
+            hybrid,
             new_profile_options: NewProfileOptions {
                 skip_saving_profile: true,
                 save_to_profile: None,
@@ -296,6 +337,31 @@ impl CliTestFramework {
         .await
     }
 
+    /// Transfers coins from the account at `sender_index`, signing with the keys of `profile`
+    pub async fn transfer_coins_with_profile(
+        &self,
+        profile: &str,
+        sender_index: usize,
+        receiver_index: usize,
+        amount: u64,
+    ) -> CliTypedResult<TransferSummary> {
+        TransferCoins {
+            txn_options: TransactionOptions {
+                profile_options: ProfileOptions {
+                    profile: Some(profile.to_string()),
+                },
+                sender_account: Some(self.account_id(sender_index)),
+                rest_options: self.rest_options(),
+                prompt_options: PromptOptions::yes(),
+                ..Default::default()
+            },
+            account: self.account_id(receiver_index),
+            amount,
+        }
+        .execute()
+        .await
+    }
+
     pub async fn transfer_invalid_addr(
         &self,
         sender_index: usize,
@@ -540,6 +606,18 @@ impl CliTestFramework {
     }
 
     pub async fn init(&self, private_key: &Ed25519PrivateKey) -> CliTypedResult<()> {
+        self.init_with_account_scheme(None, private_key, AccountScheme::Ed25519, None)
+            .await
+    }
+
+    /// Initializes `profile` (or the default profile) with the given account scheme
+    pub async fn init_with_account_scheme(
+        &self,
+        profile: Option<&str>,
+        private_key: &Ed25519PrivateKey,
+        account_scheme: AccountScheme,
+        post_quantum_key_file: Option<PathBuf>,
+    ) -> CliTypedResult<()> {
         InitTool {
             network: Some(Network::Custom),
             rest_url: Some(self.endpoint.clone()),
@@ -549,12 +627,16 @@ impl CliTestFramework {
             },
             rng_args: RngArgs::from_seed([0; 32]),
             private_key_options: PrivateKeyInputOptions::from_private_key(private_key)?,
-            profile_options: Default::default(),
+            profile_options: ProfileOptions {
+                profile: profile.map(str::to_string),
+            },
             prompt_options: PromptOptions::yes(),
             encoding_options: EncodingOptions::default(),
             skip_faucet: false,
             ledger: false,
             hardware_wallet_options: Default::default(),
+            account_scheme,
+            post_quantum_key_file,
         }
         .execute()
         .await
@@ -746,6 +828,27 @@ impl CliTestFramework {
         .await
     }
 
+    pub async fn generate_dilithium3_key(
+        &self,
+        output_file: PathBuf,
+    ) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
+        GenerateKey {
+            key_type: KeyType::Dilithium3,
+            rng_args: RngArgs::from_seed([0; 32]),
+            save_params: SaveKey {
+                file_options: SaveFile {
+                    output_file,
+                    prompt_options: PromptOptions::yes(),
+                },
+                encoding_options: Default::default(),
+            },
+            vanity_prefix: None,
+            vanity_multisig: false,
+        }
+        .execute()
+        .await
+    }
+
     pub async fn extract_peer(
         &self,
         host: HostAndPort,
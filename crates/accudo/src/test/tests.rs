@@ -2,11 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    account::AccountTool,
+    common::types::AccountScheme,
     move_tool::{ArgWithType, FunctionArgType},
+    op::key::SaveKey,
     CliResult, Tool,
 };
+use accudo_crypto::encoding_type::EncodingType;
+use accudo_temppath::TempPath;
 use clap::Parser;
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
 /// In order to ensure that there aren't duplicate input arguments for untested CLI commands,
 /// we call help on every command to ensure it at least runs
@@ -127,6 +132,125 @@ async fn ensure_can_parse_args_with_urls() {
     );
 }
 
+#[tokio::test]
+async fn generate_dilithium3_key() {
+    let key_dir = TempPath::new();
+    key_dir.create_as_dir().unwrap();
+    let key_file = key_dir.path().join("key");
+    let key_file_str = key_file.to_str().unwrap();
+
+    run_cmd(&[
+        "accudo",
+        "key",
+        "generate",
+        "--key-type",
+        "dilithium3",
+        "--output-file",
+        key_file_str,
+    ])
+    .await
+    .unwrap();
+
+    // Both halves are written out and load back as a matching keypair
+    assert!(key_file.exists());
+    assert!(key_dir.path().join("key.pub").exists());
+    SaveKey::load_dilithium3_key(EncodingType::Hex, &key_file).unwrap();
+
+    // Dilithium3 public keys can't be derived from the private key
+    assert!(run_cmd(&[
+        "accudo",
+        "key",
+        "extract-public-key",
+        "--key-type",
+        "dilithium3",
+        "--private-key-file",
+        key_file_str,
+        "--output-file",
+        key_dir.path().join("extracted").to_str().unwrap(),
+    ])
+    .await
+    .is_err());
+}
+
+#[test]
+fn parse_post_quantum_init_args() {
+    for (scheme, expected) in [
+        ("post-quantum", AccountScheme::PostQuantum),
+        ("hybrid", AccountScheme::Hybrid),
+    ] {
+        let tool = Tool::try_parse_from([
+            "accudo",
+            "init",
+            "--account-scheme",
+            scheme,
+            "--post-quantum-key-file",
+            "key",
+        ])
+        .unwrap();
+        let Tool::Init(init) = tool else {
+            panic!("Expected init command");
+        };
+        assert_eq!(init.account_scheme, expected);
+        assert_eq!(init.post_quantum_key_file, Some(PathBuf::from("key")));
+    }
+
+    let Tool::Init(init) = Tool::try_parse_from(["accudo", "init"]).unwrap() else {
+        panic!("Expected init command");
+    };
+    assert_eq!(init.account_scheme, AccountScheme::Ed25519);
+}
+
+#[test]
+fn parse_post_quantum_rotate_key_args() {
+    for hybrid in [false, true] {
+        let mut args = vec![
+            "accudo",
+            "account",
+            "rotate-key",
+            "--new-post-quantum-key-file",
+            "key",
+            "--skip-saving-profile",
+        ];
+        if hybrid {
+            args.push("--hybrid");
+        }
+        let Tool::Account(AccountTool::RotateKey(rotate)) = Tool::try_parse_from(args).unwrap()
+        else {
+            panic!("Expected rotate-key command");
+        };
+        assert_eq!(
+            rotate.new_auth_key_options.new_post_quantum_key_file,
+            Some(PathBuf::from("key"))
+        );
+        assert_eq!(rotate.hybrid, hybrid);
+    }
+
+    // A hybrid key needs a Dilithium3 key to go with the Ed25519 key
+    assert!(Tool::try_parse_from([
+        "accudo",
+        "account",
+        "rotate-key",
+        "--new-private-key",
+        "0x1",
+        "--hybrid",
+        "--skip-saving-profile",
+    ])
+    .is_err());
+
+    // Only one new key may be given
+    assert!(Tool::try_parse_from([
+        "accudo",
+        "account",
+        "rotate-key",
+        "--new-private-key",
+        "0x1",
+        "--new-post-quantum-key-file",
+        "key",
+        "--skip-saving-profile",
+    ])
+    .is_err());
+}
+
 async fn assert_cmd_not_panic(args: &[&str]) {
     // When a command fails, it will have a panic in it due to an improperly setup command
     // thread 'main' panicked at 'Command propose: Argument names must be unique, but 'assume-yes' is
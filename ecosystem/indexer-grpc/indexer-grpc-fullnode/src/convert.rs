@@ -668,6 +668,18 @@ fn convert_signature(signature: &Signature) -> transaction::AnySignature {
                 signature: s.value.clone().into(),
             })),
         },
+        // Post-quantum and hybrid signatures have no dedicated proto type yet, so only the raw
+        // signature bytes are exported.
+        Signature::PostQuantum(s) => transaction::AnySignature {
+            r#type: transaction::any_signature::Type::Unspecified as i32,
+            signature: s.value.clone().into(),
+            signature_variant: None,
+        },
+        Signature::Hybrid(s) => transaction::AnySignature {
+            r#type: transaction::any_signature::Type::Unspecified as i32,
+            signature: [s.ed25519.inner(), s.post_quantum.value.inner()].concat(),
+            signature_variant: None,
+        },
     }
 }
 
@@ -693,6 +705,14 @@ fn convert_public_key(public_key: &PublicKey) -> transaction::AnyPublicKey {
             r#type: transaction::any_public_key::Type::FederatedKeyless as i32,
             public_key: p.value.clone().into(),
         },
+        PublicKey::PostQuantum(p) => transaction::AnyPublicKey {
+            r#type: transaction::any_public_key::Type::Unspecified as i32,
+            public_key: p.value.clone().into(),
+        },
+        PublicKey::Hybrid(p) => transaction::AnyPublicKey {
+            r#type: transaction::any_public_key::Type::Unspecified as i32,
+            public_key: [p.ed25519.inner(), p.post_quantum.value.inner()].concat(),
+        },
     }
 }
 
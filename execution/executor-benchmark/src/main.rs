@@ -11,7 +11,8 @@ use accudo_block_partitioner::{
     v2::config::PartitionerV2Config,
 };
 use accudo_config::config::{
-    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, StateMerklePrunerConfig,
+    EpochSnapshotPrunerConfig, LedgerColdStorageConfig, LedgerPrunerConfig, PrunerConfig,
+    StateMerklePrunerConfig,
 };
 use accudo_executor_benchmark::{
     default_benchmark_features,
@@ -105,6 +106,7 @@ impl PrunerOpt {
                 batch_size: self.ledger_pruning_batch_size,
                 user_pruning_window_offset: 0,
             },
+            ledger_cold_storage_config: LedgerColdStorageConfig::default(),
         }
     }
 }
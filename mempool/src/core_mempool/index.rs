@@ -110,6 +110,10 @@ impl AccountTransactions {
             .chain(self.sequence_number_transactions.values())
     }
 
+    pub(crate) fn seq_nums(&self) -> impl Iterator<Item = u64> + '_ {
+        self.sequence_number_transactions.keys().copied()
+    }
+
     pub(crate) fn orderless_txns_len(&self) -> usize {
         self.nonce_transactions.len()
     }
@@ -154,6 +158,18 @@ impl PriorityIndex {
         self.data.contains(&self.make_key(txn))
     }
 
+    /// Returns the number of transactions ahead of `txn` in the queue, counting at most
+    /// `max_rank` of them, or `None` if `txn` is not in the queue.
+    pub(crate) fn rank(&self, txn: &MempoolTransaction, max_rank: usize) -> Option<usize> {
+        let key = self.make_key(txn);
+        self.data.contains(&key).then(|| {
+            self.data
+                .range((Bound::Excluded(&key), Bound::Unbounded))
+                .take(max_rank)
+                .count()
+        })
+    }
+
     fn make_key(&self, txn: &MempoolTransaction) -> OrderedQueueKey {
         OrderedQueueKey {
             gas_ranking_score: txn.ranking_score,
@@ -483,10 +499,7 @@ impl MultiBucketTimelineIndex {
 
     #[inline]
     fn get_timeline(&mut self, ranking_score: RankingScore) -> &mut TimelineIndex {
-        let index = self
-            .bucket_mins
-            .binary_search(&ranking_score)
-            .unwrap_or_else(|i| i - 1);
+        let index = self.get_bucket_index(ranking_score);
         self.timelines.get_mut(index).unwrap()
     }
 
@@ -516,11 +529,15 @@ impl MultiBucketTimelineIndex {
 
     #[inline]
     pub(crate) fn get_bucket(&self, ranking_score: RankingScore) -> &str {
-        let index = self
-            .bucket_mins
+        self.bucket_mins_to_string[self.get_bucket_index(ranking_score)].as_str()
+    }
+
+    /// Returns the index of the timeline holding transactions with the given ranking score.
+    #[inline]
+    pub(crate) fn get_bucket_index(&self, ranking_score: RankingScore) -> usize {
+        self.bucket_mins
             .binary_search(&ranking_score)
-            .unwrap_or_else(|i| i - 1);
-        self.bucket_mins_to_string[index].as_str()
+            .unwrap_or_else(|i| i - 1)
     }
 }
 
@@ -656,6 +673,14 @@ impl ParkingLotIndex {
             .map(|(addr, txns)| (*addr, txns.len() as u64))
             .collect::<Vec<(AccountAddress, u64)>>()
     }
+
+    /// Returns the sequence numbers of the parked transactions of each account.
+    pub(crate) fn get_sequence_numbers(&self) -> Vec<(AccountAddress, Vec<u64>)> {
+        self.data
+            .iter()
+            .map(|(addr, txns)| (*addr, txns.iter().map(|(seq_num, _)| *seq_num).collect()))
+            .collect()
+    }
 }
 
 /// Logical pointer to `MempoolTransaction`.
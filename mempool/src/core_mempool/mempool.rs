@@ -9,12 +9,15 @@ use crate::{
         index::TxnPointer,
         transaction::{InsertionInfo, MempoolTransaction, TimelineState},
         transaction_store::{sender_bucket, TransactionStore},
+        TimelinePosition,
     },
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
     shared_mempool::types::{
-        MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+        MempoolParkedAccount, MempoolSenderBucket, MempoolTransactionEvent,
+        MempoolTransactionStatus, MempoolTransactionSummary, MultiBucketTimelineIndexIds,
+        TimelineIndexIdentifier,
     },
 };
 use accudo_config::config::NodeConfig;
@@ -32,6 +35,7 @@ use std::{
     sync::atomic::Ordering,
     time::{Duration, Instant, SystemTime},
 };
+use tokio::sync::broadcast;
 
 pub struct Mempool {
     // Stores the metadata of all transactions in mempool (of all states).
@@ -284,6 +288,22 @@ impl Mempool {
         self.transactions.get_by_hash(hash)
     }
 
+    /// Returns the status of a transaction and its position in the broadcast timelines, if it's
+    /// in the Mempool.
+    pub(crate) fn get_transaction_status(
+        &self,
+        hash: HashValue,
+    ) -> Option<(MempoolTransactionStatus, Option<TimelinePosition>)> {
+        self.transactions.get_status_by_hash(hash)
+    }
+
+    /// Subscribes to the events of transactions entering and leaving the Mempool.
+    pub(crate) fn subscribe_transaction_events(
+        &self,
+    ) -> broadcast::Receiver<MempoolTransactionEvent> {
+        self.transactions.subscribe_events()
+    }
+
     /// Used to add a transaction to the Mempool.
     /// Performs basic validation: checks account's sequence number.
     pub(crate) fn add_txn(
@@ -668,4 +688,54 @@ impl Mempool {
     pub fn get_parking_lot_addresses(&self) -> Vec<(AccountAddress, u64)> {
         self.transactions.get_parking_lot_addresses()
     }
+
+    pub(crate) fn get_transactions_by_sender(
+        &self,
+        address: &AccountAddress,
+    ) -> Vec<MempoolTransactionSummary> {
+        self.transactions.get_transactions_by_sender(address)
+    }
+
+    pub(crate) fn get_parked_accounts(&self) -> Vec<MempoolParkedAccount> {
+        self.transactions.get_parked_accounts()
+    }
+
+    pub(crate) fn get_priority_queue(&self, limit: usize) -> Vec<MempoolTransactionSummary> {
+        self.transactions.get_priority_queue(limit)
+    }
+
+    /// Evicts the transaction with the given hash on request of an operator.
+    pub(crate) fn evict_transaction(
+        &mut self,
+        hash: HashValue,
+    ) -> Option<MempoolTransactionSummary> {
+        let evicted = self.transactions.evict_by_hash(hash);
+        Self::log_evicted_transactions(evicted.as_slice());
+        evicted
+    }
+
+    /// Evicts all transactions of the given account on request of an operator.
+    pub(crate) fn evict_sender(
+        &mut self,
+        address: &AccountAddress,
+    ) -> Vec<MempoolTransactionSummary> {
+        let evicted = self.transactions.evict_sender(address);
+        Self::log_evicted_transactions(&evicted);
+        evicted
+    }
+
+    fn log_evicted_transactions(evicted: &[MempoolTransactionSummary]) {
+        if evicted.is_empty() {
+            return;
+        }
+        let mut txns_log = TxnsLog::new();
+        for txn in evicted {
+            txns_log.add(txn.sender, txn.replay_protector);
+        }
+        info!(
+            LogSchema::new(LogEntry::RemoveTxn).txns(txns_log),
+            "Evicted {} transactions on operator request",
+            evicted.len()
+        );
+    }
 }
@@ -7,6 +7,7 @@ mod mempool;
 pub mod transaction;
 mod transaction_store;
 
+pub(crate) use self::transaction_store::TimelinePosition;
 pub use self::{
     index::TimelineId, mempool::Mempool as CoreMempool, transaction::TimelineState,
     transaction_store::TXN_INDEX_ESTIMATED_BYTES,
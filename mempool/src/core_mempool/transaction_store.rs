@@ -6,7 +6,7 @@ use crate::{
     core_mempool::{
         index::{
             AccountTransactions, MultiBucketTimelineIndex, ParkingLotIndex, PriorityIndex,
-            PriorityQueueIter, TTLIndex,
+            PriorityQueueIter, TTLIndex, TimelineId,
         },
         mempool::Mempool,
         transaction::{InsertionInfo, MempoolTransaction, TimelineState},
@@ -15,7 +15,10 @@ use crate::{
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
     shared_mempool::types::{
-        MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+        MempoolParkedAccount, MempoolSenderBucket, MempoolTransactionEvent,
+        MempoolTransactionEventKind, MempoolTransactionState, MempoolTransactionStatus,
+        MempoolTransactionSummary, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+        MAX_TRANSACTION_STATUS_RANK,
     },
 };
 use accudo_config::config::MempoolConfig;
@@ -24,15 +27,17 @@ use accudo_logger::{prelude::*, Level};
 use accudo_types::{
     account_address::AccountAddress,
     mempool_status::{MempoolStatus, MempoolStatusCode},
-    transaction::{ReplayProtector, SignedTransaction},
+    transaction::{ReplayProtector, SignedTransaction, TransactionExecutableRef},
 };
 use std::{
     cmp::max,
     collections::HashMap,
     mem::size_of,
     ops::Bound,
-    time::{Duration, Instant, SystemTime},
+    sync::atomic::Ordering,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
+use tokio::sync::broadcast;
 
 /// Estimated per-txn overhead of indexes. Needs to be updated if additional indexes are added.
 pub const TXN_INDEX_ESTIMATED_BYTES: usize = size_of::<crate::core_mempool::index::OrderedQueueKey>() // priority_index
@@ -40,6 +45,10 @@ pub const TXN_INDEX_ESTIMATED_BYTES: usize = size_of::<crate::core_mempool::inde
     + (size_of::<u64>() * 3 + size_of::<AccountAddress>()) // timeline_index
     + (size_of::<HashValue>() + size_of::<u64>() + size_of::<AccountAddress>()); // hash_index
 
+/// Position of a transaction in the broadcast timelines: its sender bucket, the timeline within
+/// that bucket, and its id in that timeline.
+pub(crate) type TimelinePosition = (MempoolSenderBucket, TimelineIndexIdentifier, TimelineId);
+
 pub fn sender_bucket(
     address: &AccountAddress,
     num_sender_buckets: MempoolSenderBucket,
@@ -47,6 +56,13 @@ pub fn sender_bucket(
     address.as_ref()[address.as_ref().len() - 1] as MempoolSenderBucket % num_sender_buckets
 }
 
+/// The minimum gas unit price for replacing a transaction paying `gas_price` by one with different
+/// inputs. It's always higher than `gas_price`, even for a zero bump or gas price.
+fn min_replacement_gas_price(gas_price: u64, bump_pct: u64) -> u64 {
+    let bump = (gas_price as u128 * bump_pct as u128).div_ceil(100).max(1);
+    gas_price.saturating_add(bump.min(u64::MAX as u128) as u64)
+}
+
 /// TransactionStore is in-memory storage for all transactions in mempool.
 pub struct TransactionStore {
     // main DS
@@ -95,10 +111,15 @@ pub struct TransactionStore {
     // Maximum number of orderless transactions allowed in the Mempool per user
     orderless_txn_capacity_per_user: usize,
     max_batch_bytes: u64,
+    // Minimum gas unit price increase (in percent) to replace a transaction with different inputs
+    replacement_min_gas_price_bump_pct: Option<u64>,
 
     // eager expiration
     eager_expire_threshold: Option<Duration>,
     eager_expire_time: Duration,
+
+    // Publishes the events of transactions entering and leaving the store to subscribers
+    event_sender: broadcast::Sender<MempoolTransactionEvent>,
 }
 
 impl TransactionStore {
@@ -134,10 +155,13 @@ impl TransactionStore {
             capacity_per_user: config.capacity_per_user,
             orderless_txn_capacity_per_user: config.orderless_txn_capacity_per_user,
             max_batch_bytes: config.shared_mempool_max_batch_bytes,
+            replacement_min_gas_price_bump_pct: config.replacement_min_gas_price_bump_pct,
 
             // eager expiration
             eager_expire_threshold: config.eager_expire_threshold_ms.map(Duration::from_millis),
             eager_expire_time: Duration::from_millis(config.eager_expire_time_ms),
+
+            event_sender: broadcast::channel(config.transaction_event_channel_size.max(1)).0,
         }
     }
 
@@ -183,6 +207,55 @@ impl TransactionStore {
         }
     }
 
+    /// Returns the status of the transaction with the given hash, and its position in the broadcast
+    /// timelines if it's ready for broadcast. Broadcast peers are left for the caller to fill in.
+    pub(crate) fn get_status_by_hash(
+        &self,
+        hash: HashValue,
+    ) -> Option<(MempoolTransactionStatus, Option<TimelinePosition>)> {
+        let (address, replay_protector) = self.hash_index.get(&hash)?;
+        let txn = self.get_mempool_txn(address, *replay_protector)?;
+        let rank = self.priority_index.rank(txn, MAX_TRANSACTION_STATUS_RANK);
+        let state = if rank.is_some() {
+            MempoolTransactionState::Ready
+        } else {
+            MempoolTransactionState::Parked
+        };
+        let timeline_position = match txn.timeline_state {
+            TimelineState::Ready(timeline_id) => {
+                let sender_bucket = sender_bucket(address, self.num_sender_buckets);
+                self.timeline_index
+                    .get(&sender_bucket)
+                    .map(|timeline_index| {
+                        (
+                            sender_bucket,
+                            timeline_index.get_bucket_index(txn.ranking_score)
+                                as TimelineIndexIdentifier,
+                            timeline_id,
+                        )
+                    })
+            },
+            TimelineState::NotReady | TimelineState::NonQualified => None,
+        };
+        let status = MempoolTransactionStatus {
+            sender: *address,
+            replay_protector: *replay_protector,
+            gas_unit_price: txn.get_gas_price(),
+            ranking_score: txn.ranking_score,
+            account_sequence_number: self.account_sequence_numbers.get(address).copied(),
+            state,
+            rank,
+            insertion_time: txn.insertion_info.insertion_time,
+            park_time: txn.insertion_info.park_time,
+            broadcast_peers: vec![],
+            consensus_pulled_count: txn
+                .insertion_info
+                .consensus_pulled_counter
+                .load(Ordering::Relaxed),
+        };
+        Some((status, timeline_position))
+    }
+
     pub(crate) fn get_insertion_info_and_bucket(
         &self,
         address: &AccountAddress,
@@ -249,36 +322,52 @@ impl TransactionStore {
             )
         });
 
-        // If the transaction is already in Mempool, we only allow the user to
-        // increase the gas unit price to speed up a transaction, but not the max gas.
+        // If the transaction is already in Mempool, we allow the user to increase the gas unit
+        // price to speed up a transaction. Changing any other input (e.g. to fix an argument or to
+        // cancel the transaction) requires the gas unit price to be raised by at least
+        // `replacement_min_gas_price_bump_pct`, so replacements can't be used to spam the network.
         //
         // Transactions with all the same inputs (but possibly signed differently) are idempotent
         // since the raw transaction is the same
         if let Some(txns) = self.transactions.get_mut(&address) {
             if let Some(current_version) = txns.get_mut(&txn_replay_protector) {
-                if current_version.txn.payload() != txn.txn.payload() {
-                    return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
-                        "Transaction already in mempool with a different payload".to_string(),
-                    );
-                } else if current_version.txn.expiration_timestamp_secs()
-                    != txn.txn.expiration_timestamp_secs()
-                {
-                    return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
-                        "Transaction already in mempool with a different expiration timestamp"
-                            .to_string(),
-                    );
-                } else if current_version.txn.max_gas_amount() != txn.txn.max_gas_amount() {
-                    return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
-                        "Transaction already in mempool with a different max gas amount"
-                            .to_string(),
-                    );
-                } else if current_version.get_gas_price() < txn.get_gas_price() {
+                let current_gas_price = current_version.get_gas_price();
+                if let Some(difference) = Self::replacement_difference(current_version, &txn) {
+                    let Some(bump_pct) = self.replacement_min_gas_price_bump_pct else {
+                        return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
+                            format!(
+                                "Transaction already in mempool with a different {}",
+                                difference
+                            ),
+                        );
+                    };
+                    let min_gas_price = min_replacement_gas_price(current_gas_price, bump_pct);
+                    if txn.get_gas_price() < min_gas_price {
+                        return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
+                            format!(
+                                "Transaction already in mempool with a different {}, replacing it requires a gas unit price of at least {}",
+                                difference, min_gas_price,
+                            ),
+                        );
+                    }
+                    if let Some(txn) = txns.remove(&txn_replay_protector) {
+                        self.index_remove(&txn, MempoolTransactionEventKind::Removed);
+                    };
+                    let replacement_label = if txn.txn.payload().is_cancellation() {
+                        counters::CANCELLED_LABEL
+                    } else {
+                        counters::REPLACED_LABEL
+                    };
+                    counters::CORE_MEMPOOL_REPLACED_TXNS
+                        .with_label_values(&[replacement_label])
+                        .inc();
+                } else if current_gas_price < txn.get_gas_price() {
                     // Update txn if gas unit price is a larger value than before
                     if let Some(txn) = txns.remove(&txn_replay_protector) {
-                        self.index_remove(&txn);
+                        self.index_remove(&txn, MempoolTransactionEventKind::Removed);
                     };
                     counters::CORE_MEMPOOL_GAS_UPGRADED_TXNS.inc();
-                } else if current_version.get_gas_price() > txn.get_gas_price() {
+                } else if current_gas_price > txn.get_gas_price() {
                     return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
                         "Transaction already in mempool with a higher gas price".to_string(),
                     );
@@ -353,6 +442,11 @@ impl TransactionStore {
                 self.account_sequence_numbers.insert(address, acc_seq_num);
             }
             self.size_bytes += txn.get_estimated_bytes();
+            Self::send_event(
+                &self.event_sender,
+                &txn,
+                MempoolTransactionEventKind::Inserted,
+            );
             txns.insert(txn);
             self.track_indices();
         }
@@ -368,6 +462,25 @@ impl TransactionStore {
         MempoolStatus::new(MempoolStatusCode::Accepted)
     }
 
+    /// Returns which input, other than the gas unit price, differs between a transaction in
+    /// Mempool and a new one with the same replay protector, if any.
+    fn replacement_difference(
+        current_version: &MempoolTransaction,
+        txn: &MempoolTransaction,
+    ) -> Option<&'static str> {
+        if current_version.txn.payload() != txn.txn.payload() {
+            Some("payload")
+        } else if current_version.txn.expiration_timestamp_secs()
+            != txn.txn.expiration_timestamp_secs()
+        {
+            Some("expiration timestamp")
+        } else if current_version.txn.max_gas_amount() != txn.txn.max_gas_amount() {
+            Some("max gas amount")
+        } else {
+            None
+        }
+    }
+
     fn track_indices(&self) {
         counters::core_mempool_index_size(
             counters::SYSTEM_TTL_INDEX_LABEL,
@@ -437,7 +550,7 @@ impl TransactionStore {
                     );
                     evicted_bytes += txn.get_estimated_bytes() as u64;
                     evicted_txns += 1;
-                    self.index_remove(&txn);
+                    self.index_remove(&txn, MempoolTransactionEventKind::Removed);
                     if !self.is_full() {
                         break;
                     }
@@ -654,7 +767,7 @@ impl TransactionStore {
             };
             for transaction in txns_for_removal.values() {
                 rm_txns.add(transaction.get_sender(), transaction.get_replay_protector());
-                self.index_remove(transaction);
+                self.index_remove(transaction, MempoolTransactionEventKind::Committed);
             }
             trace!(
                 LogSchema::new(LogEntry::CleanCommittedTxn).txns(rm_txns),
@@ -691,7 +804,7 @@ impl TransactionStore {
             ReplayProtector::Nonce(nonce) => {
                 if let Some(txns) = self.transactions.get_mut(account) {
                     if let Some(txn) = txns.remove(&ReplayProtector::Nonce(nonce)) {
-                        self.index_remove(&txn);
+                        self.index_remove(&txn, MempoolTransactionEventKind::Committed);
                         trace!(
                             LogSchema::new(LogEntry::CleanCommittedTxn).txns(TxnsLog::new_txn(
                                 txn.get_sender(),
@@ -723,7 +836,7 @@ impl TransactionStore {
             if let Some(txns) = self.transactions.get_mut(account) {
                 txns.remove(&replay_protector);
             }
-            self.index_remove(&txn_to_remove);
+            self.index_remove(&txn_to_remove, MempoolTransactionEventKind::Rejected);
 
             if accudo_logger::enabled!(Level::Trace) {
                 let mut txns_log = TxnsLog::new();
@@ -736,9 +849,11 @@ impl TransactionStore {
         }
     }
 
-    /// Removes transaction from all indexes. Only call after removing from main transactions DS.
-    fn index_remove(&mut self, txn: &MempoolTransaction) {
+    /// Removes transaction from all indexes and notifies event subscribers with the given kind.
+    /// Only call after removing from main transactions DS.
+    fn index_remove(&mut self, txn: &MempoolTransaction, event_kind: MempoolTransactionEventKind) {
         counters::CORE_MEMPOOL_REMOVED_TXNS.inc();
+        Self::send_event(&self.event_sender, txn, event_kind);
         self.system_ttl_index.remove(txn);
         self.expiration_time_index.remove(txn);
         self.priority_index.remove(txn);
@@ -768,6 +883,39 @@ impl TransactionStore {
         self.track_indices();
     }
 
+    /// Subscribes to the events of transactions entering and leaving the store.
+    pub(crate) fn subscribe_events(&self) -> broadcast::Receiver<MempoolTransactionEvent> {
+        self.event_sender.subscribe()
+    }
+
+    fn send_event(
+        event_sender: &broadcast::Sender<MempoolTransactionEvent>,
+        txn: &MempoolTransaction,
+        kind: MempoolTransactionEventKind,
+    ) {
+        // Avoid building events nobody listens to
+        if event_sender.receiver_count() == 0 {
+            return;
+        }
+        let entry_function = match txn.txn.executable_ref() {
+            Ok(TransactionExecutableRef::EntryFunction(entry_function)) => Some((
+                entry_function.module().clone(),
+                entry_function.function().to_owned(),
+            )),
+            _ => None,
+        };
+        // Sending only fails if all receivers were dropped in the meantime
+        let _ = event_sender.send(MempoolTransactionEvent {
+            kind,
+            hash: txn.get_committed_hash(),
+            sender: txn.get_sender(),
+            replay_protector: txn.get_replay_protector(),
+            gas_unit_price: txn.get_gas_price(),
+            entry_function,
+            timestamp: SystemTime::now(),
+        });
+    }
+
     /// Read at most `count` transactions from timeline since `timeline_id`.
     /// This method takes into account the max number of bytes per transaction batch.
     /// Returns block of transactions along with their transaction ready times
@@ -993,7 +1141,7 @@ impl TransactionStore {
                     }
 
                     // remove txn
-                    self.index_remove(&txn);
+                    self.index_remove(&txn, MempoolTransactionEventKind::Removed);
                 }
             }
         }
@@ -1052,4 +1200,153 @@ impl TransactionStore {
     pub(crate) fn get_parking_lot_addresses(&self) -> Vec<(AccountAddress, u64)> {
         self.parking_lot_index.get_addresses()
     }
+
+    /// Returns the transactions of the given account: orderless ones first, then the sequence
+    /// number based ones in order.
+    pub(crate) fn get_transactions_by_sender(
+        &self,
+        address: &AccountAddress,
+    ) -> Vec<MempoolTransactionSummary> {
+        self.transactions
+            .get(address)
+            .map(|txns| txns.values().map(|txn| self.summarize(txn)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the accounts with parked transactions, along with the ranges of sequence numbers
+    /// missing before their parked transactions can become ready.
+    pub(crate) fn get_parked_accounts(&self) -> Vec<MempoolParkedAccount> {
+        self.parking_lot_index
+            .get_sequence_numbers()
+            .into_iter()
+            .map(|(address, parked_sequence_numbers)| {
+                let account_sequence_number = self.account_sequence_numbers.get(&address).copied();
+                let mut gaps = vec![];
+                if let Some(txns) = self.transactions.get(&address) {
+                    // Without a known account sequence number, gaps can only be found between
+                    // the transactions themselves
+                    let mut next_sequence_number = account_sequence_number;
+                    for sequence_number in txns.seq_nums() {
+                        let expected = *next_sequence_number.get_or_insert(sequence_number);
+                        if sequence_number > expected {
+                            gaps.push(expected..sequence_number);
+                        }
+                        next_sequence_number = Some(max(expected, sequence_number + 1));
+                    }
+                }
+                MempoolParkedAccount {
+                    address,
+                    account_sequence_number,
+                    parked_sequence_numbers,
+                    gaps,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns at most `limit` ready transactions, in the order consensus would pull them.
+    pub(crate) fn get_priority_queue(&self, limit: usize) -> Vec<MempoolTransactionSummary> {
+        self.iter_queue()
+            .filter_map(|key| self.get_mempool_txn(&key.address, key.replay_protector))
+            .take(limit)
+            .map(|txn| self.summarize(txn))
+            .collect()
+    }
+
+    /// Removes the transaction with the given hash, and returns it if it was in the store.
+    /// Later transactions of the same account are left in place, but parked until the gap is
+    /// filled.
+    pub(crate) fn evict_by_hash(&mut self, hash: HashValue) -> Option<MempoolTransactionSummary> {
+        let (address, replay_protector) = *self.hash_index.get(&hash)?;
+        let evicted = self.evict(&address, replay_protector)?;
+        if let ReplayProtector::SequenceNumber(seq_num) = replay_protector {
+            self.park_transactions_after(&address, seq_num);
+        }
+        Some(evicted)
+    }
+
+    /// Marks the sequence number based transactions of the account after `seq_num` as non-ready.
+    fn park_transactions_after(&mut self, address: &AccountAddress, seq_num: u64) {
+        let Some(txns) = self.transactions.get_mut(address) else {
+            return;
+        };
+        for (_, t) in txns.seq_num_range_mut((Bound::Excluded(seq_num), Bound::Unbounded)) {
+            self.parking_lot_index.insert(t);
+            self.priority_index.remove(t);
+            let sender_bucket = sender_bucket(&t.get_sender(), self.num_sender_buckets);
+            self.timeline_index
+                .get_mut(&sender_bucket)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Unable to get the timeline index for the sender bucket {}",
+                        sender_bucket
+                    )
+                })
+                .remove(t);
+            if let TimelineState::Ready(_) = t.timeline_state {
+                t.timeline_state = TimelineState::NotReady;
+            }
+        }
+        self.track_indices();
+    }
+
+    /// Removes all transactions of the given account, and returns them.
+    pub(crate) fn evict_sender(
+        &mut self,
+        address: &AccountAddress,
+    ) -> Vec<MempoolTransactionSummary> {
+        let replay_protectors: Vec<_> = match self.transactions.get(address) {
+            Some(txns) => txns
+                .values()
+                .map(|txn| txn.get_replay_protector())
+                .collect(),
+            None => return vec![],
+        };
+        replay_protectors
+            .into_iter()
+            .filter_map(|replay_protector| self.evict(address, replay_protector))
+            .collect()
+    }
+
+    fn evict(
+        &mut self,
+        address: &AccountAddress,
+        replay_protector: ReplayProtector,
+    ) -> Option<MempoolTransactionSummary> {
+        let txn = self
+            .transactions
+            .get_mut(address)?
+            .remove(&replay_protector)?;
+        // Summarize before removing from the indexes, which determine the state
+        let summary = self.summarize(&txn);
+        self.index_remove(&txn, MempoolTransactionEventKind::Removed);
+        Some(summary)
+    }
+
+    fn summarize(&self, txn: &MempoolTransaction) -> MempoolTransactionSummary {
+        let state = if self.parking_lot_index.contains(
+            &txn.get_sender(),
+            txn.get_replay_protector(),
+            txn.get_committed_hash(),
+        ) {
+            MempoolTransactionState::Parked
+        } else {
+            MempoolTransactionState::Ready
+        };
+        MempoolTransactionSummary {
+            hash: txn.get_committed_hash(),
+            sender: txn.get_sender(),
+            replay_protector: txn.get_replay_protector(),
+            gas_unit_price: txn.get_gas_price(),
+            ranking_score: txn.ranking_score,
+            expiration_timestamp_secs: txn.txn.expiration_timestamp_secs(),
+            state,
+            insertion_timestamp_usecs: txn
+                .insertion_info
+                .insertion_time
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
+        }
+    }
 }
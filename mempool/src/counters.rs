@@ -44,6 +44,10 @@ pub const GC_CLIENT_EXP_LABEL: &str = "client_expiration";
 pub const GC_ACTIVE_TXN_LABEL: &str = "active";
 pub const GC_PARKED_TXN_LABEL: &str = "parked";
 
+// Core mempool txn replacement type labels
+pub const REPLACED_LABEL: &str = "replaced";
+pub const CANCELLED_LABEL: &str = "cancelled";
+
 // Mempool service request type labels
 pub const GET_BLOCK_LABEL: &str = "get_block";
 pub const GET_BLOCK_LOCK_LABEL: &str = "get_block_lock";
@@ -201,6 +205,26 @@ pub static CORE_MEMPOOL_GAS_UPGRADED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter tracking number of txns replaced by a txn with different inputs for the same replay protector
+pub static CORE_MEMPOOL_REPLACED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "accudo_core_mempool_replaced_txns_count",
+        "Number of txns replaced by a txn with different inputs for the same replay protector",
+        &["type"]
+    )
+    .unwrap()
+});
+
+/// Counter tracking number of client submitted txns rejected by a mempool admission quota
+pub static ADMISSION_QUOTA_REJECTED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "accudo_mempool_admission_quota_rejected_txns_count",
+        "Number of client submitted txns rejected by a mempool admission quota",
+        &["quota"]
+    )
+    .unwrap()
+});
+
 pub static CORE_MEMPOOL_PQ_SIGNATURE_PRESENT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
         "accudo_core_mempool_pq_signature_present_total",
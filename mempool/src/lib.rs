@@ -62,7 +62,9 @@ pub use shared_mempool::{
     bootstrap, network,
     network::MempoolSyncMsg,
     types::{
-        MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver, QuorumStoreRequest,
+        MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver, MempoolParkedAccount,
+        MempoolTransactionEvent, MempoolTransactionEventKind, MempoolTransactionState,
+        MempoolTransactionStatus, MempoolTransactionSummary, QuorumStoreRequest,
         QuorumStoreResponse, SubmissionStatus,
     },
 };
@@ -190,6 +190,7 @@ pub enum LogEntry {
     UnexpectedNetworkMsg,
     MempoolSnapshot,
     TransactionFilter,
+    Journal,
 }
 
 #[derive(Clone, Copy, Serialize)]
@@ -0,0 +1,171 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Admission quotas for the transactions submitted by clients of the node, on top of the mempool
+//! capacity limits. See `MempoolAdmissionQuotasConfig`.
+
+use crate::counters;
+use accudo_config::config::{AdmissionQuotaConfig, MempoolAdmissionQuotasConfig};
+use accudo_infallible::Mutex;
+use accudo_rate_limiter::rate_limit::{Bucket, SharedBucket, TokenBucketRateLimiter};
+use accudo_types::{
+    account_address::AccountAddress,
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    transaction::{SignedTransaction, TransactionExecutableRef},
+};
+use move_core_types::language_storage::ModuleId;
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+const IP_QUOTA_LABEL: &str = "ip";
+const SENDER_QUOTA_LABEL: &str = "sender";
+const MODULE_QUOTA_LABEL: &str = "module";
+const GAS_PRICE_TIER_QUOTA_LABEL: &str = "gas_price_tier";
+
+/// Interval at which the buckets of keys that haven't been limited lately are dropped.
+const GARBAGE_COLLECTION_INTERVAL: Duration = Duration::from_secs(60);
+
+pub(crate) struct AdmissionControl {
+    per_ip: Option<TokenBucketRateLimiter<IpAddr>>,
+    per_sender: Option<TokenBucketRateLimiter<AccountAddress>>,
+    per_module: Option<TokenBucketRateLimiter<ModuleId>>,
+    // Sorted by increasing minimum gas unit price
+    per_gas_price_tier: Vec<(u64, SharedBucket)>,
+    last_garbage_collection: Mutex<Instant>,
+}
+
+impl AdmissionControl {
+    pub fn new(config: &MempoolAdmissionQuotasConfig) -> Self {
+        let mut per_gas_price_tier: Vec<_> = config
+            .per_gas_price_tier
+            .iter()
+            .map(|tier| {
+                let bucket = Bucket::new(
+                    GAS_PRICE_TIER_QUOTA_LABEL.to_string(),
+                    String::new(),
+                    tier.min_gas_unit_price.to_string(),
+                    tier.quota.bucket_size,
+                    tier.quota.bucket_size,
+                    tier.quota.fill_rate,
+                    None,
+                );
+                (tier.min_gas_unit_price, Arc::new(Mutex::new(bucket)))
+            })
+            .collect();
+        per_gas_price_tier.sort_by_key(|(min_gas_unit_price, _)| *min_gas_unit_price);
+
+        Self {
+            per_ip: config
+                .per_ip
+                .as_ref()
+                .map(|quota| rate_limiter(IP_QUOTA_LABEL, quota)),
+            per_sender: config
+                .per_sender
+                .as_ref()
+                .map(|quota| rate_limiter(SENDER_QUOTA_LABEL, quota)),
+            per_module: config
+                .per_module
+                .as_ref()
+                .map(|quota| rate_limiter(MODULE_QUOTA_LABEL, quota)),
+            per_gas_price_tier,
+            last_garbage_collection: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Admits the transaction if none of the quotas it falls under is exhausted, in which case
+    /// it uses up one token of each of them. Otherwise returns a `RateLimited` status.
+    pub fn admit(
+        &self,
+        transaction: &SignedTransaction,
+        client_ip: Option<IpAddr>,
+    ) -> Result<(), MempoolStatus> {
+        self.maybe_garbage_collect();
+
+        let mut buckets = vec![];
+        if let (Some(per_ip), Some(client_ip)) = (&self.per_ip, client_ip) {
+            buckets.push((IP_QUOTA_LABEL, per_ip.bucket(client_ip)));
+        }
+        if let Some(per_sender) = &self.per_sender {
+            buckets.push((SENDER_QUOTA_LABEL, per_sender.bucket(transaction.sender())));
+        }
+        if let Some(per_module) = &self.per_module {
+            if let Ok(TransactionExecutableRef::EntryFunction(entry_function)) =
+                transaction.executable_ref()
+            {
+                buckets.push((
+                    MODULE_QUOTA_LABEL,
+                    per_module.bucket(entry_function.module().clone()),
+                ));
+            }
+        }
+        if let Some((_, bucket)) = self
+            .per_gas_price_tier
+            .iter()
+            .rev()
+            .find(|(min_gas_unit_price, _)| transaction.gas_unit_price() >= *min_gas_unit_price)
+        {
+            buckets.push((GAS_PRICE_TIER_QUOTA_LABEL, bucket.clone()));
+        }
+
+        for (idx, (label, bucket)) in buckets.iter().enumerate() {
+            if bucket.lock().acquire_all_tokens(1).is_err() {
+                // Give back the tokens already taken from the other quotas
+                for (_, bucket) in &buckets[..idx] {
+                    bucket.lock().return_tokens(1);
+                }
+                counters::ADMISSION_QUOTA_REJECTED_TXNS
+                    .with_label_values(&[label])
+                    .inc();
+                return Err(
+                    MempoolStatus::new(MempoolStatusCode::RateLimited).with_message(format!(
+                        "Exceeded the mempool admission quota per {}",
+                        label
+                    )),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops the buckets of keys that are back to a full quota, so that buckets don't pile up for
+    /// every IP, sender and module ever seen.
+    fn maybe_garbage_collect(&self) {
+        {
+            let mut last_garbage_collection = self.last_garbage_collection.lock();
+            if last_garbage_collection.elapsed() < GARBAGE_COLLECTION_INTERVAL {
+                return;
+            }
+            *last_garbage_collection = Instant::now();
+        }
+
+        if let Some(per_ip) = &self.per_ip {
+            per_ip.garbage_collect_full_buckets();
+        }
+        if let Some(per_sender) = &self.per_sender {
+            per_sender.garbage_collect_full_buckets();
+        }
+        if let Some(per_module) = &self.per_module {
+            per_module.garbage_collect_full_buckets();
+        }
+    }
+}
+
+fn rate_limiter<Key: Eq + Hash + Clone + Debug>(
+    label: &'static str,
+    quota: &AdmissionQuotaConfig,
+) -> TokenBucketRateLimiter<Key> {
+    // New buckets start full, so that dropping full buckets loses no state
+    TokenBucketRateLimiter::new(
+        label,
+        String::new(),
+        100,
+        quota.bucket_size,
+        quota.fill_rate,
+        None,
+    )
+}
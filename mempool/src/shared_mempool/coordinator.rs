@@ -10,6 +10,7 @@ use crate::{
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{
+        journal::JournalEntry,
         tasks::{self, process_committed_transactions},
         types::{
             notify_subscribers, MempoolMessageId, ScheduledBroadcast, SharedMempool,
@@ -64,6 +65,7 @@ pub(crate) async fn coordinator<NetworkClient, TransactionValidator, ConfigProvi
     mut mempool_reconfig_events: ReconfigNotificationListener<ConfigProvider>,
     peer_update_interval_ms: u64,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    journal_entries: Vec<JournalEntry>,
 ) where
     NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
     TransactionValidator: TransactionValidation + 'static,
@@ -104,6 +106,13 @@ pub(crate) async fn coordinator<NetworkClient, TransactionValidator, ConfigProvi
     )
     .await;
 
+    // Replay the transactions journaled before the restart, now that the validator is set up
+    if !journal_entries.is_empty() {
+        bounded_executor
+            .spawn(tasks::process_journal_replay(smp.clone(), journal_entries))
+            .await;
+    }
+
     loop {
         let _timer = counters::MAIN_LOOP.start_timer();
         ::futures::select! {
@@ -173,7 +182,7 @@ async fn handle_client_request<NetworkClient, TransactionValidator>(
     TransactionValidator: TransactionValidation + 'static,
 {
     match request {
-        MempoolClientRequest::SubmitTransaction(txn, callback) => {
+        MempoolClientRequest::SubmitTransaction(txn, client_ip, callback) => {
             // This timer measures how long it took for the bounded executor to *schedule* the
             // task.
             let _timer = counters::task_spawn_latency_timer(
@@ -191,6 +200,7 @@ async fn handle_client_request<NetworkClient, TransactionValidator>(
                 .spawn(tasks::process_client_transaction_submission(
                     smp.clone(),
                     txn,
+                    client_ip,
                     callback,
                     task_start_timer,
                 ))
@@ -222,6 +232,60 @@ async fn handle_client_request<NetworkClient, TransactionValidator>(
                 .spawn(tasks::process_parking_lot_addresses(smp.clone(), callback))
                 .await;
         },
+        MempoolClientRequest::GetTransactionStatus(hash, callback) => {
+            bounded_executor
+                .spawn(tasks::process_client_get_transaction_status(
+                    smp.clone(),
+                    hash,
+                    callback,
+                ))
+                .await;
+        },
+        MempoolClientRequest::SubscribeTransactionEvents(callback) => {
+            bounded_executor
+                .spawn(tasks::process_client_subscribe_transaction_events(
+                    smp.clone(),
+                    callback,
+                ))
+                .await;
+        },
+        MempoolClientRequest::GetTransactionsBySender(address, callback) => {
+            bounded_executor
+                .spawn(tasks::process_get_transactions_by_sender(
+                    smp.clone(),
+                    address,
+                    callback,
+                ))
+                .await;
+        },
+        MempoolClientRequest::GetParkingLot(callback) => {
+            bounded_executor
+                .spawn(tasks::process_get_parking_lot(smp.clone(), callback))
+                .await;
+        },
+        MempoolClientRequest::GetPriorityQueue(limit, callback) => {
+            bounded_executor
+                .spawn(tasks::process_get_priority_queue(
+                    smp.clone(),
+                    limit,
+                    callback,
+                ))
+                .await;
+        },
+        MempoolClientRequest::EvictTransaction(hash, callback) => {
+            bounded_executor
+                .spawn(tasks::process_evict_transaction(
+                    smp.clone(),
+                    hash,
+                    callback,
+                ))
+                .await;
+        },
+        MempoolClientRequest::EvictSender(address, callback) => {
+            bounded_executor
+                .spawn(tasks::process_evict_sender(smp.clone(), address, callback))
+                .await;
+        },
     }
 }
 
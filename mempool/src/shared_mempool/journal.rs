@@ -0,0 +1,303 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! On-disk journal of the transactions accepted into mempool.
+//!
+//! Accepted transactions are appended to the journal as length-prefixed BCS records. Removals
+//! are not recorded: on startup, all journaled transactions are replayed through validation, so
+//! committed ones are rejected again and expired ones are skipped. Once the journal outgrows its
+//! size limit, it is rewritten in the background with only the transactions still pending in
+//! mempool.
+
+use accudo_infallible::Mutex;
+use accudo_types::transaction::SignedTransaction;
+use anyhow::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Size of the length prefix of each journal record.
+const RECORD_LENGTH_BYTES: u64 = size_of::<u32>() as u64;
+
+/// A transaction accepted into mempool, as recorded in the journal.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct JournalEntry {
+    pub transaction: SignedTransaction,
+    /// Whether the transaction was eligible for broadcast when it was accepted.
+    pub broadcast: bool,
+    /// Whether the transaction was submitted by a client of this node, rather than by a peer.
+    pub client_submitted: bool,
+    /// Time the transaction was accepted, in seconds since the Unix epoch.
+    pub insertion_time_secs: u64,
+}
+
+impl JournalEntry {
+    pub fn new(transaction: SignedTransaction, broadcast: bool, client_submitted: bool) -> Self {
+        Self {
+            transaction,
+            broadcast,
+            client_submitted,
+            insertion_time_secs: now_secs(),
+        }
+    }
+
+    /// Returns true if the transaction expired or outlived the mempool system TTL, in which case
+    /// it is not worth replaying.
+    pub fn is_expired(&self, system_transaction_timeout: Duration) -> bool {
+        let now = now_secs();
+        self.transaction.expiration_timestamp_secs() <= now
+            || self
+                .insertion_time_secs
+                .saturating_add(system_transaction_timeout.as_secs())
+                <= now
+    }
+}
+
+pub(crate) struct MempoolJournal {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    size_bytes: u64,
+    max_bytes: u64,
+    /// Whether a compaction is in progress.
+    compacting: bool,
+}
+
+impl MempoolJournal {
+    /// Opens the journal at `path`, creating it if needed, and returns it along with the entries
+    /// to replay. The entries are moved aside to `<path>.replay` and the journal is emptied, as
+    /// they are expected to be journaled again once accepted. The moved entries are kept until
+    /// `finish_replay` is called, so that they survive a restart in the middle of the replay.
+    pub fn open(path: PathBuf, max_bytes: u64) -> Result<(Self, Vec<JournalEntry>)> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+
+        // Entries left over by an unfinished replay are replayed again, along with the ones
+        // journaled since
+        let replay_path = replay_path(&path);
+        let mut entries = if replay_path.exists() {
+            read_entries(&replay_path, max_bytes)?
+        } else {
+            vec![]
+        };
+        if path.exists() {
+            if entries.is_empty() {
+                fs::rename(&path, &replay_path)
+                    .with_context(|| format!("Failed to move mempool journal {:?}", path))?;
+                entries = read_entries(&replay_path, max_bytes)?;
+            } else {
+                entries.extend(read_entries(&path, max_bytes)?);
+                write_entries(&replay_path, &entries)?;
+            }
+        }
+        if entries.is_empty() && replay_path.exists() {
+            fs::remove_file(&replay_path)
+                .with_context(|| format!("Failed to remove {:?}", replay_path))?;
+        }
+
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create mempool journal {:?}", path))?;
+        let journal = Self {
+            path,
+            writer: BufWriter::new(file),
+            size_bytes: 0,
+            max_bytes,
+            compacting: false,
+        };
+        Ok((journal, entries))
+    }
+
+    /// Drops the entries returned by `open`, once they have been replayed.
+    pub fn finish_replay(&self) -> Result<()> {
+        let replay_path = replay_path(&self.path);
+        if replay_path.exists() {
+            fs::remove_file(&replay_path)
+                .with_context(|| format!("Failed to remove {:?}", replay_path))?;
+        }
+        Ok(())
+    }
+
+    /// Appends the given entries to the journal. Compaction is left to the caller, see
+    /// `needs_compaction`, so that appends stay cheap.
+    pub fn append(&mut self, entries: &[JournalEntry]) -> Result<()> {
+        for entry in entries {
+            self.size_bytes += write_entry(&mut self.writer, entry)?;
+        }
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns true if the journal grew beyond its size limit and no compaction is in progress.
+    pub fn needs_compaction(&self) -> bool {
+        self.size_bytes > self.max_bytes && !self.compacting
+    }
+
+    /// Rewrites the journal with only the pending entries, once each. The newest ones are kept
+    /// within half of the size limit, so that compaction doesn't happen again on the next append.
+    ///
+    /// The journal is only locked to start and finish the compaction, not while it is rewritten,
+    /// so that appends are not held up. Entries appended in the meantime are all kept.
+    pub fn compact(
+        journal: &Mutex<Self>,
+        is_pending: impl Fn(&SignedTransaction) -> bool,
+    ) -> Result<()> {
+        let (path, snapshot_bytes, max_bytes) = {
+            let mut journal = journal.lock();
+            if journal.compacting {
+                return Ok(());
+            }
+            journal.writer.flush()?;
+            journal.compacting = true;
+            (journal.path.clone(), journal.size_bytes, journal.max_bytes)
+        };
+
+        let result = rewrite_pending(&path, snapshot_bytes, max_bytes, is_pending);
+        let mut journal = journal.lock();
+        journal.compacting = false;
+        let (tmp_path, size_bytes) = result?;
+        journal.finish_compaction(tmp_path, snapshot_bytes, size_bytes)
+    }
+
+    /// Carries the entries appended since `snapshot_bytes` over to the compacted journal at
+    /// `tmp_path`, and replaces the journal with it.
+    fn finish_compaction(
+        &mut self,
+        tmp_path: PathBuf,
+        snapshot_bytes: u64,
+        size_bytes: u64,
+    ) -> Result<()> {
+        self.writer.flush()?;
+        let mut appended = File::open(&self.path)?;
+        appended.seek(SeekFrom::Start(snapshot_bytes))?;
+        let mut tmp_file = OpenOptions::new().append(true).open(&tmp_path)?;
+        let appended_bytes = io::copy(&mut appended, &mut tmp_file)?;
+        tmp_file.sync_all()?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Failed to replace mempool journal {:?}", self.path))?;
+
+        let file = OpenOptions::new().append(true).open(&self.path)?;
+        self.writer = BufWriter::new(file);
+        self.size_bytes = size_bytes + appended_bytes;
+        Ok(())
+    }
+}
+
+/// Path of the entries moved aside by `MempoolJournal::open` until they are replayed.
+fn replay_path(path: &Path) -> PathBuf {
+    let mut replay_path = path.as_os_str().to_owned();
+    replay_path.push(".replay");
+    PathBuf::from(replay_path)
+}
+
+/// Writes the pending entries among the first `snapshot_bytes` of the journal at `path` to a
+/// temporary file, and returns its path and size.
+fn rewrite_pending(
+    path: &Path,
+    snapshot_bytes: u64,
+    max_bytes: u64,
+    is_pending: impl Fn(&SignedTransaction) -> bool,
+) -> Result<(PathBuf, u64)> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open mempool journal {:?}", path))?;
+    let entries = read_entries_from(file.take(snapshot_bytes), max_bytes)?;
+
+    let mut kept = vec![];
+    let mut kept_hashes = HashSet::new();
+    let mut size_bytes = 0;
+    for entry in entries.into_iter().rev() {
+        if !is_pending(&entry.transaction)
+            || !kept_hashes.insert(entry.transaction.committed_hash())
+        {
+            continue;
+        }
+        let entry_bytes = RECORD_LENGTH_BYTES + bcs::serialized_size(&entry)? as u64;
+        if size_bytes + entry_bytes > max_bytes / 2 {
+            break;
+        }
+        size_bytes += entry_bytes;
+        kept.push(entry);
+    }
+    kept.reverse();
+
+    let tmp_path = path.with_extension("tmp");
+    let size_bytes = write_entries_to(&tmp_path, &kept)?;
+    Ok((tmp_path, size_bytes))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn write_entry(writer: &mut impl Write, entry: &JournalEntry) -> Result<u64> {
+    let bytes = bcs::to_bytes(entry)?;
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(RECORD_LENGTH_BYTES + bytes.len() as u64)
+}
+
+/// Writes the entries to a temporary file, and returns its size.
+fn write_entries_to(tmp_path: &Path, entries: &[JournalEntry]) -> Result<u64> {
+    let mut writer = BufWriter::new(
+        File::create(tmp_path).with_context(|| format!("Failed to create {:?}", tmp_path))?,
+    );
+    let mut size_bytes = 0;
+    for entry in entries {
+        size_bytes += write_entry(&mut writer, entry)?;
+    }
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    Ok(size_bytes)
+}
+
+/// Atomically replaces the file at `path` with the given entries.
+fn write_entries(path: &Path, entries: &[JournalEntry]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    write_entries_to(&tmp_path, entries)?;
+    fs::rename(&tmp_path, path).with_context(|| format!("Failed to replace {:?}", path))?;
+    Ok(())
+}
+
+/// Reads all entries of the journal at `path`. A truncated last record, e.g. left by a crash in
+/// the middle of a write, is ignored. Records larger than `max_bytes` are rejected.
+fn read_entries(path: &Path, max_bytes: u64) -> Result<Vec<JournalEntry>> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open mempool journal {:?}", path))?;
+    read_entries_from(file, max_bytes)
+}
+
+fn read_entries_from(reader: impl Read, max_bytes: u64) -> Result<Vec<JournalEntry>> {
+    let mut reader = BufReader::new(reader);
+    let mut entries = vec![];
+    loop {
+        let mut length_bytes = [0u8; RECORD_LENGTH_BYTES as usize];
+        match reader.read_exact(&mut length_bytes) {
+            Ok(()) => {},
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        let length = u32::from_le_bytes(length_bytes) as u64;
+        ensure!(
+            length <= max_bytes,
+            "Mempool journal record of {} bytes exceeds the journal size limit of {} bytes",
+            length,
+            max_bytes
+        );
+        let mut bytes = vec![0u8; length as usize];
+        match reader.read_exact(&mut bytes) {
+            Ok(()) => {},
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        entries.push(bcs::from_bytes(&bytes).context("Failed to decode mempool journal entry")?);
+    }
+    Ok(entries)
+}
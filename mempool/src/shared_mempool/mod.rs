@@ -2,6 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod admission;
 pub mod network;
 mod priority;
 mod runtime;
@@ -10,5 +11,6 @@ pub use runtime::bootstrap;
 #[cfg(any(test, feature = "fuzzing"))]
 pub(crate) use runtime::start_shared_mempool;
 mod coordinator;
+pub(crate) mod journal;
 pub(crate) mod tasks;
 pub(crate) mod use_case_history;
@@ -5,6 +5,7 @@
 //! Interface between Mempool and Network layers.
 
 use crate::{
+    core_mempool::TimelinePosition,
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     shared_mempool::{
@@ -681,4 +682,25 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
     pub fn sync_states_exists(&self, peer: &PeerNetworkId) -> bool {
         self.sync_states.read().get(peer).is_some()
     }
+
+    /// Returns the peers a transaction at the given position of the broadcast timelines has been
+    /// broadcast to.
+    pub(crate) fn broadcast_peers(
+        &self,
+        timeline_position: TimelinePosition,
+    ) -> Vec<PeerNetworkId> {
+        let (sender_bucket, timeline_index_identifier, timeline_id) = timeline_position;
+        self.sync_states
+            .read()
+            .iter()
+            .filter(|(_, state)| {
+                state
+                    .timelines
+                    .get(&sender_bucket)
+                    .and_then(|ids| ids.id_per_bucket.get(timeline_index_identifier as usize))
+                    .is_some_and(|broadcast_id| *broadcast_id >= timeline_id)
+            })
+            .map(|(peer, _)| *peer)
+            .collect()
+    }
 }
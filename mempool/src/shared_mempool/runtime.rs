@@ -4,9 +4,11 @@
 
 use crate::{
     core_mempool::CoreMempool,
+    logging::{LogEntry, LogEvent, LogSchema},
     network::MempoolSyncMsg,
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator, snapshot_job},
+        journal::MempoolJournal,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
@@ -14,7 +16,7 @@ use crate::{
 use accudo_config::config::{NodeConfig, NodeType};
 use accudo_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
 use accudo_infallible::{Mutex, RwLock};
-use accudo_logger::Level;
+use accudo_logger::{prelude::*, Level};
 use accudo_mempool_notifications::MempoolNotificationListener;
 use accudo_network::application::{
     interface::{NetworkClient, NetworkServiceEvents},
@@ -32,6 +34,7 @@ use tokio::runtime::{Handle, Runtime};
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers).
 ///   - inbound_network_task (task that handles inbound mempool messages and network events).
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL).
+/// If the mempool journal is enabled, the journaled transactions are replayed by the coordinator.
 pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
     executor: &Handle,
     config: &NodeConfig,
@@ -52,7 +55,7 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
 {
     let node_type = NodeType::extract_from_config(config);
     let transaction_filter_config = config.transaction_filters.mempool_filter.clone();
-    let smp: SharedMempool<NetworkClient<MempoolSyncMsg>, TransactionValidator> =
+    let mut smp: SharedMempool<NetworkClient<MempoolSyncMsg>, TransactionValidator> =
         SharedMempool::new(
             mempool.clone(),
             config.mempool.clone(),
@@ -64,6 +67,23 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
             node_type,
         );
 
+    let mut journal_entries = vec![];
+    let journal_config = &config.mempool.journal;
+    if journal_config.enabled {
+        let path = config.get_data_dir().join(&journal_config.path);
+        match MempoolJournal::open(path, journal_config.max_bytes) {
+            Ok((journal, entries)) => {
+                smp.journal = Some(Arc::new(Mutex::new(journal)));
+                journal_entries = entries;
+            },
+            Err(e) => {
+                error!(LogSchema::event_log(LogEntry::Journal, LogEvent::Start)
+                    .error(&e)
+                    .message("Failed to open mempool journal, continuing without it"));
+            },
+        }
+    }
+
     executor.spawn(coordinator(
         smp,
         executor.clone(),
@@ -74,6 +94,7 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
         mempool_reconfig_events,
         config.mempool.shared_mempool_peer_update_interval_ms,
         peers_and_metadata,
+        journal_entries,
     ));
 
     executor.spawn(gc_coordinator(
@@ -10,9 +10,11 @@ use crate::{
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastError, BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{
+        journal::{JournalEntry, MempoolJournal},
         types::{
-            notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
-            SubmissionStatusBundle,
+            notify_subscribers, MempoolParkedAccount, MempoolTransactionEvent,
+            MempoolTransactionStatus, MempoolTransactionSummary, ScheduledBroadcast, SharedMempool,
+            SharedMempoolNotification, SubmissionStatusBundle,
         },
         use_case_history::UseCaseHistory,
     },
@@ -38,13 +40,15 @@ use accudo_types::{
 use accudo_vm_validator::vm_validator::{get_account_sequence_number, TransactionValidation};
 use anyhow::Result;
 use futures::{channel::oneshot, stream::FuturesUnordered};
+use itertools::Itertools;
 use rayon::prelude::*;
 use std::{
     cmp,
+    net::IpAddr,
     sync::Arc,
     time::{Duration, Instant},
 };
-use tokio::runtime::Handle;
+use tokio::{runtime::Handle, sync::broadcast};
 // ============================== //
 //  broadcast_coordinator tasks  //
 // ============================== //
@@ -126,10 +130,11 @@ pub(crate) async fn execute_broadcast<NetworkClient, TransactionValidator>(
 // Tasks processing txn submission //
 // =============================== //
 
-/// Processes transactions directly submitted by client.
+/// Processes transactions directly submitted by client, once admitted by the admission quotas.
 pub(crate) async fn process_client_transaction_submission<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
     transaction: SignedTransaction,
+    client_ip: Option<IpAddr>,
     callback: oneshot::Sender<Result<SubmissionStatus>>,
     timer: HistogramTimer,
 ) where
@@ -146,12 +151,15 @@ pub(crate) async fn process_client_transaction_submission<NetworkClient, Transac
         TimelineState::NotReady
     };
     let statuses: Vec<(SignedTransaction, (MempoolStatus, Option<StatusCode>))> =
-        process_incoming_transactions(
-            &smp,
-            vec![(transaction, None, Some(BroadcastPeerPriority::Primary))],
-            timeline_state,
-            true,
-        );
+        match smp.admission_control.admit(&transaction, client_ip) {
+            Ok(()) => process_incoming_transactions(
+                &smp,
+                vec![(transaction, None, Some(BroadcastPeerPriority::Primary))],
+                timeline_state,
+                true,
+            ),
+            Err(mempool_status) => vec![(transaction, (mempool_status, None))],
+        };
     log_txn_process_results(&statuses, None);
 
     if let Some(status) = statuses.first() {
@@ -184,6 +192,111 @@ pub(crate) async fn process_parking_lot_addresses<NetworkClient, TransactionVali
     }
 }
 
+/// Processes get transactions by sender request by an operator.
+pub(crate) async fn process_get_transactions_by_sender<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    address: AccountAddress,
+    callback: oneshot::Sender<Vec<MempoolTransactionSummary>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let transactions = smp.mempool.lock().get_transactions_by_sender(&address);
+
+    if callback.send(transactions).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get parking lot request by an operator.
+pub(crate) async fn process_get_parking_lot<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    callback: oneshot::Sender<Vec<MempoolParkedAccount>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let parked_accounts = smp.mempool.lock().get_parked_accounts();
+
+    if callback.send(parked_accounts).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get priority queue request by an operator.
+pub(crate) async fn process_get_priority_queue<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    limit: usize,
+    callback: oneshot::Sender<Vec<MempoolTransactionSummary>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let transactions = smp.mempool.lock().get_priority_queue(limit);
+
+    if callback.send(transactions).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes evict transaction request by an operator.
+pub(crate) async fn process_evict_transaction<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    hash: HashValue,
+    callback: oneshot::Sender<Option<MempoolTransactionSummary>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let evicted = smp.mempool.lock().evict_transaction(hash);
+    if evicted.is_some() {
+        compact_journal_after_eviction(&smp).await;
+    }
+
+    if callback.send(evicted).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes evict sender request by an operator.
+pub(crate) async fn process_evict_sender<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    address: AccountAddress,
+    callback: oneshot::Sender<Vec<MempoolTransactionSummary>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let evicted = smp.mempool.lock().evict_sender(&address);
+    if !evicted.is_empty() {
+        compact_journal_after_eviction(&smp).await;
+    }
+
+    if callback.send(evicted).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes get transaction by hash request by client.
 pub(crate) async fn process_client_get_transaction<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
@@ -207,6 +320,109 @@ pub(crate) async fn process_client_get_transaction<NetworkClient, TransactionVal
     }
 }
 
+/// Processes get transaction status request by client.
+pub(crate) async fn process_client_get_transaction_status<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    hash: HashValue,
+    callback: oneshot::Sender<Option<MempoolTransactionStatus>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let status = smp.mempool.lock().get_transaction_status(hash);
+    let status = status.map(|(mut status, timeline_position)| {
+        if let Some(timeline_position) = timeline_position {
+            status.broadcast_peers = smp.network_interface.broadcast_peers(timeline_position);
+        }
+        status
+    });
+
+    if callback.send(status).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::GetTransaction,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes subscription request for transaction events by client.
+pub(crate) async fn process_client_subscribe_transaction_events<
+    NetworkClient,
+    TransactionValidator,
+>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    callback: oneshot::Sender<broadcast::Receiver<MempoolTransactionEvent>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let receiver = smp.mempool.lock().subscribe_transaction_events();
+
+    if callback.send(receiver).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Replays the transactions of the mempool journal through validation, e.g. after a restart.
+/// Transactions that expired in the meantime are skipped.
+pub(crate) async fn process_journal_replay<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    entries: Vec<JournalEntry>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let system_transaction_timeout =
+        Duration::from_secs(smp.config.system_transaction_timeout_secs);
+    let batch_size = cmp::max(smp.config.shared_mempool_batch_size, 1);
+    let mut num_accepted = 0;
+
+    // Replay in batches, so that the mempool isn't locked for the whole journal at once
+    for ((broadcast, client_submitted), entries) in &entries
+        .into_iter()
+        .filter(|entry| !entry.is_expired(system_transaction_timeout))
+        .chunk_by(|entry| (entry.broadcast, entry.client_submitted))
+    {
+        let timeline_state = if broadcast {
+            TimelineState::NotReady
+        } else {
+            TimelineState::NonQualified
+        };
+        let priority = client_submitted.then_some(BroadcastPeerPriority::Primary);
+        for batch in &entries.chunks(batch_size) {
+            let transactions = batch
+                .map(|entry| (entry.transaction, None, priority.clone()))
+                .collect();
+            let results =
+                process_incoming_transactions(&smp, transactions, timeline_state, client_submitted);
+            num_accepted += results
+                .iter()
+                .filter(|(_, (mempool_status, _))| {
+                    mempool_status.code == MempoolStatusCode::Accepted
+                })
+                .count();
+        }
+    }
+
+    // The replayed transactions are journaled again once accepted, so they can be dropped now
+    if let Some(journal) = &smp.journal {
+        if let Err(e) = journal.lock().finish_replay() {
+            error!(LogSchema::new(LogEntry::Journal)
+                .error(&e)
+                .message("Failed to finish mempool journal replay"));
+        }
+    }
+
+    info!(LogSchema::event_log(LogEntry::Journal, LogEvent::Success)
+        .num_txns(num_accepted)
+        .message("Replayed mempool journal"));
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
@@ -400,10 +616,88 @@ where
         &mut statuses,
         client_submitted,
     );
+    if let Some(journal) = &smp.journal {
+        journal_accepted_transactions(smp, journal, &statuses, timeline_state, client_submitted);
+    }
     notify_subscribers(SharedMempoolNotification::NewTransactions, &smp.subscribers);
     statuses
 }
 
+/// Appends the transactions accepted into mempool to the mempool journal.
+fn journal_accepted_transactions<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+    journal: &Mutex<MempoolJournal>,
+    statuses: &[SubmissionStatusBundle],
+    timeline_state: TimelineState,
+    client_submitted: bool,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let broadcast = timeline_state != TimelineState::NonQualified;
+    let entries: Vec<_> = statuses
+        .iter()
+        .filter(|(_, (mempool_status, _))| mempool_status.code == MempoolStatusCode::Accepted)
+        .map(|(transaction, _)| JournalEntry::new(transaction.clone(), broadcast, client_submitted))
+        .collect();
+    if entries.is_empty() {
+        return;
+    }
+
+    let needs_compaction = {
+        let mut journal = journal.lock();
+        if let Err(e) = journal.append(&entries) {
+            error!(LogSchema::new(LogEntry::Journal)
+                .error(&e)
+                .message("Failed to append to mempool journal"));
+        }
+        journal.needs_compaction()
+    };
+    // Compact in the background, so that submissions aren't held up by the rewrite
+    if needs_compaction {
+        let mempool = smp.mempool.clone();
+        let journal = smp.journal.clone();
+        tokio::task::spawn_blocking(move || compact_journal(&mempool, journal.as_deref()));
+    }
+}
+
+/// Drops evicted transactions from the journal before the operator is answered, running the
+/// rewrite on the blocking pool so that it doesn't hold up the runtime.
+async fn compact_journal_after_eviction<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let mempool = smp.mempool.clone();
+    let journal = smp.journal.clone();
+    if let Err(e) =
+        tokio::task::spawn_blocking(move || compact_journal(&mempool, journal.as_deref())).await
+    {
+        error!(LogSchema::new(LogEntry::Journal)
+            .message(&format!("Mempool journal compaction task failed: {}", e)));
+    }
+}
+
+/// Rewrites the journal without the transactions that left mempool, so that evicted transactions
+/// are not replayed on restart.
+fn compact_journal(mempool: &Mutex<CoreMempool>, journal: Option<&Mutex<MempoolJournal>>) {
+    let Some(journal) = journal else {
+        return;
+    };
+    let is_pending = |transaction: &SignedTransaction| {
+        mempool
+            .lock()
+            .get_by_hash(transaction.committed_hash())
+            .is_some()
+    };
+    if let Err(e) = MempoolJournal::compact(journal, is_pending) {
+        error!(LogSchema::new(LogEntry::Journal)
+            .error(&e)
+            .message("Failed to compact mempool journal"));
+    }
+}
+
 /// Filters transactions based on the transaction filter configuration. Any
 /// transactions that are filtered out will have their statuses marked accordingly.
 fn filter_transactions(
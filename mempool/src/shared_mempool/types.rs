@@ -6,7 +6,9 @@
 use crate::{
     core_mempool::{CoreMempool, TimelineId},
     network::{MempoolNetworkInterface, MempoolSyncMsg},
-    shared_mempool::use_case_history::UseCaseHistory,
+    shared_mempool::{
+        admission::AdmissionControl, journal::MempoolJournal, use_case_history::UseCaseHistory,
+    },
 };
 use accudo_config::{
     config::{MempoolConfig, NodeType, TransactionFilterConfig},
@@ -20,7 +22,9 @@ use accudo_infallible::{Mutex, RwLock};
 use accudo_network::application::interface::NetworkClientInterface;
 use accudo_storage_interface::DbReader;
 use accudo_types::{
-    account_address::AccountAddress, mempool_status::MempoolStatus, transaction::SignedTransaction,
+    account_address::AccountAddress,
+    mempool_status::MempoolStatus,
+    transaction::{ReplayProtector, SignedTransaction},
     vm_status::DiscardedVMStatus,
 };
 use accudo_vm_validator::vm_validator::TransactionValidation;
@@ -30,17 +34,20 @@ use futures::{
     future::Future,
     task::{Context, Poll},
 };
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
     collections::{BTreeMap, BTreeSet, HashMap},
     fmt,
+    net::IpAddr,
+    ops::Range,
     pin::Pin,
     sync::Arc,
     task::Waker,
     time::{Instant, SystemTime},
 };
-use tokio::runtime::Handle;
+use tokio::{runtime::Handle, sync::broadcast};
 
 pub type MempoolSenderBucket = u8;
 pub type TimelineIndexIdentifier = u8;
@@ -57,6 +64,8 @@ pub(crate) struct SharedMempool<NetworkClient, TransactionValidator> {
     pub broadcast_within_validator_network: Arc<RwLock<bool>>,
     pub use_case_history: Arc<Mutex<UseCaseHistory>>,
     pub transaction_filter_config: TransactionFilterConfig,
+    pub journal: Option<Arc<Mutex<MempoolJournal>>>,
+    pub admission_control: Arc<AdmissionControl>,
 }
 
 impl<
@@ -76,6 +85,7 @@ impl<
     ) -> Self {
         let network_interface =
             MempoolNetworkInterface::new(network_client, node_type, config.clone());
+        let admission_control = AdmissionControl::new(&config.admission_quotas);
         let use_case_history = UseCaseHistory::new(
             config.usecase_stats_num_blocks_to_track,
             config.usecase_stats_num_top_to_track,
@@ -90,6 +100,8 @@ impl<
             broadcast_within_validator_network: Arc::new(RwLock::new(true)),
             use_case_history: Arc::new(Mutex::new(use_case_history)),
             transaction_filter_config,
+            journal: None,
+            admission_control: Arc::new(admission_control),
         }
     }
 
@@ -239,13 +251,133 @@ pub type SubmissionStatus = (MempoolStatus, Option<DiscardedVMStatus>);
 pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 
 pub enum MempoolClientRequest {
-    /// Submits a transaction to the mempool and returns its submission status
-    SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
+    /// Submits a transaction to the mempool, along with the IP address of the client that
+    /// submitted it (if known), and returns its submission status
+    SubmitTransaction(
+        SignedTransaction,
+        Option<IpAddr>,
+        oneshot::Sender<Result<SubmissionStatus>>,
+    ),
     /// Retrieves a signed transaction from the mempool using its hash
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
     /// Retrieves all addresses with transactions in the mempool's parking lot and
     /// the number of transactions for each address
     GetAddressesFromParkingLot(oneshot::Sender<Vec<(AccountAddress, u64)>>),
+    /// Retrieves the status of a transaction in the mempool using its hash
+    GetTransactionStatus(HashValue, oneshot::Sender<Option<MempoolTransactionStatus>>),
+    /// Subscribes to the events of transactions entering and leaving the mempool
+    SubscribeTransactionEvents(oneshot::Sender<broadcast::Receiver<MempoolTransactionEvent>>),
+    /// Retrieves the transactions of an account in the mempool
+    GetTransactionsBySender(
+        AccountAddress,
+        oneshot::Sender<Vec<MempoolTransactionSummary>>,
+    ),
+    /// Retrieves all accounts with transactions in the mempool's parking lot, along with the
+    /// sequence numbers they are waiting for
+    GetParkingLot(oneshot::Sender<Vec<MempoolParkedAccount>>),
+    /// Retrieves at most the given number of ready transactions, in priority queue order
+    GetPriorityQueue(usize, oneshot::Sender<Vec<MempoolTransactionSummary>>),
+    /// Removes a transaction from the mempool using its hash, and returns it if it was there
+    EvictTransaction(
+        HashValue,
+        oneshot::Sender<Option<MempoolTransactionSummary>>,
+    ),
+    /// Removes all transactions of an account from the mempool, and returns them
+    EvictSender(
+        AccountAddress,
+        oneshot::Sender<Vec<MempoolTransactionSummary>>,
+    ),
+}
+
+/// What happened to a transaction in a `MempoolTransactionEvent`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MempoolTransactionEventKind {
+    /// The transaction was inserted into the mempool.
+    Inserted,
+    /// The transaction, or a later one with the same sender, was committed.
+    Committed,
+    /// The transaction was rejected by consensus.
+    Rejected,
+    /// The transaction was removed for any other reason, e.g. it expired, was evicted from the
+    /// parking lot or was replaced.
+    Removed,
+}
+
+/// An event of a transaction entering or leaving the mempool, see
+/// `MempoolClientRequest::SubscribeTransactionEvents`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MempoolTransactionEvent {
+    pub kind: MempoolTransactionEventKind,
+    pub hash: HashValue,
+    pub sender: AccountAddress,
+    pub replay_protector: ReplayProtector,
+    pub gas_unit_price: u64,
+    /// The entry function called by the transaction, if any.
+    pub entry_function: Option<(ModuleId, Identifier)>,
+    pub timestamp: SystemTime,
+}
+
+/// Where a transaction currently sits in the mempool.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolTransactionState {
+    /// Not ready for consensus yet, e.g. waiting for transactions with lower sequence numbers.
+    Parked,
+    /// In the priority queue, i.e. can be pulled by consensus.
+    Ready,
+}
+
+/// The highest rank reported in a `MempoolTransactionStatus`. Counting the transactions ahead
+/// happens under the mempool lock, so it stops here rather than walking the whole queue.
+pub const MAX_TRANSACTION_STATUS_RANK: usize = 1_000;
+
+/// Status of a transaction in the mempool, see `MempoolClientRequest::GetTransactionStatus`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MempoolTransactionStatus {
+    pub sender: AccountAddress,
+    pub replay_protector: ReplayProtector,
+    pub gas_unit_price: u64,
+    pub ranking_score: u64,
+    /// Sequence number of the sender's account as last seen by the mempool, if known.
+    pub account_sequence_number: Option<u64>,
+    pub state: MempoolTransactionState,
+    /// Number of ready transactions ahead of this one in the priority queue, if ready. Capped
+    /// at `MAX_TRANSACTION_STATUS_RANK`, i.e. at least that many transactions are ahead.
+    pub rank: Option<usize>,
+    pub insertion_time: SystemTime,
+    /// When the transaction was last parked, if ever.
+    pub park_time: Option<SystemTime>,
+    /// Peers the transaction has been broadcast to.
+    pub broadcast_peers: Vec<PeerNetworkId>,
+    /// Number of times consensus pulled the transaction into a batch.
+    pub consensus_pulled_count: usize,
+}
+
+/// A transaction in the mempool, as listed and evicted by the admin requests of
+/// `MempoolClientRequest`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct MempoolTransactionSummary {
+    pub hash: HashValue,
+    pub sender: AccountAddress,
+    pub replay_protector: ReplayProtector,
+    pub gas_unit_price: u64,
+    pub ranking_score: u64,
+    pub expiration_timestamp_secs: u64,
+    pub state: MempoolTransactionState,
+    /// Time the transaction was inserted, in microseconds since the Unix epoch.
+    pub insertion_timestamp_usecs: u64,
+}
+
+/// An account with transactions in the parking lot, see `MempoolClientRequest::GetParkingLot`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct MempoolParkedAccount {
+    pub address: AccountAddress,
+    /// Sequence number of the account as last seen by the mempool, if known.
+    pub account_sequence_number: Option<u64>,
+    pub parked_sequence_numbers: Vec<u64>,
+    /// Ranges of sequence numbers missing from the mempool, which keep the parked transactions
+    /// from becoming ready.
+    pub gaps: Vec<Range<u64>>,
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
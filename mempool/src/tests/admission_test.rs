@@ -0,0 +1,85 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{shared_mempool::admission::AdmissionControl, tests::common::TestTransaction};
+use accudo_config::config::{
+    AdmissionQuotaConfig, GasPriceTierQuotaConfig, MempoolAdmissionQuotasConfig,
+};
+use accudo_types::{
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    transaction::{ReplayProtector, SignedTransaction},
+};
+use std::net::{IpAddr, Ipv4Addr};
+
+fn quota(bucket_size: usize) -> AdmissionQuotaConfig {
+    AdmissionQuotaConfig {
+        bucket_size,
+        fill_rate: 1,
+    }
+}
+
+fn transaction(address: usize, gas_price: u64) -> SignedTransaction {
+    TestTransaction::new(address, ReplayProtector::SequenceNumber(0), gas_price)
+        .make_signed_transaction()
+}
+
+fn assert_rate_limited(result: Result<(), MempoolStatus>) {
+    assert_eq!(result.unwrap_err().code, MempoolStatusCode::RateLimited);
+}
+
+#[test]
+fn test_admission_per_ip_and_sender() {
+    let admission_control = AdmissionControl::new(&MempoolAdmissionQuotasConfig {
+        per_ip: Some(quota(2)),
+        per_sender: Some(quota(1)),
+        ..Default::default()
+    });
+    let ip = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    let other_ip = Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    admission_control.admit(&transaction(0, 1), ip).unwrap();
+    assert_rate_limited(admission_control.admit(&transaction(0, 1), ip));
+
+    // The rejection by the sender quota gave back the token taken from the IP quota
+    admission_control.admit(&transaction(1, 1), ip).unwrap();
+    assert_rate_limited(admission_control.admit(&transaction(2, 1), ip));
+    admission_control
+        .admit(&transaction(2, 1), other_ip)
+        .unwrap();
+
+    // Transactions from an unknown IP are only limited by the other quotas
+    admission_control.admit(&transaction(3, 1), None).unwrap();
+}
+
+#[test]
+fn test_admission_per_gas_price_tier() {
+    let admission_control = AdmissionControl::new(&MempoolAdmissionQuotasConfig {
+        per_gas_price_tier: vec![
+            GasPriceTierQuotaConfig {
+                min_gas_unit_price: 100,
+                quota: quota(2),
+            },
+            GasPriceTierQuotaConfig {
+                min_gas_unit_price: 10,
+                quota: quota(1),
+            },
+        ],
+        ..Default::default()
+    });
+
+    // Transactions priced below the lowest tier are not limited
+    for address in 0..3 {
+        admission_control
+            .admit(&transaction(address, 1), None)
+            .unwrap();
+    }
+
+    admission_control.admit(&transaction(0, 10), None).unwrap();
+    assert_rate_limited(admission_control.admit(&transaction(1, 99), None));
+
+    admission_control.admit(&transaction(0, 100), None).unwrap();
+    admission_control
+        .admit(&transaction(1, 1_000), None)
+        .unwrap();
+    assert_rate_limited(admission_control.admit(&transaction(2, 100), None));
+}
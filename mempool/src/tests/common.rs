@@ -110,6 +110,22 @@ impl TestTransaction {
         }
     }
 
+    pub(crate) fn new_cancellation(
+        address: usize,
+        replay_protector: ReplayProtector,
+        gas_price: u64,
+    ) -> Self {
+        let TransactionExecutable::Script(script) = TransactionExecutable::cancellation() else {
+            unreachable!("Cancellation is a script");
+        };
+        Self {
+            address: TestTransaction::get_address(address),
+            replay_protector,
+            gas_price,
+            script: Some(script),
+        }
+    }
+
     pub(crate) fn new_with_address(
         address: AccountAddress,
         replay_protector: ReplayProtector,
@@ -5,9 +5,12 @@
 use crate::{
     core_mempool::{sender_bucket, CoreMempool, MempoolTransaction, SubmittedBy, TimelineState},
     network::BroadcastPeerPriority,
+    shared_mempool::types::{
+        MempoolTransactionEventKind, MempoolTransactionState, MAX_TRANSACTION_STATUS_RANK,
+    },
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, setup_mempool,
-        setup_mempool_with_broadcast_buckets, txn_bytes_len, TestTransaction,
+        setup_mempool_with_broadcast_buckets, txn_bytes_len, ConsensusMock, TestTransaction,
     },
 };
 use accudo_config::config::{MempoolConfig, NodeConfig};
@@ -308,7 +311,11 @@ fn test_fail_for_same_gas_amount_and_not_same_expiration_time() {
 
 #[test]
 fn test_update_invalid_transaction_in_mempool() {
-    let (mut mempool, mut consensus) = setup_mempool();
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.broadcast_buckets = vec![0];
+    config.mempool.replacement_min_gas_price_bump_pct = None;
+    let mut mempool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
     let txns = add_txns_to_mempool(
         &mut mempool,
         vec![
@@ -333,6 +340,154 @@ fn test_update_invalid_transaction_in_mempool() {
     assert_eq!(next_tnx[0].gas_unit_price(), 1);
 }
 
+#[test]
+fn test_replace_transaction_in_mempool() {
+    let (mut mempool, mut consensus) = setup_mempool();
+    let txns = add_txns_to_mempool(
+        &mut mempool,
+        vec![
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 100),
+            TestTransaction::new(1, ReplayProtector::SequenceNumber(0), 105),
+        ],
+    );
+
+    // A replacement with a different max gas amount needs a gas price bump of at least 10%.
+    let replacement = TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 109)
+        .make_signed_transaction_with_max_gas_amount(200);
+    assert!(add_signed_txn(&mut mempool, replacement).is_err());
+    let replacement = TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 110)
+        .make_signed_transaction_with_max_gas_amount(200);
+    add_signed_txn(&mut mempool, replacement.clone()).unwrap();
+
+    assert_eq!(mempool.transactions.priority_index.size(), 2);
+    assert_eq!(
+        mempool.get_by_hash(txns[0].committed_hash()),
+        None,
+        "Replaced transaction should be removed"
+    );
+    assert_eq!(
+        consensus.get_block(&mut mempool, 1, 1024),
+        vec![replacement]
+    );
+    assert_eq!(
+        consensus.get_block(&mut mempool, 1, 1024),
+        vec![txns[1].clone()]
+    );
+}
+
+#[test]
+fn test_cancel_transaction_in_mempool() {
+    let (mut mempool, _) = setup_mempool();
+    let txns = add_txns_to_mempool(
+        &mut mempool,
+        vec![
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 10),
+            TestTransaction::new(0, ReplayProtector::Nonce(123), 10),
+        ],
+    );
+
+    for (txn, replay_protector) in txns.iter().zip([
+        ReplayProtector::SequenceNumber(0),
+        ReplayProtector::Nonce(123),
+    ]) {
+        // Cancellation is a replacement, so it also needs to bump the gas price.
+        assert!(add_txn(
+            &mut mempool,
+            TestTransaction::new_cancellation(0, replay_protector, 10)
+        )
+        .is_err());
+        let cancellation = add_txn(
+            &mut mempool,
+            TestTransaction::new_cancellation(0, replay_protector, 11),
+        )
+        .unwrap();
+        assert!(cancellation.payload().is_cancellation());
+        assert_eq!(mempool.get_by_hash(txn.committed_hash()), None);
+        assert_eq!(
+            mempool.get_by_hash(cancellation.committed_hash()),
+            Some(cancellation)
+        );
+    }
+    assert_eq!(mempool.transactions.priority_index.size(), 2);
+}
+
+#[test]
+fn test_include_replaced() {
+    let (mut pool, _) = setup_mempool();
+    let original = add_txn(
+        &mut pool,
+        TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 10),
+    )
+    .unwrap();
+    let original_summary = TransactionSummary::new(
+        original.sender(),
+        original.replay_protector(),
+        original.committed_hash(),
+    );
+    let exclude_transactions = btreemap! {
+        original_summary => TransactionInProgress::new(10)
+    };
+    assert!(pool
+        .get_batch(10, 10240, true, exclude_transactions.clone())
+        .is_empty());
+
+    // The replacement is pulled again even though the original is already in a batch.
+    let cancellation = add_txn(
+        &mut pool,
+        TestTransaction::new_cancellation(0, ReplayProtector::SequenceNumber(0), 11),
+    )
+    .unwrap();
+    assert_eq!(
+        pool.get_batch(10, 10240, true, exclude_transactions),
+        vec![cancellation]
+    );
+}
+
+#[test]
+fn test_transaction_events() {
+    let (mut pool, _) = setup_mempool();
+    let mut events = pool.subscribe_transaction_events();
+    let mut assert_event = |kind: MempoolTransactionEventKind, txn: &SignedTransaction| {
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.kind, kind);
+        assert_eq!(event.hash, txn.committed_hash());
+        assert_eq!(event.sender, txn.sender());
+        assert_eq!(event.replay_protector, txn.replay_protector());
+        assert_eq!(event.gas_unit_price, txn.gas_unit_price());
+    };
+
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 1),
+            TestTransaction::new(1, ReplayProtector::SequenceNumber(0), 1),
+        ],
+    );
+    assert_event(MempoolTransactionEventKind::Inserted, &txns[0]);
+    assert_event(MempoolTransactionEventKind::Inserted, &txns[1]);
+
+    // A gas price upgrade removes the original transaction
+    let upgraded = add_txn(
+        &mut pool,
+        TestTransaction::new(1, ReplayProtector::SequenceNumber(0), 2),
+    )
+    .unwrap();
+    assert_event(MempoolTransactionEventKind::Removed, &txns[1]);
+    assert_event(MempoolTransactionEventKind::Inserted, &upgraded);
+
+    pool.commit_transaction(&txns[0].sender(), txns[0].replay_protector());
+    assert_event(MempoolTransactionEventKind::Committed, &txns[0]);
+
+    pool.reject_transaction(
+        &upgraded.sender(),
+        upgraded.replay_protector(),
+        &upgraded.committed_hash(),
+        &DiscardedVMStatus::MALFORMED,
+    );
+    assert_event(MempoolTransactionEventKind::Rejected, &upgraded);
+    assert!(events.try_recv().is_err());
+}
+
 #[test]
 fn test_commit_transaction() {
     let (mut pool, mut consensus) = setup_mempool();
@@ -1852,3 +2007,175 @@ fn test_include_gas_upgraded() {
     );
     assert_eq!(batch.len(), 0);
 }
+
+#[test]
+fn test_get_parked_accounts() {
+    let (mut pool, _) = setup_mempool();
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 1),
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(2), 1),
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(3), 1),
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(6), 1),
+            TestTransaction::new(1, ReplayProtector::SequenceNumber(0), 1),
+        ],
+    );
+
+    let parked_accounts = pool.get_parked_accounts();
+    assert_eq!(parked_accounts.len(), 1);
+    let parked_account = &parked_accounts[0];
+    assert_eq!(parked_account.address, TestTransaction::get_address(0));
+    assert_eq!(parked_account.account_sequence_number, Some(0));
+    assert_eq!(parked_account.parked_sequence_numbers, vec![2, 3, 6]);
+    assert_eq!(parked_account.gaps, vec![1..2, 4..6]);
+}
+
+#[test]
+fn test_get_priority_queue() {
+    let (mut pool, _) = setup_mempool();
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 1),
+            TestTransaction::new(1, ReplayProtector::SequenceNumber(0), 5),
+            TestTransaction::new(2, ReplayProtector::SequenceNumber(0), 3),
+            TestTransaction::new(3, ReplayProtector::SequenceNumber(1), 10),
+        ],
+    );
+
+    // The parked transaction is left out, and the others are ordered by gas price
+    let queue: Vec<_> = pool
+        .get_priority_queue(10)
+        .into_iter()
+        .map(|txn| txn.hash)
+        .collect();
+    assert_eq!(
+        queue,
+        vec![
+            txns[1].committed_hash(),
+            txns[2].committed_hash(),
+            txns[0].committed_hash(),
+        ]
+    );
+    assert_eq!(pool.get_priority_queue(2).len(), 2);
+}
+
+#[test]
+fn test_evict_transactions() {
+    let (mut pool, _) = setup_mempool();
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 1),
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(1), 1),
+            TestTransaction::new(0, ReplayProtector::Nonce(100), 1),
+            TestTransaction::new(1, ReplayProtector::SequenceNumber(0), 1),
+        ],
+    );
+    let sender = TestTransaction::get_address(0);
+
+    let evicted = pool.evict_transaction(txns[1].committed_hash()).unwrap();
+    assert_eq!(evicted.replay_protector, ReplayProtector::SequenceNumber(1));
+    assert_eq!(evicted.state, MempoolTransactionState::Ready);
+    assert!(pool.evict_transaction(txns[1].committed_hash()).is_none());
+    assert_eq!(pool.get_transactions_by_sender(&sender).len(), 2);
+
+    let evicted: Vec<_> = pool
+        .evict_sender(&sender)
+        .into_iter()
+        .map(|txn| txn.hash)
+        .collect();
+    assert_eq!(
+        evicted,
+        vec![txns[2].committed_hash(), txns[0].committed_hash()]
+    );
+    assert!(pool.get_transactions_by_sender(&sender).is_empty());
+    assert!(pool.get_by_hash(txns[0].committed_hash()).is_none());
+
+    // Transactions of other accounts are left in place
+    let queue: Vec<_> = pool
+        .get_priority_queue(10)
+        .into_iter()
+        .map(|txn| txn.hash)
+        .collect();
+    assert_eq!(queue, vec![txns[3].committed_hash()]);
+}
+
+#[test]
+fn test_evict_transaction_parks_later_transactions() {
+    let (mut pool, _) = setup_mempool();
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 1),
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(1), 1),
+            TestTransaction::new(0, ReplayProtector::SequenceNumber(2), 1),
+        ],
+    );
+    let sender = TestTransaction::get_address(0);
+    let states = |pool: &CoreMempool| -> Vec<_> {
+        pool.get_transactions_by_sender(&sender)
+            .into_iter()
+            .map(|txn| (txn.replay_protector, txn.state))
+            .collect()
+    };
+
+    // The transaction after the gap is parked
+    pool.evict_transaction(txns[1].committed_hash()).unwrap();
+    assert_eq!(
+        states(&pool),
+        vec![
+            (
+                ReplayProtector::SequenceNumber(0),
+                MempoolTransactionState::Ready
+            ),
+            (
+                ReplayProtector::SequenceNumber(2),
+                MempoolTransactionState::Parked
+            ),
+        ]
+    );
+    let queue: Vec<_> = pool
+        .get_priority_queue(10)
+        .into_iter()
+        .map(|txn| txn.hash)
+        .collect();
+    assert_eq!(queue, vec![txns[0].committed_hash()]);
+
+    // And ready again once the gap is filled
+    add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, ReplayProtector::SequenceNumber(1), 1)],
+    );
+    assert!(states(&pool)
+        .iter()
+        .all(|(_, state)| *state == MempoolTransactionState::Ready));
+    assert_eq!(pool.get_priority_queue(10).len(), 3);
+}
+
+#[test]
+fn test_transaction_status_rank_is_capped() {
+    let (mut pool, _) = setup_mempool();
+
+    // The lowest priced transaction is ranked behind all the others
+    let mut txns = vec![TestTransaction::new_with_address(
+        AccountAddress::random(),
+        ReplayProtector::SequenceNumber(0),
+        1,
+    )];
+    txns.extend((0..MAX_TRANSACTION_STATUS_RANK + 1).map(|_| {
+        TestTransaction::new_with_address(
+            AccountAddress::random(),
+            ReplayProtector::SequenceNumber(0),
+            2,
+        )
+    }));
+    let txns = add_txns_to_mempool(&mut pool, txns);
+
+    let (status, _) = pool
+        .get_transaction_status(txns[0].committed_hash())
+        .unwrap();
+    assert_eq!(status.state, MempoolTransactionState::Ready);
+    assert_eq!(status.rank, Some(MAX_TRANSACTION_STATUS_RANK));
+}
@@ -2,11 +2,15 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::tests::{
-    common::TestTransaction,
-    test_framework::{test_transaction, MempoolNode, MempoolTestFrameworkBuilder},
+use crate::{
+    tests::{
+        common::TestTransaction,
+        test_framework::{test_transaction, MempoolNode, MempoolTestFrameworkBuilder},
+    },
+    MempoolTransactionState,
 };
 use accudo_config::network_id::PeerNetworkId;
+use accudo_crypto::HashValue;
 use accudo_netcore::transport::ConnectionOrigin;
 use accudo_network::{
     testutils::{
@@ -274,6 +278,70 @@ async fn test_update_gas_price() {
         .await;
 }
 
+/// Test that cancelling a transaction replaces it and pushes the cancellation onward to other nodes
+#[tokio::test]
+async fn test_cancel_transaction() {
+    let cancellation = TestTransaction::new_cancellation(1, ReplayProtector::SequenceNumber(0), 2);
+    let cancellation = &[cancellation];
+
+    let mut node = MempoolTestFrameworkBuilder::single_validator();
+    let (other_peer_network_id, other_metadata) =
+        validator_mock_connection(ConnectionOrigin::Outbound, &ALL_PROTOCOLS);
+
+    // Get first txn
+    node.add_txns_via_client(&TXN_1).await;
+    node.assert_txns_in_mempool(&TXN_1);
+
+    // Send to other node
+    node.connect_self(other_peer_network_id.network_id(), other_metadata.clone());
+    node.send_broadcast_and_receive_ack(other_peer_network_id, &TXN_1)
+        .await;
+
+    // Cancel txn
+    node.add_txns_via_client(cancellation).await;
+    node.assert_only_txns_in_mempool(cancellation);
+
+    // Cancellation should be sent
+    node.send_broadcast_and_receive_ack(other_peer_network_id, cancellation)
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_transaction_status() {
+    let mut node = MempoolTestFrameworkBuilder::single_validator();
+    let (other_peer_network_id, other_metadata) =
+        validator_mock_connection(ConnectionOrigin::Outbound, &ALL_PROTOCOLS);
+
+    // Unknown txn
+    assert!(node
+        .get_transaction_status_via_client(HashValue::random())
+        .await
+        .is_none());
+
+    // Second txn is parked until the first one arrives
+    node.add_txns_via_client(&TXN_2).await;
+    let hash = node.get_txn_hash_in_mempool(&TXN_2[0]);
+    let status = node.get_transaction_status_via_client(hash).await.unwrap();
+    assert_eq!(status.sender, TXN_2[0].address);
+    assert_eq!(status.replay_protector, TXN_2[0].replay_protector);
+    assert_eq!(status.state, MempoolTransactionState::Parked);
+    assert_eq!(status.rank, None);
+    assert!(status.broadcast_peers.is_empty());
+
+    node.add_txns_via_client(&TXN_1).await;
+    let status = node.get_transaction_status_via_client(hash).await.unwrap();
+    assert_eq!(status.state, MempoolTransactionState::Ready);
+    assert!(status.rank.is_some_and(|rank| rank < ALL_TXNS.len()));
+    assert!(status.broadcast_peers.is_empty());
+
+    // Both txns are reported as broadcast after the ack
+    node.connect_self(other_peer_network_id.network_id(), other_metadata.clone());
+    node.send_broadcast_and_receive_ack(other_peer_network_id, &ALL_TXNS)
+        .await;
+    let status = node.get_transaction_status_via_client(hash).await.unwrap();
+    assert_eq!(status.broadcast_peers, vec![other_peer_network_id]);
+}
+
 /// In the event of a full mempool, retry and broadcast again
 #[tokio::test]
 async fn test_mempool_full_rebroadcast() {
@@ -0,0 +1,139 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    shared_mempool::journal::{JournalEntry, MempoolJournal},
+    tests::common::TestTransaction,
+};
+use accudo_infallible::Mutex;
+use accudo_temppath::TempPath;
+use accudo_types::transaction::{ReplayProtector, SignedTransaction};
+use std::{fs::OpenOptions, io::Write, time::Duration};
+
+fn journal_entry(address: usize, sequence_number: u64) -> JournalEntry {
+    let txn = TestTransaction::new(address, ReplayProtector::SequenceNumber(sequence_number), 1)
+        .make_signed_transaction();
+    JournalEntry::new(txn, true, true)
+}
+
+#[test]
+fn test_journal_replays_appended_entries() {
+    let path = TempPath::new();
+    let (mut journal, entries) = MempoolJournal::open(path.path().to_path_buf(), u64::MAX).unwrap();
+    assert!(entries.is_empty());
+
+    let appended = vec![
+        journal_entry(0, 0),
+        journal_entry(1, 0),
+        journal_entry(0, 1),
+    ];
+    journal.append(&appended[..2]).unwrap();
+    journal.append(&appended[2..]).unwrap();
+    drop(journal);
+
+    // A record torn by a crash in the middle of a write is ignored
+    OpenOptions::new()
+        .append(true)
+        .open(path.path())
+        .unwrap()
+        .write_all(&[100, 0, 0, 0, 1, 2, 3])
+        .unwrap();
+
+    let (journal, entries) = MempoolJournal::open(path.path().to_path_buf(), u64::MAX).unwrap();
+    assert_eq!(entries, appended);
+    journal.finish_replay().unwrap();
+    drop(journal);
+
+    // Reopening after the replay finished empties the journal, as the entries are journaled
+    // again when replayed
+    let (_journal, entries) = MempoolJournal::open(path.path().to_path_buf(), u64::MAX).unwrap();
+    assert!(entries.is_empty());
+}
+
+#[test]
+fn test_journal_survives_interrupted_replay() {
+    let path = TempPath::new();
+    let replayed = journal_entry(0, 0);
+    let appended = journal_entry(1, 0);
+    let (mut journal, _) = MempoolJournal::open(path.path().to_path_buf(), u64::MAX).unwrap();
+    journal.append(&[replayed.clone()]).unwrap();
+    drop(journal);
+
+    // The node restarts before the replay finishes, after journaling a new transaction
+    let (mut journal, entries) = MempoolJournal::open(path.path().to_path_buf(), u64::MAX).unwrap();
+    assert_eq!(entries, vec![replayed.clone()]);
+    journal.append(&[appended.clone()]).unwrap();
+    drop(journal);
+
+    // Both the unfinished replay and the new transaction are replayed
+    let (journal, entries) = MempoolJournal::open(path.path().to_path_buf(), u64::MAX).unwrap();
+    assert_eq!(entries, vec![replayed, appended]);
+    journal.finish_replay().unwrap();
+}
+
+#[test]
+fn test_journal_rejects_oversized_record() {
+    let path = TempPath::new();
+    drop(MempoolJournal::open(path.path().to_path_buf(), 1024).unwrap());
+
+    OpenOptions::new()
+        .append(true)
+        .open(path.path())
+        .unwrap()
+        .write_all(&u32::MAX.to_le_bytes())
+        .unwrap();
+    assert!(MempoolJournal::open(path.path().to_path_buf(), 1024).is_err());
+}
+
+#[test]
+fn test_journal_compaction() {
+    let path = TempPath::new();
+    let entries: Vec<_> = (0..10).map(|i| journal_entry(0, i)).collect();
+    let entry_bytes = 4 + bcs::serialized_size(&entries[0]).unwrap() as u64;
+    let (mut journal, _) =
+        MempoolJournal::open(path.path().to_path_buf(), 10 * entry_bytes).unwrap();
+
+    journal.append(&entries).unwrap();
+    assert!(!journal.needs_compaction());
+    journal.append(&[entries[0].clone()]).unwrap();
+    assert!(journal.needs_compaction());
+
+    // Compaction keeps the newest pending transactions within half of the limit
+    let journal = Mutex::new(journal);
+    let is_pending = |txn: &SignedTransaction| txn.sequence_number() % 2 == 0;
+    MempoolJournal::compact(&journal, is_pending).unwrap();
+    assert!(!journal.lock().needs_compaction());
+
+    // Entries appended after the compaction are kept
+    journal.lock().append(&[entries[1].clone()]).unwrap();
+    drop(journal);
+
+    let (_journal, compacted) =
+        MempoolJournal::open(path.path().to_path_buf(), 10 * entry_bytes).unwrap();
+    assert_eq!(compacted, vec![
+        entries[2].clone(),
+        entries[4].clone(),
+        entries[6].clone(),
+        entries[8].clone(),
+        entries[0].clone(),
+        entries[1].clone(),
+    ]);
+}
+
+#[test]
+fn test_journal_entry_expiration() {
+    let txn = TestTransaction::new(0, ReplayProtector::SequenceNumber(0), 1);
+    let timeout = Duration::from_secs(600);
+
+    assert!(!JournalEntry::new(txn.make_signed_transaction(), true, true).is_expired(timeout));
+    assert!(JournalEntry::new(
+        txn.make_signed_transaction_with_expiration_time(0),
+        true,
+        true
+    )
+    .is_expired(timeout));
+
+    let mut entry = JournalEntry::new(txn.make_signed_transaction(), true, true);
+    entry.insertion_time_secs -= timeout.as_secs();
+    assert!(entry.is_expired(timeout));
+}
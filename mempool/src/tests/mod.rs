@@ -5,6 +5,8 @@
 // Command to run all the mempool tests:
 //      cargo test --package accudo-mempool --lib -- tests --show-output
 
+#[cfg(test)]
+mod admission_test;
 #[cfg(test)]
 mod common;
 #[cfg(test)]
@@ -12,6 +14,8 @@ mod core_mempool_test;
 #[cfg(test)]
 mod integration_tests;
 #[cfg(test)]
+mod journal_test;
+#[cfg(test)]
 mod multi_node_test;
 #[cfg(test)]
 mod node;
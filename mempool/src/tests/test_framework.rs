@@ -9,13 +9,15 @@ use crate::{
         types::{MempoolMessageId, MempoolSenderBucket},
     },
     tests::common::{self, TestTransaction},
-    MempoolClientRequest, MempoolClientSender, MempoolSyncMsg, QuorumStoreRequest,
+    MempoolClientRequest, MempoolClientSender, MempoolSyncMsg, MempoolTransactionStatus,
+    QuorumStoreRequest,
 };
 use accudo_channels::{accudo_channel, message_queues::QueueStyle};
 use accudo_config::{
     config::NodeConfig,
     network_id::{NetworkId, PeerNetworkId},
 };
+use accudo_crypto::HashValue;
 use accudo_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
 use accudo_id_generator::U32IdGenerator;
 use accudo_infallible::{Mutex, RwLock};
@@ -153,7 +155,7 @@ impl MempoolNode {
             let (sender, receiver) = oneshot::channel();
 
             self.mempool_client_sender
-                .send(MempoolClientRequest::SubmitTransaction(txn, sender))
+                .send(MempoolClientRequest::SubmitTransaction(txn, None, sender))
                 .await
                 .unwrap();
             let status = receiver.await.unwrap().unwrap();
@@ -178,6 +180,28 @@ impl MempoolNode {
         receiver.await.unwrap()
     }
 
+    pub async fn get_transaction_status_via_client(
+        &mut self,
+        hash: HashValue,
+    ) -> Option<MempoolTransactionStatus> {
+        let (sender, receiver) = oneshot::channel();
+        self.mempool_client_sender
+            .send(MempoolClientRequest::GetTransactionStatus(hash, sender))
+            .await
+            .unwrap();
+        receiver.await.unwrap()
+    }
+
+    /// Returns the hash of a transaction in mempool, as the signature differs per signing
+    pub fn get_txn_hash_in_mempool(&self, txn: &TestTransaction) -> HashValue {
+        self.mempool
+            .lock()
+            .get_transaction_store()
+            .get(&txn.address, txn.replay_protector)
+            .expect("Transaction should be in mempool")
+            .committed_hash()
+    }
+
     /// Asynchronously waits for up to 1 second for txns to appear in mempool
     pub async fn wait_on_txns_in_mempool(&self, txns: &[TestTransaction]) {
         for _ in 0..10 {
@@ -13,7 +13,7 @@
 use accudo_config::{
     config::{
         DiscoveryMethod, NetworkConfig, Peer, PeerRole, PeerSet, RoleType, CONNECTION_BACKOFF_BASE,
-        CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONNECTION_DELAY_MS, MAX_FRAME_SIZE,
+        CONNECTIVITY_CHECK_INTERVAL_MS, HANDSHAKE_VERSION, MAX_CONNECTION_DELAY_MS, MAX_FRAME_SIZE,
         MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS, NETWORK_CHANNEL_SIZE,
     },
     network_id::NetworkContext,
@@ -79,6 +79,7 @@ impl NetworkBuilder {
         time_service: TimeService,
         listen_address: NetworkAddress,
         authentication_mode: AuthenticationMode,
+        handshake_version: u8,
         max_frame_size: usize,
         max_message_size: usize,
         enable_proxy_protocol: bool,
@@ -95,6 +96,7 @@ impl NetworkBuilder {
             listen_address,
             peers_and_metadata.clone(),
             authentication_mode,
+            handshake_version,
             network_channel_size,
             max_frame_size,
             max_message_size,
@@ -135,6 +137,7 @@ impl NetworkBuilder {
             time_service,
             listen_address,
             authentication_mode,
+            HANDSHAKE_VERSION,
             MAX_FRAME_SIZE,
             MAX_MESSAGE_SIZE,
             false, /* Disable proxy protocol */
@@ -170,10 +173,12 @@ impl NetworkBuilder {
         let peer_id = config.peer_id();
         let identity_key = config.identity_key();
         let pq_identity_key = config.identity_post_quantum_key();
-        if HANDSHAKE_VERSION > 0 && pq_identity_key.is_none() {
+        let previous_pq_identity_key = config.identity_previous_post_quantum_key();
+        let handshake_version = config.handshake_version();
+        if handshake_version > 0 && pq_identity_key.is_none() {
             panic!(
                 "network {} is missing a post-quantum identity key required for handshake version {}",
-                config.network_id, HANDSHAKE_VERSION
+                config.network_id, handshake_version
             );
         }
 
@@ -181,11 +186,13 @@ impl NetworkBuilder {
             AuthenticationMode::Mutual {
                 network_private_key: identity_key,
                 post_quantum_private_key: pq_identity_key.clone(),
+                previous_post_quantum_private_key: previous_pq_identity_key,
             }
         } else {
             AuthenticationMode::MaybeMutual {
                 network_private_key: identity_key,
                 post_quantum_private_key: pq_identity_key.clone(),
+                previous_post_quantum_private_key: previous_pq_identity_key,
             }
         };
 
@@ -198,6 +205,7 @@ impl NetworkBuilder {
             time_service,
             config.listen_address.clone(),
             authentication_mode,
+            handshake_version,
             config.max_frame_size,
             config.max_message_size,
             config.enable_proxy_protocol,
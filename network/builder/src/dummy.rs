@@ -102,6 +102,7 @@ pub fn setup_network() -> DummyNetwork {
     let authentication_mode = AuthenticationMode::Mutual {
         network_private_key: listener_identity_private_key,
         post_quantum_private_key: Some(listener_pq_private_key),
+        previous_post_quantum_private_key: None,
     };
     let listener_peers_and_metadata = PeersAndMetadata::new(&[network_id]);
     let mut listener_connection_events = listener_peers_and_metadata.subscribe();
@@ -140,6 +141,7 @@ pub fn setup_network() -> DummyNetwork {
     let authentication_mode = AuthenticationMode::Mutual {
         network_private_key: dialer_identity_private_key,
         post_quantum_private_key: Some(dialer_pq_private_key),
+        previous_post_quantum_private_key: None,
     };
 
     let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
@@ -176,6 +176,17 @@ impl NoiseUpgrader {
         }
     }
 
+    /// Keep accepting inbound hybrid handshakes made with a rotated out Kyber key.
+    pub fn with_previous_post_quantum_key(
+        mut self,
+        previous_pq_key: Option<KyberPrivateKey>,
+    ) -> Self {
+        self.noise_config = self
+            .noise_config
+            .with_previous_post_quantum_key(previous_pq_key);
+        self
+    }
+
     /// Returns the configured Kyber public key, if hybrid mode is enabled.
     pub fn post_quantum_public_key(&self) -> Option<KyberPublicKey> {
         self.noise_config.post_quantum_public_key().cloned()
@@ -335,6 +346,17 @@ impl NoiseUpgrader {
         PeerRole::Unknown
     }
 
+    /// Returns true iff a client message that failed to parse as a classical handshake
+    /// carries the Kyber ciphertext length prefix of the hybrid handshake (right after
+    /// the encrypted static key) and we hold a Kyber key to decapsulate it with.
+    fn is_hybrid_client_message(&self, client_init_message: &[u8]) -> bool {
+        let offset = x25519::PUBLIC_KEY_SIZE + noise::encrypted_len(x25519::PUBLIC_KEY_SIZE);
+        let pq_len_prefix = (KYBER_CIPHERTEXT_LENGTH as u16).to_le_bytes();
+        self.noise_config.post_quantum_public_key().is_some()
+            && client_init_message.get(offset..offset + pq_len_prefix.len())
+                == Some(&pq_len_prefix[..])
+    }
+
     /// Perform an inbound protocol upgrade on this connection.
     ///
     /// This runs the "server" side of the Noise IK handshake to establish a
@@ -343,6 +365,10 @@ impl NoiseUpgrader {
     /// that successfully authenticate to a public key in our `trusted_peers` set.
     /// In addition, we will expect the client to include an anti replay attack
     /// counter in the Noise handshake payload in mutual auth scenarios.
+    ///
+    /// A `handshake_version` above zero requires the client to run the hybrid
+    /// Noise + Kyber768 handshake. Otherwise, both the classical and the hybrid
+    /// handshakes are accepted.
     pub async fn upgrade_inbound<TSocket>(
         &self,
         mut socket: TSocket,
@@ -403,14 +429,36 @@ impl NoiseUpgrader {
         }
 
         // parse it
-        let (remote_public_key, handshake_state, payload) = self
-            .noise_config
-            .parse_client_init_message_with_options(
-                &prologue,
-                &client_init_message,
-                handshake_version,
-            )
-            .map_err(|err| NoiseHandshakeError::ServerParseClient(remote_peer_short, err))?;
+        let parsed = self.noise_config.parse_client_init_message_with_options(
+            &prologue,
+            &client_init_message,
+            handshake_version,
+        );
+        let parsed = match parsed {
+            Err(_)
+                if handshake_version == 0
+                    && self.is_hybrid_client_message(&client_init_message) =>
+            {
+                // the client is running the hybrid handshake, so read the rest of its message
+                let classical_msg_len = client_init_message.len();
+                client_init_message.resize(
+                    noise::handshake_init_msg_len(payload_len, 2 + KYBER_CIPHERTEXT_LENGTH),
+                    0,
+                );
+                socket
+                    .read_exact(&mut client_init_message[classical_msg_len..])
+                    .await
+                    .map_err(NoiseHandshakeError::ServerReadFailed)?;
+                self.noise_config.parse_client_init_message_with_options(
+                    &prologue,
+                    &client_init_message,
+                    HANDSHAKE_VERSION,
+                )
+            },
+            parsed => parsed,
+        };
+        let (remote_public_key, handshake_state, payload) =
+            parsed.map_err(|err| NoiseHandshakeError::ServerParseClient(remote_peer_short, err))?;
 
         // if mutual auth mode, verify the remote pubkey is in our set of trusted peers
         let network_id = self.network_context.network_id();
@@ -566,6 +614,7 @@ mod test {
     use crate::{testutils, testutils::fake_socket::ReadWriteTestSocket};
     use accudo_config::config::{Peer, PeerRole, RoleType};
     use accudo_crypto::{
+        pq::KyberKeyPair,
         test_utils::TEST_SEED,
         traits::Uniform as _,
         x25519::{PrivateKey, PublicKey},
@@ -1150,6 +1199,53 @@ mod test {
         block_on(join(client_connection_task, server_connection_task));
     }
 
+    #[test]
+    fn test_handshake_accepts_previous_post_quantum_key() {
+        // Rebuild the testing peers with Kyber keys, where the server has rotated its key
+        let ((client, _), (server, server_public_key)) = build_peers(false, None);
+        let mut rng = StdRng::from_seed(TEST_SEED);
+        let (client_private_key, _) = create_key_pair(&mut rng);
+        let (server_private_key, _) = create_key_pair(&mut rng);
+        let previous_server_pq_key = KyberKeyPair::generate().unwrap();
+        let server_pq_key = KyberKeyPair::generate().unwrap();
+        let client = NoiseUpgrader::new(
+            client.network_context,
+            client_private_key,
+            Some(KyberKeyPair::generate().unwrap().private),
+            client.auth_mode,
+        );
+        let server = NoiseUpgrader::new(
+            server.network_context,
+            server_private_key,
+            Some(server_pq_key.private),
+            server.auth_mode,
+        )
+        .with_previous_post_quantum_key(Some(previous_server_pq_key.private));
+
+        // Clients may encapsulate to either the current or the previous key
+        let unknown_pq_key = KyberKeyPair::generate().unwrap().public;
+        for (server_pq_public_key, should_succeed) in [
+            (server_pq_key.public, true),
+            (previous_server_pq_key.public, true),
+            (unknown_pq_key, false),
+        ] {
+            let (dialer_socket, listener_socket) = MemorySocket::new_pair();
+            let (client_res, server_res) = block_on(join(
+                client.upgrade_outbound(
+                    dialer_socket,
+                    server.network_context.peer_id(),
+                    server_public_key,
+                    Some(server_pq_public_key),
+                    HANDSHAKE_VERSION,
+                    AntiReplayTimestamps::now,
+                ),
+                server.upgrade_inbound(listener_socket, HANDSHAKE_VERSION),
+            ));
+            assert_eq!(server_res.is_ok(), should_succeed);
+            assert_eq!(client_res.is_ok(), should_succeed);
+        }
+    }
+
     /// Inserts the given peers into the trusted peer set for the specified network
     fn insert_new_trusted_peers(
         peers_and_metadata: &Arc<PeersAndMetadata>,
@@ -18,7 +18,7 @@ use crate::{
     ProtocolId,
 };
 use accudo_channels::{self, accudo_channel, message_queues::QueueStyle};
-use accudo_config::{config::HANDSHAKE_VERSION, network_id::NetworkContext};
+use accudo_config::network_id::NetworkContext;
 use accudo_crypto::{pq::KyberPrivateKey, x25519};
 use accudo_logger::prelude::*;
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
@@ -43,12 +43,14 @@ pub enum AuthenticationMode {
     MaybeMutual {
         network_private_key: x25519::PrivateKey,
         post_quantum_private_key: Option<KyberPrivateKey>,
+        previous_post_quantum_private_key: Option<KyberPrivateKey>,
     },
     /// Both dialer and listener will verify public keys of each other in the
     /// handshake.
     Mutual {
         network_private_key: x25519::PrivateKey,
         post_quantum_private_key: Option<KyberPrivateKey>,
+        previous_post_quantum_private_key: Option<KyberPrivateKey>,
     },
 }
 
@@ -56,6 +58,7 @@ struct TransportContext {
     chain_id: ChainId,
     supported_protocols: ProtocolIdSet,
     authentication_mode: AuthenticationMode,
+    handshake_version: u8,
     peers_and_metadata: Arc<PeersAndMetadata>,
     enable_proxy_protocol: bool,
 }
@@ -173,6 +176,7 @@ impl PeerManagerBuilder {
         listen_address: NetworkAddress,
         peers_and_metadata: Arc<PeersAndMetadata>,
         authentication_mode: AuthenticationMode,
+        handshake_version: u8,
         channel_size: usize,
         max_frame_size: usize,
         max_message_size: usize,
@@ -197,6 +201,7 @@ impl PeerManagerBuilder {
                 chain_id,
                 supported_protocols: ProtocolIdSet::empty(),
                 authentication_mode,
+                handshake_version,
                 peers_and_metadata: peers_and_metadata.clone(),
                 enable_proxy_protocol,
             }),
@@ -256,24 +261,37 @@ impl PeerManagerBuilder {
         let protos = transport_context.supported_protocols;
         let chain_id = transport_context.chain_id;
         let enable_proxy_protocol = transport_context.enable_proxy_protocol;
-
-        let ((network_private_key, post_quantum_private_key), auth_mode) =
-            match transport_context.authentication_mode {
-                AuthenticationMode::MaybeMutual {
+        let handshake_version = transport_context.handshake_version;
+
+        let (
+            (network_private_key, post_quantum_private_key, previous_post_quantum_private_key),
+            auth_mode,
+        ) = match transport_context.authentication_mode {
+            AuthenticationMode::MaybeMutual {
+                network_private_key,
+                post_quantum_private_key,
+                previous_post_quantum_private_key,
+            } => (
+                (
                     network_private_key,
                     post_quantum_private_key,
-                } => (
-                    (network_private_key, post_quantum_private_key),
-                    HandshakeAuthMode::maybe_mutual(transport_context.peers_and_metadata),
+                    previous_post_quantum_private_key,
                 ),
-                AuthenticationMode::Mutual {
+                HandshakeAuthMode::maybe_mutual(transport_context.peers_and_metadata),
+            ),
+            AuthenticationMode::Mutual {
+                network_private_key,
+                post_quantum_private_key,
+                previous_post_quantum_private_key,
+            } => (
+                (
                     network_private_key,
                     post_quantum_private_key,
-                } => (
-                    (network_private_key, post_quantum_private_key),
-                    HandshakeAuthMode::mutual(transport_context.peers_and_metadata),
+                    previous_post_quantum_private_key,
                 ),
-            };
+                HandshakeAuthMode::mutual(transport_context.peers_and_metadata),
+            ),
+        };
 
         let mut accudo_tcp_transport = ACCUDO_TCP_TRANSPORT.clone();
         let tcp_cfg = self.get_tcp_buffers_cfg();
@@ -288,8 +306,9 @@ impl PeerManagerBuilder {
                         self.time_service.clone(),
                         network_private_key,
                         post_quantum_private_key,
+                        previous_post_quantum_private_key,
                         auth_mode,
-                        HANDSHAKE_VERSION,
+                        handshake_version,
                         chain_id,
                         protos,
                         enable_proxy_protocol,
@@ -305,8 +324,9 @@ impl PeerManagerBuilder {
                     self.time_service.clone(),
                     network_private_key,
                     post_quantum_private_key,
+                    previous_post_quantum_private_key,
                     auth_mode,
-                    HANDSHAKE_VERSION,
+                    handshake_version,
                     chain_id,
                     protos,
                     enable_proxy_protocol,
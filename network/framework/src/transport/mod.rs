@@ -15,7 +15,7 @@ use crate::{
     },
 };
 use accudo_config::{
-    config::{PeerRole, HANDSHAKE_VERSION},
+    config::{PeerRole, CLASSICAL_HANDSHAKE_VERSION, HANDSHAKE_VERSION},
     network_id::{NetworkContext, NetworkId},
 };
 use accudo_crypto::{
@@ -215,6 +215,8 @@ where
 /// Common context for performing both inbound and outbound connection upgrades.
 pub struct UpgradeContext {
     noise: NoiseUpgrader,
+    /// The minimum handshake version accepted from peers. Above zero, every peer
+    /// must run the hybrid Noise + Kyber768 handshake.
     handshake_version: u8,
     supported_protocols: BTreeMap<MessagingProtocolVersion, ProtocolIdSet>,
     chain_id: ChainId,
@@ -269,6 +271,15 @@ fn is_post_quantum_negotiation_error(err: &NoiseHandshakeError) -> bool {
     }
 }
 
+/// Returns the handshake version to advertise alongside a peer's (optional) Kyber key
+fn advertised_handshake_version(pq_pubkey: &Option<KyberPublicKey>) -> u8 {
+    if pq_pubkey.is_some() {
+        HANDSHAKE_VERSION
+    } else {
+        CLASSICAL_HANDSHAKE_VERSION
+    }
+}
+
 /// Upgrade an inbound connection. This means we run a Noise IK handshake for
 /// authentication and then negotiate common supported protocols. If
 /// `ctxt.noise.auth_mode` is `HandshakeAuthMode::Mutual( anti_replay_timestamps , trusted_peers )`,
@@ -330,36 +341,26 @@ async fn upgrade_inbound<T: TSocket>(
             add_pp_addr(proxy_protocol_enabled, err, &addr)
         })?;
     let remote_pubkey = socket.get_remote_static();
-    let resolved_pq_pubkey = if ctxt.handshake_version > 0 {
-        match ctxt
-            .noise
-            .remote_post_quantum_public_key(remote_peer_id)
-            .or_else(|| remote_pq_pubkey.clone())
-        {
-            Some(key) => Some(key),
-            None => {
-                counters::inc_post_quantum_handshake_failure(
-                    &ctxt.noise.network_context,
-                    origin,
-                    "missing_peer_key",
-                );
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!(
-                        "missing post-quantum Kyber key for peer {} on network {}",
-                        remote_peer_id,
-                        ctxt.noise.network_context.network_id()
-                    ),
-                ));
-            },
-        }
-    } else {
-        None
-    };
+    let resolved_pq_pubkey = ctxt.noise.remote_post_quantum_public_key(remote_peer_id);
+    if ctxt.handshake_version > 0 && resolved_pq_pubkey.is_none() {
+        counters::inc_post_quantum_handshake_failure(
+            &ctxt.noise.network_context,
+            origin,
+            "missing_peer_key",
+        );
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "missing post-quantum Kyber key for peer {} on network {}",
+                remote_peer_id,
+                ctxt.noise.network_context.network_id()
+            ),
+        ));
+    }
     let addr = addr.append_prod_protos_with_pq(
         remote_pubkey,
         resolved_pq_pubkey.clone(),
-        HANDSHAKE_VERSION,
+        advertised_handshake_version(&resolved_pq_pubkey),
     );
 
     // exchange HandshakeMsg
@@ -412,15 +413,16 @@ pub async fn upgrade_outbound<T: TSocket>(
     let origin = ConnectionOrigin::Outbound;
     let socket = fut_socket.await?;
 
-    // noise handshake
+    // noise handshake, hybrid whenever we know the remote's Kyber key
+    let handshake_version = advertised_handshake_version(&remote_pq_pubkey);
     let (mut socket, peer_role) = ctxt
         .noise
         .upgrade_outbound(
             socket,
             remote_peer_id,
             remote_pubkey,
-            resolved_pq_pubkey,
-            ctxt.handshake_version,
+            remote_pq_pubkey,
+            handshake_version,
             AntiReplayTimestamps::now,
         )
         .await
@@ -519,6 +521,7 @@ where
         time_service: TimeService,
         identity_key: x25519::PrivateKey,
         pq_identity_key: Option<KyberPrivateKey>,
+        previous_pq_identity_key: Option<KyberPrivateKey>,
         auth_mode: HandshakeAuthMode,
         handshake_version: u8,
         chain_id: ChainId,
@@ -539,7 +542,8 @@ where
         }
 
         let upgrade_context = UpgradeContext::new(
-            NoiseUpgrader::new(network_context, identity_key, pq_identity_key, auth_mode),
+            NoiseUpgrader::new(network_context, identity_key, pq_identity_key, auth_mode)
+                .with_previous_post_quantum_key(previous_pq_identity_key),
             handshake_version,
             supported_protocols,
             chain_id,
@@ -654,16 +658,21 @@ where
         let (base_addr, pubkey, pq_key, handshake_version) = Self::parse_dial_addr(&addr)?;
 
         // Check that the parsed handshake version from the dial addr is supported.
-        if self.ctxt.handshake_version != handshake_version {
+        if handshake_version > HANDSHAKE_VERSION {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 format!(
-                    "Attempting to dial remote with unsupported handshake version: {}, expected: {}",
-                    handshake_version, self.ctxt.handshake_version,
+                    "Attempting to dial remote with unsupported handshake version: {}, expected at most: {}",
+                    handshake_version, HANDSHAKE_VERSION,
                 ),
             ));
         }
-        if handshake_version > 0 && pq_key.is_none() {
+
+        // Peers that advertise a Kyber key are dialed with the hybrid handshake whenever we
+        // have a post-quantum identity key of our own. Everyone else is dialed with the
+        // classical handshake, unless this network requires hybrid handshakes.
+        let pq_key = pq_key.filter(|_| self.identity_pq_pubkey.is_some());
+        if self.ctxt.handshake_version > 0 && pq_key.is_none() {
             counters::inc_post_quantum_handshake_failure(
                 &self.ctxt.noise.network_context,
                 ConnectionOrigin::Outbound,
@@ -673,7 +682,7 @@ where
                 io::ErrorKind::InvalidData,
                 format!(
                     "Remote address '{}' is missing a noise-kyber segment required for handshake version {}",
-                    addr, handshake_version
+                    addr, self.ctxt.handshake_version
                 ),
             ));
         }
@@ -728,15 +737,10 @@ where
         // (e.g., `/memory/<port>` with no trailers), so we don't need to do any
         // parsing here.
         let (listener, listen_addr) = self.base_transport.listen_on(addr)?;
-        let advertised_pq_key = if self.ctxt.handshake_version > 0 {
-            self.identity_pq_pubkey.clone()
-        } else {
-            None
-        };
         let listen_addr = listen_addr.append_prod_protos_with_pq(
             self.identity_pubkey,
-            advertised_pq_key,
-            self.ctxt.handshake_version,
+            self.identity_pq_pubkey.clone(),
+            advertised_handshake_version(&self.identity_pq_pubkey),
         );
 
         // need to move a ctxt into stream task
@@ -8,7 +8,9 @@ use crate::{
     testutils,
     transport::*,
 };
-use accudo_config::config::{Peer, PeerRole, PeerSet, HANDSHAKE_VERSION};
+use accudo_config::config::{
+    Peer, PeerRole, PeerSet, CLASSICAL_HANDSHAKE_VERSION, HANDSHAKE_VERSION,
+};
 use accudo_crypto::{
     pq::{KyberKeyPair, KyberPublicKey},
     test_utils::TEST_SEED,
@@ -68,6 +70,28 @@ fn setup<TTransport>(
     Arc<PeersAndMetadata>,
     ProtocolIdSet,
 )
+where
+    TTransport: Transport<Error = io::Error> + Clone,
+    TTransport::Output: TSocket,
+    TTransport::Outbound: Send + 'static,
+    TTransport::Inbound: Send + 'static,
+    TTransport::Listener: Send + 'static,
+{
+    setup_with_handshake_version(base_transport, auth, HANDSHAKE_VERSION)
+}
+
+fn setup_with_handshake_version<TTransport>(
+    base_transport: TTransport,
+    auth: Auth,
+    handshake_version: u8,
+) -> (
+    Runtime,
+    MockTimeService,
+    (PeerId, AccudoNetTransport<TTransport>),
+    (PeerId, AccudoNetTransport<TTransport>),
+    Arc<PeersAndMetadata>,
+    ProtocolIdSet,
+)
 where
     TTransport: Transport<Error = io::Error> + Clone,
     TTransport::Output: TSocket,
@@ -197,8 +221,9 @@ where
         time_service.clone(),
         listener_key,
         Some(listener_pq_key.private.clone()),
+        None,
         listener_auth_mode,
-        HANDSHAKE_VERSION,
+        handshake_version,
         chain_id,
         supported_protocols.clone(),
         false, /* Disable proxy protocol */
@@ -210,8 +235,9 @@ where
         time_service.clone(),
         dialer_key,
         Some(dialer_pq_key.private.clone()),
+        None,
         dialer_auth_mode,
-        HANDSHAKE_VERSION,
+        handshake_version,
         chain_id,
         supported_protocols.clone(),
         false, /* Disable proxy protocol */
@@ -593,6 +619,97 @@ fn test_memory_transport_maybe_mutual() {
     );
 }
 
+/// Dials the listener of a network that doesn't require hybrid handshakes, either
+/// at its advertised (hybrid) address or at the classical address an older peer
+/// would have published.
+fn test_optional_post_quantum_dial(dial_with_kyber_key: bool) {
+    let (
+        rt,
+        _mock_time,
+        (listener_peer_id, listener_transport),
+        (dialer_peer_id, dialer_transport),
+        _,
+        _,
+    ) = setup_with_handshake_version(
+        memory::MemoryTransport,
+        Auth::Mutual,
+        CLASSICAL_HANDSHAKE_VERSION,
+    );
+
+    let _guard = rt.enter();
+    let (mut inbounds, listener_addr) = listener_transport
+        .listen_on("/memory/0".parse().unwrap())
+        .unwrap();
+
+    // The listener should still advertise its Kyber key
+    let (port, pubkey) = match listener_addr.as_slice() {
+        [Memory(port), NoiseIK(pubkey), NoiseKyber(_), Handshake(HANDSHAKE_VERSION)] => {
+            (*port, *pubkey)
+        },
+        _ => panic!("unexpected listener addr: '{}'", listener_addr),
+    };
+    let dial_addr = if dial_with_kyber_key {
+        listener_addr
+    } else {
+        NetworkAddress::try_from(vec![Memory(port)])
+            .unwrap()
+            .append_prod_protos_with_pq(pubkey, None, CLASSICAL_HANDSHAKE_VERSION)
+    };
+
+    let listener_task = async move {
+        let (inbound, _dialer_addr) = inbounds.next().await.unwrap().unwrap();
+        let conn = inbound.await.unwrap();
+        assert_eq!(conn.metadata.remote_peer_id, dialer_peer_id);
+    };
+    let dialer_task = async move {
+        let conn = dialer_transport
+            .dial(listener_peer_id, dial_addr)
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(conn.metadata.remote_peer_id, listener_peer_id);
+    };
+
+    rt.block_on(future::join(listener_task, dialer_task));
+}
+
+#[test]
+fn test_memory_transport_optional_pq_dials_hybrid_addr() {
+    test_optional_post_quantum_dial(true);
+}
+
+#[test]
+fn test_memory_transport_optional_pq_dials_classical_addr() {
+    test_optional_post_quantum_dial(false);
+}
+
+#[test]
+fn test_memory_transport_required_pq_rejects_classical_addr() {
+    let (rt, _mock_time, (listener_peer_id, listener_transport), (_, dialer_transport), _, _) =
+        setup(memory::MemoryTransport, Auth::Mutual);
+
+    let _guard = rt.enter();
+    let (_inbounds, listener_addr) = listener_transport
+        .listen_on("/memory/0".parse().unwrap())
+        .unwrap();
+
+    // Strip the Kyber key from the listener's address
+    let (port, pubkey) = match listener_addr.as_slice() {
+        [Memory(port), NoiseIK(pubkey), NoiseKyber(_), Handshake(HANDSHAKE_VERSION)] => {
+            (*port, *pubkey)
+        },
+        _ => panic!("unexpected listener addr: '{}'", listener_addr),
+    };
+    let classical_addr = NetworkAddress::try_from(vec![Memory(port)])
+        .unwrap()
+        .append_prod_protos_with_pq(pubkey, None, CLASSICAL_HANDSHAKE_VERSION);
+
+    // The dialer requires hybrid handshakes, so it should refuse to dial
+    assert!(dialer_transport
+        .dial(listener_peer_id, classical_addr)
+        .is_err());
+}
+
 /////////////////////////////////////
 // AccudoNetTransport<TcpTransport> //
 /////////////////////////////////////
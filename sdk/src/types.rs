@@ -32,7 +32,9 @@ use accudo_types::{
     },
     transaction::{
         authenticator::{
-            AnyPublicKey, EphemeralPublicKey, EphemeralSignature, PostQuantumPublicKey,
+            AccountAuthenticator, AnyPublicKey, AnySignature, EphemeralPublicKey,
+            EphemeralSignature, MultiKey, MultiKeyAuthenticator, PostQuantumPublicKey,
+            SingleKeyAuthenticator,
         },
         Auth,
     },
@@ -66,6 +68,8 @@ lazy_static! {
 #[derive(Debug)]
 enum LocalAccountAuthenticator {
     PrivateKey(AccountKey),
+    PostQuantum(PostQuantumAccountKey),
+    Hybrid(HybridAccountKey),
     Keyless(KeylessAccount),
     FederatedKeyless(FederatedKeylessAccount),
     Abstraction(AbstractedAccount), // TODO: Add support for keyless authentication
@@ -76,6 +80,8 @@ impl LocalAccountAuthenticator {
     pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
         match self {
             LocalAccountAuthenticator::PrivateKey(key) => key.sign_transaction(txn),
+            LocalAccountAuthenticator::PostQuantum(key) => key.sign_transaction(txn),
+            LocalAccountAuthenticator::Hybrid(key) => key.sign_transaction(txn),
             LocalAccountAuthenticator::Keyless(keyless_account) => {
                 let sig = self.build_keyless_signature(txn.clone(), &keyless_account);
                 SignedTransaction::new_keyless(txn, keyless_account.public_key.clone(), sig)
@@ -175,6 +181,30 @@ impl LocalAccount {
         }
     }
 
+    pub fn new_post_quantum(
+        address: AccountAddress,
+        key: PostQuantumAccountKey,
+        sequence_number: u64,
+    ) -> Self {
+        Self {
+            address,
+            auth: LocalAccountAuthenticator::PostQuantum(key),
+            sequence_number: AtomicU64::new(sequence_number),
+        }
+    }
+
+    pub fn new_hybrid(
+        address: AccountAddress,
+        key: HybridAccountKey,
+        sequence_number: u64,
+    ) -> Self {
+        Self {
+            address,
+            auth: LocalAccountAuthenticator::Hybrid(key),
+            sequence_number: AtomicU64::new(sequence_number),
+        }
+    }
+
     pub fn new_keyless(
         address: AccountAddress,
         keyless_account: KeylessAccount,
@@ -352,6 +382,26 @@ impl LocalAccount {
         Self::new(address, key, 0)
     }
 
+    /// Generate a new post-quantum account locally, with the address derived
+    /// from its Dilithium3 authentication key.
+    pub fn generate_post_quantum() -> Result<Self> {
+        let key = PostQuantumAccountKey::generate()?;
+        let address = key.authentication_key().account_address();
+
+        Ok(Self::new_post_quantum(address, key, 0))
+    }
+
+    /// Generate a new hybrid Ed25519 + Dilithium3 account locally.
+    pub fn generate_hybrid<R>(rng: &mut R) -> Result<Self>
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let key = HybridAccountKey::generate(rng)?;
+        let address = key.authentication_key().account_address();
+
+        Ok(Self::new_hybrid(address, key, 0))
+    }
+
     pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
         self.auth.sign_transaction(txn)
     }
@@ -483,9 +533,19 @@ impl LocalAccount {
         self.address
     }
 
+    /// Returns the Ed25519 signing key of the account.
+    ///
+    /// # Panics
+    ///
+    /// Panics for post-quantum and hybrid accounts, which do not sign with a
+    /// single Ed25519 key. Use [`LocalAccount::try_private_key`] if the account
+    /// type is not known.
     pub fn private_key(&self) -> &Ed25519PrivateKey {
         match &self.auth {
             LocalAccountAuthenticator::PrivateKey(key) => key.private_key(),
+            LocalAccountAuthenticator::PostQuantum(_) | LocalAccountAuthenticator::Hybrid(_) => {
+                panic!("Post-quantum and hybrid accounts have no single Ed25519 private key")
+            },
             LocalAccountAuthenticator::Keyless(_) => todo!(),
             LocalAccountAuthenticator::FederatedKeyless(_) => todo!(),
             LocalAccountAuthenticator::Abstraction(..) => todo!(),
@@ -493,9 +553,27 @@ impl LocalAccount {
         }
     }
 
+    /// Returns the Ed25519 signing key of the account, if it has one.
+    pub fn try_private_key(&self) -> Option<&Ed25519PrivateKey> {
+        match &self.auth {
+            LocalAccountAuthenticator::PrivateKey(key) => Some(key.private_key()),
+            _ => None,
+        }
+    }
+
+    /// Returns the Ed25519 public key of the account.
+    ///
+    /// # Panics
+    ///
+    /// Panics for post-quantum and hybrid accounts, which do not sign with a
+    /// single Ed25519 key. Use [`LocalAccount::try_public_key`] if the account
+    /// type is not known.
     pub fn public_key(&self) -> &Ed25519PublicKey {
         match &self.auth {
             LocalAccountAuthenticator::PrivateKey(key) => key.public_key(),
+            LocalAccountAuthenticator::PostQuantum(_) | LocalAccountAuthenticator::Hybrid(_) => {
+                panic!("Post-quantum and hybrid accounts have no single Ed25519 public key")
+            },
             LocalAccountAuthenticator::Keyless(_) => todo!(),
             LocalAccountAuthenticator::FederatedKeyless(_) => todo!(),
             LocalAccountAuthenticator::Abstraction(..) => todo!(),
@@ -503,9 +581,19 @@ impl LocalAccount {
         }
     }
 
+    /// Returns the Ed25519 public key of the account, if it has one.
+    pub fn try_public_key(&self) -> Option<&Ed25519PublicKey> {
+        match &self.auth {
+            LocalAccountAuthenticator::PrivateKey(key) => Some(key.public_key()),
+            _ => None,
+        }
+    }
+
     pub fn authentication_key(&self) -> AuthenticationKey {
         match &self.auth {
             LocalAccountAuthenticator::PrivateKey(key) => key.authentication_key(),
+            LocalAccountAuthenticator::PostQuantum(key) => key.authentication_key(),
+            LocalAccountAuthenticator::Hybrid(key) => key.authentication_key(),
             LocalAccountAuthenticator::Keyless(keyless_account) => {
                 keyless_account.authentication_key()
             },
@@ -520,6 +608,12 @@ impl LocalAccount {
     pub fn auth(&self) -> Auth<'_> {
         match &self.auth {
             LocalAccountAuthenticator::PrivateKey(key) => Auth::Ed25519(key.private_key()),
+            LocalAccountAuthenticator::PostQuantum(key) => Auth::PostQuantum(key.keypair()),
+            LocalAccountAuthenticator::Hybrid(key) => Auth::Hybrid {
+                private_key: key.private_key(),
+                post_quantum_keypair: key.post_quantum_keypair(),
+                multi_key: key.multi_key(),
+            },
             LocalAccountAuthenticator::Keyless(_) => todo!(),
             LocalAccountAuthenticator::FederatedKeyless(_) => todo!(),
             LocalAccountAuthenticator::Abstraction(aa) => {
@@ -561,9 +655,18 @@ impl LocalAccount {
             .store(sequence_number, Ordering::SeqCst);
     }
 
-    pub fn rotate_key<T: Into<AccountKey>>(&mut self, new_key: T) -> AccountKey {
+    /// Switches the local signer to an Ed25519 key, returning the previous
+    /// Ed25519 key. Post-quantum and hybrid accounts are switched over as
+    /// well, in which case `None` is returned.
+    pub fn rotate_key<T: Into<AccountKey>>(&mut self, new_key: T) -> Option<AccountKey> {
         match &mut self.auth {
-            LocalAccountAuthenticator::PrivateKey(key) => std::mem::replace(key, new_key.into()),
+            LocalAccountAuthenticator::PrivateKey(key) => {
+                Some(std::mem::replace(key, new_key.into()))
+            },
+            LocalAccountAuthenticator::PostQuantum(_) | LocalAccountAuthenticator::Hybrid(_) => {
+                self.auth = LocalAccountAuthenticator::PrivateKey(new_key.into());
+                None
+            },
             LocalAccountAuthenticator::Keyless(_) => todo!(),
             LocalAccountAuthenticator::FederatedKeyless(_) => todo!(),
             LocalAccountAuthenticator::Abstraction(..) => todo!(),
@@ -571,6 +674,17 @@ impl LocalAccount {
         }
     }
 
+    /// Switches the local signer to a post-quantum key, e.g. after the account's
+    /// authentication key was rotated on chain. The address is kept as is.
+    pub fn rotate_to_post_quantum(&mut self, new_key: PostQuantumAccountKey) {
+        self.auth = LocalAccountAuthenticator::PostQuantum(new_key);
+    }
+
+    /// Switches the local signer to a hybrid Ed25519 + Dilithium3 multi-key.
+    pub fn rotate_to_hybrid(&mut self, new_key: HybridAccountKey) {
+        self.auth = LocalAccountAuthenticator::Hybrid(new_key);
+    }
+
     pub fn received_event_key(&self) -> EventKey {
         EventKey::new(2, self.address)
     }
@@ -802,6 +916,181 @@ impl From<Ed25519PrivateKey> for AccountKey {
     }
 }
 
+/// Dilithium3 key backing a post-quantum single-key account.
+///
+/// The authentication key is derived from the Dilithium3 public key alone, so
+/// transactions carry a single post-quantum signature.
+pub struct PostQuantumAccountKey {
+    keypair: Dilithium3KeyPair,
+    authentication_key: AuthenticationKey,
+}
+
+impl fmt::Debug for PostQuantumAccountKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PostQuantumAccountKey")
+            .field("authentication_key", &self.authentication_key)
+            .finish()
+    }
+}
+
+impl PostQuantumAccountKey {
+    pub fn generate() -> Result<Self> {
+        Ok(Self::from_keypair(Dilithium3KeyPair::generate()?))
+    }
+
+    pub fn from_keypair(keypair: Dilithium3KeyPair) -> Self {
+        let authentication_key = AuthenticationKey::any_key(AnyPublicKey::post_quantum(
+            SchemeId::Dilithium3,
+            keypair.public_key().to_vec(),
+        ));
+        Self {
+            keypair,
+            authentication_key,
+        }
+    }
+
+    pub fn keypair(&self) -> &Dilithium3KeyPair {
+        &self.keypair
+    }
+
+    pub fn public_key(&self) -> AnyPublicKey {
+        AnyPublicKey::post_quantum(SchemeId::Dilithium3, self.keypair.public_key().to_vec())
+    }
+
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        self.authentication_key
+    }
+
+    pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
+        let signing_bytes =
+            signing_message(&txn).expect("Unable to compute signing message for RawTransaction");
+        let signature = self
+            .keypair
+            .sign(&signing_bytes)
+            .expect("Dilithium signing should not fail");
+        let authenticator = AccountAuthenticator::single_key(SingleKeyAuthenticator::new(
+            self.public_key(),
+            AnySignature::post_quantum(signature),
+        ));
+        SignedTransaction::new_single_sender(txn, authenticator)
+    }
+}
+
+impl From<Dilithium3KeyPair> for PostQuantumAccountKey {
+    fn from(keypair: Dilithium3KeyPair) -> Self {
+        Self::from_keypair(keypair)
+    }
+}
+
+/// Ed25519 and Dilithium3 keys backing a hybrid 2-of-2 multi-key account.
+///
+/// Both keys must sign every transaction, so the account stays secure as long
+/// as either scheme holds. The Dilithium3 key also serves as the post-quantum
+/// companion of the Ed25519 member, which the multi-key verifier requires.
+pub struct HybridAccountKey {
+    private_key: Ed25519PrivateKey,
+    public_key: Ed25519PublicKey,
+    post_quantum_keypair: Dilithium3KeyPair,
+    multi_key: MultiKey,
+    authentication_key: AuthenticationKey,
+}
+
+impl fmt::Debug for HybridAccountKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HybridAccountKey")
+            .field("public_key", &self.public_key)
+            .field("authentication_key", &self.authentication_key)
+            .finish()
+    }
+}
+
+impl HybridAccountKey {
+    pub fn generate<R>(rng: &mut R) -> Result<Self>
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        Self::from_keys(
+            Ed25519PrivateKey::generate(rng),
+            Dilithium3KeyPair::generate()?,
+        )
+    }
+
+    pub fn from_keys(
+        private_key: Ed25519PrivateKey,
+        post_quantum_keypair: Dilithium3KeyPair,
+    ) -> Result<Self> {
+        let public_key = Ed25519PublicKey::from(&private_key);
+        let pq_public_key = post_quantum_keypair.public_key().to_vec();
+        let multi_key = MultiKey::new_with_post_quantum(
+            vec![
+                AnyPublicKey::ed25519(public_key.clone()),
+                AnyPublicKey::post_quantum(SchemeId::Dilithium3, pq_public_key.clone()),
+            ],
+            2,
+            vec![
+                Some(PostQuantumPublicKey::new(
+                    SchemeId::Dilithium3,
+                    pq_public_key,
+                )),
+                None,
+            ],
+        )?;
+        let authentication_key = AuthenticationKey::multi_key(multi_key.clone());
+
+        Ok(Self {
+            private_key,
+            public_key,
+            post_quantum_keypair,
+            multi_key,
+            authentication_key,
+        })
+    }
+
+    pub fn private_key(&self) -> &Ed25519PrivateKey {
+        &self.private_key
+    }
+
+    pub fn public_key(&self) -> &Ed25519PublicKey {
+        &self.public_key
+    }
+
+    pub fn post_quantum_keypair(&self) -> &Dilithium3KeyPair {
+        &self.post_quantum_keypair
+    }
+
+    pub fn multi_key(&self) -> &MultiKey {
+        &self.multi_key
+    }
+
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        self.authentication_key
+    }
+
+    pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
+        let signature = self
+            .private_key
+            .sign(&txn)
+            .expect("Signing a txn can't fail");
+        let signing_bytes =
+            signing_message(&txn).expect("Unable to compute signing message for RawTransaction");
+        let pq_signature = self
+            .post_quantum_keypair
+            .sign(&signing_bytes)
+            .expect("Dilithium signing should not fail");
+        let authenticator =
+            MultiKeyAuthenticator::new_with_post_quantum(self.multi_key.clone(), vec![
+                (
+                    0,
+                    AnySignature::ed25519(signature),
+                    Some(pq_signature.clone()),
+                ),
+                (1, AnySignature::post_quantum(pq_signature), None),
+            ])
+            .expect("Hybrid signatures always match the multi-key layout");
+        SignedTransaction::new_single_sender(txn, AccountAuthenticator::multi_key(authenticator))
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Deserialize)]
 pub enum EphemeralPrivateKey {
     Ed25519 {
@@ -1317,6 +1606,64 @@ mod tests {
         assert!(LocalAccount::from_private_key("invalid_private_key", 0).is_err());
     }
 
+    #[test]
+    fn test_post_quantum_and_hybrid_accounts_sign_transactions() {
+        let factory =
+            crate::transaction_builder::TransactionFactory::new(chain_id::ChainId::test());
+        let receiver = AccountAddress::random();
+
+        let pq_account = LocalAccount::generate_post_quantum().unwrap();
+        let txn = pq_account.sign_with_transaction_builder(factory.transfer(receiver, 1));
+        txn.verify_signature().unwrap();
+        assert_eq!(pq_account.sequence_number(), 1);
+
+        let mut hybrid_account = LocalAccount::generate_hybrid(&mut rand::rngs::OsRng).unwrap();
+        let txn = hybrid_account.sign_with_transaction_builder(factory.transfer(receiver, 1));
+        txn.verify_signature().unwrap();
+
+        // Rotating keeps the address but switches the signer and auth key.
+        let address = hybrid_account.address();
+        let new_key = PostQuantumAccountKey::generate().unwrap();
+        let new_auth_key = new_key.authentication_key();
+        hybrid_account.rotate_to_post_quantum(new_key);
+        assert_eq!(hybrid_account.address(), address);
+        assert_eq!(hybrid_account.authentication_key(), new_auth_key);
+        let txn = hybrid_account.sign_with_transaction_builder(factory.transfer(receiver, 1));
+        txn.verify_signature().unwrap();
+
+        // Rotating back to Ed25519 drops the post-quantum signer.
+        let new_key = AccountKey::generate(&mut rand::rngs::OsRng);
+        let new_auth_key = new_key.authentication_key();
+        assert!(hybrid_account.rotate_key(new_key).is_none());
+        assert_eq!(hybrid_account.authentication_key(), new_auth_key);
+        assert!(hybrid_account.try_private_key().is_some());
+        assert!(pq_account.try_private_key().is_none());
+        assert!(pq_account.try_public_key().is_none());
+    }
+
+    #[test]
+    fn test_post_quantum_and_hybrid_accounts_sign_with_auth() {
+        let factory =
+            crate::transaction_builder::TransactionFactory::new(chain_id::ChainId::test());
+        let receiver = AccountAddress::random();
+        let pq_account = LocalAccount::generate_post_quantum().unwrap();
+        let hybrid_account = LocalAccount::generate_hybrid(&mut rand::rngs::OsRng).unwrap();
+
+        let txn = pq_account.sign_aa_transaction_with_transaction_builder(
+            vec![&hybrid_account],
+            None,
+            factory.transfer(receiver, 1),
+        );
+        txn.verify_signature().unwrap();
+
+        let txn = hybrid_account.sign_aa_transaction_with_transaction_builder(
+            vec![&pq_account],
+            None,
+            factory.transfer(receiver, 1),
+        );
+        txn.verify_signature().unwrap();
+    }
+
     #[ignore]
     #[tokio::test]
     async fn test_derive_keyless_account() {
@@ -0,0 +1,46 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A reference implementation of the remote signer protocol described in
+//! `accudo_secure_storage::RemoteSigner`. It holds the consensus key of an identity file in memory
+//! and is intended for tests and local networks. Production deployments should instead put a
+//! signer backed by an HSM or KMS behind the same socket.
+
+use accudo_config::config::IdentityBlob;
+use accudo_logger::info;
+use accudo_secure_storage::LocalSigner;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(about)]
+struct Args {
+    /// Path of the Unix domain socket to listen on
+    #[clap(long)]
+    socket_path: PathBuf,
+    /// Identity file holding the consensus private key to sign with
+    #[clap(long)]
+    identity_file: PathBuf,
+}
+
+fn main() -> Result<()> {
+    accudo_logger::Logger::new().init();
+    let args = Args::parse();
+
+    let consensus_private_key = IdentityBlob::from_file(&args.identity_file)?
+        .consensus_private_key
+        .ok_or_else(|| anyhow!("Identity file is missing a consensus private key"))?;
+
+    let listener = LocalSigner::bind(&args.socket_path)?;
+    info!("Serving consensus key at {}", args.socket_path.display());
+
+    LocalSigner::new(vec![consensus_private_key]).serve(listener)?;
+    Ok(())
+}
+
+#[test]
+fn verify_tool() {
+    use clap::CommandFactory;
+    Args::command().debug_assert()
+}
@@ -0,0 +1,315 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A secure storage backend whose consensus key is held by an external signer (e.g., an
+//! HSM-backed service), so that the key never has to be exported into this process.
+//!
+//! The signer is reached over a Unix domain socket. Each connection carries exactly one
+//! `SignerRequest` followed by one `SignerResponse`. Both are BCS-encoded and prefixed with
+//! their length as a big-endian `u32`, and frames may not exceed `MAX_FRAME_SIZE` bytes:
+//!
+//! - `PublicKeys`: returns the public keys of all consensus keys held by the signer.
+//! - `Sign { public_key, signing_message }`: signs `signing_message` with the consensus key
+//!   matching `public_key`. The message is the output of `accudo_crypto::signing_message`,
+//!   i.e., the `CryptoHasher` seed followed by the BCS-encoded consensus message.
+//!
+//! Any failure is reported back as `SignerResponse::Error`. Signatures returned by the signer
+//! are verified before use.
+
+use crate::{CryptoStorage, Error, GetResponse, KVStorage, PublicKeyResponse, Storage};
+use accudo_crypto::{
+    bls12381,
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    hash::CryptoHash,
+    PrivateKey, Signature,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+#[cfg(unix)]
+use std::os::unix::{
+    fs::PermissionsExt,
+    net::{UnixListener, UnixStream},
+};
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The maximum size of a single request or response frame
+pub const MAX_FRAME_SIZE: usize = 1024 * 1024; /* 1 MiB */
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum SignerRequest {
+    /// Returns the public keys of all consensus keys held by the signer
+    PublicKeys,
+    /// Signs the message with the consensus key matching the public key
+    Sign {
+        public_key: bls12381::PublicKey,
+        signing_message: Vec<u8>,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum SignerResponse {
+    PublicKeys(Vec<bls12381::PublicKey>),
+    Signature(bls12381::Signature),
+    Error(String),
+}
+
+/// Writes a length-prefixed, BCS-encoded frame
+pub fn write_frame<W: Write, T: Serialize>(writer: &mut W, value: &T) -> Result<(), Error> {
+    let bytes = bcs::to_bytes(value)?;
+    if bytes.len() > MAX_FRAME_SIZE {
+        return Err(Error::SerializationError(format!(
+            "Frame of {} bytes exceeds the maximum of {} bytes",
+            bytes.len(),
+            MAX_FRAME_SIZE
+        )));
+    }
+    writer.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a length-prefixed, BCS-encoded frame
+pub fn read_frame<R: Read, T: DeserializeOwned>(reader: &mut R) -> Result<T, Error> {
+    let mut length = [0u8; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_FRAME_SIZE {
+        return Err(Error::SerializationError(format!(
+            "Frame of {} bytes exceeds the maximum of {} bytes",
+            length, MAX_FRAME_SIZE
+        )));
+    }
+    let mut bytes = vec![0; length];
+    reader.read_exact(&mut bytes)?;
+    Ok(bcs::from_bytes(&bytes)?)
+}
+
+/// A client for an external signer listening on a Unix domain socket
+#[derive(Clone, Debug)]
+pub struct RemoteSigner {
+    socket_path: PathBuf,
+    timeout: Duration,
+}
+
+impl RemoteSigner {
+    pub fn new(socket_path: PathBuf, timeout_ms: u64) -> Self {
+        Self {
+            socket_path,
+            timeout: Duration::from_millis(timeout_ms),
+        }
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Returns the public keys of all consensus keys held by the signer
+    pub fn public_keys(&self) -> Result<Vec<bls12381::PublicKey>, Error> {
+        match self.request(&SignerRequest::PublicKeys)? {
+            SignerResponse::PublicKeys(public_keys) => Ok(public_keys),
+            response => Err(unexpected_response(response)),
+        }
+    }
+
+    /// Signs the message with the consensus key matching `public_key`. The signature is verified
+    /// before it is returned, so a faulty signer cannot hand out invalid signatures.
+    pub fn sign(
+        &self,
+        public_key: &bls12381::PublicKey,
+        signing_message: &[u8],
+    ) -> Result<bls12381::Signature, Error> {
+        let request = SignerRequest::Sign {
+            public_key: public_key.clone(),
+            signing_message: signing_message.to_vec(),
+        };
+        match self.request(&request)? {
+            SignerResponse::Signature(signature) => {
+                signature
+                    .verify_arbitrary_msg(signing_message, public_key)
+                    .map_err(|error| {
+                        Error::InternalError(format!(
+                            "Remote signer returned an invalid signature: {}",
+                            error
+                        ))
+                    })?;
+                Ok(signature)
+            },
+            response => Err(unexpected_response(response)),
+        }
+    }
+
+    #[cfg(unix)]
+    fn request(&self, request: &SignerRequest) -> Result<SignerResponse, Error> {
+        let mut stream = UnixStream::connect(&self.socket_path)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        write_frame(&mut stream, request)?;
+        read_frame(&mut stream)
+    }
+
+    #[cfg(not(unix))]
+    fn request(&self, _request: &SignerRequest) -> Result<SignerResponse, Error> {
+        Err(Error::InternalError(
+            "Remote signers are only supported on Unix platforms".into(),
+        ))
+    }
+}
+
+fn unexpected_response(response: SignerResponse) -> Error {
+    match response {
+        SignerResponse::Error(error) => {
+            Error::InternalError(format!("Remote signer error: {}", error))
+        },
+        response => {
+            Error::SerializationError(format!("Unexpected remote signer response: {:?}", response))
+        },
+    }
+}
+
+/// A reference signer that holds consensus keys in memory and serves them over a Unix domain
+/// socket. It is intended for tests and as a template for signers backed by an HSM or KMS.
+pub struct LocalSigner {
+    keys: Vec<bls12381::PrivateKey>,
+}
+
+impl LocalSigner {
+    pub fn new(keys: Vec<bls12381::PrivateKey>) -> Self {
+        Self { keys }
+    }
+
+    pub fn handle(&self, request: SignerRequest) -> SignerResponse {
+        match request {
+            SignerRequest::PublicKeys => {
+                SignerResponse::PublicKeys(self.keys.iter().map(|key| key.public_key()).collect())
+            },
+            SignerRequest::Sign {
+                public_key,
+                signing_message,
+            } => match self.keys.iter().find(|key| key.public_key() == public_key) {
+                Some(key) => SignerResponse::Signature(key.sign_signing_message(&signing_message)),
+                None => SignerResponse::Error(format!("Unknown public key: {:?}", public_key)),
+            },
+        }
+    }
+
+    /// Binds a socket at the given path, replacing a stale one left behind by a previous run. The
+    /// socket is only accessible to the current user, as whoever can connect to it can sign
+    /// consensus messages.
+    #[cfg(unix)]
+    pub fn bind(socket_path: &Path) -> Result<UnixListener, Error> {
+        if socket_path.exists() {
+            std::fs::remove_file(socket_path)?;
+        }
+        let listener = UnixListener::bind(socket_path)?;
+        std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+        Ok(listener)
+    }
+
+    /// Serves requests from the listener until it fails
+    #[cfg(unix)]
+    pub fn serve(&self, listener: UnixListener) -> Result<(), Error> {
+        for stream in listener.incoming() {
+            let mut stream = stream?;
+            let response = match read_frame(&mut stream) {
+                Ok(request) => self.handle(request),
+                Err(error) => SignerResponse::Error(error.to_string()),
+            };
+            if let Err(error) = write_frame(&mut stream, &response) {
+                accudo_logger::warn!("Failed to write remote signer response: {}", error);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A secure storage whose consensus key is held by a `RemoteSigner`. Everything else (e.g., safety
+/// data, the waypoint and Ed25519 keys) is kept in the wrapped storage.
+pub struct RemoteSignerStorage {
+    storage: Box<Storage>,
+    signer: RemoteSigner,
+}
+
+impl RemoteSignerStorage {
+    pub fn new(storage: Box<Storage>, signer: RemoteSigner) -> Self {
+        Self { storage, signer }
+    }
+
+    pub fn signer(&self) -> &RemoteSigner {
+        &self.signer
+    }
+}
+
+impl KVStorage for RemoteSignerStorage {
+    fn available(&self) -> Result<(), Error> {
+        self.storage.available()?;
+        self.signer.public_keys().map(|_| ())
+    }
+
+    fn get<T: DeserializeOwned>(&self, key: &str) -> Result<GetResponse<T>, Error> {
+        self.storage.get(key)
+    }
+
+    fn set<T: Serialize>(&mut self, key: &str, value: T) -> Result<(), Error> {
+        self.storage.set(key, value)
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    fn reset_and_clear(&mut self) -> Result<(), Error> {
+        self.storage.reset_and_clear()
+    }
+}
+
+impl CryptoStorage for RemoteSignerStorage {
+    fn create_key(&mut self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        self.storage.create_key(name)
+    }
+
+    fn export_private_key(&self, name: &str) -> Result<Ed25519PrivateKey, Error> {
+        self.storage.export_private_key(name)
+    }
+
+    fn import_private_key(&mut self, name: &str, key: Ed25519PrivateKey) -> Result<(), Error> {
+        self.storage.import_private_key(name, key)
+    }
+
+    fn export_private_key_for_version(
+        &self,
+        name: &str,
+        version: Ed25519PublicKey,
+    ) -> Result<Ed25519PrivateKey, Error> {
+        self.storage.export_private_key_for_version(name, version)
+    }
+
+    fn get_public_key(&self, name: &str) -> Result<PublicKeyResponse, Error> {
+        self.storage.get_public_key(name)
+    }
+
+    fn get_public_key_previous_version(&self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        self.storage.get_public_key_previous_version(name)
+    }
+
+    fn rotate_key(&mut self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        self.storage.rotate_key(name)
+    }
+
+    fn sign<T: CryptoHash + Serialize>(
+        &self,
+        name: &str,
+        message: &T,
+    ) -> Result<Ed25519Signature, Error> {
+        self.storage.sign(name, message)
+    }
+
+    fn sign_using_version<T: CryptoHash + Serialize>(
+        &self,
+        name: &str,
+        version: Ed25519PublicKey,
+        message: &T,
+    ) -> Result<Ed25519Signature, Error> {
+        self.storage.sign_using_version(name, version, message)
+    }
+}
@@ -7,7 +7,7 @@
 //! operations (e.g., generate_key, sign and rotate_key).
 use crate::{
     CryptoStorage, Error, GetResponse, InMemoryStorage, KVStorage, Namespaced, OnDiskStorage,
-    PublicKeyResponse, VaultStorage,
+    PublicKeyResponse, RemoteSigner, RemoteSignerStorage, VaultStorage,
 };
 use accudo_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
 use enum_dispatch::enum_dispatch;
@@ -21,6 +21,17 @@ pub enum Storage {
     InMemoryStorage(InMemoryStorage),
     NamespacedStorage(Namespaced<Box<Storage>>),
     OnDiskStorage(OnDiskStorage),
+    RemoteSignerStorage(RemoteSignerStorage),
+}
+
+impl Storage {
+    /// Returns the remote signer holding the consensus key, if the key is not held locally
+    pub fn remote_signer(&self) -> Option<&RemoteSigner> {
+        match self {
+            Storage::RemoteSignerStorage(storage) => Some(storage.signer()),
+            _ => None,
+        }
+    }
 }
 
 impl KVStorage for Box<Storage> {
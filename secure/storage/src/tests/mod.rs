@@ -4,5 +4,7 @@
 
 mod in_memory;
 mod on_disk;
+#[cfg(unix)]
+mod remote_signer;
 mod suite;
 mod vault;
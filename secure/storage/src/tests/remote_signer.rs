@@ -0,0 +1,70 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    tests::suite, InMemoryStorage, LocalSigner, RemoteSigner, RemoteSignerStorage, Storage,
+};
+use accudo_crypto::{bls12381, test_utils::TEST_SEED, PrivateKey, Signature, Uniform};
+use accudo_temppath::TempPath;
+use rand::{rngs::StdRng, SeedableRng};
+use std::{os::unix::fs::PermissionsExt, thread};
+
+/// Starts a local signer for the given keys and returns a client connected to it. The returned
+/// path must be kept alive for as long as the signer is used.
+fn spawn_signer(keys: Vec<bls12381::PrivateKey>) -> (TempPath, RemoteSigner) {
+    let path = TempPath::new();
+    path.create_as_dir().unwrap();
+    let socket_path = path.path().join("signer.sock");
+    let listener = LocalSigner::bind(&socket_path).unwrap();
+    thread::spawn(move || LocalSigner::new(keys).serve(listener));
+    (path, RemoteSigner::new(socket_path, 5_000))
+}
+
+#[test]
+fn remote_signer_storage() {
+    let (_path, signer) = spawn_signer(vec![]);
+    let mut storage = Storage::from(RemoteSignerStorage::new(
+        Box::new(Storage::from(InMemoryStorage::new())),
+        signer,
+    ));
+    suite::execute_all_storage_tests(&mut storage);
+}
+
+#[test]
+fn remote_signer_signs_with_held_keys() {
+    let mut rng = StdRng::from_seed(TEST_SEED);
+    let private_key = bls12381::PrivateKey::generate(&mut rng);
+    let public_key = private_key.public_key();
+    let unknown_public_key = bls12381::PrivateKey::generate(&mut rng).public_key();
+    let (_path, signer) = spawn_signer(vec![private_key]);
+
+    assert_eq!(signer.public_keys().unwrap(), vec![public_key.clone()]);
+
+    let message = b"consensus message";
+    let signature = signer.sign(&public_key, message).unwrap();
+    signature
+        .verify_arbitrary_msg(message, &public_key)
+        .unwrap();
+    signer.sign(&unknown_public_key, message).unwrap_err();
+}
+
+#[test]
+fn local_signer_socket_permissions() {
+    let (path, _signer) = spawn_signer(vec![]);
+    let socket_path = path.path().join("signer.sock");
+    let mode = std::fs::metadata(&socket_path)
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // A stale socket is replaced
+    LocalSigner::bind(&socket_path).unwrap();
+}
+
+#[test]
+fn remote_signer_unavailable() {
+    let path = TempPath::new();
+    let signer = RemoteSigner::new(path.path().join("missing.sock"), 1_000);
+    signer.public_keys().unwrap_err();
+}
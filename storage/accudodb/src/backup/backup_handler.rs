@@ -162,6 +162,31 @@ impl BackupHandler {
         Ok(Box::new(iterator))
     }
 
+    /// Iterates through the state changes between two state snapshots, ordered by the hashed
+    /// keys. A deleted key comes with `None`. Both snapshots must not have been pruned.
+    pub fn get_state_changes_iter(
+        &self,
+        base_version: Version,
+        version: Version,
+    ) -> Result<impl Iterator<Item = Result<(StateKey, Option<StateValue>)>> + Send + use<>> {
+        ensure!(
+            base_version <= version,
+            "Bad state snapshot versions: base {} > {}",
+            base_version,
+            version
+        );
+        let iterator = self
+            .state_store
+            .get_state_changes_iter(base_version, version)?
+            .enumerate()
+            .map(move |(idx, res)| {
+                BACKUP_STATE_SNAPSHOT_VERSION.set(version as i64);
+                BACKUP_STATE_SNAPSHOT_LEAF_IDX.set(idx as i64);
+                res
+            });
+        Ok(Box::new(iterator))
+    }
+
     /// Gets the proof that proves a range of accounts.
     pub fn get_account_state_range_proof(
         &self,
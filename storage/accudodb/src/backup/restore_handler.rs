@@ -137,6 +137,17 @@ impl RestoreHandler {
         self.accudodb.get_state_snapshot_before(version)
     }
 
+    /// Sets the pruner progress as if the DB had been pruned, so that history missing from a
+    /// restored DB is reported as pruned instead of not found, and the pruners start from there.
+    pub fn save_min_readable_versions(
+        &self,
+        ledger_version: Version,
+        state_merkle_version: Version,
+    ) -> Result<()> {
+        self.accudodb
+            .save_min_readable_versions(ledger_version, state_merkle_version)
+    }
+
     pub fn get_in_progress_state_kv_snapshot_version(&self) -> Result<Option<Version>> {
         let db = self.accudodb.state_kv_db.metadata_db_arc();
         let mut iter = db.iter::<DbMetadataSchema>()?;
@@ -4,9 +4,12 @@
 use crate::{
     db::AccudoDB,
     event_store::EventStore,
-    ledger_db::LedgerDb,
+    ledger_db::{LedgerDb, LEDGER_COLD_DB_FOLDER_NAME},
     metrics::{API_LATENCY_SECONDS, CONCURRENCY_GAUGE},
-    pruner::{LedgerPrunerManager, PrunerManager, StateKvPrunerManager, StateMerklePrunerManager},
+    pruner::{
+        LedgerColdStorageManager, LedgerPrunerManager, PrunerManager, StateKvPrunerManager,
+        StateMerklePrunerManager,
+    },
     rocksdb_property_reporter::RocksdbPropertyReporter,
     state_kv_db::StateKvDb,
     state_merkle_db::StateMerkleDb,
@@ -82,14 +85,24 @@ impl AccudoDB {
             pruner_config.ledger_pruner_config,
             internal_indexer_db,
         );
+        let ledger_cold_storage = LedgerColdStorageManager::new(
+            Arc::clone(&ledger_db),
+            pruner_config.ledger_cold_storage_config,
+        );
+
+        let mut event_store = EventStore::new(ledger_db.event_db().db_arc());
+        if let Some(ledger_cold_db) = ledger_db.ledger_cold_db() {
+            event_store.set_cold_db(Arc::clone(ledger_cold_db));
+        }
 
         AccudoDB {
             ledger_db: Arc::clone(&ledger_db),
             state_kv_db: Arc::clone(&state_kv_db),
-            event_store: Arc::new(EventStore::new(ledger_db.event_db().db_arc())),
+            event_store: Arc::new(event_store),
             state_store,
             transaction_store: Arc::new(TransactionStore::new(Arc::clone(&ledger_db))),
             ledger_pruner,
+            ledger_cold_storage,
             _rocksdb_property_reporter: RocksdbPropertyReporter::new(
                 ledger_db,
                 state_merkle_db,
@@ -128,7 +141,7 @@ impl AccudoDB {
             /* estimated_entry_charge = */ 0,
         );
 
-        let (ledger_db, state_merkle_db, state_kv_db) = Self::open_dbs(
+        let (mut ledger_db, state_merkle_db, state_kv_db) = Self::open_dbs(
             db_paths,
             rocksdb_configs,
             Some(&env),
@@ -136,6 +149,22 @@ impl AccudoDB {
             readonly,
             max_num_nodes_per_lru_cache_shard,
         )?;
+        // Once created, the cold DB has to be opened even if moving data to it is turned off,
+        // since part of the ledger history might be there.
+        let ledger_cold_db_root_path = db_paths.ledger_cold_db_root_path();
+        if pruner_config.ledger_cold_storage_config.enable
+            || ledger_cold_db_root_path
+                .join(LEDGER_COLD_DB_FOLDER_NAME)
+                .exists()
+        {
+            ledger_db = ledger_db.with_cold_db(
+                ledger_cold_db_root_path,
+                &rocksdb_configs.ledger_db_config,
+                Some(&env),
+                Some(&block_cache),
+                readonly,
+            )?;
+        }
 
         let mut myself = Self::new_with_dbs(
             ledger_db,
@@ -154,6 +183,9 @@ impl AccudoDB {
                 myself
                     .ledger_pruner
                     .maybe_set_pruner_target_db_version(version);
+                myself
+                    .ledger_cold_storage
+                    .maybe_set_target_db_version(version);
                 myself
                     .state_store
                     .state_kv_pruner
@@ -250,6 +282,26 @@ impl AccudoDB {
         .expect("Unable to open AccudoDB")
     }
 
+    /// Marks the ledger history and the state KVs before `ledger_version`, and the state trees
+    /// before `state_merkle_version`, as pruned.
+    pub(crate) fn save_min_readable_versions(
+        &self,
+        ledger_version: Version,
+        state_merkle_version: Version,
+    ) -> Result<()> {
+        self.ledger_pruner
+            .save_min_readable_version(ledger_version)?;
+        self.state_store
+            .state_kv_pruner
+            .save_min_readable_version(ledger_version)?;
+        self.state_store
+            .state_merkle_pruner
+            .save_min_readable_version(state_merkle_version)?;
+        self.state_store
+            .epoch_snapshot_pruner
+            .save_min_readable_version(state_merkle_version)
+    }
+
     pub(super) fn error_if_ledger_pruned(&self, data_type: &str, version: Version) -> Result<()> {
         let min_readable_version = self.ledger_pruner.get_min_readable_version();
         ensure!(
@@ -634,7 +634,12 @@ impl DbReader for AccudoDB {
         version: Version,
     ) -> Result<Option<StateValue>> {
         gauged_api("get_state_value_by_version", || {
-            self.error_if_state_kv_pruned("StateValue", version)?;
+            if !self
+                .state_kv_db
+                .is_state_value_history_kept(state_store_key, version)
+            {
+                self.error_if_state_kv_pruned("StateValue", version)?;
+            }
 
             self.state_store
                 .get_state_value_by_version(state_store_key, version)
@@ -647,7 +652,12 @@ impl DbReader for AccudoDB {
         version: Version,
     ) -> Result<Option<(Version, StateValue)>> {
         gauged_api("get_state_value_with_version_by_version", || {
-            self.error_if_state_kv_pruned("StateValue", version)?;
+            if !self
+                .state_kv_db
+                .is_state_value_history_kept(state_key, version)
+            {
+                self.error_if_state_kv_pruned("StateValue", version)?;
+            }
 
             self.state_store
                 .get_state_value_with_version_by_version(state_key, version)
@@ -14,12 +14,12 @@ use crate::{
     schema::stale_node_index::StaleNodeIndexSchema,
 };
 use accudo_config::config::{
-    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, RocksdbConfigs,
-    StateMerklePrunerConfig, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
+    EpochSnapshotPrunerConfig, LedgerColdStorageConfig, LedgerPrunerConfig, PrunerConfig,
+    RocksdbConfigs, StateMerklePrunerConfig, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
     DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
 };
 use accudo_crypto::{hash::CryptoHash, HashValue};
-use accudo_storage_interface::{DbReader, Order};
+use accudo_storage_interface::{DbReader, DbWriter, Order};
 use accudo_temppath::TempPath;
 use accudo_types::{
     ledger_info::LedgerInfoWithSignatures,
@@ -47,6 +47,11 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit(), threshold in 10..20usize) {
         test_sync_transactions_impl(input, threshold);
     }
+
+    #[test]
+    fn test_create_live_checkpoint(input in arb_blocks_to_commit()) {
+        test_create_live_checkpoint_impl(input);
+    }
 }
 
 #[test]
@@ -225,6 +230,52 @@ fn test_get_latest_ledger_summary() {
     assert_eq!(bootstrapped.state_summary.root_hash(), state_hash);
 }
 
+fn test_create_live_checkpoint_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db =
+        AccudoDB::new_for_test_with_sharding(&tmp_dir, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD);
+
+    let mut next_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        db.save_transactions_for_test(
+            txns_to_commit,
+            next_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+            true, /* sync_commit */
+        )
+        .unwrap();
+        next_ver += txns_to_commit.len() as u64;
+    }
+
+    // The source DB stays open while the checkpoint is taken.
+    let cp_dir = TempPath::new();
+    db.create_live_checkpoint(cp_dir.path()).unwrap();
+
+    let cp_db =
+        AccudoDB::new_for_test_with_sharding(&cp_dir, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD);
+    assert_eq!(
+        cp_db.get_latest_ledger_info().unwrap(),
+        db.get_latest_ledger_info().unwrap()
+    );
+    assert_eq!(
+        cp_db.get_synced_version().unwrap(),
+        db.get_synced_version().unwrap()
+    );
+    assert_eq!(
+        cp_db
+            .get_pre_committed_ledger_summary()
+            .unwrap()
+            .state_summary
+            .root_hash(),
+        db.get_pre_committed_ledger_summary()
+            .unwrap()
+            .state_summary
+            .root_hash(),
+    );
+}
+
 pub fn test_state_merkle_pruning_impl(
     input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
 ) {
@@ -240,6 +291,7 @@ pub fn test_state_merkle_pruning_impl(
                 batch_size: 1,
                 user_pruning_window_offset: 0,
             },
+            ledger_cold_storage_config: LedgerColdStorageConfig::default(),
             state_merkle_pruner_config: StateMerklePrunerConfig {
                 enable: true,
                 prune_window: 5,
@@ -20,6 +20,7 @@ use crate::{
 };
 use accudo_crypto::HashValue;
 use accudo_experimental_runtimes::thread_manager::THREAD_MANAGER;
+use accudo_logger::info;
 use accudo_metrics_core::TimerHelper;
 use accudo_schemadb::batch::SchemaBatch;
 use accudo_storage_interface::{
@@ -38,7 +39,7 @@ use accudo_types::{
 };
 use itertools::Itertools;
 use rayon::prelude::*;
-use std::{iter::Iterator, time::Instant};
+use std::{iter::Iterator, path::Path, time::Instant};
 
 impl DbWriter for AccudoDB {
     fn pre_commit_ledger(&self, chunk: ChunkToCommit, sync_commit: bool) -> Result<()> {
@@ -239,6 +240,32 @@ impl DbWriter for AccudoDB {
             Ok(())
         })
     }
+
+    fn create_live_checkpoint(&self, cp_path: &Path) -> Result<()> {
+        gauged_api("create_live_checkpoint", || {
+            let start = Instant::now();
+            info!("Creating checkpoint for live AccudoDB.");
+
+            // The ledger DB goes first, so that the state DBs checkpointed after it are never
+            // behind the overall commit progress recorded in it. Anything beyond that progress is
+            // truncated when the checkpoint is opened.
+            self.ledger_db.create_checkpoint_from_open_db(cp_path)?;
+            if self.state_kv_db.enabled_sharding() {
+                self.state_kv_db.create_checkpoint_from_open_db(cp_path)?;
+            }
+            self.state_store
+                .state_db
+                .state_merkle_db
+                .create_checkpoint_from_open_db(cp_path)?;
+
+            info!(
+                cp_path = cp_path,
+                time_ms = %start.elapsed().as_millis(),
+                "Made live AccudoDB checkpoint."
+            );
+            Ok(())
+        })
+    }
 }
 
 impl AccudoDB {
@@ -630,6 +657,8 @@ impl AccudoDB {
             // in their async thread.
             self.ledger_pruner
                 .maybe_set_pruner_target_db_version(version);
+            self.ledger_cold_storage
+                .maybe_set_target_db_version(version);
             self.state_store
                 .state_kv_pruner
                 .maybe_set_pruner_target_db_version(version);
@@ -3,12 +3,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    backup::backup_handler::BackupHandler, event_store::EventStore, ledger_db::LedgerDb,
-    pruner::LedgerPrunerManager, rocksdb_property_reporter::RocksdbPropertyReporter,
-    state_kv_db::StateKvDb, state_merkle_db::StateMerkleDb, state_store::StateStore,
+    backup::backup_handler::BackupHandler,
+    event_store::EventStore,
+    ledger_db::LedgerDb,
+    pruner::{LedgerColdStorageManager, LedgerPrunerManager},
+    rocksdb_property_reporter::RocksdbPropertyReporter,
+    state_kv_db::StateKvDb,
+    state_merkle_db::StateMerkleDb,
+    state_store::StateStore,
     transaction_store::TransactionStore,
 };
-use accudo_config::config::{PrunerConfig, RocksdbConfigs, StorageDirPaths};
+use accudo_config::config::{
+    PrunerConfig, RocksdbConfigs, StateValueHistoryConfig, StorageDirPaths,
+};
 use accudo_db_indexer::{db_indexer::InternalIndexerDB, Indexer};
 use accudo_logger::prelude::*;
 use accudo_schemadb::{batch::SchemaBatch, Cache, Env};
@@ -31,6 +38,7 @@ pub struct AccudoDB {
     pub(crate) state_store: Arc<StateStore>,
     pub(crate) transaction_store: Arc<TransactionStore>,
     ledger_pruner: LedgerPrunerManager,
+    ledger_cold_storage: LedgerColdStorageManager,
     _rocksdb_property_reporter: RocksdbPropertyReporter,
     /// This is just to detect concurrent calls to `pre_commit_ledger()`
     pre_commit_lock: std::sync::Mutex<()>,
@@ -144,6 +152,23 @@ impl AccudoDB {
         Ok(())
     }
 
+    /// Keeps the history of the state keys selected by `config` beyond the prune window, so that
+    /// `get_state_value_by_version` keeps serving them at pruned versions. The history of keys
+    /// not selected before is available since the next version committed, so this needs to be
+    /// called right after opening the DB.
+    pub fn enable_state_value_history(&self, config: &StateValueHistoryConfig) -> Result<()> {
+        if !config.enable {
+            return Ok(());
+        }
+        let next_version = self
+            .ledger_db
+            .metadata_db()
+            .get_synced_version()?
+            .map_or(0, |version| version + 1);
+        self.state_kv_db
+            .enable_state_value_history(config, next_version)
+    }
+
     /// Gets an instance of `BackupHandler` for data backup purpose.
     pub fn get_backup_handler(&self) -> BackupHandler {
         BackupHandler::new(Arc::clone(&self.state_store), Arc::clone(&self.ledger_db))
@@ -0,0 +1,387 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_debugger::common::DbDir;
+use accudo_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use accudo_storage_interface::{AccudoDbError, Result};
+use accudo_types::{
+    access_path::Path,
+    state_store::state_key::{inner::StateKeyInner, StateKey},
+    transaction::Version,
+};
+use clap::{Parser, ValueEnum};
+use indicatif::{ProgressBar, ProgressStyle};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{stdout, Write},
+    path::PathBuf,
+    sync::Arc,
+    thread,
+};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum OutputFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Parser)]
+#[clap(
+    about = "Aggregate the state storage usage at a version by account, resource type, table \
+    handle and module. Resources in a resource group are attributed to the group type, and \
+    module code to the module itself."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long)]
+    version: Version,
+
+    #[clap(long, default_value = "32")]
+    concurrency: usize,
+
+    #[clap(long, value_enum, default_value_t = OutputFormat::Json)]
+    format: OutputFormat,
+
+    /// Only output the top N entries (by bytes) of each category.
+    #[clap(long)]
+    top: Option<usize>,
+
+    /// Writes to stdout if not set.
+    #[clap(long, value_parser)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+struct Usage {
+    items: usize,
+    bytes: usize,
+}
+
+impl Usage {
+    fn add(&mut self, other: Usage) {
+        self.items += other.items;
+        self.bytes += other.bytes;
+    }
+}
+
+#[derive(Default)]
+struct UsageByCategory {
+    total: Usage,
+    by_account: HashMap<String, Usage>,
+    by_resource_type: HashMap<String, Usage>,
+    by_table_handle: HashMap<String, Usage>,
+    by_module: HashMap<String, Usage>,
+}
+
+impl UsageByCategory {
+    fn add_item(&mut self, key: &StateKey, value_size: usize) {
+        let usage = Usage {
+            items: 1,
+            bytes: key.size() + value_size,
+        };
+        self.total.add(usage);
+
+        match key.inner() {
+            StateKeyInner::AccessPath(access_path) => {
+                Self::add_to(
+                    &mut self.by_account,
+                    access_path.address.to_hex_literal(),
+                    usage,
+                );
+                match access_path.get_path() {
+                    Path::Resource(struct_tag) | Path::ResourceGroup(struct_tag) => {
+                        Self::add_to(
+                            &mut self.by_module,
+                            format!(
+                                "{}::{}",
+                                struct_tag.address.to_hex_literal(),
+                                struct_tag.module
+                            ),
+                            usage,
+                        );
+                        Self::add_to(
+                            &mut self.by_resource_type,
+                            struct_tag.to_canonical_string(),
+                            usage,
+                        );
+                    },
+                    Path::Code(module_id) => {
+                        Self::add_to(
+                            &mut self.by_module,
+                            format!(
+                                "{}::{}",
+                                module_id.address().to_hex_literal(),
+                                module_id.name()
+                            ),
+                            usage,
+                        );
+                    },
+                }
+            },
+            StateKeyInner::TableItem { handle, .. } => {
+                Self::add_to(&mut self.by_table_handle, handle.0.to_hex_literal(), usage);
+            },
+            StateKeyInner::Raw(_) => {},
+        }
+    }
+
+    fn add_to(map: &mut HashMap<String, Usage>, key: String, usage: Usage) {
+        map.entry(key).or_default().add(usage);
+    }
+
+    fn merge(&mut self, other: UsageByCategory) {
+        self.total.add(other.total);
+        for (map, other_map) in [
+            (&mut self.by_account, other.by_account),
+            (&mut self.by_resource_type, other.by_resource_type),
+            (&mut self.by_table_handle, other.by_table_handle),
+            (&mut self.by_module, other.by_module),
+        ] {
+            for (key, usage) in other_map {
+                Self::add_to(map, key, usage);
+            }
+        }
+    }
+
+    fn into_report(self, version: Version, top: Option<usize>) -> Report {
+        let sorted = |map: HashMap<String, Usage>| {
+            let mut entries: Vec<_> = map
+                .into_iter()
+                .map(|(key, usage)| Entry {
+                    key,
+                    items: usage.items,
+                    bytes: usage.bytes,
+                })
+                .collect();
+            entries.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.key.cmp(&b.key)));
+            if let Some(top) = top {
+                entries.truncate(top);
+            }
+            entries
+        };
+
+        Report {
+            version,
+            total: self.total,
+            by_account: sorted(self.by_account),
+            by_resource_type: sorted(self.by_resource_type),
+            by_table_handle: sorted(self.by_table_handle),
+            by_module: sorted(self.by_module),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Entry {
+    key: String,
+    items: usize,
+    bytes: usize,
+}
+
+#[derive(Serialize)]
+struct Report {
+    version: Version,
+    total: Usage,
+    by_account: Vec<Entry>,
+    by_resource_type: Vec<Entry>,
+    by_table_handle: Vec<Entry>,
+    by_module: Vec<Entry>,
+}
+
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    category: &'static str,
+    key: &'a str,
+    items: usize,
+    bytes: usize,
+}
+
+impl Report {
+    fn write_json(&self, writer: impl Write) -> Result<()> {
+        serde_json::to_writer_pretty(writer, self)
+            .map_err(|err| AccudoDbError::Other(err.to_string()))
+    }
+
+    fn write_csv(&self, writer: impl Write) -> Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        let total_row = CsvRow {
+            category: "total",
+            key: "",
+            items: self.total.items,
+            bytes: self.total.bytes,
+        };
+        let rows = std::iter::once(total_row).chain(
+            [
+                ("account", &self.by_account),
+                ("resource_type", &self.by_resource_type),
+                ("table_handle", &self.by_table_handle),
+                ("module", &self.by_module),
+            ]
+            .into_iter()
+            .flat_map(|(category, entries)| {
+                entries.iter().map(move |entry| CsvRow {
+                    category,
+                    key: &entry.key,
+                    items: entry.items,
+                    bytes: entry.bytes,
+                })
+            }),
+        );
+        for row in rows {
+            csv_writer
+                .serialize(row)
+                .map_err(|err| AccudoDbError::Other(err.to_string()))?;
+        }
+        csv_writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        eprintln!(
+            "{}",
+            format!(
+                "* Analyze the state storage usage in snapshot at version {}. \n",
+                self.version,
+            )
+            .yellow()
+        );
+
+        let state_kv_db = Arc::new(self.db_dir.open_state_kv_db()?);
+        let state_merkle_db = Arc::new(self.db_dir.open_state_merkle_db()?);
+        let total_leaves = state_merkle_db.get_leaf_count(self.version)?;
+        eprintln!("total leaves: {}", total_leaves);
+
+        let bar = ProgressBar::new(total_leaves as u64);
+        bar.set_style(ProgressStyle::default_bar().template(
+            "[{elapsed_precise} {per_sec}] {bar:100.cyan/blue} {pos} / {len} {percent}% ETA {eta_precise}",
+        ));
+
+        let concurrency = self.concurrency.max(1);
+        let chunk_size = total_leaves.div_ceil(concurrency);
+        let version = self.version;
+        let workers: Vec<_> = (0..concurrency)
+            .map(|i| {
+                let start = i * chunk_size;
+                let len = chunk_size.min(total_leaves.saturating_sub(start));
+                let state_merkle_db = state_merkle_db.clone();
+                let state_kv_db = state_kv_db.clone();
+                let bar = bar.clone();
+                thread::spawn(move || -> Result<UsageByCategory> {
+                    let mut usage = UsageByCategory::default();
+                    if len == 0 {
+                        return Ok(usage);
+                    }
+                    let leaf_iter =
+                        JellyfishMerkleIterator::new_by_index(state_merkle_db, version, start)?
+                            .take(len);
+                    for leaf_res in leaf_iter {
+                        let (_key_hash, (key, key_version)) = leaf_res?;
+                        let value_size = state_kv_db
+                            .get_state_value_with_version_by_version(&key, key_version)?
+                            .map_or(0, |(_version, value)| value.size());
+                        usage.add_item(&key, value_size);
+                        bar.inc(1);
+                    }
+                    Ok(usage)
+                })
+            })
+            .collect();
+
+        let mut usage = UsageByCategory::default();
+        for worker in workers {
+            usage.merge(worker.join().expect("Worker thread panicked.")?);
+        }
+        bar.finish();
+
+        let report = usage.into_report(self.version, self.top);
+        let writer: Box<dyn Write> = match &self.output {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(stdout()),
+        };
+        match self.format {
+            OutputFormat::Json => report.write_json(writer)?,
+            OutputFormat::Csv => report.write_csv(writer)?,
+        }
+
+        eprintln!("{}", "Analysis complete.".to_string().yellow());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use accudo_types::{account_address::AccountAddress, state_store::table::TableHandle};
+    use move_core_types::{ident_str, language_storage::StructTag};
+
+    fn struct_tag(name: &str) -> StructTag {
+        format!("0x1::coin::{}", name).parse().unwrap()
+    }
+
+    #[test]
+    fn test_usage_by_category() {
+        let account = AccountAddress::from_hex_literal("0xa").unwrap();
+        let resource = StateKey::resource(&account, &struct_tag("CoinStore")).unwrap();
+        let group = StateKey::resource_group(&account, &struct_tag("Group"));
+        let module = StateKey::module(&AccountAddress::ONE, ident_str!("coin"));
+        let table_item = StateKey::table_item(&TableHandle(account), b"item");
+
+        // Aggregated by two workers, as in `Cmd::run()`.
+        let mut usage = UsageByCategory::default();
+        usage.add_item(&resource, 100);
+        usage.add_item(&module, 1000);
+        let mut other_usage = UsageByCategory::default();
+        other_usage.add_item(&group, 10);
+        other_usage.add_item(&table_item, 1);
+        usage.merge(other_usage);
+
+        let size = |key: &StateKey, value_size: usize| key.size() + value_size;
+        let report = usage.into_report(7, Some(1));
+        assert_eq!(report.total.items, 4);
+        assert_eq!(
+            report.total.bytes,
+            size(&resource, 100) + size(&module, 1000) + size(&group, 10) + size(&table_item, 1)
+        );
+        // Truncated to the top entry of each category.
+        let keys = |entries: &[Entry]| -> Vec<String> {
+            entries.iter().map(|entry| entry.key.clone()).collect()
+        };
+        assert_eq!(keys(&report.by_account), vec!["0x1"]);
+        assert_eq!(keys(&report.by_resource_type), vec!["0x1::coin::CoinStore"]);
+        assert_eq!(keys(&report.by_table_handle), vec!["0xa"]);
+        assert_eq!(keys(&report.by_module), vec!["0x1::coin"]);
+        assert_eq!(report.by_module[0].items, 3);
+        assert_eq!(
+            report.by_module[0].bytes,
+            size(&resource, 100) + size(&module, 1000) + size(&group, 10)
+        );
+
+        let mut json = vec![];
+        report.write_json(&mut json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json["version"], 7);
+        assert_eq!(json["total"]["items"], 4);
+        assert_eq!(json["by_table_handle"][0]["key"], "0xa");
+        assert_eq!(json["by_table_handle"][0]["items"], 1);
+
+        let mut csv = vec![];
+        report.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "category,key,items,bytes");
+        assert_eq!(lines[1], format!("total,,4,{}", report.total.bytes));
+        assert_eq!(
+            lines[5],
+            format!("module,0x1::coin,3,{}", report.by_module[0].bytes)
+        );
+    }
+}
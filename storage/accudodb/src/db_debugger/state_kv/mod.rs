@@ -1,6 +1,7 @@
 // Copyright © Accudo Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+mod analyze;
 mod get_value;
 mod scan_snapshot;
 
@@ -8,6 +9,7 @@ use accudo_storage_interface::Result;
 
 #[derive(clap::Subcommand)]
 pub enum Cmd {
+    Analyze(analyze::Cmd),
     GetValue(get_value::Cmd),
     ScanSnapshot(scan_snapshot::Cmd),
 }
@@ -15,6 +17,7 @@ pub enum Cmd {
 impl Cmd {
     pub fn run(self) -> Result<()> {
         match self {
+            Self::Analyze(cmd) => cmd.run(),
             Self::GetValue(cmd) => cmd.run(),
             Self::ScanSnapshot(cmd) => cmd.run(),
         }
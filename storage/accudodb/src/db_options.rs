@@ -25,6 +25,7 @@ pub(super) fn ledger_db_column_families() -> Vec<ColumnFamilyName> {
         PERSISTED_AUXILIARY_INFO_CF_NAME,
         STALE_STATE_VALUE_INDEX_CF_NAME,
         STATE_VALUE_CF_NAME,
+        STATE_VALUE_HISTORY_CF_NAME,
         TRANSACTION_CF_NAME,
         TRANSACTION_ACCUMULATOR_CF_NAME,
         TRANSACTION_ACCUMULATOR_HASH_CF_NAME,
@@ -102,6 +103,16 @@ pub(super) fn write_set_db_column_families() -> Vec<ColumnFamilyName> {
     ]
 }
 
+pub(super) fn ledger_cold_db_column_families() -> Vec<ColumnFamilyName> {
+    vec![
+        /* empty cf */ DEFAULT_COLUMN_FAMILY_NAME,
+        DB_METADATA_CF_NAME,
+        EVENT_CF_NAME,
+        TRANSACTION_CF_NAME,
+        WRITE_SET_CF_NAME,
+    ]
+}
+
 pub(super) fn ledger_metadata_db_column_families() -> Vec<ColumnFamilyName> {
     vec![
         /* empty cf */ DEFAULT_COLUMN_FAMILY_NAME,
@@ -144,6 +155,7 @@ pub(super) fn state_kv_db_new_key_column_families() -> Vec<ColumnFamilyName> {
         DB_METADATA_CF_NAME,
         STALE_STATE_VALUE_INDEX_BY_KEY_HASH_CF_NAME,
         STATE_VALUE_BY_KEY_HASH_CF_NAME,
+        STATE_VALUE_HISTORY_CF_NAME,
         STATE_VALUE_INDEX_CF_NAME, // we still need this cf before deleting all the write callsites
     ]
 }
@@ -189,6 +201,7 @@ where
 fn with_state_key_extractor_processor(cf_name: ColumnFamilyName, cf_opts: &mut Options) {
     if cf_name == STATE_VALUE_CF_NAME
         || cf_name == STATE_VALUE_BY_KEY_HASH_CF_NAME
+        || cf_name == STATE_VALUE_HISTORY_CF_NAME
         || cf_name == HOT_STATE_VALUE_BY_KEY_HASH_CF_NAME
     {
         let prefix_extractor =
@@ -256,6 +269,14 @@ pub(super) fn gen_write_set_cfds(
     gen_cfds(rocksdb_config, block_cache, cfs, |_, _| {})
 }
 
+pub(super) fn gen_ledger_cold_cfds(
+    rocksdb_config: &RocksdbConfig,
+    block_cache: Option<&Cache>,
+) -> Vec<ColumnFamilyDescriptor> {
+    let cfs = ledger_cold_db_column_families();
+    gen_cfds(rocksdb_config, block_cache, cfs, |_, _| {})
+}
+
 pub(super) fn gen_ledger_metadata_cfds(
     rocksdb_config: &RocksdbConfig,
     block_cache: Option<&Cache>,
@@ -7,7 +7,10 @@
 #![allow(unused)]
 
 use super::AccudoDB;
-use crate::schema::{event::EventSchema, event_accumulator::EventAccumulatorSchema};
+use crate::{
+    ledger_db::ledger_cold_db::LedgerColdDb,
+    schema::{event::EventSchema, event_accumulator::EventAccumulatorSchema},
+};
 use accudo_accumulator::HashReader;
 use accudo_crypto::{hash::CryptoHash, HashValue};
 use accudo_db_indexer_schemas::schema::{
@@ -33,11 +36,19 @@ use std::{
 #[derive(Debug)]
 pub struct EventStore {
     event_db: Arc<DB>,
+    cold_db: Option<Arc<LedgerColdDb>>,
 }
 
 impl EventStore {
     pub fn new(event_db: Arc<DB>) -> Self {
-        Self { event_db }
+        Self {
+            event_db,
+            cold_db: None,
+        }
+    }
+
+    pub(crate) fn set_cold_db(&mut self, cold_db: Arc<LedgerColdDb>) {
+        self.cold_db = Some(cold_db);
     }
 
     pub fn get_event_by_version_and_index(
@@ -45,9 +56,18 @@ impl EventStore {
         version: Version,
         index: u64,
     ) -> Result<ContractEvent> {
-        self.event_db
-            .get::<EventSchema>(&(version, index))?
-            .ok_or_else(|| AccudoDbError::NotFound(format!("Event {} of Txn {}", index, version)))
+        if let Some(event) = self.event_db.get::<EventSchema>(&(version, index))? {
+            return Ok(event);
+        }
+        if let Some(cold_db) = &self.cold_db {
+            if let Some(event) = cold_db.db().get::<EventSchema>(&(version, index))? {
+                return Ok(event);
+            }
+        }
+        Err(AccudoDbError::NotFound(format!(
+            "Event {} of Txn {}",
+            index, version
+        )))
     }
 
     pub fn get_txn_ver_by_seq_num(&self, event_key: &EventKey, seq_num: u64) -> Result<u64> {
@@ -16,7 +16,7 @@ use accudo_types::{
 };
 use anyhow::anyhow;
 use either::Either;
-use std::{sync::Arc, time::Instant};
+use std::{path::Path, sync::Arc, time::Instant};
 use tokio::sync::watch::Sender;
 pub const SECONDARY_DB_DIR: &str = "fast_sync_secondary";
 
@@ -56,6 +56,7 @@ impl FastSyncStorageWrapper {
             internal_indexer_db,
         )
         .map_err(|err| anyhow!("fast sync DB failed to open {}", err))?;
+        db_main.enable_state_value_history(&config.storage.state_value_history_config)?;
         if let Some(sender) = update_sender {
             db_main.add_version_update_subscriber(sender)?;
         }
@@ -181,6 +182,11 @@ impl DbWriter for FastSyncStorageWrapper {
         self.get_accudo_db_write_ref()
             .commit_ledger(version, ledger_info_with_sigs, chunk_opt)
     }
+
+    fn create_live_checkpoint(&self, cp_path: &Path) -> Result<()> {
+        self.get_accudo_db_write_ref()
+            .create_live_checkpoint(cp_path)
+    }
 }
 
 impl DbReader for FastSyncStorageWrapper {
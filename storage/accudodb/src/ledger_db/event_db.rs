@@ -3,6 +3,7 @@
 
 use crate::{
     event_store::{EmptyReader, EventStore},
+    ledger_db::ledger_cold_db::{tiered_iter, LedgerColdDb, TieredIter},
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
         event::EventSchema,
@@ -33,11 +34,21 @@ pub(crate) struct EventDb {
     db: Arc<DB>,
     // TODO(grao): Remove this after sharding migration.
     event_store: EventStore,
+    cold_db: Option<Arc<LedgerColdDb>>,
 }
 
 impl EventDb {
     pub(super) fn new(db: Arc<DB>, event_store: EventStore) -> Self {
-        Self { db, event_store }
+        Self {
+            db,
+            event_store,
+            cold_db: None,
+        }
+    }
+
+    pub(super) fn set_cold_db(&mut self, cold_db: Arc<LedgerColdDb>) {
+        self.event_store.set_cold_db(Arc::clone(&cold_db));
+        self.cold_db = Some(cold_db);
     }
 
     pub(super) fn create_checkpoint(&self, path: impl AsRef<Path>) -> Result<()> {
@@ -67,9 +78,8 @@ impl EventDb {
     pub(crate) fn get_events_by_version(&self, version: Version) -> Result<Vec<ContractEvent>> {
         let mut events = vec![];
 
-        let mut iter = self.db.iter::<EventSchema>()?;
         // Grab the first event and then iterate until we get all events for this version.
-        iter.seek(&version)?;
+        let mut iter = self.tiered_iter(version)?;
         while let Some(((ver, _index), event)) = iter.next().transpose()? {
             if ver != version {
                 break;
@@ -102,11 +112,8 @@ impl EventDb {
         start_version: Version,
         num_versions: usize,
     ) -> Result<EventsByVersionIter<'_>> {
-        let mut iter = self.db.iter::<EventSchema>()?;
-        iter.seek(&start_version)?;
-
         Ok(EventsByVersionIter::new(
-            iter,
+            self.tiered_iter(start_version)?,
             start_version,
             start_version.checked_add(num_versions as u64).ok_or(
                 AccudoDbError::TooManyRequested(num_versions as u64, Version::MAX),
@@ -119,10 +126,25 @@ impl EventDb {
         let mut iter = self.db.iter::<EventSchema>()?;
         iter.seek_to_last();
         if let Some(((version, _), _)) = iter.next().transpose()? {
-            Ok(Some(version))
-        } else {
-            Ok(None)
+            return Ok(Some(version));
         }
+        if let Some(cold_db) = &self.cold_db {
+            let mut iter = cold_db.db().iter::<EventSchema>()?;
+            iter.seek_to_last();
+            if let Some(((version, _), _)) = iter.next().transpose()? {
+                return Ok(Some(version));
+            }
+        }
+        Ok(None)
+    }
+
+    fn tiered_iter(&self, start_version: Version) -> Result<TieredIter<'_, EventSchema>> {
+        tiered_iter::<EventSchema, _>(
+            &self.db,
+            self.cold_db.as_deref(),
+            start_version,
+            |(version, _index)| *version,
+        )
     }
 
     /// Saves contract events yielded by multiple transactions starting from version
@@ -221,6 +243,28 @@ impl EventDb {
         Ok(ret)
     }
 
+    /// Copies events in version range [begin, end) into `cold_batch`, and deletes them from this
+    /// DB in `hot_batch`. Indices and the event accumulator stay in this DB.
+    pub(crate) fn move_events(
+        &self,
+        begin: Version,
+        end: Version,
+        cold_batch: &mut SchemaBatch,
+        hot_batch: &mut SchemaBatch,
+    ) -> Result<()> {
+        let mut iter = self.db.iter::<EventSchema>()?;
+        iter.seek(&begin)?;
+        for item in iter {
+            let (key, event) = item?;
+            if key.0 >= end {
+                break;
+            }
+            cold_batch.put::<EventSchema>(&key, &event)?;
+            hot_batch.delete::<EventSchema>(&key)?;
+        }
+        Ok(())
+    }
+
     /// Deletes a set of events in the range of version in [begin, end), and all related indices.
     pub(crate) fn prune_events(
         &self,
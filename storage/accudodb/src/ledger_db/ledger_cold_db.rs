@@ -0,0 +1,125 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+    utils::get_progress,
+};
+use accudo_schemadb::{
+    batch::SchemaBatch,
+    schema::{Schema, SeekKeyCodec},
+    DB,
+};
+use accudo_storage_interface::Result;
+use accudo_types::transaction::{AtomicVersion, Version};
+use std::{
+    cmp::max,
+    path::Path,
+    sync::{atomic::Ordering, Arc},
+};
+
+/// A boxed iterator over a schema, which might be served by more than one DB.
+pub(crate) type TieredIter<'a, S> =
+    Box<dyn Iterator<Item = Result<(<S as Schema>::Key, <S as Schema>::Value)>> + 'a>;
+
+/// Holds the ledger history (transactions, write sets and events) moved out of the ledger DB by
+/// the ledger cold storage mover, usually on a cheaper disk.
+///
+/// All versions below `boundary` are readable from the cold DB. The ledger DB is guaranteed to
+/// hold all versions at or above it, and might still hold some below it until they are trimmed.
+#[derive(Debug)]
+pub(crate) struct LedgerColdDb {
+    db: Arc<DB>,
+    boundary: AtomicVersion,
+}
+
+impl LedgerColdDb {
+    pub(super) fn new(db: Arc<DB>) -> Result<Self> {
+        let boundary = get_progress(&db, &DbMetadataKey::LedgerColdStorageProgress)?.unwrap_or(0);
+        Ok(Self {
+            db,
+            boundary: AtomicVersion::new(boundary),
+        })
+    }
+
+    pub(super) fn create_checkpoint(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.db.create_checkpoint(path)
+    }
+
+    pub(crate) fn db(&self) -> &DB {
+        &self.db
+    }
+
+    /// Returns the version below which the ledger history is served by the cold DB.
+    pub(crate) fn boundary(&self) -> Version {
+        self.boundary.load(Ordering::SeqCst)
+    }
+
+    /// Returns the version below which the moved ledger history has been deleted from the ledger
+    /// DB.
+    pub(crate) fn trim_progress(&self) -> Result<Version> {
+        Ok(get_progress(&self.db, &DbMetadataKey::LedgerColdStorageTrimProgress)?.unwrap_or(0))
+    }
+
+    /// Persists the ledger history copied into `batch` together with the new boundary, and only
+    /// then exposes the new boundary to readers. The boundary never goes backwards, which happens
+    /// when data left behind by a crash is moved again.
+    pub(crate) fn commit_move(&self, mut batch: SchemaBatch, boundary: Version) -> Result<()> {
+        let boundary = max(self.boundary(), boundary);
+        batch.put::<DbMetadataSchema>(
+            &DbMetadataKey::LedgerColdStorageProgress,
+            &DbMetadataValue::Version(boundary),
+        )?;
+        self.db.write_schemas(batch)?;
+        self.boundary.store(boundary, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub(crate) fn write_trim_progress(&self, version: Version) -> Result<()> {
+        self.db.put::<DbMetadataSchema>(
+            &DbMetadataKey::LedgerColdStorageTrimProgress,
+            &DbMetadataValue::Version(version),
+        )
+    }
+}
+
+/// Returns an iterator over `S` starting from `start_version`, which reads the versions below the
+/// cold boundary from `cold_db` and the rest from `hot_db`. `version_of` extracts the version from
+/// a key of `S`.
+pub(crate) fn tiered_iter<'a, S, F>(
+    hot_db: &'a DB,
+    cold_db: Option<&'a LedgerColdDb>,
+    start_version: Version,
+    version_of: F,
+) -> Result<TieredIter<'a, S>>
+where
+    S: Schema,
+    Version: SeekKeyCodec<S>,
+    F: Fn(&S::Key) -> Version + Copy + 'a,
+{
+    let mut hot_iter = hot_db.iter::<S>()?;
+    hot_iter.seek(&start_version)?;
+    let Some(cold_db) = cold_db else {
+        return Ok(Box::new(hot_iter));
+    };
+
+    // The hot iterator is created before reading the boundary. Because the mover only trims the
+    // ledger DB below a boundary already published, it's guaranteed to see all versions at or
+    // above the boundary read here.
+    let boundary = cold_db.boundary();
+    let hot_iter = hot_iter.filter(move |res| {
+        res.as_ref()
+            .map_or(true, |(key, _value)| version_of(key) >= boundary)
+    });
+    if start_version >= boundary {
+        return Ok(Box::new(hot_iter));
+    }
+
+    let mut cold_iter = cold_db.db().iter::<S>()?;
+    cold_iter.seek(&start_version)?;
+    let cold_iter = cold_iter.take_while(move |res| {
+        res.as_ref()
+            .map_or(true, |(key, _value)| version_of(key) < boundary)
+    });
+    Ok(Box::new(cold_iter.chain(hot_iter)))
+}
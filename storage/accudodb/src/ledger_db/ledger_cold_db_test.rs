@@ -0,0 +1,260 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ledger_db::{transaction_db_test::init_db, LedgerDb},
+    pruner::LedgerColdStorageManager,
+    schema::{event::EventSchema, transaction::TransactionSchema, write_set::WriteSetSchema},
+};
+use accudo_config::config::{LedgerColdStorageConfig, RocksdbConfig, RocksdbConfigs};
+use accudo_proptest_helpers::Index;
+use accudo_schemadb::batch::SchemaBatch;
+use accudo_storage_interface::Result;
+use accudo_temppath::TempPath;
+use accudo_types::{
+    contract_event::ContractEvent,
+    proptest_types::{AccountInfoUniverse, SignatureCheckedTransactionGen},
+    transaction::{
+        ExecutionStatus, Transaction, TransactionAuxiliaryData, TransactionOutput, Version,
+    },
+    write_set::WriteSet,
+};
+use proptest::{collection::vec, prelude::*};
+use std::{path::Path, sync::Arc};
+
+fn open_ledger_db(path: &Path, sharding: bool) -> Arc<LedgerDb> {
+    let rocksdb_configs = RocksdbConfigs {
+        enable_storage_sharding: sharding,
+        ..Default::default()
+    };
+    Arc::new(
+        LedgerDb::new(path, rocksdb_configs, None, None, /*readonly=*/ false)
+            .unwrap()
+            .with_cold_db(
+                path,
+                &RocksdbConfig::default(),
+                None,
+                None,
+                /*readonly=*/ false,
+            )
+            .unwrap(),
+    )
+}
+
+fn verify_reads(
+    ledger_db: &LedgerDb,
+    txns: &[Transaction],
+    write_sets: &[WriteSet],
+    events: &[Vec<ContractEvent>],
+) {
+    let num_txns = txns.len();
+    for version in 0..num_txns {
+        assert_eq!(
+            ledger_db
+                .transaction_db()
+                .get_transaction(version as Version)
+                .unwrap(),
+            txns[version]
+        );
+        assert_eq!(
+            ledger_db
+                .write_set_db()
+                .get_write_set(version as Version)
+                .unwrap(),
+            write_sets[version]
+        );
+        assert_eq!(
+            ledger_db
+                .event_db()
+                .get_events_by_version(version as Version)
+                .unwrap(),
+            events[version]
+        );
+
+        assert_eq!(
+            ledger_db
+                .transaction_db()
+                .get_transaction_iter(version as Version, num_txns - version)
+                .unwrap()
+                .collect::<Result<Vec<_>>>()
+                .unwrap(),
+            txns[version..]
+        );
+        assert_eq!(
+            ledger_db
+                .write_set_db()
+                .get_write_sets(version as Version, num_txns as Version)
+                .unwrap(),
+            write_sets[version..]
+        );
+        assert_eq!(
+            ledger_db
+                .event_db()
+                .get_events_by_version_iter(version as Version, num_txns - version)
+                .unwrap()
+                .collect::<Result<Vec<_>>>()
+                .unwrap(),
+            events[version..]
+        );
+    }
+    assert!(ledger_db
+        .transaction_db()
+        .get_transaction(num_txns as Version)
+        .is_err());
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_move_to_cold_db(
+        universe in any_with::<AccountInfoUniverse>(3),
+        gens in vec(
+            (any::<Index>(), any::<SignatureCheckedTransactionGen>()),
+            2..20
+        ),
+        write_sets in vec(any::<WriteSet>(), 20),
+        events in vec(vec(any::<ContractEvent>().no_shrink(), 0..3), 20),
+        hot_window in 1u64..5,
+        batch_size in 1usize..5,
+        sharding in any::<bool>(),
+    ) {
+        let tmp_dir = TempPath::new();
+        let ledger_db = open_ledger_db(tmp_dir.path(), sharding);
+
+        let txns = init_db(universe, gens, ledger_db.transaction_db());
+        let num_txns = txns.len();
+        let write_sets = &write_sets[..num_txns];
+        let events = &events[..num_txns];
+        let txn_outs = write_sets
+            .iter()
+            .map(|write_set| {
+                TransactionOutput::new(
+                    write_set.clone(),
+                    vec![],
+                    0,
+                    ExecutionStatus::Success.into(),
+                    TransactionAuxiliaryData::default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        ledger_db.write_set_db().commit_write_sets(0, &txn_outs).unwrap();
+        let mut batch = SchemaBatch::new();
+        ledger_db
+            .event_db()
+            .put_events_multiple_versions(0, events, &mut batch)
+            .unwrap();
+        ledger_db.event_db().write_schemas(batch).unwrap();
+
+        let latest_version = num_txns as Version - 1;
+        {
+            let manager = LedgerColdStorageManager::new(
+                Arc::clone(&ledger_db),
+                LedgerColdStorageConfig {
+                    enable: true,
+                    hot_window,
+                    batch_size,
+                },
+            );
+            manager.wake_and_wait_mover(latest_version);
+        }
+
+        let boundary = ledger_db.ledger_cold_db().unwrap().boundary();
+        if latest_version >= batch_size as Version + hot_window {
+            prop_assert_eq!(boundary, latest_version - hot_window);
+        } else {
+            prop_assert_eq!(boundary, 0);
+        }
+        for version in 0..num_txns as Version {
+            let in_hot = ledger_db
+                .transaction_db()
+                .db()
+                .get::<TransactionSchema>(&version)
+                .unwrap()
+                .is_some();
+            prop_assert_eq!(in_hot, version >= boundary);
+            prop_assert_eq!(
+                ledger_db.write_set_db().db().get::<WriteSetSchema>(&version).unwrap().is_some(),
+                version >= boundary
+            );
+            if !events[version as usize].is_empty() {
+                prop_assert_eq!(
+                    ledger_db.event_db().db().get::<EventSchema>(&(version, 0)).unwrap().is_some(),
+                    version >= boundary
+                );
+            }
+        }
+        verify_reads(&ledger_db, &txns, write_sets, events);
+
+        // The moved data is still readable after reopening.
+        drop(ledger_db);
+        let ledger_db = open_ledger_db(tmp_dir.path(), sharding);
+        prop_assert_eq!(ledger_db.ledger_cold_db().unwrap().boundary(), boundary);
+        verify_reads(&ledger_db, &txns, write_sets, events);
+    }
+}
+
+#[test]
+fn test_hot_db_is_trimmed_after_crash() {
+    let tmp_dir = TempPath::new();
+    let ledger_db = open_ledger_db(tmp_dir.path(), /*sharding=*/ true);
+    let write_sets = vec![WriteSet::default(); 10];
+    for (version, write_set) in write_sets.iter().enumerate() {
+        ledger_db
+            .write_set_db()
+            .db()
+            .put::<WriteSetSchema>(&(version as Version), write_set)
+            .unwrap();
+    }
+
+    // Simulate a crash after the data is copied to the cold DB but before it's deleted from the
+    // ledger DB.
+    let ledger_cold_db = Arc::clone(ledger_db.ledger_cold_db().unwrap());
+    let mut cold_batch = SchemaBatch::new();
+    let mut hot_batch = SchemaBatch::new();
+    ledger_db
+        .write_set_db()
+        .move_write_sets(0, 5, &mut cold_batch, &mut hot_batch)
+        .unwrap();
+    ledger_cold_db.commit_move(cold_batch, 5).unwrap();
+    assert_eq!(ledger_cold_db.trim_progress().unwrap(), 0);
+    assert_eq!(
+        ledger_db.write_set_db().get_write_sets(0, 10).unwrap(),
+        write_sets
+    );
+    drop(ledger_cold_db);
+    drop(ledger_db);
+
+    let ledger_db = open_ledger_db(tmp_dir.path(), /*sharding=*/ true);
+    assert_eq!(ledger_db.ledger_cold_db().unwrap().boundary(), 5);
+    let manager = LedgerColdStorageManager::new(
+        Arc::clone(&ledger_db),
+        LedgerColdStorageConfig {
+            enable: true,
+            hot_window: 3,
+            batch_size: 2,
+        },
+    );
+    manager.wake_and_wait_mover(9);
+    drop(manager);
+
+    // The range left behind is trimmed, even though the hot window isn't full enough to move more.
+    let ledger_cold_db = ledger_db.ledger_cold_db().unwrap();
+    assert_eq!(ledger_cold_db.boundary(), 5);
+    assert_eq!(ledger_cold_db.trim_progress().unwrap(), 5);
+    for version in 0..10 {
+        assert_eq!(
+            ledger_db
+                .write_set_db()
+                .db()
+                .get::<WriteSetSchema>(&version)
+                .unwrap()
+                .is_some(),
+            version >= 5
+        );
+    }
+    assert_eq!(
+        ledger_db.write_set_db().get_write_sets(0, 10).unwrap(),
+        write_sets
+    );
+}
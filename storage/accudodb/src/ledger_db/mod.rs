@@ -6,17 +6,19 @@
 
 use crate::{
     db_options::{
-        event_db_column_families, gen_event_cfds, gen_ledger_cfds, gen_ledger_metadata_cfds,
-        gen_persisted_auxiliary_info_cfds, gen_transaction_accumulator_cfds,
-        gen_transaction_auxiliary_data_cfds, gen_transaction_cfds, gen_transaction_info_cfds,
-        gen_write_set_cfds, ledger_db_column_families, ledger_metadata_db_column_families,
-        persisted_auxiliary_info_db_column_families, transaction_accumulator_db_column_families,
-        transaction_auxiliary_data_db_column_families, transaction_db_column_families,
-        transaction_info_db_column_families, write_set_db_column_families,
+        event_db_column_families, gen_event_cfds, gen_ledger_cfds, gen_ledger_cold_cfds,
+        gen_ledger_metadata_cfds, gen_persisted_auxiliary_info_cfds,
+        gen_transaction_accumulator_cfds, gen_transaction_auxiliary_data_cfds,
+        gen_transaction_cfds, gen_transaction_info_cfds, gen_write_set_cfds,
+        ledger_cold_db_column_families, ledger_db_column_families,
+        ledger_metadata_db_column_families, persisted_auxiliary_info_db_column_families,
+        transaction_accumulator_db_column_families, transaction_auxiliary_data_db_column_families,
+        transaction_db_column_families, transaction_info_db_column_families,
+        write_set_db_column_families,
     },
     event_store::EventStore,
     ledger_db::{
-        event_db::EventDb, ledger_metadata_db::LedgerMetadataDb,
+        event_db::EventDb, ledger_cold_db::LedgerColdDb, ledger_metadata_db::LedgerMetadataDb,
         persisted_auxiliary_info_db::PersistedAuxiliaryInfoDb,
         transaction_accumulator_db::TransactionAccumulatorDb,
         transaction_auxiliary_data_db::TransactionAuxiliaryDataDb, transaction_db::TransactionDb,
@@ -41,6 +43,9 @@ use std::{
 mod event_db;
 #[cfg(test)]
 mod event_db_test;
+pub(crate) mod ledger_cold_db;
+#[cfg(test)]
+mod ledger_cold_db_test;
 pub(crate) mod ledger_metadata_db;
 #[cfg(test)]
 mod ledger_metadata_db_test;
@@ -62,6 +67,8 @@ pub(crate) mod write_set_db;
 mod write_set_db_test;
 
 pub const LEDGER_DB_FOLDER_NAME: &str = "ledger_db";
+pub const LEDGER_COLD_DB_FOLDER_NAME: &str = "ledger_cold_db";
+pub const LEDGER_COLD_DB_NAME: &str = "ledger_cold_db";
 pub const LEDGER_DB_NAME: &str = "ledger_db";
 pub const LEDGER_METADATA_DB_NAME: &str = "ledger_metadata_db";
 pub const EVENT_DB_NAME: &str = "event_db";
@@ -115,6 +122,7 @@ pub struct LedgerDb {
     transaction_db: TransactionDb,
     transaction_info_db: TransactionInfoDb,
     write_set_db: WriteSetDb,
+    ledger_cold_db: Option<Arc<LedgerColdDb>>,
     enable_storage_sharding: bool,
 }
 
@@ -149,26 +157,7 @@ impl LedgerDb {
 
         if !sharding {
             info!("Individual ledger dbs are not enabled!");
-            return Ok(Self {
-                ledger_metadata_db: LedgerMetadataDb::new(Arc::clone(&ledger_metadata_db)),
-                event_db: EventDb::new(
-                    Arc::clone(&ledger_metadata_db),
-                    EventStore::new(Arc::clone(&ledger_metadata_db)),
-                ),
-                persisted_auxiliary_info_db: PersistedAuxiliaryInfoDb::new(Arc::clone(
-                    &ledger_metadata_db,
-                )),
-                transaction_accumulator_db: TransactionAccumulatorDb::new(Arc::clone(
-                    &ledger_metadata_db,
-                )),
-                transaction_auxiliary_data_db: TransactionAuxiliaryDataDb::new(Arc::clone(
-                    &ledger_metadata_db,
-                )),
-                transaction_db: TransactionDb::new(Arc::clone(&ledger_metadata_db)),
-                transaction_info_db: TransactionInfoDb::new(Arc::clone(&ledger_metadata_db)),
-                write_set_db: WriteSetDb::new(Arc::clone(&ledger_metadata_db)),
-                enable_storage_sharding: false,
-            });
+            return Ok(Self::new_unsharded(ledger_metadata_db));
         }
 
         let ledger_db_folder = db_root_path.as_ref().join(LEDGER_DB_FOLDER_NAME);
@@ -289,10 +278,75 @@ impl LedgerDb {
             transaction_db: transaction_db.unwrap(),
             transaction_info_db: transaction_info_db.unwrap(),
             write_set_db: write_set_db.unwrap(),
+            ledger_cold_db: None,
             enable_storage_sharding: true,
         })
     }
 
+    /// Keeps all ledger data in a single in-memory DB, laid out like an unsharded ledger DB.
+    #[cfg(test)]
+    pub(crate) fn new_in_memory_for_test() -> Self {
+        Self::new_unsharded(Arc::new(DB::open_in_memory(
+            LEDGER_DB_NAME,
+            ledger_db_column_families(),
+        )))
+    }
+
+    fn new_unsharded(ledger_metadata_db: Arc<DB>) -> Self {
+        Self {
+            ledger_metadata_db: LedgerMetadataDb::new(Arc::clone(&ledger_metadata_db)),
+            event_db: EventDb::new(
+                Arc::clone(&ledger_metadata_db),
+                EventStore::new(Arc::clone(&ledger_metadata_db)),
+            ),
+            persisted_auxiliary_info_db: PersistedAuxiliaryInfoDb::new(Arc::clone(
+                &ledger_metadata_db,
+            )),
+            transaction_accumulator_db: TransactionAccumulatorDb::new(Arc::clone(
+                &ledger_metadata_db,
+            )),
+            transaction_auxiliary_data_db: TransactionAuxiliaryDataDb::new(Arc::clone(
+                &ledger_metadata_db,
+            )),
+            transaction_db: TransactionDb::new(Arc::clone(&ledger_metadata_db)),
+            transaction_info_db: TransactionInfoDb::new(Arc::clone(&ledger_metadata_db)),
+            write_set_db: WriteSetDb::new(Arc::clone(&ledger_metadata_db)),
+            ledger_cold_db: None,
+            enable_storage_sharding: false,
+        }
+    }
+
+    /// Opens the ledger cold DB under `cold_db_root_path`, and lets the transaction, write set
+    /// and event DBs fall through to it for the history moved out of the ledger DB.
+    pub(crate) fn with_cold_db<P: AsRef<Path>>(
+        mut self,
+        cold_db_root_path: P,
+        db_config: &RocksdbConfig,
+        env: Option<&Env>,
+        block_cache: Option<&Cache>,
+        readonly: bool,
+    ) -> Result<Self> {
+        let ledger_cold_db = Arc::new(LedgerColdDb::new(Arc::new(Self::open_rocksdb(
+            cold_db_root_path.as_ref().join(LEDGER_COLD_DB_FOLDER_NAME),
+            LEDGER_COLD_DB_NAME,
+            db_config,
+            env,
+            block_cache,
+            readonly,
+        )?))?);
+
+        info!(
+            boundary = ledger_cold_db.boundary(),
+            "Opened ledger cold db!"
+        );
+
+        self.event_db.set_cold_db(Arc::clone(&ledger_cold_db));
+        self.transaction_db.set_cold_db(Arc::clone(&ledger_cold_db));
+        self.write_set_db.set_cold_db(Arc::clone(&ledger_cold_db));
+        self.ledger_cold_db = Some(ledger_cold_db);
+        Ok(self)
+    }
+
     pub(crate) fn enable_storage_sharding(&self) -> bool {
         self.enable_storage_sharding
     }
@@ -319,13 +373,38 @@ impl LedgerDb {
         };
         let env = None;
         let block_cache = None;
-        let ledger_db = Self::new(
-            db_root_path,
+        let mut ledger_db = Self::new(
+            db_root_path.as_ref(),
             rocksdb_configs,
             env,
             block_cache,
             /*readonly=*/ false,
         )?;
+        if db_root_path
+            .as_ref()
+            .join(LEDGER_COLD_DB_FOLDER_NAME)
+            .exists()
+        {
+            ledger_db = ledger_db.with_cold_db(
+                db_root_path,
+                &rocksdb_configs.ledger_db_config,
+                env,
+                block_cache,
+                /*readonly=*/ false,
+            )?;
+        }
+        ledger_db.create_checkpoint_from_open_db(cp_root_path)
+    }
+
+    /// Creates a checkpoint of the already opened ledger DB under `cp_root_path`.
+    ///
+    /// The metadata DB, which carries the overall commit progress, is checkpointed first, so the
+    /// other sub DBs in the checkpoint are never behind it even if commits are ongoing.
+    pub(crate) fn create_checkpoint_from_open_db(
+        &self,
+        cp_root_path: impl AsRef<Path>,
+    ) -> Result<()> {
+        let sharding = self.enable_storage_sharding;
         let cp_ledger_db_folder = cp_root_path.as_ref().join(LEDGER_DB_FOLDER_NAME);
 
         info!(
@@ -338,34 +417,34 @@ impl LedgerDb {
             std::fs::create_dir_all(&cp_ledger_db_folder).unwrap_or(());
         }
 
-        ledger_db
-            .metadata_db()
+        self.metadata_db()
             .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref(), sharding))?;
 
         if sharding {
-            ledger_db
-                .event_db()
+            self.event_db()
                 .create_checkpoint(cp_ledger_db_folder.join(EVENT_DB_NAME))?;
-            ledger_db
-                .persisted_auxiliary_info_db()
+            self.persisted_auxiliary_info_db()
                 .create_checkpoint(cp_ledger_db_folder.join(PERSISTED_AUXILIARY_INFO_DB_NAME))?;
-            ledger_db
-                .transaction_accumulator_db()
+            self.transaction_accumulator_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_ACCUMULATOR_DB_NAME))?;
-            ledger_db
-                .transaction_auxiliary_data_db()
+            self.transaction_auxiliary_data_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_AUXILIARY_DATA_DB_NAME))?;
-            ledger_db
-                .transaction_db()
+            self.transaction_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_DB_NAME))?;
-            ledger_db
-                .transaction_info_db()
+            self.transaction_info_db()
                 .create_checkpoint(cp_ledger_db_folder.join(TRANSACTION_INFO_DB_NAME))?;
-            ledger_db
-                .write_set_db()
+            self.write_set_db()
                 .create_checkpoint(cp_ledger_db_folder.join(WRITE_SET_DB_NAME))?;
         }
 
+        // Moved history is only trimmed from the DBs above after it's persisted in the cold DB,
+        // so checkpointing the cold DB last can't miss any of it.
+        if let Some(ledger_cold_db) = &self.ledger_cold_db {
+            let cp_ledger_cold_db_folder = cp_root_path.as_ref().join(LEDGER_COLD_DB_FOLDER_NAME);
+            std::fs::remove_dir_all(&cp_ledger_cold_db_folder).unwrap_or(());
+            ledger_cold_db.create_checkpoint(cp_ledger_cold_db_folder)?;
+        }
+
         Ok(())
     }
 
@@ -387,6 +466,10 @@ impl LedgerDb {
         Ok(())
     }
 
+    pub(crate) fn ledger_cold_db(&self) -> Option<&Arc<LedgerColdDb>> {
+        self.ledger_cold_db.as_ref()
+    }
+
     pub(crate) fn metadata_db(&self) -> &LedgerMetadataDb {
         &self.ledger_metadata_db
     }
@@ -486,6 +569,7 @@ impl LedgerDb {
     fn get_column_families_by_name(name: &str) -> Vec<ColumnFamilyName> {
         match name {
             LEDGER_DB_NAME => ledger_db_column_families(),
+            LEDGER_COLD_DB_NAME => ledger_cold_db_column_families(),
             LEDGER_METADATA_DB_NAME => ledger_metadata_db_column_families(),
             EVENT_DB_NAME => event_db_column_families(),
             PERSISTED_AUXILIARY_INFO_DB_NAME => persisted_auxiliary_info_db_column_families(),
@@ -505,6 +589,7 @@ impl LedgerDb {
     ) -> Vec<ColumnFamilyDescriptor> {
         match name {
             LEDGER_DB_NAME => gen_ledger_cfds(db_config, cache),
+            LEDGER_COLD_DB_NAME => gen_ledger_cold_cfds(db_config, cache),
             LEDGER_METADATA_DB_NAME => gen_ledger_metadata_cfds(db_config, cache),
             EVENT_DB_NAME => gen_event_cfds(db_config, cache),
             PERSISTED_AUXILIARY_INFO_DB_NAME => gen_persisted_auxiliary_info_cfds(db_config, cache),
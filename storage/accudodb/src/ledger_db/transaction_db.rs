@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    ledger_db::ledger_cold_db::{tiered_iter, LedgerColdDb},
     metrics::OTHER_TIMERS_SECONDS,
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
@@ -26,11 +27,16 @@ use std::{path::Path, sync::Arc};
 #[derive(Debug)]
 pub(crate) struct TransactionDb {
     db: Arc<DB>,
+    cold_db: Option<Arc<LedgerColdDb>>,
 }
 
 impl TransactionDb {
     pub(super) fn new(db: Arc<DB>) -> Self {
-        Self { db }
+        Self { db, cold_db: None }
+    }
+
+    pub(super) fn set_cold_db(&mut self, cold_db: Arc<LedgerColdDb>) {
+        self.cold_db = Some(cold_db);
     }
 
     pub(super) fn create_checkpoint(&self, path: impl AsRef<Path>) -> Result<()> {
@@ -54,9 +60,17 @@ impl TransactionDb {
 
     /// Returns signed transaction given its `version`.
     pub(crate) fn get_transaction(&self, version: Version) -> Result<Transaction> {
-        self.db
-            .get::<TransactionSchema>(&version)?
-            .ok_or_else(|| AccudoDbError::NotFound(format!("Txn {version}")))
+        // The mover writes to the cold DB before deleting from this DB, so falling through
+        // can't miss a transaction being moved.
+        if let Some(txn) = self.db.get::<TransactionSchema>(&version)? {
+            return Ok(txn);
+        }
+        if let Some(cold_db) = &self.cold_db {
+            if let Some(txn) = cold_db.db().get::<TransactionSchema>(&version)? {
+                return Ok(txn);
+            }
+        }
+        Err(AccudoDbError::NotFound(format!("Txn {version}")))
     }
 
     /// Returns an iterator that yields at most `num_transactions` transactions starting from `start_version`.
@@ -65,9 +79,13 @@ impl TransactionDb {
         start_version: Version,
         num_transactions: usize,
     ) -> Result<impl Iterator<Item = Result<Transaction>> + '_> {
-        let mut iter = self.db.iter::<TransactionSchema>()?;
-        iter.seek(&start_version)?;
-        iter.expect_continuous_versions(start_version, num_transactions)
+        tiered_iter::<TransactionSchema, _>(
+            &self.db,
+            self.cold_db.as_deref(),
+            start_version,
+            |version| *version,
+        )?
+        .expect_continuous_versions(start_version, num_transactions)
     }
 
     /// Returns the version of a transaction given its hash.
@@ -165,6 +183,28 @@ impl TransactionDb {
         Ok(())
     }
 
+    /// Copies transactions in version range [begin, end) into `cold_batch`, and deletes them
+    /// from this DB in `hot_batch`.
+    pub(crate) fn move_transactions(
+        &self,
+        begin: Version,
+        end: Version,
+        cold_batch: &mut SchemaBatch,
+        hot_batch: &mut SchemaBatch,
+    ) -> Result<()> {
+        let mut iter = self.db.iter::<TransactionSchema>()?;
+        iter.seek(&begin)?;
+        for item in iter {
+            let (version, transaction) = item?;
+            if version >= end {
+                break;
+            }
+            cold_batch.put::<TransactionSchema>(&version, &transaction)?;
+            hot_batch.delete::<TransactionSchema>(&version)?;
+        }
+        Ok(())
+    }
+
     /// Deletes transaction data given version range [begin, end).
     pub(crate) fn prune_transactions(
         &self,
@@ -1,12 +1,11 @@
 // Copyright © Accudo Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ledger_db::transaction_db::TransactionDb, AccudoDB};
+use crate::ledger_db::{transaction_db::TransactionDb, LedgerDb};
 use accudo_crypto::hash::CryptoHash;
 use accudo_proptest_helpers::Index;
 use accudo_schemadb::batch::SchemaBatch;
 use accudo_storage_interface::Result;
-use accudo_temppath::TempPath;
 use accudo_types::{
     proptest_types::{AccountInfoUniverse, SignatureCheckedTransactionGen},
     transaction::{Transaction, Version},
@@ -24,9 +23,8 @@ proptest! {
             1..10
         ),
     ) {
-        let tmp_dir = TempPath::new();
-        let db = AccudoDB::new_for_test(&tmp_dir);
-        let transaction_db  = db.ledger_db.transaction_db();
+        let ledger_db = LedgerDb::new_in_memory_for_test();
+        let transaction_db  = ledger_db.transaction_db();
         let txns = init_db(universe, gens, transaction_db);
 
         let num_txns = txns.len();
@@ -50,9 +48,8 @@ proptest! {
             1..10
         ),
     ) {
-        let tmp_dir = TempPath::new();
-        let db = AccudoDB::new_for_test(&tmp_dir);
-        let transaction_db  = db.ledger_db.transaction_db();
+        let ledger_db = LedgerDb::new_in_memory_for_test();
+        let transaction_db  = ledger_db.transaction_db();
         let txns = init_db(universe, gens, transaction_db);
 
         let total_num_txns = txns.len();
@@ -104,9 +101,8 @@ proptest! {
             2..10
         ),
     ) {
-        let tmp_dir = TempPath::new();
-        let db = AccudoDB::new_for_test(&tmp_dir);
-        let transaction_db  = db.ledger_db.transaction_db();
+        let ledger_db = LedgerDb::new_in_memory_for_test();
+        let transaction_db  = ledger_db.transaction_db();
         let txns = init_db(universe, gens, transaction_db);
         let num_txns = txns.len();
 
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    ledger_db::ledger_cold_db::{tiered_iter, LedgerColdDb, TieredIter},
     metrics::OTHER_TIMERS_SECONDS,
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
@@ -25,11 +26,16 @@ use std::{path::Path, sync::Arc};
 #[derive(Debug)]
 pub(crate) struct WriteSetDb {
     db: Arc<DB>,
+    cold_db: Option<Arc<LedgerColdDb>>,
 }
 
 impl WriteSetDb {
     pub(super) fn new(db: Arc<DB>) -> Self {
-        Self { db }
+        Self { db, cold_db: None }
+    }
+
+    pub(super) fn set_cold_db(&mut self, cold_db: Arc<LedgerColdDb>) {
+        self.cold_db = Some(cold_db);
     }
 
     pub(super) fn create_checkpoint(&self, path: impl AsRef<Path>) -> Result<()> {
@@ -55,9 +61,18 @@ impl WriteSetDb {
 impl WriteSetDb {
     /// Returns executed transaction vm output given the `version`.
     pub(crate) fn get_write_set(&self, version: Version) -> Result<WriteSet> {
-        self.db
-            .get::<WriteSetSchema>(&version)?
-            .ok_or_else(|| AccudoDbError::NotFound(format!("WriteSet at version {}", version)))
+        if let Some(write_set) = self.db.get::<WriteSetSchema>(&version)? {
+            return Ok(write_set);
+        }
+        if let Some(cold_db) = &self.cold_db {
+            if let Some(write_set) = cold_db.db().get::<WriteSetSchema>(&version)? {
+                return Ok(write_set);
+            }
+        }
+        Err(AccudoDbError::NotFound(format!(
+            "WriteSet at version {}",
+            version
+        )))
     }
 
     /// Returns an iterator that yields `num_transactions` write sets starting from `start_version`.
@@ -66,9 +81,8 @@ impl WriteSetDb {
         start_version: Version,
         num_transactions: usize,
     ) -> Result<impl Iterator<Item = Result<WriteSet>> + '_> {
-        let mut iter = self.db.iter::<WriteSetSchema>()?;
-        iter.seek(&start_version)?;
-        iter.expect_continuous_versions(start_version, num_transactions)
+        self.tiered_iter(start_version)?
+            .expect_continuous_versions(start_version, num_transactions)
     }
 
     /// Returns write sets in `[begin_version, end_version)` half-open range.
@@ -89,8 +103,7 @@ impl WriteSetDb {
             end_version
         );
 
-        let mut iter = self.db.iter::<WriteSetSchema>()?;
-        iter.seek(&begin_version)?;
+        let mut iter = self.tiered_iter(begin_version)?;
 
         let mut ret = Vec::with_capacity((end_version - begin_version) as usize);
         for current_version in begin_version..end_version {
@@ -112,6 +125,15 @@ impl WriteSetDb {
         Ok(ret)
     }
 
+    fn tiered_iter(&self, start_version: Version) -> Result<TieredIter<'_, WriteSetSchema>> {
+        tiered_iter::<WriteSetSchema, _>(
+            &self.db,
+            self.cold_db.as_deref(),
+            start_version,
+            |version| *version,
+        )
+    }
+
     /// Commits write sets starting from `first_version` to the database.
     pub(crate) fn commit_write_sets(
         &self,
@@ -157,6 +179,28 @@ impl WriteSetDb {
         batch.put::<WriteSetSchema>(&version, write_set)
     }
 
+    /// Copies write sets in version range [begin, end) into `cold_batch`, and deletes them from
+    /// this DB in `hot_batch`.
+    pub(crate) fn move_write_sets(
+        &self,
+        begin: Version,
+        end: Version,
+        cold_batch: &mut SchemaBatch,
+        hot_batch: &mut SchemaBatch,
+    ) -> Result<()> {
+        let mut iter = self.db.iter::<WriteSetSchema>()?;
+        iter.seek(&begin)?;
+        for item in iter {
+            let (version, write_set) = item?;
+            if version >= end {
+                break;
+            }
+            cold_batch.put::<WriteSetSchema>(&version, &write_set)?;
+            hot_batch.delete::<WriteSetSchema>(&version)?;
+        }
+        Ok(())
+    }
+
     /// Deletes the write sets between a range of version in [begin, end).
     pub(crate) fn prune(begin: Version, end: Version, db_batch: &mut SchemaBatch) -> Result<()> {
         for version in begin..end {
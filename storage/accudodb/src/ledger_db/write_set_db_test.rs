@@ -1,10 +1,9 @@
 // Copyright © Accudo Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ledger_db::WriteSetDb, AccudoDB};
+use crate::ledger_db::{LedgerDb, WriteSetDb};
 use accudo_schemadb::batch::SchemaBatch;
 use accudo_storage_interface::Result;
-use accudo_temppath::TempPath;
 use accudo_types::{
     transaction::{ExecutionStatus, TransactionAuxiliaryData, TransactionOutput, Version},
     write_set::WriteSet,
@@ -21,9 +20,8 @@ proptest! {
             1..10
         ),
     ) {
-        let tmp_dir = TempPath::new();
-        let db = AccudoDB::new_for_test(&tmp_dir);
-        let write_set_db  = db.ledger_db.write_set_db();
+        let ledger_db = LedgerDb::new_in_memory_for_test();
+        let write_set_db  = ledger_db.write_set_db();
         init_db(&write_sets, write_set_db);
 
         let num_write_sets = write_sets.len();
@@ -41,9 +39,8 @@ proptest! {
             1..10
         ),
     ) {
-        let tmp_dir = TempPath::new();
-        let db = AccudoDB::new_for_test(&tmp_dir);
-        let write_set_db  = db.ledger_db.write_set_db();
+        let ledger_db = LedgerDb::new_in_memory_for_test();
+        let write_set_db  = ledger_db.write_set_db();
         init_db(&write_sets, write_set_db);
 
         let num_write_sets = write_sets.len();
@@ -94,9 +91,8 @@ proptest! {
             2..10
         ),
     ) {
-        let tmp_dir = TempPath::new();
-        let db = AccudoDB::new_for_test(&tmp_dir);
-        let write_set_db  = db.ledger_db.write_set_db();
+        let ledger_db = LedgerDb::new_in_memory_for_test();
+        let write_set_db  = ledger_db.write_set_db();
         init_db(&write_sets, write_set_db);
 
         {
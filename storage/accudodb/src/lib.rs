@@ -36,5 +36,6 @@ mod pruner;
 mod state_kv_db;
 mod state_merkle_db;
 mod state_store;
+mod state_value_history;
 mod transaction_store;
 mod versioned_node_cache;
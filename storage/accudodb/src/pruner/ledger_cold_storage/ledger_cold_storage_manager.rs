@@ -0,0 +1,94 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ledger_db::LedgerDb,
+    metrics::{PRUNER_BATCH_SIZE, PRUNER_WINDOW},
+    pruner::{
+        ledger_cold_storage::{LedgerColdStorageMover, LEDGER_COLD_STORAGE_MOVER_NAME},
+        pruner_worker::PrunerWorker,
+    },
+};
+use accudo_config::config::LedgerColdStorageConfig;
+use accudo_types::transaction::Version;
+use std::sync::Arc;
+
+/// Manages the background worker which moves the ledger history older than the hot window to the
+/// ledger cold DB, meant to be triggered by the commit path like the pruners.
+pub(crate) struct LedgerColdStorageManager {
+    ledger_db: Arc<LedgerDb>,
+    /// Number of the most recent versions kept in the ledger DB.
+    hot_window: Version,
+    /// It is None iff cold storage is not enabled.
+    mover_worker: Option<PrunerWorker>,
+    /// Ideal batch size of the versions to be moved a time.
+    batch_size: usize,
+}
+
+impl LedgerColdStorageManager {
+    /// Creates a worker thread moving data to the cold DB if enabled, in which case the ledger
+    /// cold DB must have been opened in `ledger_db`.
+    pub fn new(ledger_db: Arc<LedgerDb>, config: LedgerColdStorageConfig) -> Self {
+        let mover_worker = if config.enable {
+            Some(Self::init_mover(Arc::clone(&ledger_db), config))
+        } else {
+            None
+        };
+
+        Self {
+            ledger_db,
+            hot_window: config.hot_window,
+            mover_worker,
+            batch_size: config.batch_size,
+        }
+    }
+
+    fn init_mover(ledger_db: Arc<LedgerDb>, config: LedgerColdStorageConfig) -> PrunerWorker {
+        let mover = Arc::new(
+            LedgerColdStorageMover::new(ledger_db)
+                .expect("Failed to create ledger cold storage mover."),
+        );
+
+        PRUNER_WINDOW
+            .with_label_values(&[LEDGER_COLD_STORAGE_MOVER_NAME])
+            .set(config.hot_window as i64);
+
+        PRUNER_BATCH_SIZE
+            .with_label_values(&[LEDGER_COLD_STORAGE_MOVER_NAME])
+            .set(config.batch_size as i64);
+
+        PrunerWorker::new(mover, config.batch_size, "ledger_cold_storage")
+    }
+
+    /// Sets the mover target version when there are enough versions out of the hot window.
+    pub fn maybe_set_target_db_version(&self, latest_version: Version) {
+        let Some(mover_worker) = self.mover_worker.as_ref() else {
+            return;
+        };
+        let boundary = self
+            .ledger_db
+            .ledger_cold_db()
+            .expect("Ledger cold db must be opened.")
+            .boundary();
+        // Only wake up the mover if there are `batch_size` versions out of the hot window.
+        if latest_version >= boundary + self.batch_size as Version + self.hot_window {
+            mover_worker.set_target_db_version(latest_version - self.hot_window);
+        }
+    }
+
+    /// (For tests only.) Sets the target version and waits for the worker thread to finish
+    /// moving.
+    #[cfg(test)]
+    pub fn wake_and_wait_mover(&self, latest_version: Version) {
+        use std::{thread::sleep, time::Duration};
+
+        self.maybe_set_target_db_version(latest_version);
+        while self
+            .mover_worker
+            .as_ref()
+            .is_some_and(|w| w.is_pruning_pending())
+        {
+            sleep(Duration::from_millis(1));
+        }
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+pub(crate) mod ledger_cold_storage_manager;
+
+use crate::{
+    ledger_db::{ledger_cold_db::LedgerColdDb, LedgerDb},
+    metrics::PRUNER_VERSIONS,
+    pruner::db_pruner::DBPruner,
+};
+use accudo_logger::info;
+use accudo_schemadb::batch::SchemaBatch;
+use accudo_storage_interface::Result;
+use accudo_types::transaction::{AtomicVersion, Version};
+use std::{
+    cmp::min,
+    sync::{atomic::Ordering, Arc},
+};
+
+pub const LEDGER_COLD_STORAGE_MOVER_NAME: &str = "ledger_cold_storage_mover";
+
+/// Moves transactions, write sets and events out of the ledger DB into the ledger cold DB.
+///
+/// It's driven by a `PrunerWorker` like the pruners are, "pruning" the ledger DB by copying the
+/// data to the cold DB before deleting it. The progress is the version below which the ledger DB
+/// has been trimmed, which can lag behind the cold DB boundary after a crash, in which case the
+/// range in between is moved again.
+pub(crate) struct LedgerColdStorageMover {
+    ledger_db: Arc<LedgerDb>,
+    ledger_cold_db: Arc<LedgerColdDb>,
+    /// Keeps track of the target version that the mover needs to achieve.
+    target_version: AtomicVersion,
+    progress: AtomicVersion,
+}
+
+impl DBPruner for LedgerColdStorageMover {
+    fn name(&self) -> &'static str {
+        LEDGER_COLD_STORAGE_MOVER_NAME
+    }
+
+    fn prune(&self, max_versions: usize) -> Result<Version> {
+        let mut progress = self.progress();
+        let target_version = self.target_version();
+
+        while progress < target_version {
+            let current_batch_target_version =
+                min(progress + max_versions as Version, target_version);
+
+            info!(
+                progress = progress,
+                target_version = current_batch_target_version,
+                "Moving ledger data to the cold db."
+            );
+            self.move_to_cold_db(progress, current_batch_target_version)?;
+
+            progress = current_batch_target_version;
+            self.record_progress(progress);
+        }
+
+        Ok(target_version)
+    }
+
+    fn progress(&self) -> Version {
+        self.progress.load(Ordering::SeqCst)
+    }
+
+    fn set_target_version(&self, target_version: Version) {
+        self.target_version.store(target_version, Ordering::SeqCst);
+        PRUNER_VERSIONS
+            .with_label_values(&[LEDGER_COLD_STORAGE_MOVER_NAME, "target"])
+            .set(target_version as i64);
+    }
+
+    fn target_version(&self) -> Version {
+        self.target_version.load(Ordering::SeqCst)
+    }
+
+    fn record_progress(&self, progress: Version) {
+        self.progress.store(progress, Ordering::SeqCst);
+        PRUNER_VERSIONS
+            .with_label_values(&[LEDGER_COLD_STORAGE_MOVER_NAME, "progress"])
+            .set(progress as i64);
+    }
+}
+
+impl LedgerColdStorageMover {
+    pub fn new(ledger_db: Arc<LedgerDb>) -> Result<Self> {
+        info!(name = LEDGER_COLD_STORAGE_MOVER_NAME, "Initializing...");
+
+        let ledger_cold_db = Arc::clone(
+            ledger_db
+                .ledger_cold_db()
+                .expect("Ledger cold db must be opened."),
+        );
+        let progress = ledger_cold_db.trim_progress()?;
+        // Data below the boundary but not trimmed yet was left behind by a crash, move it again.
+        let target_version = ledger_cold_db.boundary();
+
+        let mover = LedgerColdStorageMover {
+            ledger_db,
+            ledger_cold_db,
+            target_version: AtomicVersion::new(target_version),
+            progress: AtomicVersion::new(progress),
+        };
+
+        info!(
+            name = mover.name(),
+            progress = progress,
+            boundary = mover.ledger_cold_db.boundary(),
+            "Initialized."
+        );
+
+        Ok(mover)
+    }
+
+    /// Moves the data in [begin, end) to the cold DB. Data is always persisted in the cold DB
+    /// (and exposed to readers via the boundary) before it's deleted from the ledger DB, so it's
+    /// readable from at least one of them at any time.
+    fn move_to_cold_db(&self, begin: Version, end: Version) -> Result<()> {
+        let mut cold_batch = SchemaBatch::new();
+        let mut transaction_batch = SchemaBatch::new();
+        let mut write_set_batch = SchemaBatch::new();
+        let mut event_batch = SchemaBatch::new();
+
+        self.ledger_db.transaction_db().move_transactions(
+            begin,
+            end,
+            &mut cold_batch,
+            &mut transaction_batch,
+        )?;
+        self.ledger_db.write_set_db().move_write_sets(
+            begin,
+            end,
+            &mut cold_batch,
+            &mut write_set_batch,
+        )?;
+        self.ledger_db
+            .event_db()
+            .move_events(begin, end, &mut cold_batch, &mut event_batch)?;
+
+        self.ledger_cold_db.commit_move(cold_batch, end)?;
+
+        self.ledger_db
+            .transaction_db()
+            .write_schemas(transaction_batch)?;
+        self.ledger_db
+            .write_set_db()
+            .write_schemas(write_set_batch)?;
+        self.ledger_db.event_db().write_schemas(event_batch)?;
+
+        self.ledger_cold_db.write_trim_progress(end)
+    }
+}
@@ -4,6 +4,7 @@
 
 mod db_pruner;
 mod db_sub_pruner;
+mod ledger_cold_storage;
 mod ledger_pruner;
 mod pruner_manager;
 mod pruner_utils;
@@ -11,6 +12,7 @@ mod pruner_worker;
 mod state_kv_pruner;
 mod state_merkle_pruner;
 
+pub(crate) use ledger_cold_storage::ledger_cold_storage_manager::LedgerColdStorageManager;
 pub(crate) use ledger_pruner::ledger_pruner_manager::LedgerPrunerManager;
 pub(crate) use pruner_manager::PrunerManager;
 pub(crate) use state_kv_pruner::state_kv_pruner_manager::StateKvPrunerManager;
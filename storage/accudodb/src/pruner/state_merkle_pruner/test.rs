@@ -15,11 +15,13 @@ use crate::{
     state_merkle_db::StateMerkleDb,
     state_store::StateStore,
 };
-use accudo_config::config::{LedgerPrunerConfig, StateMerklePrunerConfig};
+use accudo_config::config::{LedgerPrunerConfig, StateMerklePrunerConfig, StateValueHistoryConfig};
 use accudo_crypto::{hash::CryptoHash, HashValue};
 use accudo_storage_interface::DbReader;
 use accudo_temppath::TempPath;
 use accudo_types::{
+    account_address::AccountAddress,
+    account_config::{AccountResource, ChainIdResource},
     state_store::{
         state_key::StateKey,
         state_value::{StaleStateValueByKeyHashIndex, StaleStateValueIndex, StateValue},
@@ -392,3 +394,92 @@ fn verify_state_value<'a, I: Iterator<Item = (&'a StateKey, &'a (Version, Option
         }
     }
 }
+
+#[test]
+fn test_state_value_history() {
+    for enable_sharding in [false, true] {
+        verify_state_value_history(enable_sharding);
+    }
+}
+
+fn verify_state_value_history(enable_sharding: bool) {
+    let tmp_dir = TempPath::new();
+    let db = if enable_sharding {
+        AccudoDB::new_for_test_with_sharding(&tmp_dir, 0)
+    } else {
+        AccudoDB::new_for_test(&tmp_dir)
+    };
+    let store = &db.state_store;
+
+    let address = AccountAddress::random();
+    let kept_key = StateKey::resource_typed::<AccountResource>(&address).unwrap();
+    let unchanged_key = StateKey::resource_typed::<ChainIdResource>(&address).unwrap();
+    let other_key = StateKey::raw(b"other_key");
+    let value = |i: u8| StateValue::from(vec![i]);
+
+    // Version 0 is committed before the history is kept.
+    store.commit_block_for_test(
+        0,
+        [vec![
+            (kept_key.clone(), Some(value(0))),
+            (unchanged_key.clone(), Some(value(0))),
+            (other_key.clone(), Some(value(0))),
+        ]],
+    );
+    db.state_kv_db
+        .enable_state_value_history(
+            &StateValueHistoryConfig {
+                enable: true,
+                accounts: vec![address],
+                ..Default::default()
+            },
+            1,
+        )
+        .unwrap();
+    store.commit_block_for_test(
+        1,
+        [
+            vec![(other_key.clone(), Some(value(1)))],
+            vec![],
+            vec![(kept_key.clone(), Some(value(3)))],
+            vec![(kept_key.clone(), None)],
+        ],
+    );
+
+    let pruner = StateKvPrunerManager::new(
+        Arc::clone(&db.state_kv_db),
+        LedgerPrunerConfig {
+            enable: true,
+            prune_window: 0,
+            batch_size: 1,
+            user_pruning_window_offset: 0,
+        },
+    );
+    pruner.wake_and_wait_pruner(4 /* latest_version */).unwrap();
+    store.state_kv_pruner.save_min_readable_version(4).unwrap();
+
+    // Pruned, and the history of the key starts at version 1.
+    assert!(db.get_state_value_by_version(&kept_key, 0).is_err());
+    assert!(db.get_state_value_by_version(&other_key, 2).is_err());
+    assert_eq!(
+        store.get_state_value_by_version(&other_key, 0).unwrap(),
+        None
+    );
+
+    for (version, expected) in [
+        (1, Some(value(0))),
+        (2, Some(value(0))),
+        (3, Some(value(3))),
+        (4, None),
+    ] {
+        assert_eq!(
+            db.get_state_value_by_version(&kept_key, version).unwrap(),
+            expected
+        );
+        assert_eq!(
+            db.get_state_value_by_version(&unchanged_key, version)
+                .unwrap(),
+            Some(value(0))
+        );
+    }
+}
@@ -69,6 +69,9 @@ pub enum DbMetadataKey {
     StateMerkleShardRestoreProgress(ShardId, Version),
     TransactionAuxiliaryDataPrunerProgress,
     PersistedAuxiliaryInfoPrunerProgress,
+    LedgerColdStorageProgress,
+    LedgerColdStorageTrimProgress,
+    StateValueHistoryStartVersion(String),
 }
 
 define_schema!(
@@ -22,6 +22,7 @@ pub(crate) mod stale_state_value_index;
 pub(crate) mod stale_state_value_index_by_key_hash;
 pub(crate) mod state_value;
 pub(crate) mod state_value_by_key_hash;
+pub(crate) mod state_value_history;
 pub(crate) mod transaction;
 pub(crate) mod transaction_accumulator;
 pub(crate) mod transaction_accumulator_root_hash;
@@ -54,6 +55,7 @@ pub const STALE_STATE_VALUE_INDEX_BY_KEY_HASH_CF_NAME: ColumnFamilyName =
     "stale_state_value_index_by_key_hash";
 pub const STATE_VALUE_CF_NAME: ColumnFamilyName = "state_value";
 pub const STATE_VALUE_BY_KEY_HASH_CF_NAME: ColumnFamilyName = "state_value_by_key_hash";
+pub const STATE_VALUE_HISTORY_CF_NAME: ColumnFamilyName = "state_value_history";
 pub const STATE_VALUE_INDEX_CF_NAME: ColumnFamilyName = "state_value_index";
 pub const TRANSACTION_CF_NAME: ColumnFamilyName = "transaction";
 pub const TRANSACTION_ACCUMULATOR_CF_NAME: ColumnFamilyName = "transaction_accumulator";
@@ -122,6 +124,7 @@ pub mod fuzzing {
             assert_no_panic_decoding::<super::state_value_by_key_hash::StateValueByKeyHashSchema>(
                 data,
             );
+            assert_no_panic_decoding::<super::state_value_history::StateValueHistorySchema>(data);
             assert_no_panic_decoding::<super::transaction::TransactionSchema>(data);
             assert_no_panic_decoding::<super::transaction_accumulator::TransactionAccumulatorSchema>(
                 data,
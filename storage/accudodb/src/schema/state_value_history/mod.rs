@@ -0,0 +1,66 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the physical storage schema for the state value history, which keeps the
+//! changes to a selected set of state keys regardless of the state kv pruner.
+//!
+//! An Index Key in this data set has 2 pieces of information:
+//!     1. The state key hash
+//!     2. The version associated with the key
+//! The value associated with the key is the serialized State Value.
+//!
+//! ```text
+//! |<-------- key -------->|<------ value ---->|
+//! |  state key hash | version |  state value  |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, STATE_VALUE_HISTORY_CF_NAME};
+use accudo_crypto::HashValue;
+use accudo_schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use accudo_types::{state_store::state_value::StateValue, transaction::Version};
+use anyhow::Result;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::{io::Write, mem::size_of};
+
+type Key = (HashValue, Version);
+
+define_schema!(
+    StateValueHistorySchema,
+    Key,
+    Option<StateValue>,
+    STATE_VALUE_HISTORY_CF_NAME
+);
+
+impl KeyCodec<StateValueHistorySchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut encoded = vec![];
+        encoded.write_all(self.0.as_ref())?;
+        encoded.write_u64::<BigEndian>(!self.1)?;
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        const VERSION_SIZE: usize = size_of::<Version>();
+
+        ensure_slice_len_eq(data, VERSION_SIZE + HashValue::LENGTH)?;
+        let state_key_hash: HashValue = HashValue::from_slice(&data[..HashValue::LENGTH])?;
+        let version = !(&data[HashValue::LENGTH..]).read_u64::<BigEndian>()?;
+        Ok((state_key_hash, version))
+    }
+}
+
+impl ValueCodec<StateValueHistorySchema> for Option<StateValue> {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        bcs::to_bytes(self).map_err(Into::into)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        bcs::from_bytes(data).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,20 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use accudo_crypto::HashValue;
+use accudo_schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        state_key_hash in any::<HashValue>(),
+        version in any::<Version>(),
+        v in any::<Option<StateValue>>(),
+    ) {
+        assert_encode_decode::<StateValueHistorySchema>(&(state_key_hash, version), &v);
+    }
+}
+
+test_no_panic_decoding!(StateValueHistorySchema);
@@ -10,13 +10,17 @@ use crate::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
         state_value::StateValueSchema,
         state_value_by_key_hash::StateValueByKeyHashSchema,
+        state_value_history::StateValueHistorySchema,
     },
+    state_value_history::StateValueHistory,
     utils::{
         truncation_helper::{get_state_kv_commit_progress, truncate_state_kv_db_shards},
         ShardedStateKvSchemaBatch,
     },
 };
-use accudo_config::config::{RocksdbConfig, RocksdbConfigs, StorageDirPaths};
+use accudo_config::config::{
+    RocksdbConfig, RocksdbConfigs, StateValueHistoryConfig, StorageDirPaths,
+};
 use accudo_crypto::hash::CryptoHash;
 use accudo_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use accudo_logger::prelude::info;
@@ -26,7 +30,7 @@ use accudo_schemadb::{
     batch::{SchemaBatch, WriteBatch},
     Cache, Env, ReadOptions, DB,
 };
-use accudo_storage_interface::Result;
+use accudo_storage_interface::{db_ensure as ensure, AccudoDbError, Result};
 use accudo_types::{
     state_store::{state_key::StateKey, state_value::StateValue, NUM_STATE_SHARDS},
     transaction::Version,
@@ -35,7 +39,7 @@ use arr_macro::arr;
 use rayon::prelude::*;
 use std::{
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, OnceLock},
 };
 
 pub const STATE_KV_DB_FOLDER_NAME: &str = "state_kv_db";
@@ -48,6 +52,8 @@ pub struct StateKvDb {
     #[allow(dead_code)] // TODO(HotState): can remove later.
     hot_state_kv_db_shards: Option<[Arc<DB>; NUM_STATE_SHARDS]>,
     enabled_sharding: bool,
+    /// Set once by `enable_state_value_history()`, after the DB is opened.
+    state_value_history: OnceLock<StateValueHistory>,
 }
 
 impl StateKvDb {
@@ -67,6 +73,7 @@ impl StateKvDb {
                 state_kv_db_shards: arr![Arc::clone(&ledger_db); 16],
                 hot_state_kv_db_shards: None,
                 enabled_sharding: false,
+                state_value_history: OnceLock::new(),
             });
         }
 
@@ -159,6 +166,7 @@ impl StateKvDb {
             state_kv_db_shards,
             hot_state_kv_db_shards,
             enabled_sharding: true,
+            state_value_history: OnceLock::new(),
         };
 
         if !readonly {
@@ -233,6 +241,14 @@ impl StateKvDb {
             None,
             false,
         )?;
+        state_kv_db.create_checkpoint_from_open_db(cp_root_path)
+    }
+
+    /// Creates a checkpoint of the already opened (sharded) state KV DB under `cp_root_path`.
+    pub(crate) fn create_checkpoint_from_open_db(
+        &self,
+        cp_root_path: impl AsRef<Path>,
+    ) -> Result<()> {
         let cp_state_kv_db_path = cp_root_path.as_ref().join(STATE_KV_DB_FOLDER_NAME);
 
         info!("Creating state_kv_db checkpoint at: {cp_state_kv_db_path:?}");
@@ -240,13 +256,13 @@ impl StateKvDb {
         std::fs::remove_dir_all(&cp_state_kv_db_path).unwrap_or(());
         std::fs::create_dir_all(&cp_state_kv_db_path).unwrap_or(());
 
-        state_kv_db
+        self
             .metadata_db()
             .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref()))?;
 
         // TODO(HotState): should handle hot state as well.
         for shard_id in 0..NUM_STATE_SHARDS {
-            state_kv_db
+            self
                 .db_shard(shard_id)
                 .create_checkpoint(Self::db_shard_path(
                     cp_root_path.as_ref(),
@@ -258,6 +274,36 @@ impl StateKvDb {
         Ok(())
     }
 
+    /// Starts keeping the history of the state keys selected by `config`, beyond the prune window.
+    /// Keys not selected before have their history kept since `next_version`.
+    pub(crate) fn enable_state_value_history(
+        &self,
+        config: &StateValueHistoryConfig,
+        next_version: Version,
+    ) -> Result<()> {
+        let state_value_history = StateValueHistory::new(config, self.metadata_db(), next_version)?;
+        ensure!(
+            self.state_value_history.set(state_value_history).is_ok(),
+            "State value history is already enabled."
+        );
+        Ok(())
+    }
+
+    pub(crate) fn state_value_history(&self) -> Option<&StateValueHistory> {
+        self.state_value_history.get()
+    }
+
+    /// Returns whether the value of `state_key` at `version` is served by the state value history,
+    /// in which case it's available even if the version is pruned.
+    pub(crate) fn is_state_value_history_kept(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> bool {
+        self.state_value_history()
+            .is_some_and(|history| history.is_kept(state_key, version))
+    }
+
     pub(crate) fn metadata_db(&self) -> &DB {
         &self.state_kv_metadata_db
     }
@@ -376,6 +422,19 @@ impl StateKvDb {
         state_key: &StateKey,
         version: Version,
     ) -> Result<Option<(Version, StateValue)>> {
+        if self.is_state_value_history_kept(state_key, version) {
+            let mut read_opts = ReadOptions::default();
+            read_opts.set_prefix_same_as_start(true);
+            let mut iter = self
+                .db_shard(state_key.get_shard_id())
+                .iter_with_opts::<StateValueHistorySchema>(read_opts)?;
+            iter.seek(&(state_key.hash(), version))?;
+            if let Some(((_, version), value_opt)) = iter.next().transpose()? {
+                return Ok(value_opt.map(|value| (version, value)));
+            }
+            // Not changed since the history started, so the latest value is not pruned.
+        }
+
         let mut read_opts = ReadOptions::default();
 
         // We want `None` if the state_key changes in iteration.
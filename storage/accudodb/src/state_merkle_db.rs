@@ -182,6 +182,15 @@ impl StateMerkleDb {
             /*readonly=*/ false,
             /*max_nodes_per_lru_cache_shard=*/ 0,
         )?;
+        state_merkle_db.create_checkpoint_from_open_db(cp_root_path)
+    }
+
+    /// Creates a checkpoint of the already opened state merkle DB under `cp_root_path`.
+    pub(crate) fn create_checkpoint_from_open_db(
+        &self,
+        cp_root_path: impl AsRef<Path>,
+    ) -> Result<()> {
+        let sharding = self.enable_sharding;
         let cp_state_merkle_db_path = cp_root_path.as_ref().join(STATE_MERKLE_DB_FOLDER_NAME);
 
         info!("Creating state_merkle_db checkpoint at: {cp_state_merkle_db_path:?}");
@@ -191,13 +200,13 @@ impl StateMerkleDb {
             std::fs::create_dir_all(&cp_state_merkle_db_path).unwrap_or(());
         }
 
-        state_merkle_db
+        self
             .metadata_db()
             .create_checkpoint(Self::metadata_db_path(cp_root_path.as_ref(), sharding))?;
 
         if sharding {
             for shard_id in 0..NUM_STATE_SHARDS {
-                state_merkle_db
+                self
                     .db_shard(shard_id)
                     .create_checkpoint(Self::db_shard_path(cp_root_path.as_ref(), shard_id))?;
             }
@@ -215,19 +215,13 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotRestore<K, V> {
         Ok(hash_opt)
     }
 
-    pub fn wait_for_async_commit(&self) -> Result<()> {
-        self.tree_restore
-            .lock()
-            .as_mut()
-            .unwrap()
-            .wait_for_async_commit()
-    }
-}
-
-impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotReceiver<K, V>
-    for StateSnapshotRestore<K, V>
-{
-    fn add_chunk(&mut self, chunk: Vec<(K, V)>, proof: SparseMerkleRangeProof) -> Result<()> {
+    /// Same as `StateSnapshotReceiver::add_chunk()`, except that the chunk is not verified if
+    /// `proof` is `None`, see `JellyfishMerkleRestore::add_chunk_with_optional_proof()`.
+    pub fn add_chunk_with_optional_proof(
+        &mut self,
+        chunk: Vec<(K, V)>,
+        proof: Option<SparseMerkleRangeProof>,
+    ) -> Result<()> {
         let kv_fn = || {
             let _timer = OTHER_TIMERS_SECONDS.timer_with(&["state_value_add_chunk"]);
             self.kv_restore
@@ -243,7 +237,10 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotReceiver<K, V>
                 .lock()
                 .as_mut()
                 .unwrap()
-                .add_chunk_impl(chunk.iter().map(|(k, v)| (k, v.hash())).collect(), proof)
+                .add_chunk_with_optional_proof(
+                    chunk.iter().map(|(k, v)| (k, v.hash())).collect(),
+                    proof,
+                )
         };
         match self.restore_mode {
             StateSnapshotRestoreMode::KvOnly => kv_fn()?,
@@ -259,6 +256,22 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotReceiver<K, V>
         Ok(())
     }
 
+    pub fn wait_for_async_commit(&self) -> Result<()> {
+        self.tree_restore
+            .lock()
+            .as_mut()
+            .unwrap()
+            .wait_for_async_commit()
+    }
+}
+
+impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotReceiver<K, V>
+    for StateSnapshotRestore<K, V>
+{
+    fn add_chunk(&mut self, chunk: Vec<(K, V)>, proof: SparseMerkleRangeProof) -> Result<()> {
+        self.add_chunk_with_optional_proof(chunk, Some(proof))
+    }
+
     fn finish(self) -> Result<()> {
         match self.restore_mode {
             StateSnapshotRestoreMode::KvOnly => self.kv_restore.lock().take().unwrap().finish()?,
@@ -16,12 +16,14 @@ use crate::{
         stale_state_value_index_by_key_hash::StaleStateValueIndexByKeyHashSchema,
         state_value::StateValueSchema,
         state_value_by_key_hash::StateValueByKeyHashSchema,
+        state_value_history::StateValueHistorySchema,
         version_data::VersionDataSchema,
     },
     state_kv_db::StateKvDb,
     state_merkle_db::StateMerkleDb,
     state_restore::{StateSnapshotRestore, StateSnapshotRestoreMode, StateValueWriter},
     state_store::{buffered_state::BufferedState, persisted_state::PersistedState},
+    state_value_history::StateValueHistory,
     utils::{
         iterators::PrefixedStateValueIterator,
         truncation_helper::{
@@ -41,7 +43,10 @@ use accudo_db_indexer_schemas::{
     schema::indexer_metadata::InternalIndexerMetadataSchema,
 };
 use accudo_infallible::Mutex;
-use accudo_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use accudo_jellyfish_merkle::{
+    diff::{JellyfishMerkleDiffIterator, LeafChange},
+    iterator::JellyfishMerkleIterator,
+};
 use accudo_logger::info;
 use accudo_metrics_core::TimerHelper;
 use accudo_schemadb::batch::{NativeBatch, SchemaBatch, WriteBatch};
@@ -805,6 +810,7 @@ impl StateStore {
             state_update_refs,
             sharded_state_kv_batches,
             self.state_kv_db.enabled_sharding(),
+            self.state_kv_db.state_value_history(),
             state_reads,
             latest_state.usage().is_untracked() || current_state.version().is_none(), // ignore_state_cache_miss
         );
@@ -830,6 +836,7 @@ impl StateStore {
         state_update_refs: &PerVersionStateUpdateRefs,
         sharded_state_kv_batches: &mut ShardedStateKvSchemaBatch,
         enable_sharding: bool,
+        state_value_history: Option<&StateValueHistory>,
         sharded_state_cache: &ShardedStateCache,
         ignore_state_cache_miss: bool,
     ) {
@@ -851,6 +858,7 @@ impl StateStore {
                     updates,
                     batch,
                     enable_sharding,
+                    state_value_history,
                     ignore_state_cache_miss,
                 );
             })
@@ -864,6 +872,7 @@ impl StateStore {
         updates: &[(&'kv StateKey, StateUpdateRef<'kv>)],
         batch: &mut NativeBatch,
         enable_sharding: bool,
+        state_value_history: Option<&StateValueHistory>,
         ignore_state_cache_miss: bool,
     ) {
         let _timer = OTHER_TIMERS_SECONDS.timer_with(&[&format!("put_stale_kv_index__{shard_id}")]);
@@ -911,6 +920,10 @@ impl StateStore {
                         key,
                     )
                 }
+
+                if state_value_history.is_some_and(|history| history.kept_since(key).is_some()) {
+                    Self::put_state_value_history(batch, version, key, update_to_cold, &old_entry);
+                }
             }
         }
     }
@@ -947,6 +960,37 @@ impl StateStore {
         }
     }
 
+    /// Records the change to `key` at `version` in the state value history, together with the
+    /// value it replaces, which might predate the history (or come from a state snapshot restore)
+    /// and otherwise be pruned.
+    fn put_state_value_history(
+        batch: &mut NativeBatch,
+        version: Version,
+        key: &StateKey,
+        update: &StateUpdateRef,
+        old_entry: &StateSlot,
+    ) {
+        let key_hash = key.hash();
+        if old_entry.is_occupied() {
+            batch
+                .put::<StateValueHistorySchema>(
+                    &(key_hash, old_entry.expect_value_version()),
+                    &old_entry.as_state_value_opt().cloned(),
+                )
+                .unwrap();
+        }
+        batch
+            .put::<StateValueHistorySchema>(
+                &(key_hash, version),
+                &update
+                    .state_op
+                    .expect_as_write_op()
+                    .as_state_value_opt()
+                    .cloned(),
+            )
+            .unwrap();
+    }
+
     fn put_usage(state: &State, batch: &mut SchemaBatch) -> Result<()> {
         if let Some(version) = state.version() {
             let usage = state.usage();
@@ -1013,6 +1057,29 @@ impl StateStore {
         }))
     }
 
+    /// Iterates through the state changes from the state snapshot at `base_version` to that at
+    /// `version`, ordered by the hashed keys. A deleted key comes with `None`.
+    pub fn get_state_changes_iter(
+        self: &Arc<Self>,
+        base_version: Version,
+        version: Version,
+    ) -> Result<impl Iterator<Item = Result<(StateKey, Option<StateValue>)>> + Send + Sync + use<>>
+    {
+        let store = Arc::clone(self);
+        Ok(JellyfishMerkleDiffIterator::new(
+            Arc::clone(&self.state_merkle_db),
+            base_version,
+            version,
+        )?
+        .map(move |res| match res? {
+            LeafChange::Put(leaf) => {
+                let (key, version) = leaf.value_index();
+                Ok((key.clone(), Some(store.expect_value_by_version(key, *version)?)))
+            },
+            LeafChange::Delete(leaf) => Ok((leaf.value_index().0.clone(), None)),
+        }))
+    }
+
     pub fn get_value_chunk_with_proof(
         self: &Arc<Self>,
         version: Version,
@@ -0,0 +1,168 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keeps a sparse change log of selected state keys which is not pruned by the state kv pruner,
+//! see `StateValueHistoryConfig`.
+//!
+//! Every change to a selected key is written to `StateValueHistorySchema` at commit time, together
+//! with the value it replaces. So for any version since the history of a key is kept, the value is
+//! either found in the history, or the key hasn't changed since then and its current value (or
+//! absence) is still in the state kv DB.
+
+use crate::schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue};
+use accudo_config::config::StateValueHistoryConfig;
+use accudo_logger::info;
+use accudo_schemadb::{batch::SchemaBatch, DB};
+use accudo_storage_interface::{AccudoDbError, Result};
+use accudo_types::{
+    access_path::Path,
+    account_address::AccountAddress,
+    state_store::{
+        state_key::{inner::StateKeyInner, StateKey},
+        table::TableHandle,
+    },
+    transaction::Version,
+};
+use move_core_types::language_storage::StructTag;
+use std::{collections::HashMap, str::FromStr};
+
+/// Selects the state keys whose history is kept.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum StateKeyFilter {
+    /// Resources and modules under an account.
+    Account(AccountAddress),
+    /// A resource (or resource group) type under any account.
+    ResourceType(StructTag),
+    /// Items of a table.
+    TableHandle(TableHandle),
+}
+
+impl StateKeyFilter {
+    fn matches(&self, state_key: &StateKey) -> bool {
+        match (self, state_key.inner()) {
+            (Self::Account(address), StateKeyInner::AccessPath(access_path)) => {
+                access_path.address == *address
+            },
+            (Self::ResourceType(struct_tag), StateKeyInner::AccessPath(access_path)) => {
+                match access_path.get_path() {
+                    Path::Resource(tag) | Path::ResourceGroup(tag) => tag == *struct_tag,
+                    Path::Code(_) => false,
+                }
+            },
+            (Self::TableHandle(handle), StateKeyInner::TableItem { handle: h, .. }) => h == handle,
+            _ => false,
+        }
+    }
+
+    /// The name the start version of the filter is persisted under.
+    fn name(&self) -> String {
+        match self {
+            Self::Account(address) => format!("account:{}", address.to_hex_literal()),
+            Self::ResourceType(struct_tag) => {
+                format!("resource_type:{}", struct_tag.to_canonical_string())
+            },
+            Self::TableHandle(handle) => format!("table_handle:{}", handle.0.to_hex_literal()),
+        }
+    }
+
+    fn from_config(config: &StateValueHistoryConfig) -> Result<Vec<Self>> {
+        let mut filters: Vec<Self> = config
+            .accounts
+            .iter()
+            .map(|address| Self::Account(*address))
+            .collect();
+        for resource_type in &config.resource_types {
+            let struct_tag = StructTag::from_str(resource_type).map_err(|err| {
+                AccudoDbError::Other(format!("Invalid resource type {resource_type:?}: {err}"))
+            })?;
+            filters.push(Self::ResourceType(struct_tag));
+        }
+        filters.extend(
+            config
+                .table_handles
+                .iter()
+                .map(|handle| Self::TableHandle(TableHandle(*handle))),
+        );
+
+        let mut deduped = Vec::with_capacity(filters.len());
+        for filter in filters {
+            if !deduped.contains(&filter) {
+                deduped.push(filter);
+            }
+        }
+        Ok(deduped)
+    }
+}
+
+/// The filters selecting the state keys whose history is kept, each with the version since which
+/// its history is available.
+#[derive(Debug)]
+pub(crate) struct StateValueHistory {
+    filters: Vec<(StateKeyFilter, Version)>,
+}
+
+impl StateValueHistory {
+    /// Loads the start versions of the configured filters from `metadata_db`. Filters not seen
+    /// before start at `next_version`, and the ones no longer configured are forgotten, so that
+    /// adding them back later doesn't claim the history in between.
+    pub fn new(
+        config: &StateValueHistoryConfig,
+        metadata_db: &DB,
+        next_version: Version,
+    ) -> Result<Self> {
+        let mut persisted = HashMap::new();
+        let mut iter = metadata_db.iter::<DbMetadataSchema>()?;
+        iter.seek_to_first();
+        for item in iter {
+            if let (DbMetadataKey::StateValueHistoryStartVersion(name), value) = item? {
+                persisted.insert(name, value.expect_version());
+            }
+        }
+
+        let mut batch = SchemaBatch::new();
+        let mut filters = vec![];
+        for filter in StateKeyFilter::from_config(config)? {
+            let name = filter.name();
+            let start_version = match persisted.remove(&name) {
+                Some(version) => version,
+                None => {
+                    batch.put::<DbMetadataSchema>(
+                        &DbMetadataKey::StateValueHistoryStartVersion(name.clone()),
+                        &DbMetadataValue::Version(next_version),
+                    )?;
+                    next_version
+                },
+            };
+            info!(
+                filter = name,
+                start_version = start_version,
+                "Keeping state value history."
+            );
+            filters.push((filter, start_version));
+        }
+        for name in persisted.into_keys() {
+            info!(filter = name, "Dropping state value history.");
+            batch
+                .delete::<DbMetadataSchema>(&DbMetadataKey::StateValueHistoryStartVersion(name))?;
+        }
+        metadata_db.write_schemas(batch)?;
+
+        Ok(Self { filters })
+    }
+
+    /// Returns the earliest version since which the history of `state_key` is kept, if it's
+    /// selected by any filter.
+    pub fn kept_since(&self, state_key: &StateKey) -> Option<Version> {
+        self.filters
+            .iter()
+            .filter(|(filter, _)| filter.matches(state_key))
+            .map(|(_, start_version)| *start_version)
+            .min()
+    }
+
+    /// Returns whether the value of `state_key` at `version` can be served by the history.
+    pub fn is_kept(&self, state_key: &StateKey, version: Version) -> bool {
+        self.kept_since(state_key)
+            .is_some_and(|start_version| start_version <= version)
+    }
+}
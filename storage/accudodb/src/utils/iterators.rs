@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    ledger_db::ledger_cold_db::TieredIter,
     schema::{
         event::EventSchema, ledger_info::LedgerInfoSchema, state_value::StateValueSchema,
         transaction_summaries_by_account::TransactionSummariesByAccountSchema,
@@ -242,14 +243,14 @@ impl Iterator for EpochEndingLedgerInfoIter<'_> {
 }
 
 pub struct EventsByVersionIter<'a> {
-    inner: Peekable<SchemaIterator<'a, EventSchema>>,
+    inner: Peekable<TieredIter<'a, EventSchema>>,
     expected_next_version: Version,
     end_version: Version,
 }
 
 impl<'a> EventsByVersionIter<'a> {
     pub(crate) fn new(
-        inner: SchemaIterator<'a, EventSchema>,
+        inner: TieredIter<'a, EventSchema>,
         expected_next_version: Version,
         end_version: Version,
     ) -> Self {
@@ -19,6 +19,7 @@ use crate::{
         stale_state_value_index_by_key_hash::StaleStateValueIndexByKeyHashSchema,
         state_value::StateValueSchema,
         state_value_by_key_hash::StateValueByKeyHashSchema,
+        state_value_history::StateValueHistorySchema,
         transaction::TransactionSchema,
         transaction_accumulator::TransactionAccumulatorSchema,
         transaction_accumulator_root_hash::TransactionAccumulatorRootHashSchema,
@@ -565,6 +566,10 @@ fn delete_state_value_and_index(
                 index.state_key_hash,
                 index.stale_since_version,
             ))?;
+            batch.delete::<StateValueHistorySchema>(&(
+                index.state_key_hash,
+                index.stale_since_version,
+            ))?;
         }
     } else {
         let mut iter = state_kv_db_shard.iter::<StaleStateValueIndexSchema>()?;
@@ -573,6 +578,10 @@ fn delete_state_value_and_index(
         for item in iter {
             let (index, _) = item?;
             batch.delete::<StaleStateValueIndexSchema>(&index)?;
+            batch.delete::<StateValueHistorySchema>(&(
+                index.state_key.hash(),
+                index.stale_since_version,
+            ))?;
             batch.delete::<StateValueSchema>(&(index.state_key, index.stale_since_version))?;
         }
     }
@@ -69,6 +69,7 @@ fn end_to_end() {
                 GlobalBackupOpt {
                     max_chunk_size: 1024,
                     concurrent_data_requests: 2,
+                    dedup_state_snapshot_chunks: false,
                 },
                 client,
                 Arc::clone(&store),
@@ -201,6 +202,7 @@ async fn test_trusted_waypoints_impl(
             GlobalBackupOpt {
                 max_chunk_size: 1024,
                 concurrent_data_requests: 2,
+                dedup_state_snapshot_chunks: false,
             },
             client.clone(),
             Arc::clone(&store),
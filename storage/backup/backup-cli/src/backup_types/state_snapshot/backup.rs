@@ -4,7 +4,7 @@
 
 use crate::{
     backup_types::state_snapshot::manifest::{StateSnapshotBackup, StateSnapshotChunk},
-    metadata::Metadata,
+    metadata::{self, cache::MetadataCacheOpt, Metadata},
     metrics::backup::BACKUP_TIMER,
     storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
     utils::{
@@ -26,7 +26,9 @@ use bytes::{BufMut, Bytes, BytesMut};
 use clap::Parser;
 use futures::{StreamExt, TryStream, TryStreamExt};
 use once_cell::sync::Lazy;
-use std::{convert::TryInto, str::FromStr, sync::Arc, time::Instant};
+use std::{
+    collections::HashMap, convert::TryInto, path::PathBuf, str::FromStr, sync::Arc, time::Instant,
+};
 use tokio::{io::AsyncWriteExt, sync::mpsc::Sender};
 use tokio_stream::wrappers::ReceiverStream;
 
@@ -55,13 +57,18 @@ struct ChunkerState<RecordStream> {
     current_idx: usize,
     chunk_first_idx: usize,
     max_chunk_size: usize,
+    align_to_keys: bool,
 }
 
 impl<RecordStream> ChunkerState<RecordStream>
 where
     RecordStream: TryStream<Ok = Bytes, Error = anyhow::Error> + Unpin,
 {
-    async fn new(mut record_stream: RecordStream, max_chunk_size: usize) -> Result<Self> {
+    async fn new(
+        mut record_stream: RecordStream,
+        max_chunk_size: usize,
+        align_to_keys: bool,
+    ) -> Result<Self> {
         let first_record = record_stream
             .try_next()
             .await?
@@ -82,6 +89,7 @@ where
             current_idx: 0,
             chunk_first_idx: 0,
             max_chunk_size,
+            align_to_keys,
         })
     }
 
@@ -97,25 +105,26 @@ where
             let _timer = BACKUP_TIMER.timer_with(&["state_snapshot_process_records"]);
 
             // If buf + current_record exceeds max_chunk_size, dump current buf to a new chunk
-            let chunk_cut_opt = should_cut_chunk(&self.buf, &record_bytes, self.max_chunk_size)
-                .then(|| {
-                    let bytes = self.buf.split().freeze();
-                    let last_key = Self::parse_key(&bytes[bytes.len() - self.prev_record_len..])?;
-
-                    let chunk = Chunk {
-                        bytes,
-                        first_key: self.chunk_first_key,
-                        first_idx: self.chunk_first_idx,
-                        last_key,
-                        last_idx: self.current_idx,
-                    };
-
-                    self.chunk_first_idx = self.current_idx + 1;
-                    self.chunk_first_key = Self::parse_key(&record_bytes)?;
-
-                    Result::<_>::Ok(chunk)
-                })
-                .transpose()?;
+            let chunk_cut_opt = (should_cut_chunk(&self.buf, &record_bytes, self.max_chunk_size)
+                || self.is_aligned_boundary(&record_bytes)?)
+            .then(|| {
+                let bytes = self.buf.split().freeze();
+                let last_key = Self::parse_key(&bytes[bytes.len() - self.prev_record_len..])?;
+
+                let chunk = Chunk {
+                    bytes,
+                    first_key: self.chunk_first_key,
+                    first_idx: self.chunk_first_idx,
+                    last_key,
+                    last_idx: self.current_idx,
+                };
+
+                self.chunk_first_idx = self.current_idx + 1;
+                self.chunk_first_key = Self::parse_key(&record_bytes)?;
+
+                Result::<_>::Ok(chunk)
+            })
+            .transpose()?;
 
             // Append record to buf
             self.prev_record_len = record_bytes.len();
@@ -145,6 +154,7 @@ where
             current_idx,
             chunk_first_idx,
             max_chunk_size: _,
+            align_to_keys: _,
         } = self;
         ensure!(
             state_snapshot_file.is_none(),
@@ -164,6 +174,18 @@ where
         })
     }
 
+    /// When aligning to keys, a chunk is also cut before a key whose hash has the leading 12 bits
+    /// being 0, once the chunk is big enough. This way an update to the state only changes the
+    /// chunk containing it (and potentially the next one), instead of shifting all the following
+    /// chunk boundaries.
+    fn is_aligned_boundary(&self, record: &[u8]) -> Result<bool> {
+        if !self.align_to_keys || self.buf.len() < self.max_chunk_size / 4 {
+            return Ok(false);
+        }
+        let key = Self::parse_key(record)?;
+        Ok(key[0] == 0 && key[1] < 0x10)
+    }
+
     fn parse_key(record: &[u8]) -> Result<HashValue> {
         let (key, _): (StateKey, StateValue) = bcs::from_bytes(record)?;
         Ok(key.hash())
@@ -178,9 +200,13 @@ impl<RecordStream> Chunker<RecordStream>
 where
     RecordStream: TryStream<Ok = Bytes, Error = anyhow::Error> + Unpin,
 {
-    async fn new(record_stream: RecordStream, max_chunk_size: usize) -> Result<Self> {
+    async fn new(
+        record_stream: RecordStream,
+        max_chunk_size: usize,
+        align_to_keys: bool,
+    ) -> Result<Self> {
         Ok(Self {
-            state: Some(ChunkerState::new(record_stream, max_chunk_size).await?),
+            state: Some(ChunkerState::new(record_stream, max_chunk_size, align_to_keys).await?),
         })
     }
 
@@ -204,6 +230,9 @@ pub struct StateSnapshotBackupController {
     client: Arc<BackupServiceClient>,
     storage: Arc<dyn BackupStorage>,
     concurrent_data_requests: usize,
+    dedup_chunks: bool,
+    /// Chunks in the previous snapshot by digest, populated when `dedup_chunks`.
+    known_chunks: HashMap<HashValue, FileHandle>,
 }
 
 impl StateSnapshotBackupController {
@@ -220,6 +249,8 @@ impl StateSnapshotBackupController {
             client,
             storage,
             concurrent_data_requests: global_opt.concurrent_data_requests,
+            dedup_chunks: global_opt.dedup_state_snapshot_chunks,
+            known_chunks: HashMap::new(),
         }
     }
 
@@ -235,13 +266,16 @@ impl StateSnapshotBackupController {
 
     async fn run_impl(mut self) -> Result<FileHandle> {
         self.version = Some(self.get_version_for_epoch_ending(self.epoch).await?);
+        if self.dedup_chunks {
+            self.known_chunks = self.load_known_chunks().await?;
+        }
         let backup_handle = self
             .storage
             .create_backup_with_random_suffix(&self.backup_name())
             .await?;
 
         let record_stream = Box::pin(self.record_stream(self.concurrent_data_requests).await?);
-        let chunker = Chunker::new(record_stream, self.max_chunk_size).await?;
+        let chunker = Chunker::new(record_stream, self.max_chunk_size, self.dedup_chunks).await?;
 
         let start = Instant::now();
         let chunk_stream = futures::stream::try_unfold(chunker, |mut chunker| async {
@@ -387,6 +421,42 @@ impl StateSnapshotBackupController {
             .unwrap()
     }
 
+    /// Loads the chunks of the latest existing snapshot encrypted with the same key, if any.
+    async fn load_known_chunks(&self) -> Result<HashMap<HashValue, FileHandle>> {
+        let metadata_view = metadata::cache::sync_and_load(
+            &MetadataCacheOpt::new(None::<PathBuf>),
+            self.storage.clone(),
+            self.concurrent_data_requests,
+        )
+        .await?;
+        let encryption_key_id = self.storage.encryption_key_id();
+        let previous = match metadata_view
+            .all_state_snapshots()
+            .iter()
+            .filter(|s| s.version <= self.version() && s.encryption_key_id == encryption_key_id)
+            .max_by_key(|s| s.version)
+        {
+            Some(previous) => previous,
+            None => {
+                info!("No previous state snapshot to deduplicate chunks against.");
+                return Ok(HashMap::new());
+            },
+        };
+
+        let manifest: StateSnapshotBackup = self.storage.load_json_file(&previous.manifest).await?;
+        let known_chunks: HashMap<_, _> = manifest
+            .chunks
+            .into_iter()
+            .filter_map(|chunk| chunk.blobs_digest.map(|digest| (digest, chunk.blobs)))
+            .collect();
+        info!(
+            previous_version = previous.version,
+            num_known_chunks = known_chunks.len(),
+            "Deduplicating state snapshot chunks against previous snapshot."
+        );
+        Ok(known_chunks)
+    }
+
     async fn get_version_for_epoch_ending(&self, epoch: u64) -> Result<u64> {
         let ledger_info: LedgerInfoWithSignatures = bcs::from_bytes(
             self.client
@@ -417,12 +487,19 @@ impl StateSnapshotBackupController {
             last_key,
         } = chunk;
 
-        let (chunk_handle, mut chunk_file) = self
-            .storage
-            .create_for_write(backup_handle, &Self::chunk_name(first_idx))
-            .await?;
-        chunk_file.write_all(&bytes).await?;
-        chunk_file.shutdown().await?;
+        let blobs_digest = HashValue::sha3_256_of(&bytes);
+        let chunk_handle = match self.known_chunks.get(&blobs_digest) {
+            Some(known_handle) => known_handle.clone(),
+            None => {
+                let (chunk_handle, mut chunk_file) = self
+                    .storage
+                    .create_for_write(backup_handle, &Self::chunk_name(first_idx))
+                    .await?;
+                chunk_file.write_all(&bytes).await?;
+                chunk_file.shutdown().await?;
+                chunk_handle
+            },
+        };
         let (proof_handle, mut proof_file) = self
             .storage
             .create_for_write(backup_handle, &Self::chunk_proof_name(first_idx, last_idx))
@@ -443,6 +520,7 @@ impl StateSnapshotBackupController {
             first_key,
             last_key,
             blobs: chunk_handle,
+            blobs_digest: Some(blobs_digest),
             proof: proof_handle,
         })
     }
@@ -484,6 +562,7 @@ impl StateSnapshotBackupController {
             self.epoch,
             self.version(),
             manifest_handle.clone(),
+            self.storage.encryption_key_id(),
         );
         self.storage
             .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
@@ -22,6 +22,11 @@ pub struct StateSnapshotChunk {
     /// Repeated `len(record) + record` where `record` is BCS serialized tuple
     /// `(key, state_value)`
     pub blobs: FileHandle,
+    /// SHA3-256 of the (unencrypted) content of `blobs`, verified upon restore. It's also how a
+    /// chunk identical to one in a previous snapshot is recognized and shared, in which case
+    /// `blobs` points to a file in the previous backup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blobs_digest: Option<HashValue>,
     /// BCS serialized `SparseMerkleRangeProof` that proves this chunk adds up to the root hash
     /// indicated in the backup (`StateSnapshotBackup::root_hash`).
     pub proof: FileHandle,
@@ -22,6 +22,7 @@ use crate::{
         GlobalRestoreOptions, RestoreRunMode,
     },
 };
+use accudo_crypto::HashValue;
 use accudo_db::state_restore::StateSnapshotRestoreMode;
 use accudo_infallible::Mutex;
 use accudo_logger::prelude::*;
@@ -191,7 +192,8 @@ impl StateSnapshotRestoreController {
             let storage = storage.clone();
             async move {
                 tokio::spawn(async move {
-                    let blobs = Self::read_state_value(&storage, chunk.blobs.clone()).await?;
+                    let blobs =
+                        Self::read_state_value(&storage, &chunk.blobs, chunk.blobs_digest).await?;
                     let proof = storage.load_bcs_file(&chunk.proof).await?;
                     Result::<_>::Ok((chunk_idx, chunk, blobs, proof))
                 })
@@ -233,7 +235,7 @@ impl StateSnapshotRestoreController {
         Ok(())
     }
 
-    fn validate_modules(blob: &[(StateKey, StateValue)]) {
+    pub(crate) fn validate_modules(blob: &[(StateKey, StateValue)]) {
         // TODO: Instead of using default features, fetch them from the state.
         let features = Features::default();
 
@@ -253,12 +255,24 @@ impl StateSnapshotRestoreController {
         }
     }
 
-    async fn read_state_value(
+    pub(crate) async fn read_state_value(
         storage: &Arc<dyn BackupStorage>,
-        file_handle: FileHandle,
+        file_handle: &FileHandle,
+        expected_digest: Option<HashValue>,
     ) -> Result<Vec<(StateKey, StateValue)>> {
-        let mut file = storage.open_for_read(&file_handle).await?;
+        let bytes = storage.read_all(file_handle).await?;
+        if let Some(expected_digest) = expected_digest {
+            let digest = HashValue::sha3_256_of(&bytes);
+            ensure!(
+                digest == expected_digest,
+                "State snapshot chunk {} corrupted, digest: {}, expected: {}",
+                file_handle,
+                digest,
+                expected_digest,
+            );
+        }
 
+        let mut file = bytes.as_slice();
         let mut chunk = vec![];
 
         while let Some(record_bytes) = file.read_record_bytes().await? {
@@ -5,11 +5,17 @@
 use crate::{
     backup_types::state_snapshot::{
         backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        manifest::StateSnapshotBackup,
         restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
     },
-    storage::{local_fs::LocalFs, BackupStorage},
+    storage::{
+        encrypted::{EncryptedStorage, EncryptionKey},
+        local_fs::LocalFs,
+        BackupStorage,
+    },
     utils::{
         backup_service_client::BackupServiceClient,
+        storage_ext::BackupStorageExt,
         test_utils::{start_local_backup_service, tmp_db_with_random_content},
         ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, ReplayConcurrencyLevelOpt,
         RocksdbOpt, TrustedWaypointOpt,
@@ -66,6 +72,7 @@ fn end_to_end() {
                 GlobalBackupOpt {
                     max_chunk_size: 500,
                     concurrent_data_requests: 2,
+                    dedup_state_snapshot_chunks: false,
                 },
                 client,
                 Arc::clone(&store),
@@ -112,3 +119,103 @@ fn end_to_end() {
 
     rt.shutdown_timeout(Duration::from_secs(1));
 }
+
+#[test]
+fn dedup_and_encryption() {
+    let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
+    let tgt_db_dir = TempPath::new();
+    tgt_db_dir.create_as_dir().unwrap();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(EncryptedStorage::new(
+        Arc::new(LocalFs::new(backup_dir.path().to_path_buf())),
+        EncryptionKey::new(&[1u8; 32]).unwrap(),
+        false, /* allow_plaintext */
+    ));
+
+    let epoch = src_db
+        .get_latest_ledger_info()
+        .unwrap()
+        .ledger_info()
+        .next_block_epoch()
+        - 1;
+    let version = src_db
+        .get_epoch_ending_ledger_infos(epoch, epoch + 1)
+        .unwrap()
+        .ledger_info_with_sigs
+        .pop()
+        .unwrap()
+        .ledger_info()
+        .version();
+
+    let (rt, port) = start_local_backup_service(src_db);
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+    let backup = || {
+        rt.block_on(
+            StateSnapshotBackupController::new(
+                StateSnapshotBackupOpt { epoch },
+                GlobalBackupOpt {
+                    max_chunk_size: 500,
+                    concurrent_data_requests: 2,
+                    dedup_state_snapshot_chunks: true,
+                },
+                client.clone(),
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap()
+    };
+    let first_manifest_handle = backup();
+    let second_manifest_handle = backup();
+    assert_ne!(first_manifest_handle, second_manifest_handle);
+
+    // The second snapshot reuses all chunks of the first one.
+    let load_manifest = |handle: &str| -> StateSnapshotBackup {
+        rt.block_on(store.load_json_file(handle)).unwrap()
+    };
+    let first_manifest = load_manifest(&first_manifest_handle);
+    let second_manifest = load_manifest(&second_manifest_handle);
+    assert_eq!(first_manifest.chunks.len(), second_manifest.chunks.len());
+    for (first, second) in first_manifest
+        .chunks
+        .iter()
+        .zip(second_manifest.chunks.iter())
+    {
+        assert_eq!(first.blobs, second.blobs);
+        assert_eq!(first.blobs_digest, second.blobs_digest);
+        assert!(first.blobs_digest.is_some());
+    }
+
+    rt.block_on(
+        StateSnapshotRestoreController::new(
+            StateSnapshotRestoreOpt {
+                manifest_handle: second_manifest_handle,
+                version,
+                validate_modules: false,
+                restore_mode: StateSnapshotRestoreMode::Default,
+            },
+            GlobalRestoreOpt {
+                dry_run: true,
+                db_dir: None,
+                target_version: None, // max
+                trusted_waypoints: TrustedWaypointOpt::default(),
+                rocksdb_opt: RocksdbOpt::default(),
+                concurrent_downloads: ConcurrentDownloadsOpt::default(),
+                replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+                enable_state_indices: false,
+            }
+            .try_into()
+            .unwrap(),
+            store,
+            None, /* epoch_history */
+        )
+        .run(),
+    )
+    .unwrap();
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
@@ -0,0 +1,330 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::state_snapshot_incremental::manifest::{
+        StateSnapshotIncrementalBackup, StateSnapshotIncrementalChunk,
+    },
+    metadata::Metadata,
+    storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
+    utils::{
+        backup_service_client::BackupServiceClient, read_record_bytes::ReadRecordBytes,
+        should_cut_chunk, storage_ext::BackupStorageExt, GlobalBackupOpt,
+    },
+};
+use accudo_crypto::{hash::CryptoHash, HashValue};
+use accudo_logger::prelude::*;
+use accudo_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::TransactionInfoWithProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use anyhow::{anyhow, ensure, Result};
+use clap::Parser;
+use once_cell::sync::Lazy;
+use std::{convert::TryInto, str::FromStr, sync::Arc};
+use tokio::io::{AsyncRead, AsyncWriteExt};
+
+#[derive(Parser)]
+pub struct StateSnapshotIncrementalBackupOpt {
+    #[clap(
+        long = "base-state-snapshot-epoch",
+        help = "Epoch at the end of which the state snapshot this is based on was taken."
+    )]
+    pub base_epoch: u64,
+    #[clap(
+        long = "state-snapshot-epoch",
+        help = "Epoch at the end of which a state snapshot is to be taken."
+    )]
+    pub epoch: u64,
+}
+
+/// Changes accumulated for the next chunk.
+#[derive(Default)]
+struct ChunkBuf {
+    bytes: Vec<u8>,
+    first_key: Option<HashValue>,
+    last_key: Option<HashValue>,
+    last_existing_key: Option<HashValue>,
+}
+
+pub struct StateSnapshotIncrementalBackupController {
+    base_epoch: u64,
+    epoch: u64,
+    max_chunk_size: usize,
+    client: Arc<BackupServiceClient>,
+    storage: Arc<dyn BackupStorage>,
+}
+
+impl StateSnapshotIncrementalBackupController {
+    pub fn new(
+        opt: StateSnapshotIncrementalBackupOpt,
+        global_opt: GlobalBackupOpt,
+        client: Arc<BackupServiceClient>,
+        storage: Arc<dyn BackupStorage>,
+    ) -> Self {
+        Self {
+            base_epoch: opt.base_epoch,
+            epoch: opt.epoch,
+            max_chunk_size: global_opt.max_chunk_size,
+            client,
+            storage,
+        }
+    }
+
+    pub async fn run(self) -> Result<FileHandle> {
+        info!(
+            "Incremental state snapshot backup started, for epoch {}, based on epoch {}.",
+            self.epoch, self.base_epoch,
+        );
+        let ret = self
+            .run_impl()
+            .await
+            .map_err(|e| anyhow!("Incremental state snapshot backup failed: {}", e))?;
+        info!(
+            "Incremental state snapshot backup succeeded. Manifest: {}",
+            ret
+        );
+        Ok(ret)
+    }
+}
+
+impl StateSnapshotIncrementalBackupController {
+    async fn run_impl(self) -> Result<FileHandle> {
+        ensure!(
+            self.base_epoch <= self.epoch,
+            "Base epoch {} is newer than epoch {}.",
+            self.base_epoch,
+            self.epoch,
+        );
+        let base_version = self.get_version_for_epoch_ending(self.base_epoch).await?;
+        let version = self.get_version_for_epoch_ending(self.epoch).await?;
+
+        let backup_handle = self
+            .storage
+            .create_backup_with_random_suffix(&self.backup_name(base_version, version))
+            .await?;
+
+        let mut chunks = Vec::new();
+        let mut chunk = ChunkBuf::default();
+        let mut num_changes = 0;
+
+        let mut changes_file = self.client.get_state_changes(base_version, version).await?;
+        while let Some(record_bytes) = changes_file.read_record_bytes().await? {
+            if should_cut_chunk(&chunk.bytes, &record_bytes, self.max_chunk_size) {
+                let chunk = std::mem::take(&mut chunk);
+                chunks.push(
+                    self.write_chunk(&backup_handle, version, chunks.len(), chunk)
+                        .await?,
+                );
+            }
+
+            let (key, value): (StateKey, Option<StateValue>) = bcs::from_bytes(&record_bytes)?;
+            let key_hash = key.hash();
+            chunk.first_key.get_or_insert(key_hash);
+            chunk.last_key = Some(key_hash);
+            if value.is_some() {
+                chunk.last_existing_key = Some(key_hash);
+            }
+            chunk
+                .bytes
+                .extend((record_bytes.len() as u32).to_be_bytes());
+            chunk.bytes.extend(&record_bytes);
+            num_changes += 1;
+        }
+        if !chunk.bytes.is_empty() {
+            chunks.push(
+                self.write_chunk(&backup_handle, version, chunks.len(), chunk)
+                    .await?,
+            );
+        }
+        info!(
+            base_version = base_version,
+            version = version,
+            num_changes = num_changes,
+            "State changes written."
+        );
+
+        self.write_manifest(&backup_handle, base_version, version, chunks)
+            .await
+    }
+
+    fn backup_name(&self, base_version: Version, version: Version) -> String {
+        format!(
+            "state_incremental_epoch_{}_ver_{}-{}",
+            self.epoch, base_version, version
+        )
+    }
+
+    fn manifest_name() -> &'static ShellSafeName {
+        static NAME: Lazy<ShellSafeName> =
+            Lazy::new(|| ShellSafeName::from_str("state_incremental.manifest").unwrap());
+        &NAME
+    }
+
+    fn proof_name() -> &'static ShellSafeName {
+        static NAME: Lazy<ShellSafeName> =
+            Lazy::new(|| ShellSafeName::from_str("state.proof").unwrap());
+        &NAME
+    }
+
+    fn rightmost_key_proof_name() -> &'static ShellSafeName {
+        static NAME: Lazy<ShellSafeName> =
+            Lazy::new(|| ShellSafeName::from_str("rightmost_key.proof").unwrap());
+        &NAME
+    }
+
+    fn chunk_name(chunk_idx: usize) -> ShellSafeName {
+        format!("{}.chunk", chunk_idx).try_into().unwrap()
+    }
+
+    fn chunk_proof_name(chunk_idx: usize) -> ShellSafeName {
+        format!("{}.proof", chunk_idx).try_into().unwrap()
+    }
+
+    async fn get_version_for_epoch_ending(&self, epoch: u64) -> Result<u64> {
+        let ledger_info: LedgerInfoWithSignatures = bcs::from_bytes(
+            self.client
+                .get_epoch_ending_ledger_infos(epoch, epoch + 1)
+                .await?
+                .read_record_bytes()
+                .await?
+                .ok_or_else(|| {
+                    anyhow!("Failed to get epoch ending ledger info for epoch {}", epoch)
+                })?
+                .as_ref(),
+        )?;
+        Ok(ledger_info.ledger_info().version())
+    }
+
+    async fn write_file(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+        mut content: impl AsyncRead + Unpin,
+    ) -> Result<FileHandle> {
+        let (file_handle, mut file) = self.storage.create_for_write(backup_handle, name).await?;
+        tokio::io::copy(&mut content, &mut file).await?;
+        file.shutdown().await?;
+        Ok(file_handle)
+    }
+
+    async fn write_chunk(
+        &self,
+        backup_handle: &BackupHandleRef,
+        version: Version,
+        chunk_idx: usize,
+        chunk: ChunkBuf,
+    ) -> Result<StateSnapshotIncrementalChunk> {
+        let ChunkBuf {
+            bytes,
+            first_key,
+            last_key,
+            last_existing_key,
+        } = chunk;
+
+        let blobs = self
+            .write_file(
+                backup_handle,
+                &Self::chunk_name(chunk_idx),
+                bytes.as_slice(),
+            )
+            .await?;
+        let proof = match last_existing_key {
+            Some(key) => Some(
+                self.write_file(
+                    backup_handle,
+                    &Self::chunk_proof_name(chunk_idx),
+                    self.client.get_account_range_proof(key, version).await?,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        Ok(StateSnapshotIncrementalChunk {
+            first_key: first_key.expect("Chunk is not empty."),
+            last_key: last_key.expect("Chunk is not empty."),
+            blobs,
+            last_existing_key,
+            proof,
+        })
+    }
+
+    /// Finds the last key in the full state at `version`, if any.
+    async fn get_rightmost_key(&self, version: Version) -> Result<Option<HashValue>> {
+        let count = self.client.get_state_item_count(version).await?;
+        if count == 0 {
+            return Ok(None);
+        }
+        let record_bytes = self
+            .client
+            .get_state_snapshot_chunk(version, count - 1, 1)
+            .await?
+            .read_record_bytes()
+            .await?
+            .ok_or_else(|| anyhow!("Failed to get the last key at version {}", version))?;
+        let (key, _): (StateKey, StateValue) = bcs::from_bytes(&record_bytes)?;
+        Ok(Some(key.hash()))
+    }
+
+    async fn write_manifest(
+        &self,
+        backup_handle: &BackupHandleRef,
+        base_version: Version,
+        version: Version,
+        chunks: Vec<StateSnapshotIncrementalChunk>,
+    ) -> Result<FileHandle> {
+        let proof_bytes = self.client.get_state_root_proof(version).await?;
+        let (txn_info, _): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            bcs::from_bytes(&proof_bytes)?;
+        let proof = self
+            .write_file(backup_handle, Self::proof_name(), proof_bytes.as_slice())
+            .await?;
+
+        let rightmost_key = self.get_rightmost_key(version).await?;
+        let rightmost_key_proof = match rightmost_key {
+            Some(key) => Some(
+                self.write_file(
+                    backup_handle,
+                    Self::rightmost_key_proof_name(),
+                    self.client.get_account_range_proof(key, version).await?,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        let manifest = StateSnapshotIncrementalBackup {
+            base_version,
+            version,
+            epoch: self.epoch,
+            root_hash: txn_info.transaction_info().ensure_state_checkpoint_hash()?,
+            chunks,
+            rightmost_key,
+            rightmost_key_proof,
+            proof,
+        };
+        let manifest_handle = self
+            .write_file(
+                backup_handle,
+                Self::manifest_name(),
+                serde_json::to_vec(&manifest)?.as_slice(),
+            )
+            .await?;
+
+        let metadata = Metadata::new_state_snapshot_incremental_backup(
+            self.epoch,
+            base_version,
+            version,
+            manifest_handle.clone(),
+            self.storage.encryption_key_id(),
+        );
+        self.storage
+            .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
+            .await?;
+
+        Ok(manifest_handle)
+    }
+}
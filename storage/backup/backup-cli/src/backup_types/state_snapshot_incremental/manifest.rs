@@ -0,0 +1,52 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::FileHandle;
+use accudo_crypto::HashValue;
+use accudo_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+
+/// A chunk of an incremental state snapshot manifest, representing changes to keys in the key
+/// range [`first_key`, `last_key`] (right side inclusive).
+#[derive(Deserialize, Serialize)]
+pub struct StateSnapshotIncrementalChunk {
+    /// key of the first change in this chunk.
+    pub first_key: HashValue,
+    /// key of the last change in this chunk.
+    pub last_key: HashValue,
+    /// Repeated `len(record) + record` where `record` is BCS serialized tuple
+    /// `(key, Option<state_value>)`, `None` meaning the key is deleted.
+    pub blobs: FileHandle,
+    /// The last key in this chunk which is not deleted, if any.
+    pub last_existing_key: Option<HashValue>,
+    /// BCS serialized `SparseMerkleRangeProof` that proves all keys up to `last_existing_key` in
+    /// the full state add up to the root hash indicated in the backup
+    /// (`StateSnapshotIncrementalBackup::root_hash`). Present iff `last_existing_key` is.
+    pub proof: Option<FileHandle>,
+}
+
+/// Incremental state snapshot backup manifest, representing the state changes between two
+/// versions. Applied on top of the state at `base_version` (a full state snapshot, or a chain of
+/// incremental ones based on it), it gives the complete state view at `version`.
+#[derive(Deserialize, Serialize)]
+pub struct StateSnapshotIncrementalBackup {
+    /// Version of the state snapshot this is based on.
+    pub base_version: Version,
+    /// Version at which this state snapshot is taken.
+    pub version: Version,
+    /// Epoch in which this state snapshot is taken.
+    pub epoch: u64,
+    /// Hash of the state tree root at `version`.
+    pub root_hash: HashValue,
+    /// All changed keys in chunks, ordered by key.
+    pub chunks: Vec<StateSnapshotIncrementalChunk>,
+    /// The last key in the full state at `version`, if the state is not empty.
+    pub rightmost_key: Option<HashValue>,
+    /// BCS serialized `SparseMerkleRangeProof` that proves the full state adds up to `root_hash`,
+    /// used to verify the keys after the last change. Present iff `rightmost_key` is.
+    pub rightmost_key_proof: Option<FileHandle>,
+    /// BCS serialized
+    /// `Tuple(TransactionInfoWithProof, LedgerInfoWithSignatures)`, same as
+    /// `StateSnapshotBackup::proof`.
+    pub proof: FileHandle,
+}
@@ -0,0 +1,9 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod backup;
+pub mod manifest;
+pub mod restore;
+
+#[cfg(test)]
+pub mod tests;
@@ -0,0 +1,389 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::restore::EpochHistory,
+        state_snapshot::{manifest::StateSnapshotBackup, restore::StateSnapshotRestoreController},
+        state_snapshot_incremental::manifest::StateSnapshotIncrementalBackup,
+    },
+    metrics::{
+        restore::STATE_SNAPSHOT_VERSION, verify::VERIFY_STATE_SNAPSHOT_VERSION,
+        OTHER_TIMERS_SECONDS,
+    },
+    storage::{BackupStorage, FileHandle},
+    utils::{
+        read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt, GlobalRestoreOptions,
+        RestoreRunMode,
+    },
+};
+use accudo_crypto::{hash::CryptoHash, HashValue};
+use accudo_db::state_restore::{StateSnapshotRestore, StateSnapshotRestoreMode};
+use accudo_infallible::Mutex;
+use accudo_logger::prelude::*;
+use accudo_metrics_core::TimerHelper;
+use accudo_storage_interface::StateSnapshotReceiver;
+use accudo_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{SparseMerkleRangeProof, TransactionInfoWithProof},
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Parser;
+use std::{collections::VecDeque, sync::Arc};
+use tokio::time::Instant;
+
+/// Number of states added to the restore receiver at a time when there's no range proof at hand.
+const MAX_UNVERIFIED_CHUNK_LEN: usize = 10_000;
+
+#[derive(Parser)]
+pub struct StateSnapshotIncrementalRestoreOpt {
+    #[clap(
+        long = "base-state-manifest",
+        help = "Manifest of the full state snapshot the incremental ones are based on."
+    )]
+    pub base_manifest_handle: FileHandle,
+    #[clap(
+        long = "incremental-state-manifest",
+        num_args = 1..,
+        required = true,
+        help = "Manifests of the incremental state snapshots to apply on top of the base one, \
+        in order. Each must be based on the version of the previous one."
+    )]
+    pub incremental_manifest_handles: Vec<FileHandle>,
+    #[clap(long)]
+    pub validate_modules: bool,
+    #[clap(long)]
+    pub restore_mode: StateSnapshotRestoreMode,
+}
+
+type Receiver = Arc<Mutex<Option<StateSnapshotRestore<StateKey, StateValue>>>>;
+
+pub struct StateSnapshotIncrementalRestoreController {
+    storage: Arc<dyn BackupStorage>,
+    run_mode: Arc<RestoreRunMode>,
+    base_manifest_handle: FileHandle,
+    incremental_manifest_handles: Vec<FileHandle>,
+    /// Global "target_version" for the entire restore process, if the resulting state snapshot is
+    /// newer than this, nothing will be done, otherwise, this has no effect.
+    target_version: Version,
+    epoch_history: Option<Arc<EpochHistory>>,
+    validate_modules: bool,
+    restore_mode: StateSnapshotRestoreMode,
+}
+
+impl StateSnapshotIncrementalRestoreController {
+    pub fn new(
+        opt: StateSnapshotIncrementalRestoreOpt,
+        global_opt: GlobalRestoreOptions,
+        storage: Arc<dyn BackupStorage>,
+        epoch_history: Option<Arc<EpochHistory>>,
+    ) -> Self {
+        Self {
+            storage,
+            run_mode: global_opt.run_mode,
+            base_manifest_handle: opt.base_manifest_handle,
+            incremental_manifest_handles: opt.incremental_manifest_handles,
+            target_version: global_opt.target_version,
+            epoch_history,
+            validate_modules: opt.validate_modules,
+            restore_mode: opt.restore_mode,
+        }
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let name = self.name();
+        let start = Instant::now();
+        info!(
+            "{} started. Base manifest: {}, incremental manifests: {:?}",
+            name, self.base_manifest_handle, self.incremental_manifest_handles,
+        );
+        self.run_impl()
+            .await
+            .map_err(|e| anyhow!("{} failed: {}", name, e))?;
+        info!(time = start.elapsed().as_secs(), "{} succeeded.", name);
+        Ok(())
+    }
+}
+
+impl StateSnapshotIncrementalRestoreController {
+    fn name(&self) -> String {
+        format!("incremental state snapshot {}", self.run_mode.name())
+    }
+
+    async fn run_impl(self) -> Result<()> {
+        let base: StateSnapshotBackup = self
+            .storage
+            .load_json_file(&self.base_manifest_handle)
+            .await?;
+        let mut incrementals: Vec<StateSnapshotIncrementalBackup> = Vec::new();
+        for handle in &self.incremental_manifest_handles {
+            let manifest: StateSnapshotIncrementalBackup =
+                self.storage.load_json_file(handle).await?;
+            let prev_version = incrementals.last().map_or(base.version, |m| m.version);
+            ensure!(
+                manifest.base_version == prev_version,
+                "Incremental state snapshot {} is based on version {}, expected {}.",
+                handle,
+                manifest.base_version,
+                prev_version,
+            );
+            incrementals.push(manifest);
+        }
+        let last = incrementals
+            .last()
+            .ok_or_else(|| anyhow!("No incremental state snapshot provided."))?;
+        let version = last.version;
+
+        if version > self.target_version {
+            warn!(
+                "Trying to restore state snapshot to version {}, which is newer than the target version {}, skipping.",
+                version,
+                self.target_version,
+            );
+            return Ok(());
+        }
+
+        // Only the resulting state is verified, which covers all the states restored.
+        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            self.storage.load_bcs_file(&last.proof).await?;
+        txn_info_with_proof.verify(li.ledger_info(), version)?;
+        let state_root_hash = txn_info_with_proof
+            .transaction_info()
+            .ensure_state_checkpoint_hash()?;
+        ensure!(
+            state_root_hash == last.root_hash,
+            "Root hash mismatch with that in proof. root hash: {}, expected: {}",
+            last.root_hash,
+            state_root_hash,
+        );
+        if let Some(epoch_history) = self.epoch_history.as_ref() {
+            epoch_history.verify_ledger_info(&li)?;
+        }
+
+        let receiver: Receiver = Arc::new(Mutex::new(Some(
+            self.run_mode.get_state_restore_receiver(
+                version,
+                last.root_hash,
+                self.restore_mode,
+            )?,
+        )));
+        if self.run_mode.is_verify() {
+            VERIFY_STATE_SNAPSHOT_VERSION.set(version as i64);
+        } else {
+            STATE_SNAPSHOT_VERSION.set(version as i64);
+        }
+
+        let resume_point = receiver.lock().as_mut().unwrap().previous_key_hash()?;
+        let skip = |key: &HashValue| resume_point.is_some_and(|p| *key <= p);
+        if let Some(resume_point) = resume_point {
+            info!(
+                resume_point = resume_point.to_hex(),
+                "Resumed incremental state snapshot restore."
+            );
+        }
+
+        // Every range proof in the last incremental backup marks where a chunk ends.
+        let mut boundaries: VecDeque<(HashValue, FileHandle)> = last
+            .chunks
+            .iter()
+            .filter_map(|chunk| chunk.last_existing_key.zip(chunk.proof.clone()))
+            .collect();
+        if let Some(rightmost) = last.rightmost_key.zip(last.rightmost_key_proof.clone()) {
+            if boundaries.back().map(|(key, _)| key) != Some(&rightmost.0) {
+                boundaries.push_back(rightmost);
+            }
+        }
+        boundaries.retain(|(key, _)| !skip(key));
+
+        let mut streams = vec![RecordStream::new(
+            self.storage.clone(),
+            base.chunks
+                .into_iter()
+                .filter(|chunk| !skip(&chunk.last_key))
+                .map(|chunk| ChunkFile::Full {
+                    blobs: chunk.blobs,
+                    digest: chunk.blobs_digest,
+                })
+                .collect(),
+        )];
+        for manifest in incrementals {
+            streams.push(RecordStream::new(
+                self.storage.clone(),
+                manifest
+                    .chunks
+                    .into_iter()
+                    .filter(|chunk| !skip(&chunk.last_key))
+                    .map(|chunk| ChunkFile::Incremental { blobs: chunk.blobs })
+                    .collect(),
+            ));
+        }
+
+        let start = Instant::now();
+        let mut num_added = 0;
+        let mut chunk = Vec::new();
+        while let Some((key, state_key, value)) = Self::next_merged(&mut streams).await? {
+            let value = match value {
+                Some(value) if !skip(&key) => value,
+                _ => continue,
+            };
+            let (boundary_key, _) = boundaries
+                .front()
+                .ok_or_else(|| anyhow!("State {:x} found beyond the rightmost key.", key))?;
+            ensure!(
+                key <= *boundary_key,
+                "Chunk boundary {:x} not found in the resulting state.",
+                boundary_key,
+            );
+
+            chunk.push((state_key, value));
+            let proof = if key == *boundary_key {
+                let (_, proof_handle) = boundaries.pop_front().unwrap();
+                Some(self.storage.load_bcs_file(&proof_handle).await?)
+            } else if chunk.len() >= MAX_UNVERIFIED_CHUNK_LEN {
+                None
+            } else {
+                continue;
+            };
+            num_added += chunk.len();
+            self.add_chunk(&receiver, std::mem::take(&mut chunk), proof)
+                .await?;
+            info!(
+                num_added = num_added,
+                values_per_second =
+                    (num_added as f64 / start.elapsed().as_secs_f64()) as u64,
+                "State chunk added.",
+            );
+        }
+        if let Some((boundary_key, _)) = boundaries.front() {
+            bail!(
+                "Chunk boundary {:x} not found in the resulting state.",
+                boundary_key
+            );
+        }
+
+        tokio::task::spawn_blocking(move || receiver.lock().take().unwrap().finish()).await??;
+        self.run_mode.finish();
+        Ok(())
+    }
+
+    /// Returns the state with the smallest key among all streams, taking the value from the
+    /// latest stream that has the key, or `None` if all streams are exhausted.
+    async fn next_merged(
+        streams: &mut [RecordStream],
+    ) -> Result<Option<(HashValue, StateKey, Option<StateValue>)>> {
+        let mut min_key: Option<HashValue> = None;
+        for stream in streams.iter_mut() {
+            if let Some(key) = stream.peek_key().await? {
+                min_key = Some(min_key.map_or(key, |min| min.min(key)));
+            }
+        }
+        let min_key = match min_key {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+
+        let mut latest = None;
+        for stream in streams.iter_mut() {
+            if stream.peek_key().await? == Some(min_key) {
+                latest = stream.pop();
+            }
+        }
+        Ok(latest)
+    }
+
+    async fn add_chunk(
+        &self,
+        receiver: &Receiver,
+        mut chunk: Vec<(StateKey, StateValue)>,
+        proof: Option<SparseMerkleRangeProof>,
+    ) -> Result<()> {
+        let _timer = OTHER_TIMERS_SECONDS.timer_with(&["add_state_chunk"]);
+        if self.validate_modules {
+            chunk = tokio::task::spawn_blocking(move || {
+                StateSnapshotRestoreController::validate_modules(&chunk);
+                chunk
+            })
+            .await?;
+        }
+        let receiver = receiver.clone();
+        tokio::task::spawn_blocking(move || {
+            receiver
+                .lock()
+                .as_mut()
+                .unwrap()
+                .add_chunk_with_optional_proof(chunk, proof)
+        })
+        .await??;
+        Ok(())
+    }
+}
+
+enum ChunkFile {
+    Full {
+        blobs: FileHandle,
+        digest: Option<HashValue>,
+    },
+    Incremental {
+        blobs: FileHandle,
+    },
+}
+
+/// Records of a full or an incremental state snapshot in the order of the hashed keys, loaded
+/// one chunk at a time.
+struct RecordStream {
+    storage: Arc<dyn BackupStorage>,
+    chunks: VecDeque<ChunkFile>,
+    records: VecDeque<(HashValue, StateKey, Option<StateValue>)>,
+}
+
+impl RecordStream {
+    fn new(storage: Arc<dyn BackupStorage>, chunks: VecDeque<ChunkFile>) -> Self {
+        Self {
+            storage,
+            chunks,
+            records: VecDeque::new(),
+        }
+    }
+
+    async fn peek_key(&mut self) -> Result<Option<HashValue>> {
+        while self.records.is_empty() {
+            match self.chunks.pop_front() {
+                Some(chunk) => self.records = self.load_chunk(chunk).await?,
+                None => return Ok(None),
+            }
+        }
+        Ok(self.records.front().map(|(key, _, _)| *key))
+    }
+
+    fn pop(&mut self) -> Option<(HashValue, StateKey, Option<StateValue>)> {
+        self.records.pop_front()
+    }
+
+    async fn load_chunk(
+        &self,
+        chunk: ChunkFile,
+    ) -> Result<VecDeque<(HashValue, StateKey, Option<StateValue>)>> {
+        Ok(match chunk {
+            ChunkFile::Full { blobs, digest } => {
+                StateSnapshotRestoreController::read_state_value(&self.storage, &blobs, digest)
+                    .await?
+                    .into_iter()
+                    .map(|(key, value)| (key.hash(), key, Some(value)))
+                    .collect()
+            },
+            ChunkFile::Incremental { blobs } => {
+                let bytes = self.storage.read_all(&blobs).await?;
+                let mut file = bytes.as_slice();
+                let mut records = VecDeque::new();
+                while let Some(record_bytes) = file.read_record_bytes().await? {
+                    let (key, value): (StateKey, Option<StateValue>) =
+                        bcs::from_bytes(&record_bytes)?;
+                    records.push_back((key.hash(), key, value));
+                }
+                records
+            },
+        })
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        state_snapshot_incremental::{
+            backup::{StateSnapshotIncrementalBackupController, StateSnapshotIncrementalBackupOpt},
+            restore::{
+                StateSnapshotIncrementalRestoreController, StateSnapshotIncrementalRestoreOpt,
+            },
+        },
+    },
+    storage::{local_fs::LocalFs, BackupStorage},
+    utils::{
+        backup_service_client::BackupServiceClient,
+        test_utils::{start_local_backup_service, tmp_db_with_random_content},
+        ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, ReplayConcurrencyLevelOpt,
+        RocksdbOpt, TrustedWaypointOpt,
+    },
+};
+use accudo_db::{state_restore::StateSnapshotRestoreMode, AccudoDB};
+use accudo_storage_interface::DbReader;
+use accudo_temppath::TempPath;
+use std::{convert::TryInto, sync::Arc};
+use tokio::time::Duration;
+
+#[test]
+fn end_to_end() {
+    let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
+    let tgt_db_dir = TempPath::new();
+    tgt_db_dir.create_as_dir().unwrap();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+    let base_epoch = 0;
+    let epoch = src_db
+        .get_latest_ledger_info()
+        .unwrap()
+        .ledger_info()
+        .next_block_epoch()
+        - 1;
+    let latest_epoch_ending_li = src_db
+        .get_epoch_ending_ledger_infos(epoch, epoch + 1)
+        .unwrap()
+        .ledger_info_with_sigs
+        .pop()
+        .unwrap();
+    let version = latest_epoch_ending_li.ledger_info().version();
+    let state_root_hash = src_db
+        .get_transactions(version, 1, version, false)
+        .unwrap()
+        .consume_transaction_list_with_proof()
+        .proof
+        .transaction_infos
+        .pop()
+        .unwrap()
+        .state_checkpoint_hash()
+        .unwrap();
+
+    let (rt, port) = start_local_backup_service(src_db);
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+    let global_backup_opt = || GlobalBackupOpt {
+        max_chunk_size: 500,
+        concurrent_data_requests: 2,
+        dedup_state_snapshot_chunks: false,
+    };
+    let base_manifest_handle = rt
+        .block_on(
+            StateSnapshotBackupController::new(
+                StateSnapshotBackupOpt { epoch: base_epoch },
+                global_backup_opt(),
+                Arc::clone(&client),
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap();
+    let incremental_manifest_handle = rt
+        .block_on(
+            StateSnapshotIncrementalBackupController::new(
+                StateSnapshotIncrementalBackupOpt { base_epoch, epoch },
+                global_backup_opt(),
+                client,
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap();
+
+    rt.block_on(
+        StateSnapshotIncrementalRestoreController::new(
+            StateSnapshotIncrementalRestoreOpt {
+                base_manifest_handle,
+                incremental_manifest_handles: vec![incremental_manifest_handle],
+                validate_modules: false,
+                restore_mode: StateSnapshotRestoreMode::Default,
+            },
+            GlobalRestoreOpt {
+                dry_run: false,
+                db_dir: Some(tgt_db_dir.path().to_path_buf()),
+                target_version: None, // max
+                trusted_waypoints: TrustedWaypointOpt::default(),
+                rocksdb_opt: RocksdbOpt::default(),
+                concurrent_downloads: ConcurrentDownloadsOpt::default(),
+                replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+                enable_state_indices: false,
+            }
+            .try_into()
+            .unwrap(),
+            store,
+            None, /* epoch_history */
+        )
+        .run(),
+    )
+    .unwrap();
+
+    let tgt_db = AccudoDB::new_readonly_for_test(&tgt_db_dir);
+    assert_eq!(
+        tgt_db
+            .get_state_snapshot_before(version + 1)
+            .unwrap()
+            .unwrap(),
+        (version, state_root_hash)
+    );
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
@@ -91,6 +91,7 @@ fn test_end_to_end_impl(d: TestData) {
     let global_backup_opt = GlobalBackupOpt {
         max_chunk_size: 2048,
         concurrent_data_requests: 2,
+        dedup_state_snapshot_chunks: false,
     };
     let state_snapshot_manifest = d.state_snapshot_epoch.map(|epoch| {
         rt.block_on(
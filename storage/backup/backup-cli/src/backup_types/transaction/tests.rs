@@ -69,6 +69,7 @@ fn end_to_end() {
                     GlobalBackupOpt {
                         max_chunk_size,
                         concurrent_data_requests: 2,
+                        dedup_state_snapshot_chunks: false,
                     },
                     client.clone(),
                     Arc::clone(&store),
@@ -89,6 +90,7 @@ fn end_to_end() {
                 GlobalBackupOpt {
                     max_chunk_size,
                     concurrent_data_requests: 2,
+                    dedup_state_snapshot_chunks: false,
                 },
                 client,
                 Arc::clone(&store),
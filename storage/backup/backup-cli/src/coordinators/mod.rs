@@ -5,4 +5,5 @@
 pub mod backup;
 pub mod replay_verify;
 pub mod restore;
+pub mod scrub;
 pub mod verify;
@@ -40,6 +40,14 @@ pub struct RestoreCoordinatorOpt {
         if possible, set 0 for full ledger history."
     )]
     pub ledger_history_start_version: Option<Version>,
+    #[clap(
+        long,
+        conflicts_with = "ledger_history_start_version",
+        help = "Only restore the ledger history of this many versions before the target version, \
+        and leave the DB as if it had been pruned with this window, so that a node can start \
+        from it right away. The state trees are available since the state snapshot selected."
+    )]
+    pub output_prune_window: Option<u64>,
     #[clap(long, help = "Skip restoring epoch ending info, used for debugging.")]
     pub skip_epoch_endings: bool,
 }
@@ -50,6 +58,7 @@ pub struct RestoreCoordinator {
     metadata_cache_opt: MetadataCacheOpt,
     replay_all: bool,
     ledger_history_start_version: Option<Version>,
+    output_prune_window: Option<u64>,
     skip_epoch_endings: bool,
 }
 
@@ -65,6 +74,7 @@ impl RestoreCoordinator {
             metadata_cache_opt: opt.metadata_cache_opt,
             replay_all: opt.replay_all,
             ledger_history_start_version: opt.ledger_history_start_version,
+            output_prune_window: opt.output_prune_window,
             skip_epoch_endings: opt.skip_epoch_endings,
         }
     }
@@ -126,6 +136,16 @@ impl RestoreCoordinator {
         let max_txn_ver = metadata_view
             .max_transaction_version()?
             .ok_or_else(|| anyhow!("No transaction backup found."))?;
+        if self.output_prune_window.is_some() && self.global_opt.target_version != Version::MAX {
+            // Stopping short of the requested version silently would give a DB the node
+            // starts from with a different state than expected.
+            ensure!(
+                self.global_opt.target_version <= max_txn_ver,
+                "Target version {} is newer than the latest version in the backup, {}.",
+                self.global_opt.target_version,
+                max_txn_ver,
+            );
+        }
         let target_version = std::cmp::min(self.global_opt.target_version, max_txn_ver);
         info!(
             "User specified target version: {}, max transaction version: {}, Target version is set to {}",
@@ -133,7 +153,7 @@ impl RestoreCoordinator {
         );
 
         COORDINATOR_TARGET_VERSION.set(target_version as i64);
-        let lhs = self.ledger_history_start_version();
+        let lhs = self.ledger_history_start_version(target_version);
 
         let latest_tree_version = self
             .global_opt
@@ -233,6 +253,20 @@ impl RestoreCoordinator {
 
         do_phase_1 = do_phase_1 && !replay_all_mode;
 
+        // The version since which state KVs are available in the resulting DB, and hence the
+        // ledger history is usable.
+        let kv_history_start = match self.global_opt.run_mode.get_in_progress_state_kv_snapshot() {
+            Ok(Some(ver)) => Some(ver),
+            Ok(None) | Err(_) => kv_snapshot.as_ref().map(|s| s.version),
+        };
+        let state_history_start = match kv_history_start {
+            Some(ver) if !replay_all_mode && ver < tree_snapshot.version => ver,
+            _ => tree_snapshot.version,
+        };
+        let run_mode = Arc::clone(&self.global_opt.run_mode);
+        let output_prune_window = self.output_prune_window;
+        let tree_snapshot_version = tree_snapshot.version;
+
         // Restore the state kv between lhs and rs
         if do_phase_1 {
             info!(
@@ -372,6 +406,16 @@ impl RestoreCoordinator {
             .await?;
         }
 
+        if output_prune_window.is_some() {
+            let min_readable_version = std::cmp::max(lhs, state_history_start);
+            info!(
+                ledger_min_readable_version = min_readable_version,
+                state_merkle_min_readable_version = tree_snapshot_version,
+                "Saving pruner progress."
+            );
+            run_mode.save_min_readable_versions(min_readable_version, tree_snapshot_version)?;
+        }
+
         Ok(())
     }
 }
@@ -381,9 +425,13 @@ impl RestoreCoordinator {
         self.global_opt.target_version
     }
 
-    fn ledger_history_start_version(&self) -> Version {
-        self.ledger_history_start_version
-            .unwrap_or_else(|| self.target_version())
+    fn ledger_history_start_version(&self, actual_target_version: Version) -> Version {
+        match self.output_prune_window {
+            Some(window) => actual_target_version.saturating_sub(window),
+            None => self
+                .ledger_history_start_version
+                .unwrap_or_else(|| self.target_version()),
+        }
     }
 
     #[allow(dead_code)]
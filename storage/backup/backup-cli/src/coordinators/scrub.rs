@@ -0,0 +1,621 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::{
+            manifest::EpochEndingBackup,
+            restore::{EpochEndingRestoreController, EpochEndingRestoreOpt, EpochHistory},
+        },
+        state_snapshot::{
+            manifest::StateSnapshotBackup,
+            restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
+        },
+        state_snapshot_incremental::manifest::StateSnapshotIncrementalBackup,
+        transaction::{manifest::TransactionBackup, restore::TransactionRestoreBatchController},
+    },
+    metadata,
+    metadata::cache::MetadataCacheOpt,
+    storage::{BackupStorage, FileHandle, FileHandleRef},
+    utils::{
+        storage_ext::BackupStorageExt, stream::StreamX, GlobalRestoreOptions, RestoreRunMode,
+        TrustedWaypointOpt,
+    },
+};
+use accudo_db::state_restore::StateSnapshotRestoreMode;
+use accudo_executor_types::VerifyExecutionMode;
+use accudo_logger::prelude::*;
+use accudo_types::{
+    ledger_info::LedgerInfoWithSignatures, proof::TransactionInfoWithProof, transaction::Version,
+};
+use anyhow::{bail, ensure, Result};
+use futures::StreamExt;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
+use tokio::io::AsyncReadExt;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrubbedBackupType {
+    EpochEnding,
+    StateSnapshot,
+    StateSnapshotIncremental,
+    Transaction,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ScrubStatus {
+    Ok,
+    /// No backup covers the range at all.
+    Gap,
+    /// Files referred to by the metadata or the manifest can't be read.
+    MissingFiles {
+        files: Vec<FileHandle>,
+    },
+    /// Files are present but fail to parse or verify.
+    Corrupt {
+        error: String,
+    },
+}
+
+impl ScrubStatus {
+    fn from_result(res: Result<()>) -> Self {
+        match res {
+            Ok(()) => Self::Ok,
+            Err(e) => Self::Corrupt {
+                error: format!("{:#}", e),
+            },
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        *self == Self::Ok
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScrubEntry {
+    pub backup_type: ScrubbedBackupType,
+    /// First epoch (for epoch ending backups) or version covered. For incremental state snapshots,
+    /// the version of the state the backup is based on.
+    pub first: u64,
+    /// Last epoch (for epoch ending backups) or version covered, inclusive.
+    pub last: u64,
+    /// `None` for gaps.
+    pub manifest: Option<FileHandle>,
+    #[serde(flatten)]
+    pub status: ScrubStatus,
+}
+
+/// Machine readable result of a scrub, serialized as JSON.
+#[derive(Debug, Default, Serialize)]
+pub struct ScrubReport {
+    pub num_ok: usize,
+    pub num_failed: usize,
+    pub entries: Vec<ScrubEntry>,
+}
+
+impl ScrubReport {
+    fn add(
+        &mut self,
+        backup_type: ScrubbedBackupType,
+        first: u64,
+        last: u64,
+        manifest: Option<FileHandle>,
+        status: ScrubStatus,
+    ) {
+        if status.is_ok() {
+            self.num_ok += 1;
+        } else {
+            warn!(
+                backup_type = ?backup_type,
+                first = first,
+                last = last,
+                manifest = ?manifest,
+                status = ?status,
+                "Backup failed scrubbing."
+            );
+            self.num_failed += 1;
+        }
+        self.entries.push(ScrubEntry {
+            backup_type,
+            first,
+            last,
+            manifest,
+            status,
+        });
+    }
+}
+
+/// Verifies every backup in the storage, as opposed to `VerifyCoordinator` which verifies what's
+/// needed to restore to a certain version and stops at the first error. Nothing is written to a
+/// DB.
+pub struct ScrubCoordinator {
+    storage: Arc<dyn BackupStorage>,
+    metadata_cache_opt: MetadataCacheOpt,
+    trusted_waypoints_opt: TrustedWaypointOpt,
+    concurrent_downloads: usize,
+    skip_state_snapshots: bool,
+    output_report: Option<PathBuf>,
+}
+
+impl ScrubCoordinator {
+    pub fn new(
+        storage: Arc<dyn BackupStorage>,
+        metadata_cache_opt: MetadataCacheOpt,
+        trusted_waypoints_opt: TrustedWaypointOpt,
+        concurrent_downloads: usize,
+        skip_state_snapshots: bool,
+        output_report: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            storage,
+            metadata_cache_opt,
+            trusted_waypoints_opt,
+            concurrent_downloads,
+            skip_state_snapshots,
+            output_report,
+        }
+    }
+
+    pub async fn run(self) -> Result<ScrubReport> {
+        info!("Scrub coordinator started.");
+        let output_report = self.output_report.clone();
+        let report = self.run_impl().await?;
+
+        let json = serde_json::to_string_pretty(&report)?;
+        match output_report {
+            Some(path) => tokio::fs::write(&path, json).await?,
+            None => println!("{}", json),
+        }
+
+        ensure!(
+            report.num_failed == 0,
+            "{} out of {} backups failed scrubbing.",
+            report.num_failed,
+            report.num_failed + report.num_ok,
+        );
+        info!(
+            num_ok = report.num_ok,
+            "Scrub coordinator exiting with success."
+        );
+        Ok(report)
+    }
+
+    async fn run_impl(&self) -> Result<ScrubReport> {
+        let metadata_view = metadata::cache::sync_and_load(
+            &self.metadata_cache_opt,
+            Arc::clone(&self.storage),
+            self.concurrent_downloads,
+        )
+        .await?;
+        let global_opt = GlobalRestoreOptions {
+            target_version: Version::MAX,
+            trusted_waypoints: Arc::new(self.trusted_waypoints_opt.clone().verify()?),
+            run_mode: Arc::new(RestoreRunMode::Verify),
+            concurrent_downloads: self.concurrent_downloads,
+            replay_concurrency_level: 0, // won't replay, doesn't matter
+        };
+        let mut report = ScrubReport::default();
+
+        // Epoch endings, verified in order so that each can be verified against the previous one.
+        let mut epoch_endings = Vec::new();
+        let all_epoch_endings = metadata_view.all_epoch_endings();
+        for (first, last) in find_gaps(
+            all_epoch_endings
+                .iter()
+                .map(|b| (b.first_epoch, b.last_epoch)),
+        ) {
+            report.add(
+                ScrubbedBackupType::EpochEnding,
+                first,
+                last,
+                None,
+                ScrubStatus::Gap,
+            );
+        }
+        for backup in all_epoch_endings {
+            let first_epoch = backup.first_epoch as usize;
+            let status = match self
+                .find_missing_files(&backup.manifest, |m: &EpochEndingBackup| {
+                    m.chunks.iter().map(|c| c.ledger_infos.clone()).collect()
+                })
+                .await
+            {
+                Some(status) => status,
+                None => {
+                    let previous_li = first_epoch
+                        .checked_sub(1)
+                        .and_then(|epoch| epoch_endings.get(epoch));
+                    let res = EpochEndingRestoreController::new(
+                        EpochEndingRestoreOpt {
+                            manifest_handle: backup.manifest.clone(),
+                        },
+                        global_opt.clone(),
+                        Arc::clone(&self.storage),
+                    )
+                    .run(previous_li)
+                    .await;
+                    // Only an unbroken history since epoch 0 is used to verify other backups.
+                    if let Ok(lis) = &res {
+                        if first_epoch <= epoch_endings.len() {
+                            let num_known = epoch_endings.len() - first_epoch;
+                            epoch_endings.extend(lis.iter().skip(num_known).cloned());
+                        }
+                    }
+                    ScrubStatus::from_result(res.map(|_| ()))
+                },
+            };
+            report.add(
+                ScrubbedBackupType::EpochEnding,
+                backup.first_epoch,
+                backup.last_epoch,
+                Some(backup.manifest.clone()),
+                status,
+            );
+        }
+        let epoch_history = if epoch_endings.is_empty() {
+            None
+        } else {
+            Some(Arc::new(EpochHistory {
+                epoch_endings,
+                trusted_waypoints: global_opt.trusted_waypoints.clone(),
+            }))
+        };
+
+        // Transactions.
+        let all_transactions = metadata_view.all_transactions();
+        for (first, last) in find_gaps(
+            all_transactions
+                .iter()
+                .map(|b| (b.first_version, b.last_version)),
+        ) {
+            report.add(
+                ScrubbedBackupType::Transaction,
+                first,
+                last,
+                None,
+                ScrubStatus::Gap,
+            );
+        }
+        for backup in all_transactions {
+            let status = match self
+                .find_missing_files(&backup.manifest, |m: &TransactionBackup| {
+                    m.chunks
+                        .iter()
+                        .flat_map(|c| [c.transactions.clone(), c.proof.clone()])
+                        .collect()
+                })
+                .await
+            {
+                Some(status) => status,
+                None => ScrubStatus::from_result(
+                    TransactionRestoreBatchController::new(
+                        global_opt.clone(),
+                        Arc::clone(&self.storage),
+                        vec![backup.manifest.clone()],
+                        None, /* first_version */
+                        None, /* replay_from_version */
+                        epoch_history.clone(),
+                        VerifyExecutionMode::NoVerify,
+                        None, /* output_transaction_analysis */
+                    )
+                    .run()
+                    .await,
+                ),
+            };
+            report.add(
+                ScrubbedBackupType::Transaction,
+                backup.first_version,
+                backup.last_version,
+                Some(backup.manifest.clone()),
+                status,
+            );
+        }
+
+        // State snapshots.
+        let mut restorable_state_versions = HashSet::new();
+        for backup in metadata_view.all_state_snapshots() {
+            let status = match self
+                .find_missing_files(&backup.manifest, |m: &StateSnapshotBackup| {
+                    m.chunks
+                        .iter()
+                        .flat_map(|c| [c.blobs.clone(), c.proof.clone()])
+                        .chain(std::iter::once(m.proof.clone()))
+                        .collect()
+                })
+                .await
+            {
+                Some(status) => status,
+                None if self.skip_state_snapshots => ScrubStatus::Ok,
+                None => ScrubStatus::from_result(
+                    StateSnapshotRestoreController::new(
+                        StateSnapshotRestoreOpt {
+                            manifest_handle: backup.manifest.clone(),
+                            version: backup.version,
+                            validate_modules: false,
+                            restore_mode: StateSnapshotRestoreMode::Default,
+                        },
+                        global_opt.clone(),
+                        Arc::clone(&self.storage),
+                        epoch_history.clone(),
+                    )
+                    .run()
+                    .await,
+                ),
+            };
+            if status.is_ok() {
+                restorable_state_versions.insert(backup.version);
+            }
+            report.add(
+                ScrubbedBackupType::StateSnapshot,
+                backup.version,
+                backup.version,
+                Some(backup.manifest.clone()),
+                status,
+            );
+        }
+
+        // Incremental state snapshots, in the order of versions so that a chain of them can be
+        // followed back to a full snapshot.
+        let mut incrementals = metadata_view.all_state_snapshot_incrementals().to_vec();
+        incrementals.sort_by_key(|b| (b.version, b.base_version));
+        for backup in incrementals {
+            let status = match self
+                .find_missing_files(&backup.manifest, |m: &StateSnapshotIncrementalBackup| {
+                    m.chunks
+                        .iter()
+                        .flat_map(|c| std::iter::once(c.blobs.clone()).chain(c.proof.clone()))
+                        .chain(m.rightmost_key_proof.clone())
+                        .chain(std::iter::once(m.proof.clone()))
+                        .collect()
+                })
+                .await
+            {
+                Some(status) => status,
+                None => ScrubStatus::from_result(
+                    self.verify_state_snapshot_incremental(
+                        &backup.manifest,
+                        &restorable_state_versions,
+                        epoch_history.as_deref(),
+                    )
+                    .await,
+                ),
+            };
+            if status.is_ok() {
+                restorable_state_versions.insert(backup.version);
+            }
+            report.add(
+                ScrubbedBackupType::StateSnapshotIncremental,
+                backup.base_version,
+                backup.version,
+                Some(backup.manifest.clone()),
+                status,
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// Checks that the manifest and all files listed by `files` in it can be read.
+    async fn find_missing_files<M: DeserializeOwned>(
+        &self,
+        manifest_handle: &FileHandleRef,
+        files: impl FnOnce(&M) -> Vec<FileHandle>,
+    ) -> Option<ScrubStatus> {
+        if !Self::is_present(&self.storage, manifest_handle).await {
+            return Some(ScrubStatus::MissingFiles {
+                files: vec![manifest_handle.to_string()],
+            });
+        }
+        let manifest: M = match self.storage.load_json_file(manifest_handle).await {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                return Some(ScrubStatus::Corrupt {
+                    error: format!("{:#}", e),
+                })
+            },
+        };
+
+        let con = self.concurrent_downloads;
+        let missing: Vec<FileHandle> = futures::stream::iter(files(&manifest))
+            .map(|file| {
+                let storage = Arc::clone(&self.storage);
+                async move { (Self::is_present(&storage, &file).await, file) }
+            })
+            .buffered_x(con * 2, con)
+            .filter_map(|(present, file)| futures::future::ready((!present).then_some(file)))
+            .collect()
+            .await;
+        (!missing.is_empty()).then_some(ScrubStatus::MissingFiles { files: missing })
+    }
+
+    /// Tells whether a file can be opened and read, reading no more than a byte of it. An empty
+    /// file is present, and left to the verification to be reported as corrupt.
+    async fn is_present(storage: &Arc<dyn BackupStorage>, file_handle: &FileHandleRef) -> bool {
+        let mut buf = [0u8; 1];
+        match storage.open_for_read(file_handle).await {
+            Ok(mut file) => file.read(&mut buf).await.is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    /// Unlike a full state snapshot, an incremental one can't be verified by itself. The proof of
+    /// the resulting state is verified and it's checked that the state it's based on can be
+    /// restored from the backups.
+    async fn verify_state_snapshot_incremental(
+        &self,
+        manifest_handle: &FileHandleRef,
+        restorable_state_versions: &HashSet<Version>,
+        epoch_history: Option<&EpochHistory>,
+    ) -> Result<()> {
+        let manifest: StateSnapshotIncrementalBackup =
+            self.storage.load_json_file(manifest_handle).await?;
+        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            self.storage.load_bcs_file(&manifest.proof).await?;
+        txn_info_with_proof.verify(li.ledger_info(), manifest.version)?;
+        let state_root_hash = txn_info_with_proof
+            .transaction_info()
+            .ensure_state_checkpoint_hash()?;
+        ensure!(
+            state_root_hash == manifest.root_hash,
+            "Root hash mismatch with that in proof. root hash: {}, expected: {}",
+            manifest.root_hash,
+            state_root_hash,
+        );
+        if let Some(epoch_history) = epoch_history {
+            epoch_history.verify_ledger_info(&li)?;
+        }
+        if !restorable_state_versions.contains(&manifest.base_version) {
+            bail!(
+                "No intact backup of the base state at version {} found.",
+                manifest.base_version
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Given inclusive ranges sorted by their starts, returns the ranges not covered between 0 and
+/// the end of the last range.
+fn find_gaps(ranges: impl Iterator<Item = (u64, u64)>) -> Vec<(u64, u64)> {
+    let mut gaps = Vec::new();
+    let mut next = 0;
+    for (first, last) in ranges {
+        if first > next {
+            gaps.push((next, first - 1));
+        }
+        next = next.max(last + 1);
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        backup_types::epoch_ending::{
+            backup::{EpochEndingBackupController, EpochEndingBackupOpt},
+            manifest::EpochEndingBackup,
+        },
+        coordinators::scrub::{find_gaps, ScrubCoordinator},
+        metadata::cache::MetadataCacheOpt,
+        storage::{local_fs::LocalFs, BackupStorage, FileHandleRef},
+        utils::{
+            backup_service_client::BackupServiceClient, storage_ext::BackupStorageExt,
+            test_utils::tmp_db_with_random_content, GlobalBackupOpt, TrustedWaypointOpt,
+        },
+    };
+    use accudo_backup_service::start_backup_service;
+    use accudo_config::utils::get_available_port;
+    use accudo_temppath::TempPath;
+    use std::{
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        path::PathBuf,
+        sync::Arc,
+        time::Duration,
+    };
+
+    #[test]
+    fn test_find_gaps() {
+        assert_eq!(find_gaps(vec![].into_iter()), vec![]);
+        assert_eq!(find_gaps(vec![(0, 10), (11, 20)].into_iter()), vec![]);
+        assert_eq!(find_gaps(vec![(5, 10)].into_iter()), vec![(0, 4)]);
+        assert_eq!(
+            find_gaps(vec![(0, 10), (12, 20), (30, 40)].into_iter()),
+            vec![(11, 11), (21, 29)]
+        );
+        // Overlapping ranges.
+        assert_eq!(
+            find_gaps(vec![(0, 10), (5, 8), (9, 20), (22, 22)].into_iter()),
+            vec![(21, 21)]
+        );
+    }
+
+    #[test]
+    fn test_scrub_reports_missing_and_corrupt_files() {
+        let (_src_db_dir, src_db, blocks) = tmp_db_with_random_content();
+        let backup_dir = TempPath::new();
+        backup_dir.create_as_dir().unwrap();
+        let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+        let port = get_available_port();
+        let rt = start_backup_service(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+            src_db,
+        );
+        let client = Arc::new(BackupServiceClient::new(format!(
+            "http://localhost:{}",
+            port
+        )));
+
+        // Four backups of the same epochs, of which the first one is left intact.
+        let latest_epoch = blocks.last().unwrap().1.ledger_info().next_block_epoch();
+        let manifests: Vec<_> = (0..4)
+            .map(|_| {
+                rt.block_on(
+                    EpochEndingBackupController::new(
+                        EpochEndingBackupOpt {
+                            start_epoch: 0,
+                            end_epoch: latest_epoch,
+                        },
+                        GlobalBackupOpt {
+                            max_chunk_size: 1024,
+                            concurrent_data_requests: 2,
+                            dedup_state_snapshot_chunks: false,
+                        },
+                        Arc::clone(&client),
+                        Arc::clone(&store),
+                    )
+                    .run(),
+                )
+                .unwrap()
+            })
+            .collect();
+        let chunk_path = |manifest: &FileHandleRef| -> PathBuf {
+            let manifest: EpochEndingBackup = rt.block_on(store.load_json_file(manifest)).unwrap();
+            backup_dir.path().join(&manifest.chunks[0].ledger_infos)
+        };
+        std::fs::remove_file(chunk_path(&manifests[1])).unwrap();
+        std::fs::write(chunk_path(&manifests[2]), b"garbage").unwrap();
+        std::fs::write(chunk_path(&manifests[3]), b"").unwrap();
+
+        let metadata_cache_dir = TempPath::new();
+        let report_path = TempPath::new();
+        let err = rt
+            .block_on(
+                ScrubCoordinator::new(
+                    Arc::clone(&store),
+                    MetadataCacheOpt::new(Some(metadata_cache_dir.path())),
+                    TrustedWaypointOpt::default(),
+                    2,     /* concurrent_downloads */
+                    false, /* skip_state_snapshots */
+                    Some(report_path.path().to_path_buf()),
+                )
+                .run(),
+            )
+            .unwrap_err();
+        assert_eq!(err.to_string(), "3 out of 4 backups failed scrubbing.");
+
+        let report: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(report_path.path()).unwrap()).unwrap();
+        assert_eq!(report["num_ok"], 1);
+        assert_eq!(report["num_failed"], 3);
+        let status = |manifest: &FileHandleRef| {
+            report["entries"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|entry| entry["manifest"] == manifest)
+                .unwrap()["status"]
+                .clone()
+        };
+        assert_eq!(status(&manifests[0]), "ok");
+        assert_eq!(status(&manifests[1]), "missing_files");
+        assert_eq!(status(&manifests[2]), "corrupt");
+        // An empty file is present, but can't be verified.
+        assert_eq!(status(&manifests[3]), "corrupt");
+
+        rt.shutdown_timeout(Duration::from_secs(1));
+    }
+}
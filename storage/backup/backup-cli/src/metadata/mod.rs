@@ -17,6 +17,7 @@ use std::{cmp::Ordering, collections::HashMap, convert::TryInto};
 pub(crate) enum Metadata {
     EpochEndingBackup(EpochEndingBackupMeta),
     StateSnapshotBackup(StateSnapshotBackupMeta),
+    StateSnapshotIncrementalBackup(StateSnapshotIncrementalBackupMeta),
     TransactionBackup(TransactionBackupMeta),
     Identity(IdentityMeta),
     CompactionTimestamps(CompactionTimestampsMeta),
@@ -29,6 +30,7 @@ impl Metadata {
         first_version: Version,
         last_version: Version,
         manifest: FileHandle,
+        encryption_key_id: Option<String>,
     ) -> Self {
         Self::EpochEndingBackup(EpochEndingBackupMeta {
             first_epoch,
@@ -36,14 +38,37 @@ impl Metadata {
             first_version,
             last_version,
             manifest,
+            encryption_key_id,
         })
     }
 
-    pub fn new_state_snapshot_backup(epoch: u64, version: Version, manifest: FileHandle) -> Self {
+    pub fn new_state_snapshot_backup(
+        epoch: u64,
+        version: Version,
+        manifest: FileHandle,
+        encryption_key_id: Option<String>,
+    ) -> Self {
         Self::StateSnapshotBackup(StateSnapshotBackupMeta {
             epoch,
             version,
             manifest,
+            encryption_key_id,
+        })
+    }
+
+    pub fn new_state_snapshot_incremental_backup(
+        epoch: u64,
+        base_version: Version,
+        version: Version,
+        manifest: FileHandle,
+        encryption_key_id: Option<String>,
+    ) -> Self {
+        Self::StateSnapshotIncrementalBackup(StateSnapshotIncrementalBackupMeta {
+            epoch,
+            base_version,
+            version,
+            manifest,
+            encryption_key_id,
         })
     }
 
@@ -51,11 +76,13 @@ impl Metadata {
         first_version: Version,
         last_version: Version,
         manifest: FileHandle,
+        encryption_key_id: Option<String>,
     ) -> Self {
         Self::TransactionBackup(TransactionBackupMeta {
             first_version,
             last_version,
             manifest,
+            encryption_key_id,
         })
     }
 
@@ -112,6 +139,25 @@ impl Metadata {
         Ok((res, name.parse()?))
     }
 
+    pub fn compact_state_snapshot_incremental_backup_range(
+        backup_metas: Vec<StateSnapshotIncrementalBackupMeta>,
+    ) -> Result<(Vec<TextLine>, ShellSafeName)> {
+        ensure!(
+            !backup_metas.is_empty(),
+            "compacting an empty metadata vector"
+        );
+        let name = format!(
+            "state_snapshot_incremental_compacted_epoch_{}_{}.meta",
+            backup_metas[0].epoch,
+            backup_metas[backup_metas.len() - 1].epoch
+        );
+        let res: Vec<TextLine> = backup_metas
+            .into_iter()
+            .map(|e| Metadata::StateSnapshotIncrementalBackup(e).to_text_line())
+            .collect::<Result<_>>()?;
+        Ok((res, name.parse()?))
+    }
+
     pub fn compact_transaction_backup_range(
         backup_metas: Vec<TransactionBackupMeta>,
     ) -> Result<(Vec<TextLine>, ShellSafeName)> {
@@ -156,6 +202,10 @@ impl Metadata {
                 format!("epoch_ending_{}-{}.meta", e.first_epoch, e.last_epoch)
             },
             Self::StateSnapshotBackup(s) => format!("state_snapshot_ver_{}.meta", s.version),
+            Self::StateSnapshotIncrementalBackup(s) => format!(
+                "state_snapshot_incremental_ver_{}-{}.meta",
+                s.base_version, s.version
+            ),
             Self::TransactionBackup(t) => {
                 format!("transaction_{}-{}.meta", t.first_version, t.last_version)
             },
@@ -180,6 +230,9 @@ pub struct EpochEndingBackupMeta {
     pub first_version: Version,
     pub last_version: Version,
     pub manifest: FileHandle,
+    /// Id of the key the backup files are encrypted with, see `EncryptedStorage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
@@ -187,6 +240,20 @@ pub struct StateSnapshotBackupMeta {
     pub epoch: u64,
     pub version: Version,
     pub manifest: FileHandle,
+    /// Id of the key the backup files are encrypted with, see `EncryptedStorage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
+pub struct StateSnapshotIncrementalBackupMeta {
+    pub epoch: u64,
+    pub base_version: Version,
+    pub version: Version,
+    pub manifest: FileHandle,
+    /// Id of the key the backup files are encrypted with, see `EncryptedStorage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
@@ -194,6 +261,9 @@ pub struct TransactionBackupMeta {
     pub first_version: Version,
     pub last_version: Version,
     pub manifest: FileHandle,
+    /// Id of the key the backup files are encrypted with, see `EncryptedStorage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
@@ -5,7 +5,7 @@
 use crate::{
     metadata::{
         CompactionTimestampsMeta, EpochEndingBackupMeta, IdentityMeta, Metadata,
-        StateSnapshotBackupMeta, TransactionBackupMeta,
+        StateSnapshotBackupMeta, StateSnapshotIncrementalBackupMeta, TransactionBackupMeta,
     },
     metrics::backup::COMPACTED_TXN_VERSION,
     storage::FileHandle,
@@ -20,6 +20,7 @@ use std::{fmt, str::FromStr};
 pub struct MetadataView {
     epoch_ending_backups: Vec<EpochEndingBackupMeta>,
     state_snapshot_backups: Vec<StateSnapshotBackupMeta>,
+    state_snapshot_incremental_backups: Vec<StateSnapshotIncrementalBackupMeta>,
     transaction_backups: Vec<TransactionBackupMeta>,
     _identity: Option<IdentityMeta>,
     // The compaction timestamps of the file handles producing this view
@@ -30,6 +31,7 @@ impl MetadataView {
     pub(crate) fn new(metadata_vec: Vec<Metadata>, file_handles: Vec<FileHandle>) -> Self {
         let mut epoch_ending_backups = Vec::new();
         let mut state_snapshot_backups = Vec::new();
+        let mut state_snapshot_incremental_backups = Vec::new();
         let mut transaction_backups = Vec::new();
         let mut identity = None;
         let mut compaction_timestamps = Vec::new();
@@ -38,6 +40,9 @@ impl MetadataView {
             match meta {
                 Metadata::EpochEndingBackup(e) => epoch_ending_backups.push(e),
                 Metadata::StateSnapshotBackup(s) => state_snapshot_backups.push(s),
+                Metadata::StateSnapshotIncrementalBackup(s) => {
+                    state_snapshot_incremental_backups.push(s)
+                },
                 Metadata::TransactionBackup(t) => transaction_backups.push(t),
                 Metadata::Identity(i) => identity = Some(i),
                 Metadata::CompactionTimestamps(t) => compaction_timestamps.push(t),
@@ -47,6 +52,8 @@ impl MetadataView {
         epoch_ending_backups.dedup();
         state_snapshot_backups.sort_unstable();
         state_snapshot_backups.dedup();
+        state_snapshot_incremental_backups.sort_unstable();
+        state_snapshot_incremental_backups.dedup();
         transaction_backups.sort_unstable();
         transaction_backups.dedup();
 
@@ -72,6 +79,7 @@ impl MetadataView {
         Self {
             epoch_ending_backups,
             state_snapshot_backups,
+            state_snapshot_incremental_backups,
             transaction_backups,
             _identity: identity,
             compaction_timestamps: compaction_meta_opt,
@@ -105,10 +113,22 @@ impl MetadataView {
         self.compaction_timestamps.clone()
     }
 
+    pub fn all_epoch_endings(&self) -> &[EpochEndingBackupMeta] {
+        &self.epoch_ending_backups
+    }
+
+    pub fn all_transactions(&self) -> &[TransactionBackupMeta] {
+        &self.transaction_backups
+    }
+
     pub fn all_state_snapshots(&self) -> &[StateSnapshotBackupMeta] {
         &self.state_snapshot_backups
     }
 
+    pub fn all_state_snapshot_incrementals(&self) -> &[StateSnapshotIncrementalBackupMeta] {
+        &self.state_snapshot_incremental_backups
+    }
+
     pub fn select_state_snapshot(
         &self,
         target_version: Version,
@@ -239,6 +259,13 @@ impl MetadataView {
         Self::compact_backups(&self.state_snapshot_backups, compaction_cnt)
     }
 
+    pub fn compact_state_snapshot_incremental_backups(
+        &mut self,
+        compaction_cnt: usize,
+    ) -> Result<Vec<&[StateSnapshotIncrementalBackupMeta]>> {
+        Self::compact_backups(&self.state_snapshot_incremental_backups, compaction_cnt)
+    }
+
     pub fn get_file_handles(&self) -> Vec<FileHandle> {
         self.select_latest_compaction_timestamps()
             .as_ref()
@@ -0,0 +1,508 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    storage::{
+        BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+        TextLine,
+    },
+    utils::error_notes::ErrorNotes,
+};
+use accudo_crypto::HashValue;
+use accudo_infallible::Mutex;
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use anyhow::{ensure, format_err, Result};
+use async_trait::async_trait;
+use futures::ready;
+use std::{
+    collections::HashSet,
+    io,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Leading bytes of every encrypted file.
+const MAGIC: &[u8] = b"ACCUDOENC1";
+/// Plaintext is sealed in segments of this size, so files can be streamed and a corrupted file
+/// is detected before the entire file is read.
+const SEGMENT_SIZE: usize = 1 << 20;
+const NONCE_PREFIX_LEN: usize = 8;
+const TAG_LEN: usize = 16;
+/// Segment flag, authenticated as the associated data, so truncating a file at a segment boundary
+/// is detected.
+const MORE_SEGMENTS: u8 = 0;
+const LAST_SEGMENT: u8 = 1;
+
+/// An AES-256-GCM key used to encrypt backup files.
+pub struct EncryptionKey {
+    id: String,
+    cipher: Aes256Gcm,
+}
+
+impl EncryptionKey {
+    pub fn new(key: &[u8]) -> Result<Self> {
+        ensure!(
+            key.len() == 32,
+            "Backup encryption key must be 32 bytes, got {}.",
+            key.len()
+        );
+        Ok(Self {
+            // Not secret, it's only used to tell which key a backup is encrypted with.
+            id: HashValue::sha3_256_of(key).to_hex()[..16].to_string(),
+            cipher: Aes256Gcm::new_from_slice(key)
+                .map_err(|_| format_err!("Invalid backup encryption key."))?,
+        })
+    }
+
+    /// Loads a hex encoded key from a file.
+    pub async fn load_from_file(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path).await.err_notes(path)?;
+        Self::new(&hex::decode(content.trim())?)
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn nonce(nonce_prefix: &[u8], segment_idx: u32) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..NONCE_PREFIX_LEN].copy_from_slice(nonce_prefix);
+        nonce[NONCE_PREFIX_LEN..].copy_from_slice(&segment_idx.to_be_bytes());
+        nonce
+    }
+
+    fn seal(&self, nonce_prefix: &[u8], segment_idx: u32, flag: u8, plaintext: &[u8]) -> Vec<u8> {
+        self.cipher
+            .encrypt(
+                Nonce::from_slice(&Self::nonce(nonce_prefix, segment_idx)),
+                Payload {
+                    msg: plaintext,
+                    aad: &[flag],
+                },
+            )
+            .expect("AES-GCM encryption doesn't fail with a valid nonce.")
+    }
+
+    fn open(
+        &self,
+        nonce_prefix: &[u8],
+        segment_idx: u32,
+        flag: u8,
+        ciphertext: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(&Self::nonce(nonce_prefix, segment_idx)),
+                Payload {
+                    msg: ciphertext,
+                    aad: &[flag],
+                },
+            )
+            .map_err(|_| {
+                invalid_data(format!(
+                    "Failed to decrypt segment {}, file corrupted or encrypted with another key.",
+                    segment_idx
+                ))
+            })
+    }
+}
+
+/// A BackupStorage wrapper which encrypts every file created by `create_for_write()` with AES-GCM
+/// and decrypts them transparently in `open_for_read()`.
+///
+/// Metadata lines are stored in plain text, since they only point to manifests and are needed
+/// to be readable for the metadata compaction. They record the key id of the backups they refer
+/// to, see `BackupStorage::encryption_key_id()`.
+///
+/// File format:
+///     MAGIC | key id length (u8) | key id | nonce prefix (8 bytes) | segment*
+/// where each segment is
+///     flag (u8) | ciphertext length (u32, big endian) | ciphertext with tag
+/// and the nonce of the n-th segment is `nonce prefix | n (u32, big endian)`.
+/// Metadata files are read as is. Other files not starting with MAGIC, i.e. written without
+/// encryption, are rejected unless `allow_plaintext` is set, since anyone able to write to the
+/// storage could otherwise replace an encrypted file with an unauthenticated one.
+pub struct EncryptedStorage {
+    inner: Arc<dyn BackupStorage>,
+    key: Arc<EncryptionKey>,
+    allow_plaintext: bool,
+    /// Metadata files listed or saved, which are read as is.
+    metadata_files: Mutex<HashSet<FileHandle>>,
+}
+
+impl EncryptedStorage {
+    pub fn new(inner: Arc<dyn BackupStorage>, key: EncryptionKey, allow_plaintext: bool) -> Self {
+        Self {
+            inner,
+            key: Arc::new(key),
+            allow_plaintext,
+            metadata_files: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl BackupStorage for EncryptedStorage {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        self.inner.create_backup(name).await
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let (file_handle, file) = self.inner.create_for_write(backup_handle, name).await?;
+        Ok((
+            file_handle,
+            Box::new(EncryptingWriter::new(file, self.key.clone())),
+        ))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let allow_plaintext =
+            self.allow_plaintext || self.metadata_files.lock().contains(file_handle);
+        let file = self.inner.open_for_read(file_handle).await?;
+        Ok(Box::new(DecryptingReader::new(
+            file,
+            self.key.clone(),
+            allow_plaintext,
+        )))
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        let file_handles = self.inner.list_metadata_files().await?;
+        self.metadata_files
+            .lock()
+            .extend(file_handles.iter().cloned());
+        Ok(file_handles)
+    }
+
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        self.inner.backup_metadata_file(file_handle).await
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        let file_handle = self.inner.save_metadata_lines(name, lines).await?;
+        self.metadata_files.lock().insert(file_handle.clone());
+        Ok(file_handle)
+    }
+
+    fn encryption_key_id(&self) -> Option<String> {
+        Some(self.key.id().to_string())
+    }
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+struct EncryptingWriter {
+    inner: Box<dyn AsyncWrite + Send + Unpin>,
+    key: Arc<EncryptionKey>,
+    nonce_prefix: [u8; NONCE_PREFIX_LEN],
+    segment_idx: u32,
+    /// Plaintext not yet sealed.
+    plain: Vec<u8>,
+    /// Sealed bytes not yet written to `inner`.
+    out: Vec<u8>,
+    out_pos: usize,
+    finished: bool,
+}
+
+impl EncryptingWriter {
+    fn new(inner: Box<dyn AsyncWrite + Send + Unpin>, key: Arc<EncryptionKey>) -> Self {
+        let nonce_prefix: [u8; NONCE_PREFIX_LEN] = rand::random();
+        let mut out = MAGIC.to_vec();
+        out.push(key.id().len() as u8);
+        out.extend_from_slice(key.id().as_bytes());
+        out.extend_from_slice(&nonce_prefix);
+
+        Self {
+            inner,
+            key,
+            nonce_prefix,
+            segment_idx: 0,
+            plain: Vec::with_capacity(SEGMENT_SIZE),
+            out,
+            out_pos: 0,
+            finished: false,
+        }
+    }
+
+    fn seal_segment(&mut self, flag: u8) -> io::Result<()> {
+        let ciphertext = self
+            .key
+            .seal(&self.nonce_prefix, self.segment_idx, flag, &self.plain);
+        self.segment_idx = self
+            .segment_idx
+            .checked_add(1)
+            .ok_or_else(|| invalid_data("Too many segments.".to_string()))?;
+        self.plain.clear();
+        self.out.push(flag);
+        self.out
+            .extend_from_slice(&(ciphertext.len() as u32).to_be_bytes());
+        self.out.extend_from_slice(&ciphertext);
+        Ok(())
+    }
+
+    fn poll_write_out(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.out_pos < self.out.len() {
+            let written =
+                ready!(Pin::new(&mut self.inner).poll_write(cx, &self.out[self.out_pos..]))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.out_pos += written;
+        }
+        self.out.clear();
+        self.out_pos = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for EncryptingWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.finished {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        ready!(self.poll_write_out(cx))?;
+        if self.plain.len() == SEGMENT_SIZE {
+            self.seal_segment(MORE_SEGMENTS)?;
+            ready!(self.poll_write_out(cx))?;
+        }
+        let len = std::cmp::min(buf.len(), SEGMENT_SIZE - self.plain.len());
+        self.plain.extend_from_slice(&buf[..len]);
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // Partial segments are not sealed until shutdown, so as to not waste space on tags.
+        ready!(self.poll_write_out(cx))?;
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_write_out(cx))?;
+        if !self.finished {
+            self.seal_segment(LAST_SEGMENT)?;
+            self.finished = true;
+            ready!(self.poll_write_out(cx))?;
+        }
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+enum ReaderMode {
+    /// Not enough bytes read to tell whether the file is encrypted.
+    Detecting,
+    Plain,
+    Encrypted {
+        nonce_prefix: [u8; NONCE_PREFIX_LEN],
+    },
+}
+
+struct DecryptingReader {
+    inner: Box<dyn AsyncRead + Send + Unpin>,
+    key: Arc<EncryptionKey>,
+    allow_plaintext: bool,
+    mode: ReaderMode,
+    /// Bytes read from `inner` but not yet processed.
+    in_buf: Vec<u8>,
+    inner_eof: bool,
+    /// Plaintext not yet returned to the caller.
+    plain: Vec<u8>,
+    plain_pos: usize,
+    segment_idx: u32,
+    last_segment_seen: bool,
+}
+
+impl DecryptingReader {
+    fn new(
+        inner: Box<dyn AsyncRead + Send + Unpin>,
+        key: Arc<EncryptionKey>,
+        allow_plaintext: bool,
+    ) -> Self {
+        Self {
+            inner,
+            key,
+            allow_plaintext,
+            mode: ReaderMode::Detecting,
+            in_buf: Vec::new(),
+            inner_eof: false,
+            plain: Vec::new(),
+            plain_pos: 0,
+            segment_idx: 0,
+            last_segment_seen: false,
+        }
+    }
+
+    /// Reads more bytes from `inner` into `in_buf`.
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut buf = [0u8; 64 * 1024];
+        let mut read_buf = ReadBuf::new(&mut buf);
+        ready!(Pin::new(&mut self.inner).poll_read(cx, &mut read_buf))?;
+        if read_buf.filled().is_empty() {
+            self.inner_eof = true;
+        }
+        self.in_buf.extend_from_slice(read_buf.filled());
+        Poll::Ready(Ok(()))
+    }
+
+    /// Tries to make progress with the bytes in `in_buf`, returns false if more input is needed.
+    fn process(&mut self) -> io::Result<bool> {
+        match self.mode {
+            ReaderMode::Detecting => {
+                let header_len = MAGIC.len() + 1;
+                if self.in_buf.len() < header_len && !self.inner_eof {
+                    // It can be a plain file, bail out early if the prefix already doesn't match.
+                    if MAGIC.starts_with(&self.in_buf) {
+                        return Ok(false);
+                    }
+                }
+                if self.in_buf.len() < header_len || !self.in_buf.starts_with(MAGIC) {
+                    if !self.allow_plaintext {
+                        return Err(invalid_data(format!(
+                            "File is not encrypted, but key {} is provided.",
+                            self.key.id()
+                        )));
+                    }
+                    self.mode = ReaderMode::Plain;
+                    return Ok(true);
+                }
+                let key_id_len = self.in_buf[MAGIC.len()] as usize;
+                let full_header_len = header_len + key_id_len + NONCE_PREFIX_LEN;
+                if self.in_buf.len() < full_header_len {
+                    return self.need_more("header");
+                }
+                let key_id =
+                    String::from_utf8_lossy(&self.in_buf[header_len..header_len + key_id_len]);
+                if key_id != self.key.id() {
+                    return Err(invalid_data(format!(
+                        "File is encrypted with key {}, but key {} is provided.",
+                        key_id,
+                        self.key.id()
+                    )));
+                }
+                let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+                nonce_prefix
+                    .copy_from_slice(&self.in_buf[header_len + key_id_len..full_header_len]);
+                self.in_buf.drain(..full_header_len);
+                self.mode = ReaderMode::Encrypted { nonce_prefix };
+                Ok(true)
+            },
+            ReaderMode::Plain => {
+                if self.in_buf.is_empty() {
+                    return Ok(false);
+                }
+                self.plain = std::mem::take(&mut self.in_buf);
+                self.plain_pos = 0;
+                Ok(true)
+            },
+            ReaderMode::Encrypted { nonce_prefix } => {
+                if self.last_segment_seen {
+                    if !self.in_buf.is_empty() {
+                        return Err(invalid_data(
+                            "Trailing bytes after the last segment.".to_string(),
+                        ));
+                    }
+                    return Ok(false);
+                }
+                const SEGMENT_HEADER_LEN: usize = 1 + 4;
+                if self.in_buf.len() < SEGMENT_HEADER_LEN {
+                    return self.need_more("segment header");
+                }
+                let flag = self.in_buf[0];
+                let len = u32::from_be_bytes(self.in_buf[1..SEGMENT_HEADER_LEN].try_into().unwrap())
+                    as usize;
+                if len > SEGMENT_SIZE + TAG_LEN || flag > LAST_SEGMENT {
+                    return Err(invalid_data("Malformed segment header.".to_string()));
+                }
+                if self.in_buf.len() < SEGMENT_HEADER_LEN + len {
+                    return self.need_more("segment");
+                }
+                self.plain = self.key.open(
+                    &nonce_prefix,
+                    self.segment_idx,
+                    flag,
+                    &self.in_buf[SEGMENT_HEADER_LEN..SEGMENT_HEADER_LEN + len],
+                )?;
+                self.plain_pos = 0;
+                self.in_buf.drain(..SEGMENT_HEADER_LEN + len);
+                self.segment_idx += 1;
+                self.last_segment_seen = flag == LAST_SEGMENT;
+                Ok(true)
+            },
+        }
+    }
+
+    fn need_more(&self, what: &str) -> io::Result<bool> {
+        if self.inner_eof {
+            Err(invalid_data(format!(
+                "Truncated encrypted file: incomplete {}.",
+                what
+            )))
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn at_eof(&self) -> bool {
+        self.inner_eof
+            && self.in_buf.is_empty()
+            && match self.mode {
+                ReaderMode::Encrypted { .. } => self.last_segment_seen,
+                _ => true,
+            }
+    }
+}
+
+impl AsyncRead for DecryptingReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            if self.plain_pos < self.plain.len() {
+                let len = std::cmp::min(buf.remaining(), self.plain.len() - self.plain_pos);
+                let start = self.plain_pos;
+                buf.put_slice(&self.plain[start..start + len]);
+                self.plain_pos += len;
+                return Poll::Ready(Ok(()));
+            }
+            if self.process()? {
+                continue;
+            }
+            if self.at_eof() {
+                return Poll::Ready(Ok(()));
+            }
+            if self.inner_eof {
+                return Poll::Ready(Err(invalid_data(
+                    "Truncated encrypted file: last segment missing.".to_string(),
+                )));
+            }
+            ready!(self.poll_fill(cx))?;
+        }
+    }
+}
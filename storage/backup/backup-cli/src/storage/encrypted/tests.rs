@@ -0,0 +1,189 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::storage::{
+    local_fs::LocalFs,
+    test_util::{
+        arb_backups, arb_metadata_files, test_save_and_list_metadata_files_impl,
+        test_write_and_read_impl,
+    },
+};
+use accudo_temppath::TempPath;
+use proptest::prelude::*;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    runtime::Runtime,
+};
+
+fn key(byte: u8) -> EncryptionKey {
+    EncryptionKey::new(&[byte; 32]).unwrap()
+}
+
+fn get_store(tmpdir: &TempPath) -> Box<dyn BackupStorage> {
+    tmpdir.create_as_dir().unwrap();
+    Box::new(EncryptedStorage::new(
+        Arc::new(LocalFs::new(tmpdir.path().to_path_buf())),
+        key(1),
+        false, /* allow_plaintext */
+    ))
+}
+
+async fn write_file(store: &dyn BackupStorage, content: &[u8]) -> FileHandle {
+    let backup_handle = store
+        .create_backup(&"backup".parse().unwrap())
+        .await
+        .unwrap();
+    let (file_handle, mut file) = store
+        .create_for_write(&backup_handle, &"file".parse().unwrap())
+        .await
+        .unwrap();
+    file.write_all(content).await.unwrap();
+    file.shutdown().await.unwrap();
+    file_handle
+}
+
+async fn read_file(store: &dyn BackupStorage, file_handle: &FileHandleRef) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    store
+        .open_for_read(file_handle)
+        .await
+        .unwrap()
+        .read_to_end(&mut buf)
+        .await?;
+    Ok(buf)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_write_and_read(
+        backups in arb_backups()
+    ) {
+        let tmpdir = TempPath::new();
+        Runtime::new().unwrap().block_on(test_write_and_read_impl(get_store(&tmpdir), backups));
+    }
+
+    #[test]
+    fn test_save_list_metadata_files(
+        input in arb_metadata_files(),
+    ) {
+        let tmpdir = TempPath::new();
+        Runtime::new()
+            .unwrap()
+            .block_on(test_save_and_list_metadata_files_impl(get_store(&tmpdir), input));
+    }
+}
+
+#[test]
+fn test_multi_segment_file() {
+    Runtime::new().unwrap().block_on(async {
+        let tmpdir = TempPath::new();
+        let store = get_store(&tmpdir);
+        let content = (0..SEGMENT_SIZE * 2 + 10)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+        let file_handle = write_file(store.as_ref(), &content).await;
+
+        assert_eq!(
+            read_file(store.as_ref(), &file_handle).await.unwrap(),
+            content
+        );
+        // Stored encrypted.
+        let plain_store = LocalFs::new(tmpdir.path().to_path_buf());
+        let stored = read_file(&plain_store, &file_handle).await.unwrap();
+        assert!(stored.starts_with(MAGIC));
+        assert_ne!(&stored[stored.len() - 10..], &content[content.len() - 10..]);
+    });
+}
+
+#[test]
+fn test_wrong_key() {
+    Runtime::new().unwrap().block_on(async {
+        let tmpdir = TempPath::new();
+        let store = get_store(&tmpdir);
+        assert_eq!(store.encryption_key_id(), Some(key(1).id().to_string()));
+        let file_handle = write_file(store.as_ref(), b"content").await;
+
+        let other_store = EncryptedStorage::new(
+            Arc::new(LocalFs::new(tmpdir.path().to_path_buf())),
+            key(2),
+            false, /* allow_plaintext */
+        );
+        let err = read_file(&other_store, &file_handle).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    });
+}
+
+#[test]
+fn test_tampered_file() {
+    Runtime::new().unwrap().block_on(async {
+        let tmpdir = TempPath::new();
+        let store = get_store(&tmpdir);
+        let content = vec![7u8; SEGMENT_SIZE + 10];
+        let file_handle = write_file(store.as_ref(), &content).await;
+        let path = tmpdir.path().join(&file_handle);
+        let stored = std::fs::read(&path).unwrap();
+
+        // Flipped bit.
+        let mut tampered = stored.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        std::fs::write(&path, &tampered).unwrap();
+        assert!(read_file(store.as_ref(), &file_handle).await.is_err());
+
+        // Truncated at the segment boundary.
+        let first_segment_end =
+            MAGIC.len() + 1 + 16 + NONCE_PREFIX_LEN + 5 + SEGMENT_SIZE + TAG_LEN;
+        std::fs::write(&path, &stored[..first_segment_end]).unwrap();
+        assert!(read_file(store.as_ref(), &file_handle).await.is_err());
+
+        // Intact.
+        std::fs::write(&path, &stored).unwrap();
+        assert_eq!(
+            read_file(store.as_ref(), &file_handle).await.unwrap(),
+            content
+        );
+    });
+}
+
+#[test]
+fn test_plaintext_file() {
+    Runtime::new().unwrap().block_on(async {
+        let tmpdir = TempPath::new();
+        let store = get_store(&tmpdir);
+        let plain_store = Arc::new(LocalFs::new(tmpdir.path().to_path_buf()));
+        let file_handle = write_file(plain_store.as_ref(), b"content").await;
+
+        // Rejected unless explicitly allowed, metadata files aside.
+        let err = read_file(store.as_ref(), &file_handle).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let metadata_file_handle = store
+            .save_metadata_line(
+                &"metadata".parse().unwrap(),
+                &TextLine::new("line").unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            read_file(store.as_ref(), &metadata_file_handle)
+                .await
+                .unwrap(),
+            b"line\n"
+        );
+
+        let lenient_store =
+            EncryptedStorage::new(plain_store, key(1), true /* allow_plaintext */);
+        assert_eq!(
+            read_file(&lenient_store, &file_handle).await.unwrap(),
+            b"content"
+        );
+    });
+}
+
+#[test]
+fn test_key_length() {
+    assert!(EncryptionKey::new(&[0u8; 16]).is_err());
+    assert_eq!(key(1).id().len(), 16);
+    assert_ne!(key(1).id(), key(2).id());
+}
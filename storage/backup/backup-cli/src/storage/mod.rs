@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod command_adapter;
+pub mod encrypted;
 pub mod local_fs;
+pub mod s3;
 
 #[cfg(test)]
 mod test_util;
@@ -12,7 +14,9 @@ mod tests;
 
 use crate::storage::{
     command_adapter::{CommandAdapter, CommandAdapterOpt},
+    encrypted::{EncryptedStorage, EncryptionKey},
     local_fs::{LocalFs, LocalFsOpt},
+    s3::{S3Opt, S3},
 };
 use anyhow::{ensure, Result};
 use async_trait::async_trait;
@@ -23,7 +27,7 @@ use proptest::prelude::*;
 use regex::Regex;
 #[cfg(test)]
 use std::convert::TryInto;
-use std::{convert::TryFrom, ops::Deref, str::FromStr, sync::Arc};
+use std::{convert::TryFrom, ops::Deref, path::PathBuf, str::FromStr, sync::Arc};
 use tokio::io::{AsyncRead, AsyncWrite};
 
 /// String returned by a specific storage implementation to identify a backup, probably a folder name
@@ -186,6 +190,11 @@ pub trait BackupStorage: Send + Sync {
         name: &ShellSafeName,
         lines: &[TextLine],
     ) -> Result<FileHandle>;
+    /// Id of the key files are encrypted with, to be recorded in the metadata of new backups.
+    /// `None` if the storage doesn't encrypt files.
+    fn encryption_key_id(&self) -> Option<String> {
+        None
+    }
 }
 
 #[derive(Parser)]
@@ -199,6 +208,12 @@ pub enum StorageOpt {
     https://github.com/accudo-labs/accudo-core/tree/main/storage/backup/backup-cli/src/storage/command_adapter/sample_configs/"
     )]
     CommandAdapter(CommandAdapterOpt),
+    #[clap(
+        about = "Select the S3 backup storage type, which talks to AWS S3 or an S3 compatible \
+    object store (like MinIO) directly. See a sample config here: \
+    https://github.com/accudo-labs/accudo-core/tree/main/storage/backup/backup-cli/src/storage/s3/s3.sample.yaml"
+    )]
+    S3(S3Opt),
 }
 
 impl StorageOpt {
@@ -206,6 +221,7 @@ impl StorageOpt {
         Ok(match self {
             StorageOpt::LocalFs(opt) => Arc::new(LocalFs::new_with_opt(opt)),
             StorageOpt::CommandAdapter(opt) => Arc::new(CommandAdapter::new_with_opt(opt).await?),
+            StorageOpt::S3(opt) => Arc::new(S3::new_with_opt(opt).await?),
         })
     }
 }
@@ -214,7 +230,7 @@ impl StorageOpt {
 #[clap(group(
     ArgGroup::new("storage")
     .required(true)
-    .args(&["local_fs_dir", "command_adapter_config"]),
+    .args(&["local_fs_dir", "command_adapter_config", "s3_config"]),
 ))]
 pub struct DBToolStorageOpt {
     #[clap(
@@ -230,14 +246,46 @@ pub struct DBToolStorageOpt {
     https://github.com/accudo-labs/accudo-networks/tree/main/testnet/backups "
     )]
     command_adapter_config: Option<CommandAdapterOpt>,
+    #[clap(
+        long,
+        help = "Select the S3 backup storage type, which talks to AWS S3 or an S3 compatible \
+    object store (like MinIO) directly. See a sample config here: \
+    https://github.com/accudo-labs/accudo-core/tree/main/storage/backup/backup-cli/src/storage/s3/s3.sample.yaml"
+    )]
+    s3_config: Option<S3Opt>,
+    #[clap(
+        long,
+        value_parser,
+        help = "File holding a hex encoded 32 byte key. If specified, backup files are encrypted \
+    with AES-256-GCM upon writing and decrypted upon reading. Metadata files stay in plain text."
+    )]
+    encryption_key_file: Option<PathBuf>,
+    #[clap(
+        long,
+        requires = "encryption_key_file",
+        help = "With an encryption key, also read backup files that aren't encrypted, e.g. \
+    the ones taken before encryption was enabled. Such files aren't authenticated by the key."
+    )]
+    allow_unencrypted_files: bool,
 }
 
 impl DBToolStorageOpt {
     pub async fn init_storage(self) -> Result<Arc<dyn BackupStorage>> {
-        Ok(if self.local_fs_dir.is_some() {
+        let storage: Arc<dyn BackupStorage> = if self.local_fs_dir.is_some() {
             Arc::new(LocalFs::new_with_opt(self.local_fs_dir.unwrap()))
+        } else if self.s3_config.is_some() {
+            Arc::new(S3::new_with_opt(self.s3_config.unwrap()).await?)
         } else {
             Arc::new(CommandAdapter::new_with_opt(self.command_adapter_config.unwrap()).await?)
+        };
+
+        Ok(match self.encryption_key_file {
+            Some(path) => Arc::new(EncryptedStorage::new(
+                storage,
+                EncryptionKey::load_from_file(&path).await?,
+                self.allow_unencrypted_files,
+            )),
+            None => storage,
         })
     }
 }
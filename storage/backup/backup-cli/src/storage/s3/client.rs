@@ -0,0 +1,458 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A minimal S3 REST client covering what `S3` needs: object PUT / ranged GET / copy / delete,
+//! multipart uploads and listing by prefix. Requests are signed with AWS Signature Version 4.
+
+use crate::storage::s3::config::{S3Config, S3Credentials};
+use accudo_logger::prelude::*;
+use anyhow::{bail, ensure, format_err, Result};
+use bytes::Bytes;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use regex::Regex;
+use reqwest::{header::HeaderMap, Method, StatusCode, Url};
+use sha2_0_10_6::{Digest, Sha256};
+use std::time::Duration;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const SIGNING_ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(200);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+pub(super) struct S3Client {
+    http: reqwest::Client,
+    endpoint: Url,
+    bucket: String,
+    region: String,
+    force_path_style: bool,
+    credentials: S3Credentials,
+    max_retries: usize,
+}
+
+struct S3Request<'a> {
+    method: Method,
+    key: &'a str,
+    query: Vec<(&'static str, String)>,
+    headers: Vec<(&'static str, String)>,
+    body: Bytes,
+}
+
+impl<'a> S3Request<'a> {
+    fn new(method: Method, key: &'a str) -> Self {
+        Self {
+            method,
+            key,
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    fn query(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.query.push((key, value.into()));
+        self
+    }
+
+    fn header(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((key, value.into()));
+        self
+    }
+
+    fn body(mut self, body: Bytes) -> Self {
+        self.body = body;
+        self
+    }
+}
+
+struct S3Response {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl S3Client {
+    pub fn new(config: &S3Config) -> Result<Self> {
+        let endpoint = Url::parse(&config.endpoint())?;
+        ensure!(
+            endpoint.host_str().is_some(),
+            "S3 endpoint without host: {}",
+            endpoint
+        );
+        let credentials = match &config.credentials {
+            Some(credentials) => credentials.clone(),
+            None => S3Credentials::from_env()?,
+        };
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            endpoint,
+            bucket: config.bucket.clone(),
+            region: config.region.clone(),
+            force_path_style: config.force_path_style,
+            credentials,
+            max_retries: config.max_retries,
+        })
+    }
+
+    pub async fn put_object(&self, key: &str, body: Bytes) -> Result<()> {
+        self.send(S3Request::new(Method::PUT, key).body(body))
+            .await?;
+        Ok(())
+    }
+
+    /// Reads bytes in the inclusive range [start, end] of an object. Returns an empty buffer if
+    /// `start` is beyond the end of the object.
+    pub async fn get_object_range(&self, key: &str, start: u64, end: u64) -> Result<Bytes> {
+        let request =
+            S3Request::new(Method::GET, key).header("range", format!("bytes={}-{}", start, end));
+        match self
+            .send_allowing(request, &[StatusCode::RANGE_NOT_SATISFIABLE])
+            .await?
+        {
+            resp if resp.status == StatusCode::RANGE_NOT_SATISFIABLE => Ok(Bytes::new()),
+            resp => Ok(resp.body),
+        }
+    }
+
+    pub async fn copy_object(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        let source = format!("/{}/{}", self.bucket, uri_encode(src_key, false));
+        let resp = self
+            .send(S3Request::new(Method::PUT, dst_key).header("x-amz-copy-source", source))
+            .await?;
+        // S3 can fail a copy after responding 200, in which case the error is in the body.
+        check_body_for_error(&resp.body)
+    }
+
+    pub async fn delete_object(&self, key: &str) -> Result<()> {
+        self.send(S3Request::new(Method::DELETE, key)).await?;
+        Ok(())
+    }
+
+    /// Lists keys of all objects whose key starts with `prefix`.
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let mut request = S3Request::new(Method::GET, "")
+                .query("list-type", "2")
+                .query("prefix", prefix);
+            if let Some(token) = continuation_token.take() {
+                request = request.query("continuation-token", token);
+            }
+            let resp = self.send(request).await?;
+            let body = std::str::from_utf8(&resp.body)?;
+            keys.extend(xml_values(body, "Key"));
+
+            if xml_values(body, "IsTruncated").first().map(String::as_str) == Some("true") {
+                continuation_token = Some(
+                    xml_values(body, "NextContinuationToken")
+                        .pop()
+                        .ok_or_else(|| {
+                            format_err!("Truncated listing without continuation token.")
+                        })?,
+                );
+            } else {
+                return Ok(keys);
+            }
+        }
+    }
+
+    pub async fn create_multipart_upload(&self, key: &str) -> Result<String> {
+        let resp = self
+            .send(S3Request::new(Method::POST, key).query("uploads", ""))
+            .await?;
+        xml_values(std::str::from_utf8(&resp.body)?, "UploadId")
+            .pop()
+            .ok_or_else(|| format_err!("No UploadId in CreateMultipartUpload response."))
+    }
+
+    /// Uploads a part and returns its ETag.
+    pub async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: usize,
+        body: Bytes,
+    ) -> Result<String> {
+        let resp = self
+            .send(
+                S3Request::new(Method::PUT, key)
+                    .query("partNumber", part_number.to_string())
+                    .query("uploadId", upload_id)
+                    .body(body),
+            )
+            .await?;
+        Ok(resp
+            .headers
+            .get("etag")
+            .ok_or_else(|| format_err!("No ETag in UploadPart response."))?
+            .to_str()?
+            .to_string())
+    }
+
+    pub async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> Result<()> {
+        let parts = etags
+            .iter()
+            .enumerate()
+            .map(|(idx, etag)| {
+                format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                    idx + 1,
+                    etag
+                )
+            })
+            .collect::<String>();
+        let body = format!(
+            "<CompleteMultipartUpload>{}</CompleteMultipartUpload>",
+            parts
+        );
+        let resp = self
+            .send(
+                S3Request::new(Method::POST, key)
+                    .query("uploadId", upload_id)
+                    .body(body.into()),
+            )
+            .await?;
+        // S3 can fail the completion after responding 200, in which case the error is in the body.
+        check_body_for_error(&resp.body)
+    }
+
+    pub async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        self.send(S3Request::new(Method::DELETE, key).query("uploadId", upload_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn send(&self, request: S3Request<'_>) -> Result<S3Response> {
+        self.send_allowing(request, &[]).await
+    }
+
+    /// Sends the request, retrying on network errors and retryable responses. Statuses other than
+    /// 2xx and those in `allowed` result in an error.
+    async fn send_allowing(
+        &self,
+        request: S3Request<'_>,
+        allowed: &[StatusCode],
+    ) -> Result<S3Response> {
+        let mut delay = INITIAL_RETRY_DELAY;
+        let mut attempt = 0;
+        loop {
+            let error = match self.send_once(&request).await {
+                Ok(resp) if resp.status.is_success() || allowed.contains(&resp.status) => {
+                    return Ok(resp)
+                },
+                Ok(resp) => {
+                    let error = format_err!(
+                        "S3 {} {} failed with status {}: {}",
+                        request.method,
+                        request.key,
+                        resp.status,
+                        String::from_utf8_lossy(&resp.body),
+                    );
+                    if !is_retryable(resp.status) {
+                        return Err(error);
+                    }
+                    error
+                },
+                Err(e) => format_err!("S3 {} {} failed: {}", request.method, request.key, e),
+            };
+
+            if attempt >= self.max_retries {
+                return Err(error.context(format!("Giving up after {} retries.", attempt)));
+            }
+            attempt += 1;
+            warn!(
+                error = %error,
+                attempt = attempt,
+                delay_ms = delay.as_millis() as u64,
+                "S3 request failed, retrying."
+            );
+            tokio::time::sleep(delay).await;
+            delay = std::cmp::min(delay * 2, MAX_RETRY_DELAY);
+        }
+    }
+
+    async fn send_once(&self, request: &S3Request<'_>) -> Result<S3Response> {
+        let (url, host, path) = self.url_host_and_path(request.key);
+        let canonical_query = canonical_query_string(&request.query);
+        let payload_hash = hex::encode(Sha256::digest(&request.body));
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+
+        let mut headers: Vec<(&str, String)> = vec![
+            ("host", host),
+            ("x-amz-content-sha256", payload_hash.clone()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        headers.extend(request.headers.iter().map(|(k, v)| (*k, v.clone())));
+        headers.sort_by(|a, b| a.0.cmp(b.0));
+
+        let signed_headers = headers
+            .iter()
+            .map(|(k, _)| *k)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            request.method,
+            path,
+            canonical_query,
+            headers
+                .iter()
+                .map(|(k, v)| format!("{}:{}\n", k, v.trim()))
+                .collect::<String>(),
+            signed_headers,
+            payload_hash,
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "{}\n{}\n{}\n{}",
+            SIGNING_ALGORITHM,
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes())),
+        );
+        let signature = hex::encode(hmac_sha256(
+            &self.signing_key(&date),
+            string_to_sign.as_bytes(),
+        ));
+        let authorization = format!(
+            "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+            SIGNING_ALGORITHM, self.credentials.access_key_id, scope, signed_headers, signature,
+        );
+
+        let url = if canonical_query.is_empty() {
+            url
+        } else {
+            format!("{}?{}", url, canonical_query)
+        };
+        let mut builder = self
+            .http
+            .request(request.method.clone(), url)
+            .header("authorization", authorization);
+        for (k, v) in headers.into_iter().filter(|(k, _)| *k != "host") {
+            builder = builder.header(k, v);
+        }
+        let resp = builder.body(request.body.clone()).send().await?;
+
+        Ok(S3Response {
+            status: resp.status(),
+            headers: resp.headers().clone(),
+            body: resp.bytes().await?,
+        })
+    }
+
+    /// Returns the request URL without query string, the host header and the canonical URI.
+    fn url_host_and_path(&self, key: &str) -> (String, String, String) {
+        let base_path = self.endpoint.path().trim_end_matches('/');
+        let mut host = self
+            .endpoint
+            .host_str()
+            .expect("checked in new()")
+            .to_string();
+        if !self.force_path_style {
+            host = format!("{}.{}", self.bucket, host);
+        }
+        if let Some(port) = self.endpoint.port() {
+            host = format!("{}:{}", host, port);
+        }
+
+        let path = if self.force_path_style {
+            format!(
+                "{}/{}/{}",
+                base_path,
+                uri_encode(&self.bucket, false),
+                uri_encode(key, false)
+            )
+        } else {
+            format!("{}/{}", base_path, uri_encode(key, false))
+        };
+        let url = format!("{}://{}{}", self.endpoint.scheme(), host, path);
+        (url, host, path)
+    }
+
+    fn signing_key(&self, date: &str) -> Vec<u8> {
+        let secret = format!("AWS4{}", self.credentials.secret_access_key);
+        let date_key = hmac_sha256(secret.as_bytes(), date.as_bytes());
+        let region_key = hmac_sha256(&date_key, self.region.as_bytes());
+        let service_key = hmac_sha256(&region_key, b"s3");
+        hmac_sha256(&service_key, b"aws4_request")
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any size.");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// URI-encodes per the SigV4 spec: everything but unreserved characters is percent-encoded, and
+/// '/' is kept as is unless `encode_slash`.
+pub(super) fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            },
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn canonical_query_string(query: &[(&str, String)]) -> String {
+    let mut pairs = query
+        .iter()
+        .map(|(k, v)| (uri_encode(k, true), uri_encode(v, true)))
+        .collect::<Vec<_>>();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Extracts the text content of all elements named `tag`. The S3 responses we deal with are flat
+/// enough that a full XML parser is not needed.
+pub(super) fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let re = Regex::new(&format!("<{tag}>([^<]*)</{tag}>")).expect("Tag is a plain word.");
+    re.captures_iter(xml)
+        .map(|cap| xml_unescape(&cap[1]))
+        .collect()
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn check_body_for_error(body: &[u8]) -> Result<()> {
+    let body = String::from_utf8_lossy(body);
+    if body.contains("<Error>") {
+        bail!("S3 returned error: {}", body);
+    }
+    Ok(())
+}
@@ -0,0 +1,143 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::error_notes::ErrorNotes;
+use anyhow::{ensure, format_err, Result};
+use serde::Deserialize;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct S3Config {
+    /// Name of the bucket holding the backups.
+    pub bucket: String,
+    /// Key prefix under which everything is stored, like "backup1/e1". Empty means the bucket root.
+    #[serde(default)]
+    pub prefix: String,
+    /// Region used in request signing.
+    #[serde(default = "S3Config::default_region")]
+    pub region: String,
+    /// Endpoint of the S3 compatible service, like "http://127.0.0.1:9000" for a local MinIO.
+    /// Defaults to "https://s3.{region}.amazonaws.com".
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Address the bucket as "{endpoint}/{bucket}" instead of "{bucket}.{endpoint host}".
+    /// Most self-hosted S3 compatible services require this.
+    #[serde(default)]
+    pub force_path_style: bool,
+    /// Credentials, read from the standard AWS_* environment variables if not specified.
+    #[serde(default)]
+    pub credentials: Option<S3Credentials>,
+    /// Size of each part in a multipart upload. S3 requires at least 5MiB for all parts except
+    /// the last one. A file smaller than this is uploaded with a single PUT.
+    #[serde(default = "S3Config::default_multipart_part_size")]
+    pub multipart_part_size: usize,
+    /// Size of each ranged GET issued when reading a file.
+    #[serde(default = "S3Config::default_read_range_size")]
+    pub read_range_size: usize,
+    /// How many times a request is retried upon network errors or retryable responses.
+    #[serde(default = "S3Config::default_max_retries")]
+    pub max_retries: usize,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    #[serde(default)]
+    pub session_token: Option<String>,
+}
+
+impl S3Credentials {
+    pub fn from_env() -> Result<Self> {
+        let var = |key: &str| {
+            std::env::var(key).map_err(|_| format_err!("Environment variable {} not set.", key))
+        };
+        Ok(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+impl S3Config {
+    fn default_region() -> String {
+        "us-east-1".to_string()
+    }
+
+    fn default_multipart_part_size() -> usize {
+        64 * 1024 * 1024
+    }
+
+    fn default_read_range_size() -> usize {
+        64 * 1024 * 1024
+    }
+
+    fn default_max_retries() -> usize {
+        5
+    }
+
+    pub async fn load_from_file(path: &Path) -> Result<Self> {
+        let path_str = path.to_str().unwrap_or_default();
+        let mut file = tokio::fs::File::open(path).await.err_notes(path_str)?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).await.err_notes(path_str)?;
+
+        Self::load_from_slice(&content)
+    }
+
+    pub fn load_from_str(content: &str) -> Result<Self> {
+        Self::load_from_slice(content.as_bytes())
+    }
+
+    fn load_from_slice(content: &[u8]) -> Result<Self> {
+        let config: Self = serde_yaml::from_slice(content)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(!self.bucket.is_empty(), "bucket must be specified.");
+        ensure!(
+            !self.prefix.starts_with('/'),
+            "prefix must not start with '/': {}",
+            self.prefix
+        );
+        ensure!(
+            self.multipart_part_size > 0 && self.read_range_size > 0,
+            "multipart_part_size and read_range_size must be positive."
+        );
+        Ok(())
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", self.region))
+    }
+
+    /// Object key for a file handle, i.e. the handle under the configured prefix.
+    pub fn object_key(&self, file_handle: &str) -> String {
+        let prefix = self.prefix.trim_end_matches('/');
+        if prefix.is_empty() {
+            file_handle.to_string()
+        } else {
+            format!("{}/{}", prefix, file_handle)
+        }
+    }
+
+    /// Inverse of `object_key()`.
+    pub fn file_handle<'a>(&self, object_key: &'a str) -> Option<&'a str> {
+        let prefix = self.prefix.trim_end_matches('/');
+        if prefix.is_empty() {
+            Some(object_key)
+        } else {
+            object_key
+                .strip_prefix(prefix)
+                .and_then(|rest| rest.strip_prefix('/'))
+        }
+    }
+}
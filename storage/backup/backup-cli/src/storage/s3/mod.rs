@@ -0,0 +1,179 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+mod client;
+pub mod config;
+mod sink;
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    storage::{
+        s3::{client::S3Client, config::S3Config, sink::S3ObjectSink},
+        BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+        TextLine,
+    },
+    utils::error_notes::ErrorNotes,
+};
+use anyhow::{format_err, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use clap::Parser;
+use futures::{stream, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::io::StreamReader;
+
+#[derive(Parser, Clone, Debug, Serialize, Deserialize)]
+pub struct S3Opt {
+    #[clap(long = "config", help = "Config file for the S3 backup store.")]
+    config: PathBuf,
+}
+
+impl FromStr for S3Opt {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(S3Opt {
+            config: PathBuf::from(s),
+        })
+    }
+}
+
+/// A BackupStorage that talks to an S3 compatible object store directly, see `S3Config`.
+/// Files are laid out the same way as `LocalFs` does under the configured key prefix.
+pub struct S3 {
+    config: S3Config,
+    client: Arc<S3Client>,
+}
+
+impl S3 {
+    const METADATA_BACKUP_DIR: &'static str = "metadata_backup";
+    const METADATA_DIR: &'static str = "metadata";
+
+    pub fn new(config: S3Config) -> Result<Self> {
+        let client = Arc::new(S3Client::new(&config)?);
+        Ok(Self { config, client })
+    }
+
+    pub async fn new_with_opt(opt: S3Opt) -> Result<Self> {
+        let config = S3Config::load_from_file(&opt.config).await?;
+
+        Self::new(config)
+    }
+
+    fn key(&self, file_handle: &FileHandleRef) -> String {
+        self.config.object_key(file_handle)
+    }
+}
+
+#[async_trait]
+impl BackupStorage for S3 {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        // There are no directories in S3, the backup handle is merely a key prefix.
+        Ok(name.to_string())
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let file_handle = format!("{}/{}", backup_handle, name.as_ref());
+        let sink = S3ObjectSink::new(
+            self.client.clone(),
+            self.key(&file_handle),
+            self.config.multipart_part_size,
+        );
+        Ok((file_handle, Box::new(sink)))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let client = self.client.clone();
+        let key = self.key(file_handle);
+        let range_size = self.config.read_range_size as u64;
+
+        // Fetch the object range by range, so a failure only requires retrying the current range
+        // and memory usage is bounded. A range shorter than asked for means EOF is reached.
+        let ranges = stream::try_unfold(Some(0u64), move |offset| {
+            let client = client.clone();
+            let key = key.clone();
+            async move {
+                let offset = match offset {
+                    Some(offset) => offset,
+                    None => return Ok(None),
+                };
+                let data: Bytes = client
+                    .get_object_range(&key, offset, offset + range_size - 1)
+                    .await
+                    .err_notes(&key)?;
+                if data.is_empty() {
+                    return Ok(None);
+                }
+                let next_offset = offset + data.len() as u64;
+                let next = (data.len() as u64 == range_size).then_some(next_offset);
+                Ok::<_, anyhow::Error>(Some((data, next)))
+            }
+        })
+        .map_err(io::Error::other);
+        Ok(Box::new(StreamReader::new(Box::pin(ranges))))
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        let prefix = self.key(&format!("{}/", Self::METADATA_DIR));
+        Ok(self
+            .client
+            .list_objects(&prefix)
+            .await
+            .err_notes(&prefix)?
+            .iter()
+            .filter_map(|key| self.config.file_handle(key))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// file_handle are expected to be the return results from list_metadata_files
+    /// file_handle is a path with `metadata` in the path, Ex: metadata/epoch_ending_1.meta
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let name = Path::new(file_handle)
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or_else(|| format_err!("cannot extract filename from {}", file_handle))?;
+        let src = self.key(file_handle);
+        let dst = self.key(&format!("{}/{}", Self::METADATA_BACKUP_DIR, name));
+
+        // S3 has no rename, copy and then delete.
+        self.client.copy_object(&src, &dst).await.err_notes(&src)?;
+        self.client.delete_object(&src).await.err_notes(&src)?;
+        Ok(())
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        let file_handle = format!("{}/{}", Self::METADATA_DIR, name.as_ref());
+        let content = lines
+            .iter()
+            .map(|e| e.as_ref())
+            .collect::<Vec<&str>>()
+            .join("");
+        self.client
+            .put_object(&self.key(&file_handle), content.into())
+            .await
+            .err_notes(name)?;
+        Ok(file_handle)
+    }
+}
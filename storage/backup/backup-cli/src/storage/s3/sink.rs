@@ -0,0 +1,167 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::s3::client::S3Client;
+use accudo_logger::prelude::*;
+use anyhow::{bail, Result};
+use bytes::{Bytes, BytesMut};
+use futures::ready;
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{io::AsyncWrite, sync::mpsc, task::JoinHandle};
+use tokio_util::sync::PollSender;
+
+enum UploadMessage {
+    Part(Bytes),
+    /// Carries the remaining bytes. Only sent on `poll_shutdown()`, so a sink dropped without being
+    /// shut down leaves no (truncated) object behind.
+    Finish(Bytes),
+}
+
+/// An `AsyncWrite` which buffers data into parts and uploads them in the background, as a multipart
+/// upload if the data doesn't fit in a single part. The object is only committed, and upload
+/// errors reported, upon `shutdown()`.
+pub(super) struct S3ObjectSink {
+    buf: BytesMut,
+    part_size: usize,
+    sender: PollSender<UploadMessage>,
+    finish_sent: bool,
+    upload: JoinHandle<Result<()>>,
+}
+
+impl S3ObjectSink {
+    pub fn new(client: Arc<S3Client>, key: String, part_size: usize) -> Self {
+        // Allow one part to be buffered in the channel while another is being uploaded.
+        let (tx, rx) = mpsc::channel(1);
+        Self {
+            buf: BytesMut::with_capacity(part_size),
+            part_size,
+            sender: PollSender::new(tx),
+            finish_sent: false,
+            upload: tokio::spawn(upload(client, key, rx)),
+        }
+    }
+
+    fn poll_send(&mut self, cx: &mut Context<'_>, finish: bool) -> Poll<io::Result<()>> {
+        if ready!(self.sender.poll_reserve(cx)).is_err() {
+            // The upload task quit early, which only happens upon error.
+            return self
+                .poll_upload(cx)
+                .map(|res| res.and(Err(io::ErrorKind::BrokenPipe.into())));
+        }
+        let data = self.buf.split().freeze();
+        let msg = if finish {
+            UploadMessage::Finish(data)
+        } else {
+            UploadMessage::Part(data)
+        };
+        self.sender
+            .send_item(msg)
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_upload(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.upload).poll(cx).map(|res| match res {
+            Ok(res) => res.map_err(io::Error::other),
+            Err(join_error) => Err(io::Error::other(join_error)),
+        })
+    }
+}
+
+impl AsyncWrite for S3ObjectSink {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.finish_sent {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        if self.buf.len() >= self.part_size {
+            ready!(self.poll_send(cx, false))?;
+        }
+        let len = std::cmp::min(buf.len(), self.part_size - self.buf.len());
+        self.buf.extend_from_slice(&buf[..len]);
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // Parts can't be smaller than `part_size` except for the last one, so there's nothing to
+        // flush until shutdown.
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if !self.finish_sent {
+            ready!(self.poll_send(cx, true))?;
+            self.finish_sent = true;
+        }
+        self.poll_upload(cx)
+    }
+}
+
+async fn upload(
+    client: Arc<S3Client>,
+    key: String,
+    mut rx: mpsc::Receiver<UploadMessage>,
+) -> Result<()> {
+    let first_part = match rx.recv().await {
+        Some(UploadMessage::Finish(data)) => return client.put_object(&key, data).await,
+        Some(UploadMessage::Part(data)) => data,
+        None => bail!("Sink for {} dropped before shutdown.", key),
+    };
+
+    let upload_id = client.create_multipart_upload(&key).await?;
+    let res = upload_parts(&client, &key, &upload_id, first_part, rx).await;
+    if res.is_err() {
+        if let Err(e) = client.abort_multipart_upload(&key, &upload_id).await {
+            warn!(
+                key = %key,
+                upload_id = %upload_id,
+                error = %e,
+                "Failed to abort multipart upload."
+            );
+        }
+    }
+    res
+}
+
+async fn upload_parts(
+    client: &S3Client,
+    key: &str,
+    upload_id: &str,
+    first_part: Bytes,
+    mut rx: mpsc::Receiver<UploadMessage>,
+) -> Result<()> {
+    let mut etags = vec![client.upload_part(key, upload_id, 1, first_part).await?];
+    loop {
+        match rx.recv().await {
+            Some(UploadMessage::Part(data)) => {
+                etags.push(
+                    client
+                        .upload_part(key, upload_id, etags.len() + 1, data)
+                        .await?,
+                );
+            },
+            Some(UploadMessage::Finish(data)) => {
+                if !data.is_empty() {
+                    etags.push(
+                        client
+                            .upload_part(key, upload_id, etags.len() + 1, data)
+                            .await?,
+                    );
+                }
+                return client
+                    .complete_multipart_upload(key, upload_id, &etags)
+                    .await;
+            },
+            None => bail!("Sink for {} dropped before shutdown.", key),
+        }
+    }
+}
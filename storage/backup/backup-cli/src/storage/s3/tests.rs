@@ -0,0 +1,386 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::storage::{
+    s3::config::S3Credentials,
+    test_util::{
+        arb_backups, arb_metadata_files, test_save_and_list_metadata_files_impl,
+        test_write_and_read_impl,
+    },
+};
+use futures::Future;
+use proptest::prelude::*;
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    sync::Mutex,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    runtime::Runtime,
+};
+use warp::{
+    http::{HeaderMap, Method, Response, StatusCode},
+    hyper::Body,
+    Filter,
+};
+
+const BUCKET: &str = "test-bucket";
+// Page size of the fake ListObjectsV2, small to exercise pagination.
+const LIST_PAGE_SIZE: usize = 3;
+
+/// A stand-in for an S3 compatible service, implementing just enough of the API for `S3`.
+/// Signatures are not verified.
+#[derive(Default)]
+struct FakeS3 {
+    objects: BTreeMap<String, Vec<u8>>,
+    uploads: HashMap<String, (String, BTreeMap<usize, Vec<u8>>)>,
+    next_upload_id: usize,
+    num_failures_to_inject: usize,
+}
+
+impl FakeS3 {
+    fn handle(
+        &mut self,
+        method: Method,
+        path: &str,
+        query: HashMap<String, String>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Response<Body> {
+        if !headers.contains_key("authorization") {
+            return reply(StatusCode::FORBIDDEN, "<Error>Unsigned</Error>");
+        }
+        if self.num_failures_to_inject > 0 {
+            self.num_failures_to_inject -= 1;
+            return reply(StatusCode::SERVICE_UNAVAILABLE, "<Error>SlowDown</Error>");
+        }
+        let key = path
+            .trim_start_matches('/')
+            .strip_prefix(BUCKET)
+            .unwrap()
+            .trim_start_matches('/')
+            .to_string();
+
+        match method {
+            Method::GET if query.contains_key("list-type") => self.list(&query),
+            Method::GET => self.get(&key, &headers),
+            Method::PUT if query.contains_key("uploadId") => {
+                let part_number: usize = query["partNumber"].parse().unwrap();
+                match self.uploads.get_mut(&query["uploadId"]) {
+                    Some((_, parts)) => {
+                        parts.insert(part_number, body.to_vec());
+                        Response::builder()
+                            .header("ETag", format!("\"etag-{}\"", part_number))
+                            .body(Body::empty())
+                            .unwrap()
+                    },
+                    None => reply(StatusCode::NOT_FOUND, "<Error>NoSuchUpload</Error>"),
+                }
+            },
+            Method::PUT if headers.contains_key("x-amz-copy-source") => {
+                let source = headers["x-amz-copy-source"].to_str().unwrap();
+                let src_key = source
+                    .strip_prefix(&format!("/{}/", BUCKET))
+                    .unwrap()
+                    .to_string();
+                match self.objects.get(&src_key).cloned() {
+                    Some(content) => {
+                        self.objects.insert(key, content);
+                        reply(StatusCode::OK, "<CopyObjectResult></CopyObjectResult>")
+                    },
+                    None => reply(StatusCode::NOT_FOUND, "<Error>NoSuchKey</Error>"),
+                }
+            },
+            Method::PUT => {
+                self.objects.insert(key, body.to_vec());
+                reply(StatusCode::OK, "")
+            },
+            Method::POST if query.contains_key("uploads") => {
+                let upload_id = format!("upload-{}", self.next_upload_id);
+                self.next_upload_id += 1;
+                self.uploads
+                    .insert(upload_id.clone(), (key, BTreeMap::new()));
+                reply(
+                    StatusCode::OK,
+                    &format!(
+                        "<InitiateMultipartUploadResult><UploadId>{}</UploadId></InitiateMultipartUploadResult>",
+                        upload_id
+                    ),
+                )
+            },
+            Method::POST if query.contains_key("uploadId") => {
+                let (upload_key, mut parts) = match self.uploads.remove(&query["uploadId"]) {
+                    Some(upload) => upload,
+                    None => return reply(StatusCode::NOT_FOUND, "<Error>NoSuchUpload</Error>"),
+                };
+                assert_eq!(upload_key, key);
+                let mut content = Vec::new();
+                for part_number in
+                    client::xml_values(std::str::from_utf8(&body).unwrap(), "PartNumber")
+                {
+                    content.extend(parts.remove(&part_number.parse().unwrap()).unwrap());
+                }
+                self.objects.insert(key, content);
+                reply(
+                    StatusCode::OK,
+                    "<CompleteMultipartUploadResult></CompleteMultipartUploadResult>",
+                )
+            },
+            Method::DELETE if query.contains_key("uploadId") => {
+                self.uploads.remove(&query["uploadId"]);
+                reply(StatusCode::NO_CONTENT, "")
+            },
+            Method::DELETE => {
+                self.objects.remove(&key);
+                reply(StatusCode::NO_CONTENT, "")
+            },
+            _ => reply(StatusCode::METHOD_NOT_ALLOWED, ""),
+        }
+    }
+
+    fn get(&self, key: &str, headers: &HeaderMap) -> Response<Body> {
+        let content = match self.objects.get(key) {
+            Some(content) => content,
+            None => return reply(StatusCode::NOT_FOUND, "<Error>NoSuchKey</Error>"),
+        };
+        let (start, end) = headers["range"]
+            .to_str()
+            .unwrap()
+            .strip_prefix("bytes=")
+            .unwrap()
+            .split_once('-')
+            .unwrap();
+        let start: usize = start.parse().unwrap();
+        let end: usize = end.parse().unwrap();
+        if start >= content.len() {
+            return reply(StatusCode::RANGE_NOT_SATISFIABLE, "");
+        }
+        let end = std::cmp::min(end + 1, content.len());
+        Response::builder()
+            .status(StatusCode::PARTIAL_CONTENT)
+            .body(content[start..end].to_vec().into())
+            .unwrap()
+    }
+
+    fn list(&self, query: &HashMap<String, String>) -> Response<Body> {
+        let prefix = query.get("prefix").cloned().unwrap_or_default();
+        let start_after = query.get("continuation-token").cloned().unwrap_or_default();
+        let mut keys = self
+            .objects
+            .keys()
+            .filter(|k| k.starts_with(&prefix) && **k > start_after)
+            .take(LIST_PAGE_SIZE + 1)
+            .collect::<Vec<_>>();
+        let truncated = keys.len() > LIST_PAGE_SIZE;
+        keys.truncate(LIST_PAGE_SIZE);
+
+        let mut xml = format!("<ListBucketResult><IsTruncated>{}</IsTruncated>", truncated);
+        if truncated {
+            xml += &format!(
+                "<NextContinuationToken>{}</NextContinuationToken>",
+                keys.last().unwrap()
+            );
+        }
+        for key in keys {
+            xml += &format!("<Contents><Key>{}</Key></Contents>", key);
+        }
+        xml += "</ListBucketResult>";
+        reply(StatusCode::OK, &xml)
+    }
+}
+
+fn reply(status: StatusCode, body: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(body.to_string().into())
+        .unwrap()
+}
+
+fn start_fake_s3() -> (SocketAddr, Arc<Mutex<FakeS3>>) {
+    let state = Arc::new(Mutex::new(FakeS3::default()));
+    let state_clone = state.clone();
+    let route = warp::method()
+        .and(warp::path::full())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(warp::header::headers_cloned())
+        .and(warp::body::bytes())
+        .map(
+            move |method, path: warp::path::FullPath, query, headers, body| {
+                state_clone
+                    .lock()
+                    .unwrap()
+                    .handle(method, path.as_str(), query, headers, body)
+            },
+        );
+    let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    (addr, state)
+}
+
+fn get_store(addr: SocketAddr, prefix: &str) -> S3 {
+    S3::new(S3Config {
+        bucket: BUCKET.to_string(),
+        prefix: prefix.to_string(),
+        region: "us-east-1".to_string(),
+        endpoint: Some(format!("http://{}", addr)),
+        force_path_style: true,
+        credentials: Some(S3Credentials {
+            access_key_id: "test-key".to_string(),
+            secret_access_key: "test-secret".to_string(),
+            session_token: None,
+        }),
+        // Tiny parts and ranges to exercise multipart uploads and ranged reads.
+        multipart_part_size: 100,
+        read_range_size: 64,
+        max_retries: 3,
+    })
+    .unwrap()
+}
+
+fn block_on<F: Future<Output = ()>>(f: F) {
+    Runtime::new().unwrap().block_on(f)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_write_and_read(
+        backups in arb_backups()
+    ) {
+        block_on(async move {
+            let (addr, _state) = start_fake_s3();
+            test_write_and_read_impl(Box::new(get_store(addr, "backup1/e1")), backups).await
+        });
+    }
+
+    #[test]
+    fn test_save_list_metadata_files(
+        input in arb_metadata_files(),
+    ) {
+        block_on(async move {
+            let (addr, _state) = start_fake_s3();
+            test_save_and_list_metadata_files_impl(Box::new(get_store(addr, "")), input).await
+        });
+    }
+}
+
+#[test]
+fn test_config() {
+    let config = S3Config::load_from_str(
+        r#"
+bucket: "accudo-backup"
+prefix: "backup1/e1/"
+endpoint: "http://127.0.0.1:9000"
+force_path_style: true
+"#,
+    )
+    .unwrap();
+    assert_eq!(config.region, "us-east-1");
+    assert_eq!(
+        config.object_key("metadata/a.meta"),
+        "backup1/e1/metadata/a.meta"
+    );
+    assert_eq!(
+        config.file_handle("backup1/e1/metadata/a.meta"),
+        Some("metadata/a.meta")
+    );
+    assert_eq!(config.file_handle("backup1/e10/metadata/a.meta"), None);
+
+    assert!(S3Config::load_from_str("bucket: \"\"").is_err());
+    assert!(S3Config::load_from_str("bucket: b\nprefix: /abs").is_err());
+    assert!(S3Config::load_from_str("bucket: b\nunknown_field: 1").is_err());
+}
+
+#[test]
+fn test_uri_encode() {
+    assert_eq!(client::uri_encode("a/b c~d", false), "a/b%20c~d");
+    assert_eq!(client::uri_encode("a/b c~d", true), "a%2Fb%20c~d");
+}
+
+#[test]
+fn test_backup_metadata_file() {
+    block_on(async {
+        let (addr, state) = start_fake_s3();
+        let store = get_store(addr, "prefix");
+        let name: ShellSafeName = "epoch_ending_1.meta".parse().unwrap();
+        let file_handle = store
+            .save_metadata_line(&name, &TextLine::new("line").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(file_handle, "metadata/epoch_ending_1.meta");
+
+        store.backup_metadata_file(&file_handle).await.unwrap();
+        assert!(store.list_metadata_files().await.unwrap().is_empty());
+        let objects = &state.lock().unwrap().objects;
+        assert_eq!(
+            objects.keys().collect::<Vec<_>>(),
+            vec!["prefix/metadata_backup/epoch_ending_1.meta"]
+        );
+    });
+}
+
+#[test]
+fn test_dropped_sink_leaves_no_object() {
+    block_on(async {
+        let (addr, state) = start_fake_s3();
+        let store = get_store(addr, "");
+        let backup_handle = store
+            .create_backup(&"backup".parse().unwrap())
+            .await
+            .unwrap();
+        let (_, mut file) = store
+            .create_for_write(&backup_handle, &"file".parse().unwrap())
+            .await
+            .unwrap();
+        file.write_all(&[1u8; 1000]).await.unwrap();
+        drop(file);
+
+        // Give the upload task a chance to abort.
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let state = state.lock().unwrap();
+        assert!(state.objects.is_empty());
+        assert!(state.uploads.is_empty());
+    });
+}
+
+#[test]
+fn test_retry() {
+    block_on(async {
+        let (addr, state) = start_fake_s3();
+        let store = get_store(addr, "");
+        let backup_handle = store
+            .create_backup(&"backup".parse().unwrap())
+            .await
+            .unwrap();
+        let (file_handle, mut file) = store
+            .create_for_write(&backup_handle, &"file".parse().unwrap())
+            .await
+            .unwrap();
+        state.lock().unwrap().num_failures_to_inject = 2;
+        file.write_all(b"content").await.unwrap();
+        file.shutdown().await.unwrap();
+
+        state.lock().unwrap().num_failures_to_inject = 4;
+        let mut buf = Vec::new();
+        let res = store
+            .open_for_read(&file_handle)
+            .await
+            .unwrap()
+            .read_to_end(&mut buf)
+            .await;
+        assert!(res.is_err(), "Should give up after max_retries.");
+
+        let mut buf = Vec::new();
+        store
+            .open_for_read(&file_handle)
+            .await
+            .unwrap()
+            .read_to_end(&mut buf)
+            .await
+            .unwrap();
+        assert_eq!(buf, b"content");
+    });
+}
@@ -63,6 +63,14 @@ pub struct GlobalBackupOpt {
         concurrent requests to the fullnode backup service. "
     )]
     pub concurrent_data_requests: usize,
+    #[clap(
+        long,
+        help = "Deduplicate state snapshot chunks against the latest state snapshot in the backup \
+        storage. Chunk boundaries are aligned to state keys, so that unchanged key ranges result in \
+        identical chunks, which are referred to instead of being uploaded again. N.B. the new \
+        snapshot then depends on files of the previous backup."
+    )]
+    pub dedup_state_snapshot_chunks: bool,
 }
 
 #[derive(Clone, Parser)]
@@ -269,6 +277,19 @@ impl RestoreRunMode {
         }
     }
 
+    pub fn save_min_readable_versions(
+        &self,
+        ledger_version: Version,
+        state_merkle_version: Version,
+    ) -> Result<()> {
+        match self {
+            RestoreRunMode::Restore { restore_handler } => {
+                restore_handler.save_min_readable_versions(ledger_version, state_merkle_version)
+            },
+            RestoreRunMode::Verify => Ok(()),
+        }
+    }
+
     pub fn get_in_progress_state_kv_snapshot(&self) -> Result<Option<Version>> {
         match self {
             RestoreRunMode::Restore { restore_handler } => {
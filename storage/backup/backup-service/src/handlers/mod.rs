@@ -20,6 +20,7 @@ static STATE_RANGE_PROOF: &str = "state_range_proof";
 static STATE_SNAPSHOT: &str = "state_snapshot";
 static STATE_ITEM_COUNT: &str = "state_item_count";
 static STATE_SNAPSHOT_CHUNK: &str = "state_snapshot_chunk";
+static STATE_CHANGES: &str = "state_changes";
 static STATE_ROOT_PROOF: &str = "state_root_proof";
 static EPOCH_ENDING_LEDGER_INFOS: &str = "epoch_ending_ledger_infos";
 static TRANSACTIONS: &str = "transactions";
@@ -79,6 +80,17 @@ pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Rep
         })
         .recover(handle_rejection);
 
+    // GET state_changes/<base_version>/<version>
+    let bh = backup_handler.clone();
+    let state_changes = warp::path!(Version / Version)
+        .map(move |base_version, version| {
+            reply_with_bytes_sender(&bh, STATE_CHANGES, move |bh, sender| {
+                bh.get_state_changes_iter(base_version, version)?
+                    .try_for_each(|record_res| sender.send_size_prefixed_bcs_bytes(record_res?))
+            })
+        })
+        .recover(handle_rejection);
+
     // GET state_root_proof/<version>
     let bh = backup_handler.clone();
     let state_root_proof = warp::path!(Version)
@@ -129,6 +141,7 @@ pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Rep
         .or(warp::path(STATE_SNAPSHOT).and(state_snapshot))
         .or(warp::path(STATE_ITEM_COUNT).and(state_item_count))
         .or(warp::path(STATE_SNAPSHOT_CHUNK).and(state_snapshot_chunk))
+        .or(warp::path(STATE_CHANGES).and(state_changes))
         .or(warp::path(STATE_ROOT_PROOF).and(state_root_proof))
         .or(warp::path(EPOCH_ENDING_LEDGER_INFOS).and(epoch_ending_ledger_infos))
         .or(warp::path(TRANSACTIONS).and(transactions))
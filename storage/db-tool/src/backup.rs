@@ -6,6 +6,9 @@ use accudo_backup_cli::{
     backup_types::{
         epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
         state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        state_snapshot_incremental::backup::{
+            StateSnapshotIncrementalBackupController, StateSnapshotIncrementalBackupOpt,
+        },
         transaction::backup::{TransactionBackupController, TransactionBackupOpt},
     },
     coordinators::{
@@ -98,6 +101,12 @@ enum BackupType {
         #[clap[flatten]]
         storage: DBToolStorageOpt,
     },
+    StateSnapshotIncremental {
+        #[clap(flatten)]
+        opt: StateSnapshotIncrementalBackupOpt,
+        #[clap[flatten]]
+        storage: DBToolStorageOpt,
+    },
     Transaction {
         #[clap(flatten)]
         opt: TransactionBackupOpt,
@@ -193,6 +202,16 @@ impl Command {
                         .run()
                         .await?;
                     },
+                    BackupType::StateSnapshotIncremental { opt, storage } => {
+                        StateSnapshotIncrementalBackupController::new(
+                            opt,
+                            global_opt,
+                            client,
+                            storage.init_storage().await?,
+                        )
+                        .run()
+                        .await?;
+                    },
                     BackupType::Transaction { opt, storage } => {
                         TransactionBackupController::new(
                             opt,
@@ -1,13 +1,14 @@
 // Copyright © Accudo Foundation
 // SPDX-License-Identifier: Apache-2.0
 use accudo_backup_cli::{
-    coordinators::backup::BackupCompactor,
+    coordinators::{backup::BackupCompactor, scrub::ScrubCoordinator},
     metadata::cache::MetadataCacheOpt,
     storage::DBToolStorageOpt,
-    utils::{storage_ext::BackupStorageExt, ConcurrentDownloadsOpt},
+    utils::{storage_ext::BackupStorageExt, ConcurrentDownloadsOpt, TrustedWaypointOpt},
 };
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 /// Support compacting and cleaning obsolete metadata files
 #[derive(Subcommand)]
@@ -18,6 +19,11 @@ pub enum Command {
     Cleanup(CleanupOpt),
     #[clap(about = "Display the backup meatdata in human-readable JSON format.")]
     ReadMetadata(ReadMetadataOpt),
+    #[clap(
+        about = "Verify every backup in the storage without restoring a DB, reporting missing or \
+        corrupt files in JSON."
+    )]
+    Verify(VerifyOpt),
 }
 
 #[derive(Parser)]
@@ -59,6 +65,24 @@ pub struct ReadMetadataOpt {
     pub path: String,
 }
 
+#[derive(Parser)]
+pub struct VerifyOpt {
+    #[clap(flatten)]
+    pub metadata_cache_opt: MetadataCacheOpt,
+    #[clap(flatten)]
+    pub trusted_waypoints_opt: TrustedWaypointOpt,
+    #[clap(flatten)]
+    pub storage: DBToolStorageOpt,
+    #[clap(flatten)]
+    pub concurrent_downloads: ConcurrentDownloadsOpt,
+    /// Only check the presence of state snapshot files, which are expensive to verify.
+    #[clap(long)]
+    pub skip_state_snapshots: bool,
+    /// Write the report to the specified file instead of stdout.
+    #[clap(long, value_parser)]
+    pub output_report: Option<PathBuf>,
+}
+
 impl Command {
     pub async fn run(self) -> Result<()> {
         match self {
@@ -85,6 +109,18 @@ impl Command {
                     .await?;
                 println!("{}", serde_json::to_string_pretty(&json_value).unwrap());
             },
+            Command::Verify(opt) => {
+                ScrubCoordinator::new(
+                    opt.storage.init_storage().await?,
+                    opt.metadata_cache_opt,
+                    opt.trusted_waypoints_opt,
+                    opt.concurrent_downloads.get(),
+                    opt.skip_state_snapshots,
+                    opt.output_report,
+                )
+                .run()
+                .await?;
+            },
         }
         Ok(())
     }
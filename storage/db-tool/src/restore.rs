@@ -5,6 +5,9 @@ use accudo_backup_cli::{
     backup_types::{
         epoch_ending::restore::{EpochEndingRestoreController, EpochEndingRestoreOpt},
         state_snapshot::restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
+        state_snapshot_incremental::restore::{
+            StateSnapshotIncrementalRestoreController, StateSnapshotIncrementalRestoreOpt,
+        },
         transaction::restore::{TransactionRestoreController, TransactionRestoreOpt},
     },
     coordinators::restore::{RestoreCoordinator, RestoreCoordinatorOpt},
@@ -52,6 +55,14 @@ pub enum Oneoff {
         #[clap(flatten)]
         global: GlobalRestoreOpt,
     },
+    StateSnapshotIncremental {
+        #[clap(flatten)]
+        storage: DBToolStorageOpt,
+        #[clap(flatten)]
+        opt: StateSnapshotIncrementalRestoreOpt,
+        #[clap(flatten)]
+        global: GlobalRestoreOpt,
+    },
     Transaction {
         #[clap(flatten)]
         storage: DBToolStorageOpt,
@@ -94,6 +105,20 @@ impl Command {
                         .run()
                         .await?;
                     },
+                    Oneoff::StateSnapshotIncremental {
+                        storage,
+                        opt,
+                        global,
+                    } => {
+                        StateSnapshotIncrementalRestoreController::new(
+                            opt,
+                            global.try_into()?,
+                            storage.init_storage().await?,
+                            None, /* epoch_history */
+                        )
+                        .run()
+                        .await?;
+                    },
                     Oneoff::Transaction {
                         storage,
                         opt,
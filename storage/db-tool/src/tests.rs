@@ -597,6 +597,49 @@ mod dbtool_tests {
         rt.shutdown_timeout(Duration::from_secs(1));
     }
 
+    #[test]
+    fn test_restore_db_with_prune_window() {
+        let backup_dir = TempPath::new();
+        backup_dir.create_as_dir().unwrap();
+        let new_db_dir = TempPath::new();
+        let old_db_dir = TempPath::new();
+        let (rt, _) = db_restore_test_setup(
+            16,
+            16,
+            PathBuf::from(backup_dir.path()),
+            PathBuf::from(old_db_dir.path()),
+            PathBuf::from(new_db_dir.path()),
+        );
+
+        // Restore to version 20 with ledger history since version 15, where a snapshot is.
+        let pruned_db_dir = TempPath::new();
+        rt.block_on(
+            DBTool::try_parse_from([
+                "accudo-db-tool",
+                "restore",
+                "bootstrap-db",
+                "--output-prune-window",
+                "5",
+                "--target-version",
+                "20",
+                "--target-db-dir",
+                pruned_db_dir.path().to_str().unwrap(),
+                "--local-fs-dir",
+                backup_dir.path().to_str().unwrap(),
+            ])
+            .unwrap()
+            .run(),
+        )
+        .unwrap();
+
+        let db = AccudoDB::new_readonly_for_test(&pruned_db_dir);
+        assert_eq!(db.get_synced_version().unwrap(), Some(20));
+        assert_eq!(db.get_first_txn_version().unwrap(), Some(15));
+        assert!(db.get_transaction_by_version(14, 20, false).is_err());
+        db.get_transaction_by_version(15, 20, false).unwrap();
+        rt.shutdown_timeout(Duration::from_secs(1));
+    }
+
     fn dir_size<P: AsRef<Path>>(path: P) -> u64 {
         let mut size = 0;
 
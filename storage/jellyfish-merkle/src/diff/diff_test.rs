@@ -0,0 +1,145 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    diff::{JellyfishMerkleDiffIterator, LeafChange},
+    mock_tree_store::MockTreeStore,
+    test_helper::{gen_value, ValueBlob},
+    JellyfishMerkleTree,
+};
+use accudo_crypto::HashValue;
+use accudo_storage_interface::Result;
+use accudo_types::transaction::Version;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{collections::BTreeMap, sync::Arc};
+
+type Value = (HashValue, ValueBlob);
+
+#[test]
+fn test_diff_random_updates() {
+    for seed in 0..10 {
+        test_diff_impl(seed, 100, false);
+    }
+}
+
+#[test]
+fn test_diff_consecutive_keys() {
+    // Consecutive keys share long prefixes, so leaves move up and down the tree upon updates.
+    for seed in 0..10 {
+        test_diff_impl(seed, 50, true);
+    }
+}
+
+#[test]
+fn test_diff_identical_versions() {
+    let db = Arc::new(MockTreeStore::default());
+    commit(&db, &BTreeMap::new(), gen_updates(1, 50, false, &[]), 0);
+    assert_eq!(diff(&db, 0, 0), vec![]);
+}
+
+fn gen_keys(rng: &mut StdRng, n: usize, consecutive: bool) -> Vec<HashValue> {
+    if consecutive {
+        let start = rng.gen_range(0, 1000u64);
+        (start..start + n as u64).map(HashValue::from_u64).collect()
+    } else {
+        (0..n).map(|_| HashValue::random_with_rng(rng)).collect()
+    }
+}
+
+/// Generates updates inserting `n` keys and updating or deleting some of `existing_keys`.
+fn gen_updates(
+    seed: u64,
+    n: usize,
+    consecutive: bool,
+    existing_keys: &[HashValue],
+) -> Vec<(HashValue, Option<Value>)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut updates: BTreeMap<_, _> = gen_keys(&mut rng, n, consecutive)
+        .into_iter()
+        .map(|key| (key, Some(gen_value())))
+        .collect();
+    for key in existing_keys {
+        match rng.gen_range(0, 4) {
+            0 => {
+                updates.insert(*key, Some(gen_value()));
+            },
+            1 => {
+                updates.insert(*key, None);
+            },
+            _ => (),
+        }
+    }
+    updates.into_iter().collect()
+}
+
+fn commit(
+    db: &Arc<MockTreeStore<ValueBlob>>,
+    state: &BTreeMap<HashValue, Value>,
+    updates: Vec<(HashValue, Option<Value>)>,
+    version: Version,
+) -> (BTreeMap<HashValue, Value>, Vec<(HashValue, bool)>) {
+    let tree = JellyfishMerkleTree::new(&**db);
+    let (_root_hash, batch) = tree
+        .put_value_set_test(
+            updates.iter().map(|(k, v)| (*k, v.as_ref())).collect(),
+            version,
+        )
+        .unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+
+    let mut new_state = state.clone();
+    let mut expected_diff = vec![];
+    for (key, value) in updates {
+        match value {
+            Some(value) => {
+                expected_diff.push((key, true));
+                new_state.insert(key, value);
+            },
+            None => {
+                if new_state.remove(&key).is_some() {
+                    expected_diff.push((key, false));
+                }
+            },
+        }
+    }
+    (new_state, expected_diff)
+}
+
+fn diff(
+    db: &Arc<MockTreeStore<ValueBlob>>,
+    old_version: Version,
+    new_version: Version,
+) -> Vec<(HashValue, bool)> {
+    JellyfishMerkleDiffIterator::new(Arc::clone(db), old_version, new_version)
+        .unwrap()
+        .map(|change| {
+            change.map(|change| {
+                let key = *change.leaf().account_key();
+                (key, matches!(change, LeafChange::Put(_)))
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+        .unwrap()
+}
+
+fn test_diff_impl(seed: u64, n: usize, consecutive: bool) {
+    let db = Arc::new(MockTreeStore::default());
+    let (old_state, _) = commit(&db, &BTreeMap::new(), gen_updates(seed, n, consecutive, &[]), 0);
+    let existing_keys: Vec<_> = old_state.keys().cloned().collect();
+    let (new_state, expected_diff) = commit(
+        &db,
+        &old_state,
+        gen_updates(seed + 100, n / 10, consecutive, &existing_keys),
+        1,
+    );
+
+    assert_eq!(diff(&db, 0, 1), expected_diff);
+
+    // The reverse diff takes the state back.
+    let reverse_diff = diff(&db, 1, 0);
+    assert_eq!(reverse_diff.len(), expected_diff.len());
+    for (key, put) in reverse_diff {
+        assert_eq!(put, old_state.contains_key(&key));
+        assert_ne!(old_state.get(&key), new_state.get(&key));
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements `JellyfishMerkleDiffIterator`. Initialized with two versions of the
+//! tree, the iterator generates all the leaves that differ between them, in the order of the
+//! hashed keys. Subtrees with identical hashes in both versions are skipped without being read,
+//! so the cost is proportional to the number of changes rather than the size of the tree.
+
+#[cfg(test)]
+mod diff_test;
+
+use crate::{
+    node_type::{InternalNode, LeafNode, Node, NodeKey},
+    NibbleExt, TreeReader,
+};
+use accudo_crypto::HashValue;
+use accudo_types::{nibble::Nibble, transaction::Version};
+use accudo_storage_interface::Result;
+use std::{collections::VecDeque, sync::Arc};
+
+/// A difference between two versions of the tree, regarding a single key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LeafChange<K> {
+    /// The key is added or its value changed. Carries the leaf at the newer version.
+    Put(LeafNode<K>),
+    /// The key is deleted. Carries the leaf at the older version.
+    Delete(LeafNode<K>),
+}
+
+impl<K> LeafChange<K> {
+    pub fn leaf(&self) -> &LeafNode<K> {
+        match self {
+            Self::Put(leaf) | Self::Delete(leaf) => leaf,
+        }
+    }
+}
+
+/// A subtree at a certain position, not necessarily loaded from the storage yet.
+enum SubTree<K> {
+    Empty,
+    Ref { node_key: NodeKey, hash: HashValue },
+    Leaf(LeafNode<K>),
+}
+
+impl<K: crate::Key> SubTree<K> {
+    fn hash(&self) -> Option<HashValue> {
+        match self {
+            Self::Empty => None,
+            Self::Ref { hash, .. } => Some(*hash),
+            Self::Leaf(leaf) => Some(leaf.hash()),
+        }
+    }
+}
+
+/// A subtree with its root node loaded.
+enum LoadedSubTree<K> {
+    Empty,
+    Leaf(LeafNode<K>),
+    Internal(NodeKey, InternalNode),
+}
+
+impl<K: crate::Key> LoadedSubTree<K> {
+    /// Returns the subtree under the `n`-th child.
+    fn child(&self, depth: usize, n: Nibble) -> SubTree<K> {
+        match self {
+            Self::Empty => SubTree::Empty,
+            // A leaf stands for a subtree with a single key, so it's also the only child in the
+            // direction of its key at any depth below.
+            Self::Leaf(leaf) => {
+                if leaf.account_key().get_nibble(depth) == n {
+                    SubTree::Leaf(leaf.clone())
+                } else {
+                    SubTree::Empty
+                }
+            },
+            Self::Internal(node_key, node) => match node.child(n) {
+                Some(child) => SubTree::Ref {
+                    node_key: node_key.gen_child_node_key(child.version, n),
+                    hash: child.hash,
+                },
+                None => SubTree::Empty,
+            },
+        }
+    }
+}
+
+/// A pair of subtrees at the same position in the old and the new versions of the tree, with
+/// `depth` being the length of that position in nibbles.
+struct DiffTask<K> {
+    depth: usize,
+    old: SubTree<K>,
+    new: SubTree<K>,
+}
+
+/// The `JellyfishMerkleDiffIterator` implementation.
+pub struct JellyfishMerkleDiffIterator<R, K> {
+    /// The storage engine from which we can read nodes using node keys.
+    reader: Arc<R>,
+
+    /// Subtree pairs yet to compare, the leftmost on the top.
+    stack: Vec<DiffTask<K>>,
+
+    /// Changes found but not yet returned.
+    pending: VecDeque<LeafChange<K>>,
+}
+
+impl<R, K> JellyfishMerkleDiffIterator<R, K>
+where
+    R: TreeReader<K>,
+    K: crate::Key,
+{
+    /// Constructs a new iterator generating the changes from `old_version` to `new_version`. Both
+    /// versions of the tree must exist in the storage.
+    pub fn new(reader: Arc<R>, old_version: Version, new_version: Version) -> Result<Self> {
+        let old_root_key = NodeKey::new_empty_path(old_version);
+        let new_root_key = NodeKey::new_empty_path(new_version);
+        let old_root_hash = reader.get_node(&old_root_key)?.hash();
+        let new_root_hash = reader.get_node(&new_root_key)?.hash();
+
+        Ok(Self {
+            reader,
+            stack: vec![DiffTask {
+                depth: 0,
+                old: SubTree::Ref {
+                    node_key: old_root_key,
+                    hash: old_root_hash,
+                },
+                new: SubTree::Ref {
+                    node_key: new_root_key,
+                    hash: new_root_hash,
+                },
+            }],
+            pending: VecDeque::new(),
+        })
+    }
+
+    fn load(&self, subtree: SubTree<K>) -> Result<LoadedSubTree<K>> {
+        Ok(match subtree {
+            SubTree::Empty => LoadedSubTree::Empty,
+            SubTree::Leaf(leaf) => LoadedSubTree::Leaf(leaf),
+            SubTree::Ref { node_key, .. } => {
+                match self.reader.get_node_with_tag(&node_key, "diff")? {
+                    Node::Null => LoadedSubTree::Empty,
+                    Node::Leaf(leaf) => LoadedSubTree::Leaf(leaf),
+                    Node::Internal(node) => LoadedSubTree::Internal(node_key, node),
+                }
+            },
+        })
+    }
+
+    fn process(&mut self, task: DiffTask<K>) -> Result<()> {
+        if task.old.hash() == task.new.hash() {
+            return Ok(());
+        }
+
+        let depth = task.depth;
+        match (self.load(task.old)?, self.load(task.new)?) {
+            (LoadedSubTree::Empty, LoadedSubTree::Empty) => (),
+            (LoadedSubTree::Leaf(old), LoadedSubTree::Empty) => {
+                self.pending.push_back(LeafChange::Delete(old));
+            },
+            (LoadedSubTree::Empty, LoadedSubTree::Leaf(new)) => {
+                self.pending.push_back(LeafChange::Put(new));
+            },
+            (LoadedSubTree::Leaf(old), LoadedSubTree::Leaf(new)) => {
+                if old.account_key() == new.account_key() {
+                    if old.value_hash() != new.value_hash() {
+                        self.pending.push_back(LeafChange::Put(new));
+                    }
+                } else if old.account_key() < new.account_key() {
+                    self.pending.push_back(LeafChange::Delete(old));
+                    self.pending.push_back(LeafChange::Put(new));
+                } else {
+                    self.pending.push_back(LeafChange::Put(new));
+                    self.pending.push_back(LeafChange::Delete(old));
+                }
+            },
+            (old, new) => {
+                // At least one side is an internal node, compare child by child. Pushed in
+                // reverse order so the leftmost pair is processed first.
+                for n in (0..16u8).rev() {
+                    let n = Nibble::from(n);
+                    let old_child = old.child(depth, n);
+                    let new_child = new.child(depth, n);
+                    if old_child.hash() != new_child.hash() {
+                        self.stack.push(DiffTask {
+                            depth: depth + 1,
+                            old: old_child,
+                            new: new_child,
+                        });
+                    }
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+impl<R, K> Iterator for JellyfishMerkleDiffIterator<R, K>
+where
+    R: TreeReader<K>,
+    K: crate::Key,
+{
+    type Item = Result<LeafChange<K>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(change) = self.pending.pop_front() {
+                return Some(Ok(change));
+            }
+            let task = self.stack.pop()?;
+            if let Err(err) = self.process(task) {
+                // Stop iterating upon error.
+                self.stack.clear();
+                return Some(Err(err));
+            }
+        }
+    }
+}
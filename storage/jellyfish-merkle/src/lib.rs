@@ -72,6 +72,7 @@
 //! [`InternalNode`]: node_type/struct.InternalNode.html
 //! [`LeafNode`]: node_type/struct.LeafNode.html
 
+pub mod diff;
 pub mod iterator;
 #[cfg(test)]
 mod jellyfish_merkle_test;
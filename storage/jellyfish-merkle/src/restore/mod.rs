@@ -179,6 +179,10 @@ pub struct JellyfishMerkleRestore<K> {
     /// Already finished, deem all chunks overlap.
     finished: bool,
 
+    /// Whether some of the states added haven't been verified by a proof yet, see
+    /// `add_chunk_with_optional_proof()`.
+    unverified: bool,
+
     async_commit: bool,
     async_commit_result: Option<Receiver<Result<()>>>,
 }
@@ -230,6 +234,7 @@ where
             num_keys_received: 0,
             expected_root_hash,
             finished,
+            unverified: false,
             async_commit,
             async_commit_result: None,
         })
@@ -249,6 +254,7 @@ where
             num_keys_received: 0,
             expected_root_hash,
             finished: false,
+            unverified: false,
             async_commit: false,
             async_commit_result: None,
         })
@@ -342,8 +348,20 @@ where
     /// error will be returned and nothing will be written to storage.
     pub fn add_chunk_impl(
         &mut self,
-        mut chunk: Vec<(&K, HashValue)>,
+        chunk: Vec<(&K, HashValue)>,
         proof: SparseMerkleRangeProof,
+    ) -> Result<()> {
+        self.add_chunk_with_optional_proof(chunk, Some(proof))
+    }
+
+    /// Same as `add_chunk_impl()`, except that the chunk is not verified (and is written to
+    /// storage as is) if `proof` is `None`. Since a proof verifies all the states added so far,
+    /// the states are verified by the next chunk coming with a proof, and `finish_impl()` refuses
+    /// to finish before that.
+    pub fn add_chunk_with_optional_proof(
+        &mut self,
+        mut chunk: Vec<(&K, HashValue)>,
+        proof: Option<SparseMerkleRangeProof>,
     ) -> Result<()> {
         if self.finished {
             info!("State snapshot restore already finished, ignoring entire chunk.");
@@ -392,7 +410,13 @@ where
         }
 
         // Verify what we have added so far is all correct.
-        self.verify(proof)?;
+        match proof {
+            Some(proof) => {
+                self.verify(proof)?;
+                self.unverified = false;
+            },
+            None => self.unverified = true,
+        }
 
         // Write the frozen nodes to storage.
         if self.async_commit {
@@ -756,6 +780,10 @@ where
     /// otherwise we can not freeze the rightmost leaf and its ancestors.
     pub fn finish_impl(mut self) -> Result<()> {
         self.wait_for_async_commit()?;
+        ensure!(
+            !self.unverified,
+            "The last states added are not verified by any proof."
+        );
         // Deal with the special case when the entire tree has a single leaf or null node.
         if self.partial_nodes.len() == 1 {
             let mut num_children = 0;
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    engine::{EngineBatch, KvEngine},
     metrics::{ACCUDO_SCHEMADB_DELETES_SAMPLED, ACCUDO_SCHEMADB_PUT_BYTES_SAMPLED, TIMER},
     schema::{KeyCodec, Schema, ValueCodec},
     ColumnFamilyName, DB,
@@ -12,6 +13,7 @@ use accudo_storage_interface::Result as DbResult;
 use std::{
     collections::HashMap,
     fmt::{Debug, Formatter},
+    sync::Arc,
 };
 
 #[derive(Debug, Default)]
@@ -76,12 +78,35 @@ impl Default for SampledBatchStats {
     }
 }
 
+/// Updates in the native formats of the engines of the DB they are going to be written to.
 #[derive(Default)]
 pub struct RawBatch {
-    pub inner: rocksdb::WriteBatch,
+    // Indexed the same way as the engines of the DB, `None` if an engine is not touched.
+    pub(crate) engine_batches: Vec<Option<EngineBatch>>,
     pub stats: SampledBatchStats,
 }
 
+impl RawBatch {
+    pub(crate) fn engine_batch(
+        &mut self,
+        engine_idx: usize,
+        engine: &Arc<dyn KvEngine>,
+    ) -> &mut EngineBatch {
+        if self.engine_batches.len() <= engine_idx {
+            self.engine_batches.resize_with(engine_idx + 1, || None);
+        }
+        self.engine_batches[engine_idx].get_or_insert_with(|| engine.new_batch())
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.engine_batches
+            .iter()
+            .flatten()
+            .map(EngineBatch::size_in_bytes)
+            .sum()
+    }
+}
+
 pub trait IntoRawBatch {
     fn into_raw_batch(self, db: &DB) -> DbResult<RawBatch>;
 }
@@ -179,25 +204,26 @@ impl IntoRawBatch for SchemaBatch {
 
         let Self { rows, stats } = self;
 
-        let mut db_batch = rocksdb::WriteBatch::default();
+        let mut raw_batch = RawBatch {
+            engine_batches: Vec::new(),
+            stats,
+        };
         for (cf_name, rows) in rows.iter() {
-            let cf_handle = db.get_cf_handle(cf_name)?;
             for write_op in rows {
                 match write_op {
-                    WriteOp::Value { key, value } => db_batch.put_cf(cf_handle, key, value),
-                    WriteOp::Deletion { key } => db_batch.delete_cf(cf_handle, key),
+                    WriteOp::Value { key, value } => {
+                        db.batch_put(&mut raw_batch, *cf_name, key, value)?
+                    },
+                    WriteOp::Deletion { key } => db.batch_delete(&mut raw_batch, *cf_name, key)?,
                 }
             }
         }
 
-        Ok(RawBatch {
-            inner: db_batch,
-            stats,
-        })
+        Ok(raw_batch)
     }
 }
 
-/// Similar to SchemaBatch, but wraps around rocksdb::WriteBatch directly.
+/// Similar to SchemaBatch, but builds the batches in the native formats of the engines directly.
 /// For that to work, a reference to the DB needs to be held.
 pub struct NativeBatch<'db> {
     db: &'db DB,
@@ -226,19 +252,12 @@ impl WriteBatch for NativeBatch<'_> {
     }
 
     fn raw_put(&mut self, cf_name: ColumnFamilyName, key: Vec<u8>, value: Vec<u8>) -> DbResult<()> {
-        self.raw_batch
-            .inner
-            .put_cf(&self.db.get_cf_handle(cf_name)?, &key, &value);
-
-        Ok(())
+        self.db
+            .batch_put(&mut self.raw_batch, cf_name, &key, &value)
     }
 
     fn raw_delete(&mut self, cf_name: ColumnFamilyName, key: Vec<u8>) -> DbResult<()> {
-        self.raw_batch
-            .inner
-            .delete_cf(&self.db.get_cf_handle(cf_name)?, &key);
-
-        Ok(())
+        self.db.batch_delete(&mut self.raw_batch, cf_name, &key)
     }
 }
 
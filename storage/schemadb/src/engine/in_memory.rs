@@ -0,0 +1,303 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    batch::WriteOp,
+    engine::{wrong_batch_type, EngineBatch, KvEngine, RawIterator},
+    ColumnFamilyName, ReadOptions,
+};
+use accudo_infallible::RwLock;
+use accudo_storage_interface::{AccudoDbError, Result as DbResult};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+    sync::Arc,
+};
+
+type ColumnFamily = Arc<BTreeMap<Vec<u8>, Vec<u8>>>;
+
+/// Extracts the prefix of a key, like a RocksDB `SliceTransform` does.
+pub type PrefixExtractor = fn(&[u8]) -> &[u8];
+
+/// An engine keeping everything in memory, meant for tests.
+///
+/// Keys are ordered bytewise, like RocksDB does with the default comparator. Column families are
+/// copied on write while being iterated, so iterators see a consistent snapshot. Iteration bounds
+/// in `ReadOptions` are honored, and so is `prefix_same_as_start` for column families given a
+/// prefix extractor via [`InMemoryEngine::with_prefix_extractor`].
+#[derive(Debug)]
+pub struct InMemoryEngine {
+    column_families: RwLock<HashMap<ColumnFamilyName, ColumnFamily>>,
+    prefix_extractors: HashMap<ColumnFamilyName, PrefixExtractor>,
+}
+
+impl InMemoryEngine {
+    pub fn new(column_families: &[ColumnFamilyName]) -> Self {
+        Self {
+            column_families: RwLock::new(
+                column_families
+                    .iter()
+                    .map(|cf_name| (*cf_name, ColumnFamily::default()))
+                    .collect(),
+            ),
+            prefix_extractors: HashMap::new(),
+        }
+    }
+
+    /// Uses `extractor` to find the prefix of keys in `cf_name` when iterating with
+    /// `prefix_same_as_start`, the counterpart of setting a prefix extractor on a RocksDB column
+    /// family.
+    pub fn with_prefix_extractor(
+        mut self,
+        cf_name: ColumnFamilyName,
+        extractor: PrefixExtractor,
+    ) -> Self {
+        self.prefix_extractors.insert(cf_name, extractor);
+        self
+    }
+
+    fn column_family(&self, cf_name: &str) -> DbResult<ColumnFamily> {
+        self.column_families
+            .read()
+            .get(cf_name)
+            .cloned()
+            .ok_or_else(|| Self::cf_not_found(cf_name))
+    }
+
+    fn ensure_cf_exists(&self, cf_name: &str) -> DbResult<()> {
+        if self.column_families.read().contains_key(cf_name) {
+            Ok(())
+        } else {
+            Err(Self::cf_not_found(cf_name))
+        }
+    }
+
+    fn cf_not_found(cf_name: &str) -> AccudoDbError {
+        AccudoDbError::Other(format!(
+            "Column family not found in InMemoryEngine: {}",
+            cf_name
+        ))
+    }
+
+    fn ops(batch: &mut EngineBatch) -> DbResult<&mut Vec<(ColumnFamilyName, WriteOp)>> {
+        match batch {
+            EngineBatch::Ops(ops) => Ok(ops),
+            EngineBatch::RocksDb(_) => Err(wrong_batch_type("InMemoryEngine")),
+        }
+    }
+}
+
+impl KvEngine for InMemoryEngine {
+    fn get(&self, cf_name: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        Ok(self.column_family(cf_name)?.get(key).cloned())
+    }
+
+    fn raw_iter(&self, cf_name: &str, opts: ReadOptions) -> DbResult<Box<dyn RawIterator + '_>> {
+        let prefix_extractor = if opts.prefix_same_as_start() {
+            self.prefix_extractors.get(cf_name).copied()
+        } else {
+            None
+        };
+        Ok(Box::new(InMemoryIterator {
+            data: self.column_family(cf_name)?,
+            lower_bound: opts.iterate_lower_bound().map(<[u8]>::to_vec),
+            upper_bound: opts.iterate_upper_bound().map(<[u8]>::to_vec),
+            prefix_extractor,
+            prefix: None,
+            current: None,
+        }))
+    }
+
+    fn new_batch(&self) -> EngineBatch {
+        EngineBatch::Ops(Vec::new())
+    }
+
+    fn batch_put(
+        &self,
+        batch: &mut EngineBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+        value: &[u8],
+    ) -> DbResult<()> {
+        self.ensure_cf_exists(cf_name)?;
+        Self::ops(batch)?.push((
+            cf_name,
+            WriteOp::Value {
+                key: key.to_vec(),
+                value: value.to_vec(),
+            },
+        ));
+        Ok(())
+    }
+
+    fn batch_delete(
+        &self,
+        batch: &mut EngineBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+    ) -> DbResult<()> {
+        self.ensure_cf_exists(cf_name)?;
+        Self::ops(batch)?.push((cf_name, WriteOp::Deletion { key: key.to_vec() }));
+        Ok(())
+    }
+
+    fn write(&self, batch: EngineBatch, _sync: bool) -> DbResult<()> {
+        let EngineBatch::Ops(ops) = batch else {
+            return Err(wrong_batch_type("InMemoryEngine"));
+        };
+
+        let mut column_families = self.column_families.write();
+        for (cf_name, op) in ops {
+            let cf = Arc::make_mut(
+                column_families
+                    .get_mut(cf_name)
+                    .ok_or_else(|| Self::cf_not_found(cf_name))?,
+            );
+            match op {
+                WriteOp::Value { key, value } => {
+                    cf.insert(key, value);
+                },
+                WriteOp::Deletion { key } => {
+                    cf.remove(&key);
+                },
+            }
+        }
+        Ok(())
+    }
+
+    fn flush_cf(&self, cf_name: &str) -> DbResult<()> {
+        self.ensure_cf_exists(cf_name)
+    }
+}
+
+struct InMemoryIterator {
+    data: ColumnFamily,
+    // Inclusive.
+    lower_bound: Option<Vec<u8>>,
+    // Exclusive.
+    upper_bound: Option<Vec<u8>>,
+    // Only set when iterating with `prefix_same_as_start`.
+    prefix_extractor: Option<PrefixExtractor>,
+    // Prefix of the last seek key that entries must share.
+    prefix: Option<Vec<u8>>,
+    current: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+impl InMemoryIterator {
+    /// Narrows the requested range down to the iteration bounds, returning `None` if nothing is
+    /// left of it.
+    fn bounded<'a>(
+        &'a self,
+        lower: Bound<&'a [u8]>,
+        upper: Bound<&'a [u8]>,
+    ) -> Option<(Bound<&'a [u8]>, Bound<&'a [u8]>)> {
+        let lower = match (lower, self.lower_bound.as_deref()) {
+            (Bound::Included(key) | Bound::Excluded(key), Some(bound)) if key < bound => {
+                Bound::Included(bound)
+            },
+            (Bound::Unbounded, Some(bound)) => Bound::Included(bound),
+            (lower, _) => lower,
+        };
+        let upper = match (upper, self.upper_bound.as_deref()) {
+            (Bound::Included(key) | Bound::Excluded(key), Some(bound)) if key >= bound => {
+                Bound::Excluded(bound)
+            },
+            (Bound::Unbounded, Some(bound)) => Bound::Excluded(bound),
+            (upper, _) => upper,
+        };
+        // `BTreeMap::range()` panics on inverted ranges.
+        let is_empty = match (lower, upper) {
+            (Bound::Included(start), Bound::Included(end)) => start > end,
+            (
+                Bound::Included(start) | Bound::Excluded(start),
+                Bound::Included(end) | Bound::Excluded(end),
+            ) => start >= end,
+            _ => false,
+        };
+        (!is_empty).then_some((lower, upper))
+    }
+
+    fn has_prefix(&self, key: &[u8]) -> bool {
+        match (self.prefix_extractor, &self.prefix) {
+            (Some(extractor), Some(prefix)) => extractor(key) == prefix.as_slice(),
+            _ => true,
+        }
+    }
+
+    fn set_prefix(&mut self, key: Option<&[u8]>) {
+        self.prefix = self
+            .prefix_extractor
+            .zip(key)
+            .map(|(extractor, key)| extractor(key).to_vec());
+    }
+
+    fn first_from(&self, lower: Bound<&[u8]>) -> Option<(Vec<u8>, Vec<u8>)> {
+        self.bounded(lower, Bound::Unbounded)
+            .and_then(|range| self.data.range::<[u8], _>(range).next())
+            .filter(|(key, _)| self.has_prefix(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+    }
+
+    fn last_until(&self, upper: Bound<&[u8]>) -> Option<(Vec<u8>, Vec<u8>)> {
+        self.bounded(Bound::Unbounded, upper)
+            .and_then(|range| self.data.range::<[u8], _>(range).next_back())
+            .filter(|(key, _)| self.has_prefix(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+    }
+}
+
+impl RawIterator for InMemoryIterator {
+    fn seek_to_first(&mut self) {
+        self.prefix = None;
+        let current = self.first_from(Bound::Unbounded);
+        self.set_prefix(current.as_ref().map(|(key, _)| key.as_slice()));
+        self.current = current;
+    }
+
+    fn seek_to_last(&mut self) {
+        self.prefix = None;
+        let current = self.last_until(Bound::Unbounded);
+        self.set_prefix(current.as_ref().map(|(key, _)| key.as_slice()));
+        self.current = current;
+    }
+
+    fn seek(&mut self, key: &[u8]) {
+        self.set_prefix(Some(key));
+        self.current = self.first_from(Bound::Included(key));
+    }
+
+    fn seek_for_prev(&mut self, key: &[u8]) {
+        self.set_prefix(Some(key));
+        self.current = self.last_until(Bound::Included(key));
+    }
+
+    fn next(&mut self) {
+        self.current = self
+            .current
+            .as_ref()
+            .and_then(|(key, _)| self.first_from(Bound::Excluded(key.as_slice())));
+    }
+
+    fn prev(&mut self) {
+        self.current = self
+            .current
+            .as_ref()
+            .and_then(|(key, _)| self.last_until(Bound::Excluded(key.as_slice())));
+    }
+
+    fn valid(&self) -> bool {
+        self.current.is_some()
+    }
+
+    fn key(&self) -> Option<&[u8]> {
+        self.current.as_ref().map(|(key, _)| key.as_slice())
+    }
+
+    fn value(&self) -> Option<&[u8]> {
+        self.current.as_ref().map(|(_, value)| value.as_slice())
+    }
+
+    fn status(&self) -> DbResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Key-value engines that a [`DB`](crate::DB) stores raw keys and values in.
+//!
+//! Engines only deal with raw bytes. Schemas, codecs and metrics are handled on top of them by
+//! [`DB`](crate::DB), which can route different column families to different engines.
+
+mod in_memory;
+mod rocks_db;
+
+use crate::{batch::WriteOp, ColumnFamilyName, ReadOptions};
+use accudo_storage_interface::{AccudoDbError, Result as DbResult};
+pub use in_memory::{InMemoryEngine, PrefixExtractor};
+pub use rocks_db::RocksDbEngine;
+use std::{fmt::Debug, path::Path};
+
+/// A write batch in the native format of the engine it's going to be written to.
+pub enum EngineBatch {
+    RocksDb(rocksdb::WriteBatch),
+    Ops(Vec<(ColumnFamilyName, WriteOp)>),
+}
+
+impl EngineBatch {
+    pub fn size_in_bytes(&self) -> usize {
+        match self {
+            Self::RocksDb(batch) => batch.size_in_bytes(),
+            Self::Ops(ops) => ops
+                .iter()
+                .map(|(_cf_name, op)| match op {
+                    WriteOp::Value { key, value } => key.len() + value.len(),
+                    WriteOp::Deletion { key } => key.len(),
+                })
+                .sum(),
+        }
+    }
+}
+
+/// A raw iterator over a column family, modeled after `rocksdb::DBRawIterator`.
+///
+/// The iterator is positioned by one of the seek methods, after which `valid()` tells if it points
+/// to an entry.
+pub trait RawIterator {
+    fn seek_to_first(&mut self);
+
+    fn seek_to_last(&mut self);
+
+    /// Moves to the first key equal to or greater than `key`.
+    fn seek(&mut self, key: &[u8]);
+
+    /// Moves to the last key equal to or less than `key`.
+    fn seek_for_prev(&mut self, key: &[u8]);
+
+    fn next(&mut self);
+
+    fn prev(&mut self);
+
+    fn valid(&self) -> bool;
+
+    fn key(&self) -> Option<&[u8]>;
+
+    fn value(&self) -> Option<&[u8]>;
+
+    /// Returns the error, if any, that made the iterator invalid.
+    fn status(&self) -> DbResult<()>;
+}
+
+/// A key-value engine with column families, storing raw keys and values.
+pub trait KvEngine: Debug + Send + Sync {
+    fn get(&self, cf_name: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>>;
+
+    fn raw_iter(&self, cf_name: &str, opts: ReadOptions) -> DbResult<Box<dyn RawIterator + '_>>;
+
+    fn new_batch(&self) -> EngineBatch;
+
+    fn batch_put(
+        &self,
+        batch: &mut EngineBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+        value: &[u8],
+    ) -> DbResult<()>;
+
+    fn batch_delete(
+        &self,
+        batch: &mut EngineBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+    ) -> DbResult<()>;
+
+    /// Writes a batch created by `new_batch()` of this engine atomically.
+    fn write(&self, batch: EngineBatch, sync: bool) -> DbResult<()>;
+
+    fn flush_cf(&self, cf_name: &str) -> DbResult<()>;
+
+    fn get_property(&self, cf_name: &str, property_name: &str) -> DbResult<u64> {
+        Err(AccudoDbError::Other(format!(
+            "Property \"{}\" of column family \"{}\" is not supported by {:?}.",
+            property_name, cf_name, self,
+        )))
+    }
+
+    fn create_checkpoint(&self, path: &Path) -> DbResult<()> {
+        Err(AccudoDbError::Other(format!(
+            "Checkpoint at {:?} is not supported by {:?}.",
+            path, self,
+        )))
+    }
+}
+
+fn wrong_batch_type(engine: &str) -> AccudoDbError {
+    AccudoDbError::Other(format!("Batch was not created by {}.", engine))
+}
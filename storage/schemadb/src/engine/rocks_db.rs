@@ -0,0 +1,152 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    engine::{wrong_batch_type, EngineBatch, KvEngine, RawIterator},
+    ColumnFamilyName, IntoDbResult, ReadOptions,
+};
+use accudo_storage_interface::{AccudoDbError, Result as DbResult};
+use rocksdb::{DBRawIterator, WriteOptions};
+use std::path::Path;
+
+/// The engine backed by RocksDB, which is what a [`DB`](crate::DB) opened from a path uses.
+#[derive(Debug)]
+pub struct RocksDbEngine {
+    inner: rocksdb::DB,
+}
+
+impl RocksDbEngine {
+    pub fn new(inner: rocksdb::DB) -> Self {
+        Self { inner }
+    }
+
+    fn cf_handle(&self, cf_name: &str) -> DbResult<&rocksdb::ColumnFamily> {
+        self.inner.cf_handle(cf_name).ok_or_else(|| {
+            AccudoDbError::Other(format!(
+                "DB::cf_handle not found for column family name: {}",
+                cf_name
+            ))
+        })
+    }
+
+    fn rocksdb_batch(batch: &mut EngineBatch) -> DbResult<&mut rocksdb::WriteBatch> {
+        match batch {
+            EngineBatch::RocksDb(batch) => Ok(batch),
+            EngineBatch::Ops(_) => Err(wrong_batch_type("RocksDbEngine")),
+        }
+    }
+}
+
+impl KvEngine for RocksDbEngine {
+    fn get(&self, cf_name: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        self.inner
+            .get_cf(self.cf_handle(cf_name)?, key)
+            .into_db_res()
+    }
+
+    fn raw_iter(&self, cf_name: &str, opts: ReadOptions) -> DbResult<Box<dyn RawIterator + '_>> {
+        Ok(Box::new(self.inner.raw_iterator_cf_opt(
+            self.cf_handle(cf_name)?,
+            opts.to_rocksdb(),
+        )))
+    }
+
+    fn new_batch(&self) -> EngineBatch {
+        EngineBatch::RocksDb(rocksdb::WriteBatch::default())
+    }
+
+    fn batch_put(
+        &self,
+        batch: &mut EngineBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+        value: &[u8],
+    ) -> DbResult<()> {
+        Self::rocksdb_batch(batch)?.put_cf(self.cf_handle(cf_name)?, key, value);
+        Ok(())
+    }
+
+    fn batch_delete(
+        &self,
+        batch: &mut EngineBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+    ) -> DbResult<()> {
+        Self::rocksdb_batch(batch)?.delete_cf(self.cf_handle(cf_name)?, key);
+        Ok(())
+    }
+
+    fn write(&self, batch: EngineBatch, sync: bool) -> DbResult<()> {
+        let EngineBatch::RocksDb(batch) = batch else {
+            return Err(wrong_batch_type("RocksDbEngine"));
+        };
+        let mut opts = WriteOptions::default();
+        opts.set_sync(sync);
+        self.inner.write_opt(batch, &opts).into_db_res()
+    }
+
+    fn flush_cf(&self, cf_name: &str) -> DbResult<()> {
+        self.inner.flush_cf(self.cf_handle(cf_name)?).into_db_res()
+    }
+
+    fn get_property(&self, cf_name: &str, property_name: &str) -> DbResult<u64> {
+        self.inner
+            .property_int_value_cf(self.cf_handle(cf_name)?, property_name)
+            .into_db_res()?
+            .ok_or_else(|| {
+                AccudoDbError::Other(format!(
+                    "Unable to get property \"{}\" of  column family \"{}\".",
+                    property_name, cf_name,
+                ))
+            })
+    }
+
+    fn create_checkpoint(&self, path: &Path) -> DbResult<()> {
+        rocksdb::checkpoint::Checkpoint::new(&self.inner)
+            .into_db_res()?
+            .create_checkpoint(path)
+            .into_db_res()
+    }
+}
+
+impl RawIterator for DBRawIterator<'_> {
+    fn seek_to_first(&mut self) {
+        DBRawIterator::seek_to_first(self)
+    }
+
+    fn seek_to_last(&mut self) {
+        DBRawIterator::seek_to_last(self)
+    }
+
+    fn seek(&mut self, key: &[u8]) {
+        DBRawIterator::seek(self, key)
+    }
+
+    fn seek_for_prev(&mut self, key: &[u8]) {
+        DBRawIterator::seek_for_prev(self, key)
+    }
+
+    fn next(&mut self) {
+        DBRawIterator::next(self)
+    }
+
+    fn prev(&mut self) {
+        DBRawIterator::prev(self)
+    }
+
+    fn valid(&self) -> bool {
+        DBRawIterator::valid(self)
+    }
+
+    fn key(&self) -> Option<&[u8]> {
+        DBRawIterator::key(self)
+    }
+
+    fn value(&self) -> Option<&[u8]> {
+        DBRawIterator::value(self)
+    }
+
+    fn status(&self) -> DbResult<()> {
+        DBRawIterator::status(self).into_db_res()
+    }
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    IntoDbResult, KeyCodec, Schema, SeekKeyCodec, ValueCodec, ACCUDO_SCHEMADB_ITER_BYTES,
+    engine::RawIterator, KeyCodec, Schema, SeekKeyCodec, ValueCodec, ACCUDO_SCHEMADB_ITER_BYTES,
     ACCUDO_SCHEMADB_ITER_LATENCY_SECONDS, ACCUDO_SCHEMADB_SEEK_LATENCY_SECONDS,
 };
 use accudo_metrics_core::TimerHelper;
@@ -24,7 +24,7 @@ enum Status {
 /// DB Iterator parameterized on [`Schema`] that seeks with [`Schema::Key`] and yields
 /// [`Schema::Key`] and [`Schema::Value`]
 pub struct SchemaIterator<'a, S> {
-    db_iter: rocksdb::DBRawIterator<'a>,
+    db_iter: Box<dyn RawIterator + 'a>,
     direction: ScanDirection,
     status: Status,
     phantom: PhantomData<S>,
@@ -34,7 +34,7 @@ impl<'a, S> SchemaIterator<'a, S>
 where
     S: Schema,
 {
-    pub(crate) fn new(db_iter: rocksdb::DBRawIterator<'a>, direction: ScanDirection) -> Self {
+    pub(crate) fn new(db_iter: Box<dyn RawIterator + 'a>, direction: ScanDirection) -> Self {
         SchemaIterator {
             db_iter,
             direction,
@@ -102,7 +102,7 @@ where
         }
 
         if !self.db_iter.valid() {
-            self.db_iter.status().into_db_res()?;
+            self.db_iter.status()?;
             // advancing an invalid raw iter results in seg fault
             self.status = Status::Invalid;
             return Ok(None);
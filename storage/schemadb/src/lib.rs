@@ -13,12 +13,17 @@
 //! families.  To use this library to store a kind of key-value pairs, the user needs to use the
 //! [`define_schema!`] macro to define the schema name, the types of key and value, and name of the
 //! column family.
+//!
+//! Column families can also be served by other [`engine::KvEngine`]s, e.g. the in-memory engine used
+//! in tests.
 
 mod metrics;
 #[macro_use]
 pub mod schema;
 pub mod batch;
+pub mod engine;
 pub mod iterator;
+mod read_options;
 
 use crate::{
     metrics::{
@@ -31,16 +36,22 @@ use crate::{
 use accudo_logger::prelude::*;
 use accudo_metrics_core::TimerHelper;
 use accudo_storage_interface::{AccudoDbError, Result as DbResult};
-use anyhow::format_err;
-use batch::{IntoRawBatch, NativeBatch, WriteBatch};
+use batch::{IntoRawBatch, NativeBatch, RawBatch, WriteBatch};
+use engine::{InMemoryEngine, KvEngine, RocksDbEngine};
 use iterator::{ScanDirection, SchemaIterator};
-/// Type alias to `rocksdb::ReadOptions`. See [`rocksdb doc`](https://github.com/pingcap/rust-rocksdb/blob/master/src/rocksdb_options.rs)
+pub use read_options::ReadOptions;
+use rocksdb::ErrorKind;
 pub use rocksdb::{
-    BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType, Env, Options, ReadOptions,
+    BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType, Env, Options,
     SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
 };
-use rocksdb::{ErrorKind, WriteOptions};
-use std::{collections::HashSet, fmt::Debug, iter::Iterator, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    iter::Iterator,
+    path::Path,
+    sync::Arc,
+};
 
 pub type ColumnFamilyName = &'static str;
 
@@ -53,13 +64,51 @@ enum OpenMode<'a> {
 
 /// This DB is a schematized RocksDB wrapper where all data passed in and out are typed according to
 /// [`Schema`]s.
+///
+/// The raw data lives in [`KvEngine`]s, which is RocksDB unless the DB is created in memory or some
+/// column families are assigned other engines via [`DB::with_cf_engine`].
 #[derive(Debug)]
 pub struct DB {
     name: String, // for logging
-    inner: rocksdb::DB,
+    // The first engine serves all column families not found in `cf_engines`.
+    engines: Vec<Arc<dyn KvEngine>>,
+    // Column family name -> index in `engines`.
+    cf_engines: HashMap<ColumnFamilyName, usize>,
 }
 
 impl DB {
+    /// Creates a DB on top of the given engine.
+    pub fn new(name: &str, engine: Arc<dyn KvEngine>) -> Self {
+        Self {
+            name: name.to_string(),
+            engines: vec![engine],
+            cf_engines: HashMap::new(),
+        }
+    }
+
+    /// Creates a DB keeping everything in memory, for tests.
+    pub fn open_in_memory(name: &str, column_families: Vec<ColumnFamilyName>) -> Self {
+        info!(rocksdb_name = name, "Opened in-memory DB.");
+        Self::new(name, Arc::new(InMemoryEngine::new(&column_families)))
+    }
+
+    /// Serves `column_families` from `engine` instead of the engine(s) they are currently in.
+    ///
+    /// Note that a batch touching column families in different engines is written to each engine
+    /// atomically, but not to all of them as a whole.
+    pub fn with_cf_engine(
+        mut self,
+        column_families: &[ColumnFamilyName],
+        engine: Arc<dyn KvEngine>,
+    ) -> Self {
+        let engine_idx = self.engines.len();
+        self.engines.push(engine);
+        for cf_name in column_families {
+            self.cf_engines.insert(*cf_name, engine_idx);
+        }
+        self
+    }
+
     pub fn open(
         path: impl AsRef<Path>,
         name: &str,
@@ -187,10 +236,43 @@ impl DB {
             open_mode = ?open_mode,
             "Opened RocksDB."
         );
-        DB {
-            name: name.to_string(),
-            inner,
-        }
+        DB::new(name, Arc::new(RocksDbEngine::new(inner)))
+    }
+
+    fn engine_idx(&self, cf_name: &str) -> usize {
+        self.cf_engines.get(cf_name).copied().unwrap_or(0)
+    }
+
+    fn engine(&self, cf_name: &str) -> &dyn KvEngine {
+        self.engines[self.engine_idx(cf_name)].as_ref()
+    }
+
+    pub(crate) fn batch_put(
+        &self,
+        raw_batch: &mut RawBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+        value: &[u8],
+    ) -> DbResult<()> {
+        let engine_idx = self.engine_idx(cf_name);
+        let engine = &self.engines[engine_idx];
+        engine.batch_put(
+            raw_batch.engine_batch(engine_idx, engine),
+            cf_name,
+            key,
+            value,
+        )
+    }
+
+    pub(crate) fn batch_delete(
+        &self,
+        raw_batch: &mut RawBatch,
+        cf_name: ColumnFamilyName,
+        key: &[u8],
+    ) -> DbResult<()> {
+        let engine_idx = self.engine_idx(cf_name);
+        let engine = &self.engines[engine_idx];
+        engine.batch_delete(raw_batch.engine_batch(engine_idx, engine), cf_name, key)
     }
 
     /// Reads single record by key.
@@ -198,9 +280,10 @@ impl DB {
         let _timer = ACCUDO_SCHEMADB_GET_LATENCY_SECONDS.timer_with(&[S::COLUMN_FAMILY_NAME]);
 
         let k = <S::Key as KeyCodec<S>>::encode_key(schema_key)?;
-        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
 
-        let result = self.inner.get_cf(cf_handle, k).into_db_res()?;
+        let result = self
+            .engine(S::COLUMN_FAMILY_NAME)
+            .get(S::COLUMN_FAMILY_NAME, &k)?;
         ACCUDO_SCHEMADB_GET_BYTES.observe_with(
             &[S::COLUMN_FAMILY_NAME],
             result.as_ref().map_or(0.0, |v| v.len() as f64),
@@ -237,9 +320,9 @@ impl DB {
         opts: ReadOptions,
         direction: ScanDirection,
     ) -> DbResult<SchemaIterator<'_, S>> {
-        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
         Ok(SchemaIterator::new(
-            self.inner.raw_iterator_cf_opt(cf_handle, opts),
+            self.engine(S::COLUMN_FAMILY_NAME)
+                .raw_iter(S::COLUMN_FAMILY_NAME, opts)?,
             direction,
         ))
     }
@@ -267,18 +350,24 @@ impl DB {
         self.iter_with_direction::<S>(opts, ScanDirection::Backward)
     }
 
-    fn write_schemas_inner(&self, batch: impl IntoRawBatch, option: &WriteOptions) -> DbResult<()> {
+    fn write_schemas_inner(&self, batch: impl IntoRawBatch, sync: bool) -> DbResult<()> {
         let labels = [self.name.as_str()];
         let _timer = ACCUDO_SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS.timer_with(&labels);
 
         let raw_batch = batch.into_raw_batch(self)?;
 
-        let serialized_size = raw_batch.inner.size_in_bytes();
-        self.inner
-            .write_opt(raw_batch.inner, option)
-            .into_db_res()?;
+        let serialized_size = raw_batch.size_in_bytes();
+        let RawBatch {
+            engine_batches,
+            stats,
+        } = raw_batch;
+        for (engine, engine_batch) in self.engines.iter().zip(engine_batches) {
+            if let Some(engine_batch) = engine_batch {
+                engine.write(engine_batch, sync)?;
+            }
+        }
 
-        raw_batch.stats.commit();
+        stats.commit();
         ACCUDO_SCHEMADB_BATCH_COMMIT_BYTES.observe_with(&[&self.name], serialized_size as f64);
 
         Ok(())
@@ -286,7 +375,11 @@ impl DB {
 
     /// Writes a group of records wrapped in a [`SchemaBatch`].
     pub fn write_schemas(&self, batch: impl IntoRawBatch) -> DbResult<()> {
-        self.write_schemas_inner(batch, &sync_write_option())
+        // For now we always use synchronous writes. This makes sure that once the operation
+        // returns `Ok(())` the data is persisted even if the machine crashes. In the future we
+        // might consider selectively turning this off for some non-critical writes to improve
+        // performance.
+        self.write_schemas_inner(batch, /*sync=*/ true)
     }
 
     /// Writes without sync flag in write option.
@@ -295,51 +388,28 @@ impl DB {
     /// crashes (i.e., the machine does not reboot), no writes will be
     /// lost even if sync==false.
     pub fn write_schemas_relaxed(&self, batch: impl IntoRawBatch) -> DbResult<()> {
-        self.write_schemas_inner(batch, &WriteOptions::default())
-    }
-
-    fn get_cf_handle(&self, cf_name: &str) -> DbResult<&rocksdb::ColumnFamily> {
-        self.inner
-            .cf_handle(cf_name)
-            .ok_or_else(|| {
-                format_err!(
-                    "DB::cf_handle not found for column family name: {}",
-                    cf_name
-                )
-            })
-            .map_err(Into::into)
+        self.write_schemas_inner(batch, /*sync=*/ false)
     }
 
     /// Flushes memtable data. This is only used for testing `get_approximate_sizes_cf` in unit
     /// tests.
     pub fn flush_cf(&self, cf_name: &str) -> DbResult<()> {
-        self.inner
-            .flush_cf(self.get_cf_handle(cf_name)?)
-            .into_db_res()
+        self.engine(cf_name).flush_cf(cf_name)
     }
 
     pub fn get_property(&self, cf_name: &str, property_name: &str) -> DbResult<u64> {
-        self.inner
-            .property_int_value_cf(self.get_cf_handle(cf_name)?, property_name)
-            .into_db_res()?
-            .ok_or_else(|| {
-                accudo_storage_interface::AccudoDbError::Other(
-                    format!(
-                        "Unable to get property \"{}\" of  column family \"{}\".",
-                        property_name, cf_name,
-                    )
-                    .to_string(),
-                )
-            })
+        self.engine(cf_name).get_property(cf_name, property_name)
     }
 
     /// Creates new physical DB checkpoint in directory specified by `path`.
     pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> DbResult<()> {
-        rocksdb::checkpoint::Checkpoint::new(&self.inner)
-            .into_db_res()?
-            .create_checkpoint(path)
-            .into_db_res()?;
-        Ok(())
+        if !self.cf_engines.is_empty() {
+            return Err(AccudoDbError::Other(format!(
+                "Checkpoint is not supported for DB {} with per column family engines.",
+                self.name
+            )));
+        }
+        self.engines[0].create_checkpoint(path.as_ref())
     }
 }
 
@@ -349,15 +419,6 @@ impl Drop for DB {
     }
 }
 
-/// For now we always use synchronous writes. This makes sure that once the operation returns
-/// `Ok(())` the data is persisted even if the machine crashes. In the future we might consider
-/// selectively turning this off for some non-critical writes to improve performance.
-fn sync_write_option() -> rocksdb::WriteOptions {
-    let mut opts = rocksdb::WriteOptions::default();
-    opts.set_sync(true);
-    opts
-}
-
 trait DeUnc: AsRef<Path> {
     fn de_unc(&self) -> &Path {
         // `dunce` is needed to "de-UNC" because rocksdb doesn't take Windows UNC paths like `\\?\C:\`
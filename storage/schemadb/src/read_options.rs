@@ -0,0 +1,75 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+/// Options for iterating a column family, mirroring the subset of `rocksdb::ReadOptions` used
+/// in the code base.
+///
+/// Unlike `rocksdb::ReadOptions`, the options can be read back, so engines other than RocksDB
+/// can honor them.
+#[derive(Clone, Debug, Default)]
+pub struct ReadOptions {
+    iterate_lower_bound: Option<Vec<u8>>,
+    iterate_upper_bound: Option<Vec<u8>>,
+    prefix_same_as_start: bool,
+    total_order_seek: bool,
+    max_skippable_internal_keys: Option<u64>,
+}
+
+impl ReadOptions {
+    /// Sets the smallest key (inclusive) the iterator can return.
+    pub fn set_iterate_lower_bound<K: Into<Vec<u8>>>(&mut self, key: K) {
+        self.iterate_lower_bound = Some(key.into());
+    }
+
+    /// Sets the key (exclusive) the iterator stops before.
+    pub fn set_iterate_upper_bound<K: Into<Vec<u8>>>(&mut self, key: K) {
+        self.iterate_upper_bound = Some(key.into());
+    }
+
+    /// Makes the iterator stop at keys whose prefix, as defined by the prefix extractor of the
+    /// column family, differs from the prefix of the seek key.
+    pub fn set_prefix_same_as_start(&mut self, v: bool) {
+        self.prefix_same_as_start = v;
+    }
+
+    /// Makes seeks ignore the prefix extractor of the column family.
+    pub fn set_total_order_seek(&mut self, v: bool) {
+        self.total_order_seek = v;
+    }
+
+    /// Makes the iterator fail after skipping over this many deleted keys. Only meaningful for
+    /// RocksDB, other engines don't keep tombstones.
+    pub fn set_max_skippable_internal_keys(&mut self, num: u64) {
+        self.max_skippable_internal_keys = Some(num);
+    }
+
+    pub fn iterate_lower_bound(&self) -> Option<&[u8]> {
+        self.iterate_lower_bound.as_deref()
+    }
+
+    pub fn iterate_upper_bound(&self) -> Option<&[u8]> {
+        self.iterate_upper_bound.as_deref()
+    }
+
+    /// Whether iteration is restricted to the prefix of the seek key, which is the case if
+    /// `prefix_same_as_start` is set and `total_order_seek` is not.
+    pub fn prefix_same_as_start(&self) -> bool {
+        self.prefix_same_as_start && !self.total_order_seek
+    }
+
+    pub(crate) fn to_rocksdb(&self) -> rocksdb::ReadOptions {
+        let mut opts = rocksdb::ReadOptions::default();
+        if let Some(key) = &self.iterate_lower_bound {
+            opts.set_iterate_lower_bound(key.clone());
+        }
+        if let Some(key) = &self.iterate_upper_bound {
+            opts.set_iterate_upper_bound(key.clone());
+        }
+        opts.set_prefix_same_as_start(self.prefix_same_as_start);
+        opts.set_total_order_seek(self.total_order_seek);
+        if let Some(num) = self.max_skippable_internal_keys {
+            opts.set_max_skippable_internal_keys(num);
+        }
+        opts
+    }
+}
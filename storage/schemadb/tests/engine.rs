@@ -0,0 +1,297 @@
+// Copyright © Accudo Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use accudo_schemadb::{
+    batch::SchemaBatch,
+    define_schema,
+    engine::InMemoryEngine,
+    schema::{KeyCodec, Schema, ValueCodec},
+    ColumnFamilyName, ReadOptions, DB,
+};
+use anyhow::Result;
+use byteorder::{BigEndian, ReadBytesExt};
+use proptest::{collection::vec, prelude::*};
+use rocksdb::DEFAULT_COLUMN_FAMILY_NAME;
+use std::sync::Arc;
+
+define_schema!(TestSchema1, TestField, TestField, "TestCF1");
+define_schema!(TestSchema2, TestField, TestField, "TestCF2");
+
+#[derive(Debug, Eq, PartialEq)]
+struct TestField(u32);
+
+impl TestField {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_be_bytes().to_vec()
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut reader = std::io::Cursor::new(data);
+        Ok(TestField(reader.read_u32::<BigEndian>()?))
+    }
+}
+
+impl KeyCodec<TestSchema1> for TestField {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_bytes())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Self::from_bytes(data)
+    }
+}
+
+impl ValueCodec<TestSchema1> for TestField {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_bytes())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::from_bytes(data)
+    }
+}
+
+impl KeyCodec<TestSchema2> for TestField {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_bytes())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Self::from_bytes(data)
+    }
+}
+
+impl ValueCodec<TestSchema2> for TestField {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_bytes())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::from_bytes(data)
+    }
+}
+
+fn get_column_families() -> Vec<ColumnFamilyName> {
+    vec![
+        DEFAULT_COLUMN_FAMILY_NAME,
+        TestSchema1::COLUMN_FAMILY_NAME,
+        TestSchema2::COLUMN_FAMILY_NAME,
+    ]
+}
+
+fn open_rocksdb(dir: &accudo_temppath::TempPath) -> DB {
+    let mut db_opts = rocksdb::Options::default();
+    db_opts.create_if_missing(true);
+    db_opts.create_missing_column_families(true);
+    DB::open(dir.path(), "test", get_column_families(), &db_opts).expect("Failed to open DB.")
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Put(u32, u32),
+    Delete(u32),
+}
+
+fn arb_op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..100u32, any::<u32>()).prop_map(|(key, value)| Op::Put(key, value)),
+        (0..100u32).prop_map(Op::Delete),
+    ]
+}
+
+fn apply(db: &DB, batches: &[Vec<Op>]) {
+    for ops in batches {
+        let mut batch = SchemaBatch::new();
+        for op in ops {
+            match op {
+                Op::Put(key, value) => batch
+                    .put::<TestSchema1>(&TestField(*key), &TestField(*value))
+                    .unwrap(),
+                Op::Delete(key) => batch.delete::<TestSchema1>(&TestField(*key)).unwrap(),
+            }
+        }
+        db.write_schemas(batch).unwrap();
+    }
+}
+
+fn collect(db: &DB, seek_key: u32, seek_for_prev: bool, backward: bool) -> Vec<(u32, u32)> {
+    collect_with_opts(
+        db,
+        ReadOptions::default(),
+        seek_key,
+        seek_for_prev,
+        backward,
+    )
+}
+
+fn collect_with_opts(
+    db: &DB,
+    opts: ReadOptions,
+    seek_key: u32,
+    seek_for_prev: bool,
+    backward: bool,
+) -> Vec<(u32, u32)> {
+    let mut iter = if backward {
+        db.rev_iter_with_opts::<TestSchema1>(opts).unwrap()
+    } else {
+        db.iter_with_opts::<TestSchema1>(opts).unwrap()
+    };
+    if seek_for_prev {
+        iter.seek_for_prev(&TestField(seek_key)).unwrap();
+    } else {
+        iter.seek(&TestField(seek_key)).unwrap();
+    }
+    iter.map(|res| res.map(|(key, value)| (key.0, value.0)))
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(20))]
+
+    #[test]
+    fn test_in_memory_engine_matches_rocksdb(
+        batches in vec(vec(arb_op(), 0..20), 1..10),
+        seek_keys in vec(0..110u32, 1..10),
+    ) {
+        let tmpdir = accudo_temppath::TempPath::new();
+        let rocksdb = open_rocksdb(&tmpdir);
+        let in_memory = DB::open_in_memory("test", get_column_families());
+        apply(&rocksdb, &batches);
+        apply(&in_memory, &batches);
+
+        for key in 0..110 {
+            prop_assert_eq!(
+                rocksdb.get::<TestSchema1>(&TestField(key)).unwrap(),
+                in_memory.get::<TestSchema1>(&TestField(key)).unwrap()
+            );
+        }
+        for seek_key in seek_keys {
+            for (seek_for_prev, backward) in [(false, false), (false, true), (true, false), (true, true)] {
+                prop_assert_eq!(
+                    collect(&rocksdb, seek_key, seek_for_prev, backward),
+                    collect(&in_memory, seek_key, seek_for_prev, backward)
+                );
+            }
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(20))]
+
+    #[test]
+    fn test_in_memory_engine_bounds_match_rocksdb(
+        batches in vec(vec(arb_op(), 0..20), 1..10),
+        seek_keys in vec(0..110u32, 1..10),
+        lower_bound in proptest::option::of(0..110u32),
+        upper_bound in proptest::option::of(0..110u32),
+    ) {
+        // RocksDB leaves inverted bounds undefined.
+        if let (Some(lower), Some(upper)) = (lower_bound, upper_bound) {
+            prop_assume!(lower <= upper);
+        }
+        let tmpdir = accudo_temppath::TempPath::new();
+        let rocksdb = open_rocksdb(&tmpdir);
+        let in_memory = DB::open_in_memory("test", get_column_families());
+        apply(&rocksdb, &batches);
+        apply(&in_memory, &batches);
+
+        let mut opts = ReadOptions::default();
+        if let Some(key) = lower_bound {
+            opts.set_iterate_lower_bound(TestField(key).to_bytes());
+        }
+        if let Some(key) = upper_bound {
+            opts.set_iterate_upper_bound(TestField(key).to_bytes());
+        }
+        for seek_key in seek_keys {
+            for (seek_for_prev, backward) in [(false, false), (false, true), (true, false), (true, true)] {
+                prop_assert_eq!(
+                    collect_with_opts(&rocksdb, opts.clone(), seek_key, seek_for_prev, backward),
+                    collect_with_opts(&in_memory, opts.clone(), seek_key, seek_for_prev, backward)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_in_memory_iterator_is_a_snapshot() {
+    let db = DB::open_in_memory("test", get_column_families());
+    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+    db.put::<TestSchema1>(&TestField(1), &TestField(1)).unwrap();
+
+    let mut iter = db.iter::<TestSchema1>().unwrap();
+    iter.seek_to_first();
+    db.put::<TestSchema1>(&TestField(2), &TestField(2)).unwrap();
+    db.delete::<TestSchema1>(&TestField(1)).unwrap();
+
+    let values: Vec<_> = iter.map(|res| res.unwrap().0 .0).collect();
+    assert_eq!(values, vec![0, 1]);
+    assert!(db.get::<TestSchema1>(&TestField(1)).unwrap().is_none());
+    assert_eq!(
+        db.get::<TestSchema1>(&TestField(2)).unwrap(),
+        Some(TestField(2))
+    );
+}
+
+#[test]
+fn test_in_memory_unknown_column_family() {
+    let db = DB::open_in_memory(
+        "test",
+        vec![DEFAULT_COLUMN_FAMILY_NAME, TestSchema1::COLUMN_FAMILY_NAME],
+    );
+    assert!(db.get::<TestSchema2>(&TestField(0)).is_err());
+    assert!(db.iter::<TestSchema2>().is_err());
+    assert!(db.put::<TestSchema2>(&TestField(0), &TestField(0)).is_err());
+}
+
+#[test]
+fn test_per_column_family_engine() {
+    let tmpdir = accudo_temppath::TempPath::new();
+    let in_memory_engine = Arc::new(InMemoryEngine::new(&[TestSchema2::COLUMN_FAMILY_NAME]));
+    let db = open_rocksdb(&tmpdir)
+        .with_cf_engine(&[TestSchema2::COLUMN_FAMILY_NAME], in_memory_engine.clone());
+
+    let mut batch = SchemaBatch::new();
+    batch
+        .put::<TestSchema1>(&TestField(0), &TestField(1))
+        .unwrap();
+    batch
+        .put::<TestSchema2>(&TestField(2), &TestField(3))
+        .unwrap();
+    db.write_schemas(batch).unwrap();
+
+    assert_eq!(
+        db.get::<TestSchema1>(&TestField(0)).unwrap(),
+        Some(TestField(1))
+    );
+    assert_eq!(
+        db.get::<TestSchema2>(&TestField(2)).unwrap(),
+        Some(TestField(3))
+    );
+
+    // TestCF2 is served by the in-memory engine only.
+    let in_memory_view = DB::new("in_memory_view", in_memory_engine);
+    assert_eq!(
+        in_memory_view.get::<TestSchema2>(&TestField(2)).unwrap(),
+        Some(TestField(3))
+    );
+    drop(db);
+    let db = open_rocksdb(&tmpdir);
+    assert_eq!(
+        db.get::<TestSchema1>(&TestField(0)).unwrap(),
+        Some(TestField(1))
+    );
+    assert!(db.get::<TestSchema2>(&TestField(2)).unwrap().is_none());
+
+    // Checkpoints are only supported for DBs on a single engine.
+    let checkpoint_dir = accudo_temppath::TempPath::new();
+    assert!(db
+        .with_cf_engine(
+            &[TestSchema2::COLUMN_FAMILY_NAME],
+            Arc::new(InMemoryEngine::new(&[TestSchema2::COLUMN_FAMILY_NAME])),
+        )
+        .create_checkpoint(checkpoint_dir.path())
+        .is_err());
+}
@@ -4,14 +4,16 @@
 
 use accudo_schemadb::{
     define_schema,
+    engine::InMemoryEngine,
     iterator::SchemaIterator,
     schema::{KeyCodec, Schema, SeekKeyCodec, ValueCodec},
-    DB,
+    ReadOptions, DB,
 };
 use accudo_storage_interface::AccudoDbError;
 use anyhow::Result;
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use rocksdb::{ColumnFamilyDescriptor, SliceTransform, DEFAULT_COLUMN_FAMILY_NAME};
+use std::sync::Arc;
 
 define_schema!(TestSchema, TestKey, TestValue, "TestCF");
 
@@ -292,10 +294,14 @@ fn test_seek_for_prev_by_2prefix() {
 }
 
 struct TestDBWithPrefixExtractor {
-    _tmpdir: accudo_temppath::TempPath,
+    _tmpdir: Option<accudo_temppath::TempPath>,
     db: DB,
 }
 
+fn two_prefix_extractor(key: &[u8]) -> &[u8] {
+    &key[0..std::cmp::min(8, key.len())]
+}
+
 impl TestDBWithPrefixExtractor {
     fn new() -> Self {
         let tmpdir = accudo_temppath::TempPath::new();
@@ -315,7 +321,7 @@ impl TestDBWithPrefixExtractor {
                     let mut opts = rocksdb::Options::default();
                     opts.set_prefix_extractor(SliceTransform::create(
                         "2_prefix_extractor",
-                        |key| &key[0..std::cmp::min(8, key.len())],
+                        two_prefix_extractor,
                         None,
                     ));
                     opts
@@ -323,7 +329,18 @@ impl TestDBWithPrefixExtractor {
             ],
         )
         .unwrap();
+        Self::populate(Some(tmpdir), db)
+    }
 
+    /// The same data in the in-memory engine, with the same prefix extractor.
+    fn new_in_memory() -> Self {
+        let engine =
+            InMemoryEngine::new(&[DEFAULT_COLUMN_FAMILY_NAME, TestSchema::COLUMN_FAMILY_NAME])
+                .with_prefix_extractor(TestSchema::COLUMN_FAMILY_NAME, two_prefix_extractor);
+        Self::populate(None, DB::new("test_with_prefix", Arc::new(engine)))
+    }
+
+    fn populate(tmpdir: Option<accudo_temppath::TempPath>, db: DB) -> Self {
         // delete later
         db.put::<TestSchema>(&TestKey(1, 1, 1), &TestValue(111))
             .unwrap();
@@ -389,7 +406,7 @@ impl TestDBWithPrefixExtractor {
     }
 
     fn iter_with_same_prefix(&self) -> SchemaIterator<'_, TestSchema> {
-        let mut opts = rocksdb::ReadOptions::default();
+        let mut opts = ReadOptions::default();
         opts.set_prefix_same_as_start(true);
         self.db
             .iter_with_opts(opts)
@@ -397,7 +414,7 @@ impl TestDBWithPrefixExtractor {
     }
 
     fn iter_with_max_skipped_deletions(&self, num_skips: u64) -> SchemaIterator<'_, TestSchema> {
-        let mut opts = rocksdb::ReadOptions::default();
+        let mut opts = ReadOptions::default();
         opts.set_max_skippable_internal_keys(num_skips);
         self.db
             .iter_with_opts(opts)
@@ -405,7 +422,7 @@ impl TestDBWithPrefixExtractor {
     }
 
     fn iter_with_upper_bound(&self, upper_bound: Vec<u8>) -> SchemaIterator<'_, TestSchema> {
-        let mut opts = rocksdb::ReadOptions::default();
+        let mut opts = ReadOptions::default();
         opts.set_iterate_upper_bound(upper_bound);
         self.db
             .iter_with_opts(opts)
@@ -415,46 +432,52 @@ impl TestDBWithPrefixExtractor {
 
 #[test]
 fn test_iter_with_prefix_extractor() {
-    let db = TestDBWithPrefixExtractor::new();
+    for db in [
+        TestDBWithPrefixExtractor::new(),
+        TestDBWithPrefixExtractor::new_in_memory(),
+    ] {
+        let all_values = [122, 123, 125, 155, 177, 222, 277, 288, 299, 399];
 
-    let all_values = [122, 123, 125, 155, 177, 222, 277, 288, 299, 399];
+        let mut iter = db.iter();
+        iter.seek(&TestKey(0, 0, 0)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), all_values);
 
-    let mut iter = db.iter();
-    iter.seek(&TestKey(0, 0, 0)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), all_values);
+        iter.seek(&KeyPrefix2(0, 0)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), all_values);
 
-    iter.seek(&KeyPrefix2(0, 0)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), all_values);
-
-    iter.seek(&KeyPrefix1(0)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), all_values);
+        iter.seek(&KeyPrefix1(0)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), all_values);
+    }
 }
 
 #[test]
 fn test_iter_with_same_prefix() {
-    let db = TestDBWithPrefixExtractor::new();
+    for db in [
+        TestDBWithPrefixExtractor::new(),
+        TestDBWithPrefixExtractor::new_in_memory(),
+    ] {
+        let mut iter = db.iter_with_same_prefix();
+        iter.seek(&TestKey(1, 2, 0)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), [122, 123, 125]);
 
-    let mut iter = db.iter_with_same_prefix();
-    iter.seek(&TestKey(1, 2, 0)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), [122, 123, 125]);
+        iter.seek(&TestKey(1, 2, 3)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), [123, 125]);
 
-    iter.seek(&TestKey(1, 2, 3)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), [123, 125]);
+        iter.seek(&TestKey(1, 2, 4)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), [125]);
 
-    iter.seek(&TestKey(1, 2, 4)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), [125]);
+        iter.seek(&KeyPrefix2(1, 2)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), [122, 123, 125]);
 
-    iter.seek(&KeyPrefix2(1, 2)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), [122, 123, 125]);
+        iter.seek(&KeyPrefix2(1, 0)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), EMPTY);
 
-    iter.seek(&KeyPrefix2(1, 0)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), EMPTY);
+        iter.seek(&KeyPrefix2(1, 1)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), EMPTY);
 
-    iter.seek(&KeyPrefix2(1, 1)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), EMPTY);
-
-    iter.seek(&KeyPrefix1(1)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), EMPTY);
+        iter.seek(&KeyPrefix1(1)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), EMPTY);
+    }
 }
 
 #[test]
@@ -518,27 +541,30 @@ fn test_iter_with_max_skipped_deletions() {
 
 #[test]
 fn test_iter_with_upper_bound() {
-    let db = TestDBWithPrefixExtractor::new();
-
-    let mut iter =
-        db.iter_with_upper_bound(KeyCodec::<TestSchema>::encode_key(&TestKey(1, 5, 5)).unwrap());
-    iter.seek_to_first();
-    assert_eq!(collect_values_mut(&mut iter), [122, 123, 125]);
-
-    let mut iter =
-        db.iter_with_upper_bound(KeyCodec::<TestSchema>::encode_key(&TestKey(1, 2, 4)).unwrap());
-    iter.seek_to_first();
-    assert_eq!(collect_values_mut(&mut iter), [122, 123]);
-
-    let mut iter = db.iter_with_upper_bound(
-        SeekKeyCodec::<TestSchema>::encode_seek_key(&KeyPrefix1(2)).unwrap(),
-    );
-    iter.seek(&KeyPrefix1(1)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), [122, 123, 125, 155, 177]);
-
-    let mut iter = db.iter_with_upper_bound(
-        SeekKeyCodec::<TestSchema>::encode_seek_key(&KeyPrefix2(1, 5)).unwrap(),
-    );
-    iter.seek(&KeyPrefix1(1)).unwrap();
-    assert_eq!(collect_values_mut(&mut iter), [122, 123, 125]);
+    for db in [
+        TestDBWithPrefixExtractor::new(),
+        TestDBWithPrefixExtractor::new_in_memory(),
+    ] {
+        let mut iter = db
+            .iter_with_upper_bound(KeyCodec::<TestSchema>::encode_key(&TestKey(1, 5, 5)).unwrap());
+        iter.seek_to_first();
+        assert_eq!(collect_values_mut(&mut iter), [122, 123, 125]);
+
+        let mut iter = db
+            .iter_with_upper_bound(KeyCodec::<TestSchema>::encode_key(&TestKey(1, 2, 4)).unwrap());
+        iter.seek_to_first();
+        assert_eq!(collect_values_mut(&mut iter), [122, 123]);
+
+        let mut iter = db.iter_with_upper_bound(
+            SeekKeyCodec::<TestSchema>::encode_seek_key(&KeyPrefix1(2)).unwrap(),
+        );
+        iter.seek(&KeyPrefix1(1)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), [122, 123, 125, 155, 177]);
+
+        let mut iter = db.iter_with_upper_bound(
+            SeekKeyCodec::<TestSchema>::encode_seek_key(&KeyPrefix2(1, 5)).unwrap(),
+        );
+        iter.seek(&KeyPrefix1(1)).unwrap();
+        assert_eq!(collect_values_mut(&mut iter), [122, 123, 125]);
+    }
 }
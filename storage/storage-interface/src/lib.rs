@@ -32,7 +32,7 @@ use accudo_types::{
     write_set::WriteSet,
 };
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 use thiserror::Error;
 
 pub mod block_info;
@@ -645,6 +645,18 @@ pub trait DbWriter: Send + Sync {
     ) -> Result<()> {
         unimplemented!()
     }
+
+    /// Creates a physical checkpoint of the live DB in the directory specified by `cp_path`,
+    /// without interrupting ongoing commits.
+    ///
+    /// The checkpoint can be opened as a regular DB, which truncates whatever was written beyond
+    /// the overall commit progress at the moment the checkpoint started.
+    fn create_live_checkpoint(&self, cp_path: &Path) -> Result<()> {
+        crate::db_other_bail!(
+            "Live checkpoints are not supported by this DB: {:?}",
+            cp_path
+        )
+    }
 }
 
 #[derive(Clone)]
@@ -2,9 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::smoke_test_environment::SwarmBuilder;
-use accudo::{account::create::DEFAULT_FUNDED_COINS, common::types::GasOptions};
+use accudo::{
+    account::create::DEFAULT_FUNDED_COINS,
+    common::types::{AccountScheme, GasOptions},
+};
 use accudo_crypto::{PrivateKey, ValidCryptoMaterialStringExt};
 use accudo_keygen::KeyGen;
+use accudo_temppath::TempPath;
 
 #[tokio::test]
 async fn test_account_flow() {
@@ -136,3 +140,60 @@ async fn test_account_key_rotation() {
         .await
         .expect("New key should be able to transfer");
 }
+
+#[tokio::test]
+async fn test_account_post_quantum_key_rotation() {
+    let (_swarm, mut cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_accudo()
+        .build_with_cli(3)
+        .await;
+    let key_dir = TempPath::new();
+    key_dir.create_as_dir().unwrap();
+    let key_file = key_dir.path().join("dilithium3");
+    cli.generate_dilithium3_key(key_file.clone()).await.unwrap();
+
+    // Rotate one account to a Dilithium3 key and another to a hybrid multi-key
+    cli.rotate_key_to_post_quantum(0, key_file.clone(), false)
+        .await
+        .unwrap();
+    cli.rotate_key_to_post_quantum(1, key_file.clone(), true)
+        .await
+        .unwrap();
+
+    // Neither account can be used with its Ed25519 key alone anymore
+    cli.transfer_coins(0, 2, 5, None)
+        .await
+        .expect_err("Ed25519 key should not be able to transfer");
+    cli.transfer_coins(1, 2, 5, None)
+        .await
+        .expect_err("Ed25519 key should not be able to transfer");
+
+    // Both accounts can transfer when signing with their new keys. The rotation doesn't
+    // update the originating address table, so the sender is passed explicitly
+    cli.init_with_account_scheme(
+        Some("post_quantum"),
+        cli.private_key(0),
+        AccountScheme::PostQuantum,
+        Some(key_file.clone()),
+    )
+    .await
+    .unwrap();
+    cli.init_with_account_scheme(
+        Some("hybrid"),
+        cli.private_key(1),
+        AccountScheme::Hybrid,
+        Some(key_file),
+    )
+    .await
+    .unwrap();
+    let response = cli
+        .transfer_coins_with_profile("post_quantum", 0, 2, 5)
+        .await
+        .unwrap();
+    assert!(response.success);
+    let response = cli
+        .transfer_coins_with_profile("hybrid", 1, 2, 5)
+        .await
+        .unwrap();
+    assert!(response.success);
+}
@@ -66,6 +66,8 @@ pub enum MempoolStatusCode {
     UnknownStatus = 6,
     // The transaction filter has rejected the transaction
     RejectedByFilter = 7,
+    // An admission quota of the mempool has rejected the transaction
+    RateLimited = 8,
 }
 
 impl TryFrom<u64> for MempoolStatusCode {
@@ -81,6 +83,7 @@ impl TryFrom<u64> for MempoolStatusCode {
             5 => Ok(MempoolStatusCode::VmError),
             6 => Ok(MempoolStatusCode::UnknownStatus),
             7 => Ok(MempoolStatusCode::RejectedByFilter),
+            8 => Ok(MempoolStatusCode::RateLimited),
             _ => Err("invalid StatusCode"),
         }
     }
@@ -1304,6 +1304,100 @@ impl PostQuantumPublicKey {
     }
 }
 
+/// An Ed25519 public key bound to a post-quantum public key as a single unit.
+///
+/// Both halves must sign every message, so an account behind a hybrid key stays safe as long as
+/// either of the two schemes remains unbroken.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct HybridPublicKey {
+    classical: Ed25519PublicKey,
+    post_quantum: PostQuantumPublicKey,
+}
+
+impl HybridPublicKey {
+    pub fn new(classical: Ed25519PublicKey, post_quantum: PostQuantumPublicKey) -> Self {
+        Self {
+            classical,
+            post_quantum,
+        }
+    }
+
+    pub fn classical(&self) -> &Ed25519PublicKey {
+        &self.classical
+    }
+
+    pub fn post_quantum(&self) -> &PostQuantumPublicKey {
+        &self.post_quantum
+    }
+}
+
+/// The pair of signatures a [`HybridPublicKey`] produces over the same signing message.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct HybridSignature {
+    classical: SignatureBundle,
+    post_quantum: SignatureBundle,
+}
+
+impl HybridSignature {
+    pub fn new(classical: SignatureBundle, post_quantum: SignatureBundle) -> Self {
+        Self {
+            classical,
+            post_quantum,
+        }
+    }
+
+    pub fn classical(&self) -> &SignatureBundle {
+        &self.classical
+    }
+
+    pub fn post_quantum(&self) -> &SignatureBundle {
+        &self.post_quantum
+    }
+
+    /// Verifies both halves against `signing_bytes`; either one failing rejects the pair.
+    pub fn verify(&self, public_key: &HybridPublicKey, signing_bytes: &[u8]) -> Result<()> {
+        ensure!(
+            self.classical.scheme == SchemeId::Ed25519,
+            "hybrid classical signature must be ed25519, got {}",
+            self.classical.scheme
+        );
+        let pq_scheme = public_key.post_quantum.scheme();
+        ensure!(
+            pq_scheme.is_post_quantum_signature(),
+            "hybrid key carries a non post-quantum scheme {}",
+            pq_scheme
+        );
+        ensure!(
+            self.post_quantum.scheme == pq_scheme,
+            "post-quantum scheme mismatch between hybrid key and signature"
+        );
+
+        for (key_bytes, signature, half) in [
+            (
+                public_key.classical.to_bytes().to_vec(),
+                &self.classical,
+                "classical",
+            ),
+            (
+                public_key.post_quantum.as_bytes().to_vec(),
+                &self.post_quantum,
+                "post-quantum",
+            ),
+        ] {
+            let adapter = PQ_REGISTRY.signature(signature.scheme).ok_or_else(|| {
+                Error::msg(format!(
+                    "no verifier registered for scheme {}",
+                    signature.scheme
+                ))
+            })?;
+            adapter
+                .verify(&key_bytes, signing_bytes, signature.bytes())
+                .with_context(|| format!("hybrid {half} signature verification failed"))?;
+        }
+        Ok(())
+    }
+}
+
 impl SingleKeyAuthenticator {
     pub fn new(public_key: AnyPublicKey, signature: AnySignature) -> Self {
         Self {
@@ -1365,6 +1459,22 @@ impl SingleKeyAuthenticator {
         self.post_quantum_public_key.is_some() && self.post_quantum_signature.is_some()
     }
 
+    /// Returns the schemes of all post-quantum signatures carried by this authenticator, i.e.
+    /// a post-quantum primary signature and/or a post-quantum companion signature.
+    pub fn post_quantum_schemes(&self) -> Vec<SchemeId> {
+        let mut schemes = vec![];
+        if let AnySignature::PostQuantum { signature } = &self.signature {
+            schemes.push(signature.scheme);
+        }
+        if let AnySignature::Hybrid { signature } = &self.signature {
+            schemes.push(signature.post_quantum().scheme);
+        }
+        if let Some(signature) = &self.post_quantum_signature {
+            schemes.push(signature.scheme);
+        }
+        schemes
+    }
+
     pub fn requires_post_quantum(&self) -> bool {
         matches!(
             self.public_key,
@@ -1427,6 +1537,9 @@ pub enum AnySignature {
     PostQuantum {
         signature: SignatureBundle,
     },
+    Hybrid {
+        signature: HybridSignature,
+    },
 }
 
 impl AnySignature {
@@ -1450,6 +1563,10 @@ impl AnySignature {
         Self::PostQuantum { signature }
     }
 
+    pub fn hybrid(signature: HybridSignature) -> Self {
+        Self::Hybrid { signature }
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             Self::Ed25519 { .. } => "Ed25519",
@@ -1457,6 +1574,7 @@ impl AnySignature {
             Self::WebAuthn { .. } => "WebAuthn",
             Self::Keyless { .. } => "Keyless",
             Self::PostQuantum { .. } => "PostQuantum",
+            Self::Hybrid { .. } => "Hybrid",
         }
     }
 
@@ -1467,13 +1585,16 @@ impl AnySignature {
     ) -> Result<()> {
         #[cfg(feature = "quantum_strict")]
         {
-            if !matches!(self, Self::PostQuantum { .. }) {
+            if !matches!(self, Self::PostQuantum { .. } | Self::Hybrid { .. }) {
                 bail!(
                     "classical signatures are disabled in `quantum_strict` builds (saw {})",
                     self.name()
                 );
             }
-            if !matches!(public_key, AnyPublicKey::PostQuantum { .. }) {
+            if !matches!(
+                public_key,
+                AnyPublicKey::PostQuantum { .. } | AnyPublicKey::Hybrid { .. }
+            ) {
                 bail!("classical public keys are disabled in `quantum_strict` builds");
             }
         }
@@ -1506,6 +1627,10 @@ impl AnySignature {
                 adapter.verify(public_key, &signing_bytes, signature.bytes())?;
                 Ok(())
             },
+            (Self::Hybrid { signature }, AnyPublicKey::Hybrid { public_key }) => {
+                let signing_bytes = signing_message(message)?;
+                signature.verify(public_key, &signing_bytes)
+            },
             _ => bail!("Invalid key, signature pairing"),
         }
     }
@@ -1576,6 +1701,9 @@ pub enum AnyPublicKey {
         #[serde(with = "serde_bytes")]
         public_key: Vec<u8>,
     },
+    Hybrid {
+        public_key: HybridPublicKey,
+    },
 }
 
 impl AnyPublicKey {
@@ -1603,6 +1731,10 @@ impl AnyPublicKey {
         Self::PostQuantum { scheme, public_key }
     }
 
+    pub fn hybrid(public_key: HybridPublicKey) -> Self {
+        Self::Hybrid { public_key }
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         bcs::to_bytes(self).expect("Only unhandleable errors happen here.")
     }
@@ -1909,6 +2041,76 @@ mod tests {
         missing_pq.verify_dual(&payload).unwrap_err();
     }
 
+    #[test]
+    fn verify_hybrid_single_key_auth() {
+        use serde::Serialize;
+
+        #[derive(Clone, Serialize, CryptoHasher, BCSCryptoHash)]
+        struct DummyPayload {
+            value: u64,
+        }
+
+        let payload = DummyPayload { value: 11 };
+        let signing_bytes = signing_message(&payload).unwrap();
+
+        let classical = Ed25519PrivateKey::generate_for_testing();
+        let pq_keypair = Dilithium3KeyPair::generate().unwrap();
+        let hybrid_key = HybridPublicKey::new(
+            classical.public_key(),
+            PostQuantumPublicKey::new(SchemeId::Dilithium3, pq_keypair.public_key().to_vec()),
+        );
+        let classical_signature = SignatureBundle::new(
+            SchemeId::Ed25519,
+            classical.sign(&payload).unwrap().to_bytes().to_vec(),
+        );
+        let pq_signature = pq_keypair.sign(&signing_bytes).unwrap();
+
+        let hybrid_auth = SingleKeyAuthenticator::new(
+            AnyPublicKey::hybrid(hybrid_key.clone()),
+            AnySignature::hybrid(HybridSignature::new(
+                classical_signature.clone(),
+                pq_signature.clone(),
+            )),
+        );
+        hybrid_auth.verify(&payload).unwrap();
+        assert_eq!(hybrid_auth.post_quantum_schemes(), vec![SchemeId::Dilithium3]);
+
+        // The auth key commits to both halves.
+        let other_pq = Dilithium3KeyPair::generate().unwrap();
+        let other_key = HybridPublicKey::new(
+            classical.public_key(),
+            PostQuantumPublicKey::new(SchemeId::Dilithium3, other_pq.public_key().to_vec()),
+        );
+        assert_ne!(
+            AuthenticationKey::any_key(AnyPublicKey::hybrid(hybrid_key.clone())),
+            AuthenticationKey::any_key(AnyPublicKey::hybrid(other_key))
+        );
+
+        // Either half failing rejects the whole signature.
+        let wrong_pq = other_pq.sign(&signing_bytes).unwrap();
+        SingleKeyAuthenticator::new(
+            AnyPublicKey::hybrid(hybrid_key.clone()),
+            AnySignature::hybrid(HybridSignature::new(classical_signature, wrong_pq)),
+        )
+        .verify(&payload)
+        .unwrap_err();
+
+        let wrong_classical = SignatureBundle::new(
+            SchemeId::Ed25519,
+            Ed25519PrivateKey::generate_for_testing()
+                .sign(&payload)
+                .unwrap()
+                .to_bytes()
+                .to_vec(),
+        );
+        SingleKeyAuthenticator::new(
+            AnyPublicKey::hybrid(hybrid_key),
+            AnySignature::hybrid(HybridSignature::new(wrong_classical, pq_signature)),
+        )
+        .verify(&payload)
+        .unwrap_err();
+    }
+
     #[test]
     fn verify_secp256k1_ecdsa_single_key_auth() {
         let fake_sender = Ed25519PrivateKey::generate_for_testing();
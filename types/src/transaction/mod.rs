@@ -13,8 +13,9 @@ use crate::{
     ledger_info::LedgerInfo,
     proof::{TransactionInfoListWithProof, TransactionInfoWithProof},
     transaction::authenticator::{
-        AASigningData, AccountAuthenticator, AnyPublicKey, AnySignature, PostQuantumPublicKey,
-        SingleKeyAuthenticator, TransactionAuthenticator,
+        AASigningData, AccountAuthenticator, AnyPublicKey, AnySignature, MultiKey,
+        MultiKeyAuthenticator, PostQuantumPublicKey, SingleKeyAuthenticator,
+        TransactionAuthenticator,
     },
     vm_status::{DiscardedVMStatus, KeptVMStatus, StatusCode, StatusType, VMStatus},
     write_set::{HotStateOp, WriteSet},
@@ -81,7 +82,7 @@ use move_core_types::{
     vm_status::AbortLocation,
 };
 pub use multisig::{ExecutionError, Multisig, MultisigTransactionPayload};
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 pub use script::{
     ArgumentABI, EntryABI, EntryFunction, EntryFunctionABI, Script, TransactionScriptABI,
     TypeArgumentABI,
@@ -100,6 +101,13 @@ pub type AtomicVersion = AtomicU64;
 #[derive(Clone)]
 pub enum Auth<'a> {
     Ed25519(&'a Ed25519PrivateKey),
+    PostQuantum(&'a Dilithium3KeyPair),
+    /// Ed25519 and Dilithium3 members of a hybrid 2-of-2 multi-key account.
+    Hybrid {
+        private_key: &'a Ed25519PrivateKey,
+        post_quantum_keypair: &'a Dilithium3KeyPair,
+        multi_key: &'a MultiKey,
+    },
     Abstraction(FunctionInfo, Arc<dyn Fn(&[u8]) -> Vec<u8>>),
     DerivableAbstraction {
         function_info: FunctionInfo,
@@ -584,6 +592,36 @@ fn gen_auth(
 
             AccountAuthenticator::single_key(single_key_auth)
         },
+        Auth::PostQuantum(keypair) => {
+            let signing_bytes = signing_message(user_signed_message)?;
+            let public_key =
+                AnyPublicKey::post_quantum(SchemeId::Dilithium3, keypair.public_key().to_vec());
+            let signature = keypair.sign(&signing_bytes)?;
+            AccountAuthenticator::single_key(SingleKeyAuthenticator::new(
+                public_key,
+                AnySignature::post_quantum(signature),
+            ))
+        },
+        Auth::Hybrid {
+            private_key,
+            post_quantum_keypair,
+            multi_key,
+        } => {
+            let signature = private_key.sign(user_signed_message)?;
+            let signing_bytes = signing_message(user_signed_message)?;
+            let pq_signature = post_quantum_keypair.sign(&signing_bytes)?;
+            AccountAuthenticator::multi_key(MultiKeyAuthenticator::new_with_post_quantum(
+                multi_key.clone(),
+                vec![
+                    (
+                        0,
+                        AnySignature::ed25519(signature),
+                        Some(pq_signature.clone()),
+                    ),
+                    (1, AnySignature::post_quantum(pq_signature), None),
+                ],
+            )?)
+        },
         Auth::Abstraction(function_info, sign_function) => {
             let digest = AASigningData::signing_message_digest(
                 signing_message(user_signed_message)?,
@@ -687,6 +725,18 @@ pub enum TransactionPayloadInner {
     },
 }
 
+/// The code of `script { fun main() {} }`, see `TransactionExecutable::cancellation`.
+static CANCELLATION_SCRIPT_CODE: Lazy<Vec<u8>> = Lazy::new(|| {
+    let mut code = vec![];
+    move_binary_format::file_format::empty_script()
+        .serialize_for_version(
+            Some(move_binary_format::file_format_common::VERSION_6),
+            &mut code,
+        )
+        .expect("Empty script must serialize");
+    code
+});
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum TransactionExecutable {
     Script(Script),
@@ -707,6 +757,20 @@ impl TransactionExecutable {
         matches!(self, Self::EntryFunction(_))
     }
 
+    /// A script that does nothing. A pending transaction is cancelled by replacing it with one
+    /// executing this, which uses up its replay protector without any other effect.
+    pub fn cancellation() -> Self {
+        Self::Script(Script::new(
+            CANCELLATION_SCRIPT_CODE.clone(),
+            vec![],
+            vec![],
+        ))
+    }
+
+    pub fn is_cancellation(&self) -> bool {
+        self.as_ref().is_cancellation()
+    }
+
     pub fn as_ref(&self) -> TransactionExecutableRef<'_> {
         match self {
             TransactionExecutable::EntryFunction(entry_function) => {
@@ -737,6 +801,17 @@ impl TransactionExecutableRef<'_> {
     pub fn is_entry_function(&self) -> bool {
         matches!(self, Self::EntryFunction(_))
     }
+
+    pub fn is_cancellation(&self) -> bool {
+        match self {
+            Self::Script(script) => {
+                script.code() == CANCELLATION_SCRIPT_CODE.as_slice()
+                    && script.ty_args().is_empty()
+                    && script.args().is_empty()
+            },
+            Self::EntryFunction(_) | Self::Empty => false,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -794,6 +869,15 @@ impl TransactionPayload {
         }
     }
 
+    /// Whether this is the payload of a transaction cancelling a pending one, see
+    /// `TransactionExecutable::cancellation`.
+    pub fn is_cancellation(&self) -> bool {
+        !self.is_multisig()
+            && self
+                .executable_ref()
+                .is_ok_and(|executable| executable.is_cancellation())
+    }
+
     pub fn executable_ref(&self) -> Result<TransactionExecutableRef<'_>> {
         match self {
             TransactionPayload::EntryFunction(entry_function) => {
@@ -6,9 +6,9 @@ use crate::{
     account_address::AccountAddress,
     chain_id::ChainId,
     transaction::{
-        AccountOrderedTransactionsWithProof, RawTransaction, Script, SignedTransaction,
-        Transaction, TransactionInfo, TransactionListWithProof, TransactionPayload,
-        TransactionWithProof,
+        AccountOrderedTransactionsWithProof, RawTransaction, ReplayProtector, Script,
+        SignedTransaction, Transaction, TransactionExecutable, TransactionInfo,
+        TransactionListWithProof, TransactionPayload, TransactionWithProof,
     },
 };
 use accudo_crypto::{
@@ -16,6 +16,7 @@ use accudo_crypto::{
     PrivateKey, Uniform,
 };
 use bcs::test_helpers::assert_canonical_encode_decode;
+use move_binary_format::file_format::CompiledScript;
 use proptest::prelude::*;
 use std::convert::TryFrom;
 
@@ -40,6 +41,45 @@ fn test_invalid_signature() {
     )
 }
 
+#[test]
+fn test_cancellation_payload() {
+    let TransactionExecutable::Script(script) = TransactionExecutable::cancellation() else {
+        panic!("Cancellation must be a script");
+    };
+    assert!(CompiledScript::deserialize(script.code()).is_ok());
+
+    for replay_protector in [
+        ReplayProtector::SequenceNumber(7),
+        ReplayProtector::Nonce(7),
+    ] {
+        let raw_txn = RawTransaction::new_txn(
+            AccountAddress::random(),
+            replay_protector,
+            TransactionExecutable::cancellation(),
+            None,
+            0,
+            0,
+            0,
+            ChainId::test(),
+        );
+        assert_eq!(raw_txn.replay_protector(), replay_protector);
+        assert!(raw_txn.into_payload().is_cancellation());
+    }
+
+    let multisig_cancellation = RawTransaction::new_txn(
+        AccountAddress::random(),
+        ReplayProtector::SequenceNumber(7),
+        TransactionExecutable::cancellation(),
+        Some(AccountAddress::random()),
+        0,
+        0,
+        0,
+        ChainId::test(),
+    );
+    assert!(!multisig_cancellation.into_payload().is_cancellation());
+    assert!(!TransactionPayload::Script(Script::new(vec![], vec![], vec![])).is_cancellation());
+}
+
 proptest! {
     #[test]
     fn test_sign_raw_transaction(raw_txn in any::<RawTransaction>(), keypair in ed25519::keypair_strategy()) {